@@ -5,3 +5,42 @@ pub mod parser;
 pub fn unescape_literal(literal: &str) -> String {
     literal.replace(r#"\%\{"#, "%{")
 }
+
+// The literals of the optional groups can contain escaped closing braces too.
+pub fn unescape_optional_literal(literal: &str) -> String {
+    unescape_literal(literal).replace(r#"\}"#, "}")
+}
+
+pub fn escape_literal(literal: &str) -> String {
+    literal.replace("%{", r#"\%\{"#)
+}
+
+pub fn escape_optional_literal(literal: &str) -> String {
+    escape_literal(literal).replace("}", r#"\}"#)
+}
+
+// Quotes a parameter, which can contain escaped characters.
+pub fn quote_string(string: &str) -> String {
+    format!("\"{}\"", string.replace('\\', r#"\\"#).replace('"', r#"\""#))
+}
+
+// In regular expressions only the quotation marks have to be escaped, the
+// other escape sequences are passed to the regex engine.
+pub fn quote_regex(expression: &str) -> String {
+    let mut quoted = String::from("\"");
+    let mut chars = expression.chars();
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' => {
+                quoted.push(c);
+                if let Some(c) = chars.next() {
+                    quoted.push(c);
+                }
+            }
+            '"' => quoted.push_str(r#"\""#),
+            c => quoted.push(c),
+        }
+    }
+    quoted.push('"');
+    quoted
+}
@@ -1,14 +1,71 @@
-pub use self::pattern_parser::ParseError;
+use std::cell::RefCell;
+use std::collections::HashSet;
+use std::error;
+use std::fmt;
+
+use regex;
 
 use matcher::compiled_pattern::CompiledPattern;
 use parsers::ParserFactory;
-use self::pattern_parser::ParseResult;
 
 #[allow(clippy)]
 mod pattern_parser;
 
+pub type ParseResult<T> = Result<T, ParseError>;
+
+#[derive(PartialEq, Eq, Debug, Clone)]
+pub struct ParseError {
+    pub line: usize,
+    pub column: usize,
+    pub offset: usize,
+    pub expected: HashSet<&'static str>,
+    // The error of the regular expression which couldn't be compiled.
+    pub regex_error: Option<String>,
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> Result<(), fmt::Error> {
+        let mut expected: Vec<&str> = self.expected.iter().cloned().collect();
+        expected.sort();
+        try!(formatter.write_fmt(format_args!("error at {}:{}: expected ", self.line, self.column)));
+        if expected.len() == 1 {
+            try!(formatter.write_fmt(format_args!("`{}`", expected[0])));
+        } else {
+            try!(formatter.write_fmt(format_args!("one of `{}`", expected.join("`, `"))));
+        }
+        match self.regex_error {
+            Some(ref regex_error) => formatter.write_fmt(format_args!(": {}", regex_error)),
+            None => Ok(()),
+        }
+    }
+}
+
+impl error::Error for ParseError {
+    fn description(&self) -> &str {
+        "parse error"
+    }
+}
+
+// The generated parser can report only static strings as the expected tokens,
+// so the error of an invalid regular expression is passed around it.
+thread_local!(static REGEX_ERROR: RefCell<Option<String>> = RefCell::new(None));
+
+fn set_regex_error(error: regex::Error) {
+    REGEX_ERROR.with(|regex_error| *regex_error.borrow_mut() = Some(error.to_string()));
+}
+
 pub fn pattern_with_factory<F: ParserFactory>(input: &str) -> ParseResult<CompiledPattern> {
-    self::pattern_parser::pattern::<F>(input)
+    REGEX_ERROR.with(|regex_error| regex_error.borrow_mut().take());
+    self::pattern_parser::pattern::<F>(input).map_err(|error| {
+        let regex_error = REGEX_ERROR.with(|regex_error| regex_error.borrow_mut().take());
+        ParseError {
+            line: error.line,
+            column: error.column,
+            offset: error.offset,
+            regex_error: regex_error.filter(|_| error.expected.contains("valid regular expression")),
+            expected: error.expected,
+        }
+    })
 }
 
 //
@@ -25,5 +82,5 @@ pub fn pattern_with_factory<F: ParserFactory>(input: &str) -> ParseResult<Compil
 //
 pub fn pattern(input: &str) -> ParseResult<CompiledPattern> {
     use matcher::trie::parser_factory::TrieParserFactory;
-    self::pattern_parser::pattern::<TrieParserFactory>(input)
+    pattern_with_factory::<TrieParserFactory>(input)
 }
@@ -180,11 +180,316 @@ fn parse_pattern_piece<'input, F: ParserFactory>(input: &'input str,
         match choice_res {
             Matched(pos, value) => Matched(pos, value),
             Failed => {
-                let choice_res = parse_piece_literal::<F>(input, state, pos);
+                let choice_res = parse_piece_optional::<F>(input, state, pos);
                 match choice_res {
                     Matched(pos, value) => Matched(pos, value),
-                    Failed => parse_piece_parser::<F>(input, state, pos),
+                    Failed => {
+                        let choice_res = parse_piece_alternatives::<F>(input, state, pos);
+                        match choice_res {
+                            Matched(pos, value) => Matched(pos, value),
+                            Failed => {
+                                let choice_res = parse_piece_literal::<F>(input, state, pos);
+                                match choice_res {
+                                    Matched(pos, value) => Matched(pos, value),
+                                    Failed => parse_piece_parser::<F>(input, state, pos),
+                                }
+                            }
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
+fn parse_piece_optional<'input, F: ParserFactory>(input: &'input str,
+                                                  state: &mut ParseState<'input>,
+                                                  pos: usize)
+                                                  -> RuleResult<Vec<TokenType>> {
+    {
+        let start_pos = pos;
+        {
+            let seq_res = parse_OPTIONAL_BEGIN::<F>(input, state, pos);
+            match seq_res {
+                Matched(pos, _) => {
+                    {
+                        let seq_res = {
+                            let mut repeat_pos = pos;
+                            let mut repeat_value = vec![];
+                            loop {
+                                let pos = repeat_pos;
+                                let step_res = parse_optional_piece::<F>(input, state, pos);
+                                match step_res {
+                                    Matched(newpos, value) => {
+                                        repeat_pos = newpos;
+                                        repeat_value.push(value);
+                                    }
+                                    Failed => {
+                                        break;
+                                    }
+                                }
+                            }
+                            if repeat_value.len() >= 1usize {
+                                Matched(repeat_pos, repeat_value)
+                            } else {
+                                Failed
+                            }
+                        };
+                        match seq_res {
+                            Matched(pos, pieces) => {
+                                {
+                                    let seq_res = parse_PARSER_END::<F>(input, state, pos);
+                                    match seq_res {
+                                        Matched(pos, _) => {
+                                            {
+                                                let match_str = &input[start_pos..pos];
+                                                Matched(pos,
+                                                        {
+                                                            vec![TokenType::Optional(utils::flatten_vec(pieces))]
+                                                        })
+                                            }
+                                        }
+                                        Failed => Failed,
+                                    }
+                                }
+                            }
+                            Failed => Failed,
+                        }
+                    }
+                }
+                Failed => Failed,
+            }
+        }
+    }
+}
+fn parse_optional_piece<'input, F: ParserFactory>(input: &'input str,
+                                                  state: &mut ParseState<'input>,
+                                                  pos: usize)
+                                                  -> RuleResult<Vec<TokenType>> {
+    {
+        let choice_res = parse_piece_optional::<F>(input, state, pos);
+        match choice_res {
+            Matched(pos, value) => Matched(pos, value),
+            Failed => {
+                let choice_res = parse_piece_alternatives::<F>(input, state, pos);
+                match choice_res {
+                    Matched(pos, value) => Matched(pos, value),
+                    Failed => {
+                        let choice_res = parse_optional_literal::<F>(input, state, pos);
+                        match choice_res {
+                            Matched(pos, value) => Matched(pos, value),
+                            Failed => parse_piece_parser::<F>(input, state, pos),
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
+fn parse_optional_literal<'input, F: ParserFactory>(input: &'input str,
+                                                    state: &mut ParseState<'input>,
+                                                    pos: usize)
+                                                    -> RuleResult<Vec<TokenType>> {
+    {
+        let start_pos = pos;
+        {
+            let seq_res = parse_optional_literal_chars::<F>(input, state, pos);
+            match seq_res {
+                Matched(pos, literal) => {
+                    {
+                        let match_str = &input[start_pos..pos];
+                        Matched(pos, {
+                            let unescaped_literal = grammar::unescape_optional_literal(literal);
+                            vec![TokenType::Literal(unescaped_literal)]
+                        })
+                    }
+                }
+                Failed => Failed,
+            }
+        }
+    }
+}
+fn parse_optional_literal_chars<'input, F: ParserFactory>(input: &'input str,
+                                                          state: &mut ParseState<'input>,
+                                                          pos: usize)
+                                                          -> RuleResult<&'input str> {
+    {
+        let start_pos = pos;
+        {
+            let seq_res = {
+                let mut repeat_pos = pos;
+                let mut repeat_value = vec![];
+                loop {
+                    let pos = repeat_pos;
+                    let step_res = {
+                        let choice_res = slice_eq(input, state, pos, "\\}");
+                        match choice_res {
+                            Matched(pos, value) => Matched(pos, value),
+                            Failed => {
+                                let seq_res = {
+                                    let assert_res = parse_PARSER_BEGIN::<F>(input, state, pos);
+                                    match assert_res {
+                                        Failed => Matched(pos, ()),
+                                        Matched(..) => Failed,
+                                    }
+                                };
+                                match seq_res {
+                                    Matched(pos, _) => {
+                                        {
+                                            let seq_res = {
+                                                let assert_res = parse_PARSER_END::<F>(input,
+                                                                                       state,
+                                                                                       pos);
+                                                match assert_res {
+                                                    Failed => Matched(pos, ()),
+                                                    Matched(..) => Failed,
+                                                }
+                                            };
+                                            match seq_res {
+                                                Matched(pos, _) => {
+                                                    any_char(input, state, pos)
+                                                }
+                                                Failed => Failed,
+                                            }
+                                        }
+                                    }
+                                    Failed => Failed,
+                                }
+                            }
+                        }
+                    };
+                    match step_res {
+                        Matched(newpos, value) => {
+                            repeat_pos = newpos;
+                            repeat_value.push(value);
+                        }
+                        Failed => {
+                            break;
+                        }
+                    }
+                }
+                if repeat_value.len() >= 1usize {
+                    Matched(repeat_pos, ())
+                } else {
+                    Failed
+                }
+            };
+            match seq_res {
+                Matched(pos, _) => {
+                    {
+                        let match_str = &input[start_pos..pos];
+                        Matched(pos, {
+                            match_str
+                        })
+                    }
+                }
+                Failed => Failed,
+            }
+        }
+    }
+}
+fn parse_piece_alternatives<'input, F: ParserFactory>(input: &'input str,
+                                                      state: &mut ParseState<'input>,
+                                                      pos: usize)
+                                                      -> RuleResult<Vec<TokenType>> {
+    {
+        let start_pos = pos;
+        {
+            let seq_res = parse_PARSER_BEGIN::<F>(input, state, pos);
+            match seq_res {
+                Matched(pos, _) => {
+                    {
+                        let seq_res = parse_ANY::<F>(input, state, pos);
+                        match seq_res {
+                            Matched(pos, _) => {
+                                {
+                                    let seq_res = parse_PARSER_PARAMS_BEGIN::<F>(input, state, pos);
+                                    match seq_res {
+                                        Matched(pos, _) => {
+                                            {
+                                                let seq_res = {
+                                                    let mut repeat_pos = pos;
+                                                    let mut repeat_value = vec![];
+                                                    loop {
+                                                        let pos = repeat_pos;
+                                                        let pos = if !repeat_value.is_empty() {
+                                                            let sep_res = parse_comma::<F>(input,
+                                                                                           state,
+                                                                                           pos);
+                                                            match sep_res {
+                                                                Matched(newpos, _) => {
+                                                                    newpos
+                                                                }
+                                                                Failed => break,
+                                                            }
+                                                        } else {
+                                                            pos
+                                                        };
+                                                        let step_res =
+                                                            parse_escaped_string::<F>(input,
+                                                                                      state,
+                                                                                      pos);
+                                                        match step_res {
+                                                            Matched(newpos, value) => {
+                                                                repeat_pos = newpos;
+                                                                repeat_value.push(value);
+                                                            }
+                                                            Failed => {
+                                                                break;
+                                                            }
+                                                        }
+                                                    }
+                                                    if repeat_value.len() >= 1usize {
+                                                        Matched(repeat_pos, repeat_value)
+                                                    } else {
+                                                        Failed
+                                                    }
+                                                };
+                                                match seq_res {
+                                                    Matched(pos, literals) => {
+                                                        {
+                                                            let seq_res =
+                                                                parse_PARSER_PARAMS_END::<F>(input,
+                                                                                             state,
+                                                                                             pos);
+                                                            match seq_res {
+                                                                Matched(pos, _) => {
+                                                                    {
+                                                                        let seq_res =
+                                                                            parse_PARSER_END::<F>(input,
+                                                                                                  state,
+                                                                                                  pos);
+                                                                        match seq_res {
+                                                                            Matched(pos, _) => {
+                                                                                {
+                                                                                    let match_str =
+                                                                                        &input[start_pos..pos];
+                                                                                    Matched(pos,
+                                                                                            {
+                                                                                                vec![TokenType::Alternatives(literals)]
+                                                                                            })
+                                                                                }
+                                                                            }
+                                                                            Failed => Failed,
+                                                                        }
+                                                                    }
+                                                                }
+                                                                Failed => Failed,
+                                                            }
+                                                        }
+                                                    }
+                                                    Failed => Failed,
+                                                }
+                                            }
+                                        }
+                                        Failed => Failed,
+                                    }
+                                }
+                            }
+                            Failed => Failed,
+                        }
+                    }
                 }
+                Failed => Failed,
             }
         }
     }
@@ -258,7 +563,133 @@ fn parse_parser<'input, F: ParserFactory>(input: &'input str,
         let choice_res = parse_parser_SET::<F>(input, state, pos);
         match choice_res {
             Matched(pos, value) => Matched(pos, value),
-            Failed => parse_parser_INT::<F>(input, state, pos),
+            Failed => {
+                let choice_res = parse_parser_INT::<F>(input, state, pos);
+                match choice_res {
+                    Matched(pos, value) => Matched(pos, value),
+                    Failed => {
+                        let choice_res = parse_parser_IPV4::<F>(input, state, pos);
+                        match choice_res {
+                            Matched(pos, value) => Matched(pos, value),
+                            Failed => {
+                                let choice_res = parse_parser_IPV6::<F>(input, state, pos);
+                                match choice_res {
+                                    Matched(pos, value) => Matched(pos, value),
+                                    Failed => {
+                                        let choice_res = parse_parser_IPADDR::<F>(input,
+                                                                                  state,
+                                                                                  pos);
+                                        match choice_res {
+                                            Matched(pos, value) => Matched(pos, value),
+                                            Failed => {
+                                                let choice_res = parse_parser_MACADDR::<F>(input,
+                                                                                           state,
+                                                                                           pos);
+                                                match choice_res {
+                                                    Matched(pos, value) => Matched(pos, value),
+                                                    Failed => {
+                                                        let choice_res =
+                                                            parse_parser_HOSTNAME::<F>(input,
+                                                                                       state,
+                                                                                       pos);
+                                                        match choice_res {
+                                                            Matched(pos, value) => Matched(pos, value),
+                                                            Failed => {
+                                                                let choice_res =
+                                                                    parse_parser_EMAIL::<F>(input,
+                                                                                            state,
+                                                                                            pos);
+                                                                match choice_res {
+                                                                    Matched(pos, value) => Matched(pos, value),
+                                                                    Failed => {
+                                                                        let choice_res =
+                                                                            parse_parser_FLOAT::<F>(input,
+                                                                                                    state,
+                                                                                                    pos);
+                                                                        match choice_res {
+                                                                            Matched(pos, value) => Matched(pos, value),
+                                                                            Failed => {
+                                                                                let choice_res =
+                                                                                    parse_parser_NUMBER::<F>(input,
+                                                                                                             state,
+                                                                                                             pos);
+                                                                                match choice_res {
+                                                                                    Matched(pos, value) => Matched(pos, value),
+                                                                                    Failed => {
+                                                                                        let choice_res =
+                                                                                            parse_parser_HEX::<F>(input,
+                                                                                                                  state,
+                                                                                                                  pos);
+                                                                                        match choice_res {
+                                                                                            Matched(pos, value) => Matched(pos, value),
+                                                                                            Failed => {
+                                                                                                let choice_res =
+                                                                                                    parse_parser_TIMESTAMP::<F>(input,
+                                                                                                                                state,
+                                                                                                                                pos);
+                                                                                                match choice_res {
+                                                                                                    Matched(pos, value) => Matched(pos, value),
+                                                                                                    Failed => {
+                                                                                                        let choice_res =
+                                                                                                            parse_parser_KVPAIRS::<F>(input,
+                                                                                                                                      state,
+                                                                                                                                      pos);
+                                                                                                        match choice_res {
+                                                                                                            Matched(pos, value) => Matched(pos, value),
+                                                                                                            Failed => {
+                                                                                                                let choice_res =
+                                                                                                                    parse_parser_JSON::<F>(input,
+                                                                                                                                           state,
+                                                                                                                                           pos);
+                                                                                                                match choice_res {
+                                                                                                                    Matched(pos, value) => Matched(pos, value),
+                                                                                                                    Failed => {
+                                                                                                                        let choice_res =
+                                                                                                                            parse_parser_QSTRING::<F>(input,
+                                                                                                                                                      state,
+                                                                                                                                                      pos);
+                                                                                                                        match choice_res {
+                                                                                                                            Matched(pos, value) => Matched(pos, value),
+                                                                                                                            Failed => {
+                                                                                                                                let choice_res =
+                                                                                                                                    parse_parser_ESTRING::<F>(input,
+                                                                                                                                                              state,
+                                                                                                                                                              pos);
+                                                                                                                                match choice_res {
+                                                                                                                                    Matched(pos, value) => Matched(pos, value),
+                                                                                                                                    Failed => parse_parser_REGEX::<F>(input,
+                                                                                                                                                                      state,
+                                                                                                                                                                      pos),
+                                                                                                                                }
+                                                                                                                            }
+                                                                                                                        }
+                                                                                                                    }
+                                                                                                                }
+                                                                                                            }
+                                                                                                        }
+                                                                                                    }
+                                                                                                }
+                                                                                            }
+                                                                                        }
+                                                                                    }
+                                                                                }
+                                                                            }
+                                                                        }
+                                                                    }
+                                                                }
+                                                            }
+                                                        }
+                                                    }
+                                                }
+                                            }
+                                        }
+                                    }
+                                }
+                            }
+                        }
+                    }
+                }
+            }
         }
     }
 }
@@ -546,20 +977,29 @@ fn parse_parser_INT_optional_params<'input, F: ParserFactory>
         }
     }
 }
-fn parse_parser_GREEDY<'input, F: ParserFactory>(input: &'input str,
-                                                 state: &mut ParseState<'input>,
-                                                 pos: usize)
-                                                 -> RuleResult<Vec<TokenType>> {
+fn parse_parser_FLOAT<'input, F: ParserFactory>(input: &'input str,
+                                                state: &mut ParseState<'input>,
+                                                pos: usize)
+                                                -> RuleResult<Box<Parser>> {
     {
         let start_pos = pos;
         {
-            let seq_res = parse_PARSER_BEGIN::<F>(input, state, pos);
+            let seq_res = parse_FLOAT::<F>(input, state, pos);
             match seq_res {
                 Matched(pos, _) => {
                     {
-                        let seq_res = parse_GREEDY::<F>(input, state, pos);
+                        let seq_res = match parse_parser_INT_optional_params::<F>(input,
+                                                                                  state,
+                                                                                  pos) {
+                            Matched(newpos, value) => {
+                                Matched(newpos, Some(value))
+                            }
+                            Failed => {
+                                Matched(pos, None)
+                            }
+                        };
                         match seq_res {
-                            Matched(pos, _) => {
+                            Matched(pos, opt_params) => {
                                 {
                                     let seq_res = match parse_parser_name::<F>(input, state, pos) {
                                         Matched(newpos, value) => {
@@ -572,55 +1012,118 @@ fn parse_parser_GREEDY<'input, F: ParserFactory>(input: &'input str,
                                     match seq_res {
                                         Matched(pos, name) => {
                                             {
-                                                let seq_res = parse_PARSER_END::<F>(input,
-                                                                                    state,
-                                                                                    pos);
-                                                match seq_res {
-                                                    Matched(pos, _) => {
-                                                        {
-                                                            let seq_res =
-                                                                match parse_literal::<F>(input,
-                                                                                         state,
-                                                                                         pos) {
-                                                                    Matched(newpos, value) => {
-                                                                        Matched(newpos, Some(value))
-                                                                    }
-                                                                    Failed => {
-                                                                        Matched(pos, None)
-                                                                    }
-                                                                };
-                                                            match seq_res {
-                                                                Matched(pos, end_string) => {
-                                                                    {
-                                                                        let match_str =
-                                                                            &input[start_pos..pos];
-                                                                        Matched(pos,
-                                                                                {
-                                                                                    let mut tokens =
-                                                                                        Vec::new();
-                                                                                    let parser =
-                                                                                        F::new_greedy(name,
-                                                                                                      end_string);
-                                                                                    tokens.push(TokenType::Parser(parser));
-                                                                                    let end_string =
-                                                                                        end_string.map(|string|
-                                                                                                           string.to_owned());
-                                                                                    if let Some(end_string)
-                                                                                           =
-                                                                                           end_string
-                                                                                           {
-                                                                                        tokens.push(TokenType::Literal(end_string));
-                                                                                    }
-                                                                                    tokens
-                                                                                })
-                                                                    }
-                                                                }
-                                                                Failed => Failed,
-                                                            }
-                                                        }
-                                                    }
-                                                    Failed => Failed,
-                                                }
+                                                let match_str = &input[start_pos..pos];
+                                                Matched(pos, {
+                                                    F::new_float(name, opt_params)
+                                                })
+                                            }
+                                        }
+                                        Failed => Failed,
+                                    }
+                                }
+                            }
+                            Failed => Failed,
+                        }
+                    }
+                }
+                Failed => Failed,
+            }
+        }
+    }
+}
+fn parse_parser_NUMBER<'input, F: ParserFactory>(input: &'input str,
+                                                 state: &mut ParseState<'input>,
+                                                 pos: usize)
+                                                 -> RuleResult<Box<Parser>> {
+    {
+        let start_pos = pos;
+        {
+            let seq_res = parse_NUMBER::<F>(input, state, pos);
+            match seq_res {
+                Matched(pos, _) => {
+                    {
+                        let seq_res = match parse_parser_INT_optional_params::<F>(input,
+                                                                                  state,
+                                                                                  pos) {
+                            Matched(newpos, value) => {
+                                Matched(newpos, Some(value))
+                            }
+                            Failed => {
+                                Matched(pos, None)
+                            }
+                        };
+                        match seq_res {
+                            Matched(pos, opt_params) => {
+                                {
+                                    let seq_res = match parse_parser_name::<F>(input, state, pos) {
+                                        Matched(newpos, value) => {
+                                            Matched(newpos, Some(value))
+                                        }
+                                        Failed => {
+                                            Matched(pos, None)
+                                        }
+                                    };
+                                    match seq_res {
+                                        Matched(pos, name) => {
+                                            {
+                                                let match_str = &input[start_pos..pos];
+                                                Matched(pos, {
+                                                    F::new_number(name, opt_params)
+                                                })
+                                            }
+                                        }
+                                        Failed => Failed,
+                                    }
+                                }
+                            }
+                            Failed => Failed,
+                        }
+                    }
+                }
+                Failed => Failed,
+            }
+        }
+    }
+}
+fn parse_parser_HEX<'input, F: ParserFactory>(input: &'input str,
+                                              state: &mut ParseState<'input>,
+                                              pos: usize)
+                                              -> RuleResult<Box<Parser>> {
+    {
+        let start_pos = pos;
+        {
+            let seq_res = parse_HEX::<F>(input, state, pos);
+            match seq_res {
+                Matched(pos, _) => {
+                    {
+                        let seq_res = match parse_parser_INT_optional_params::<F>(input,
+                                                                                  state,
+                                                                                  pos) {
+                            Matched(newpos, value) => {
+                                Matched(newpos, Some(value))
+                            }
+                            Failed => {
+                                Matched(pos, None)
+                            }
+                        };
+                        match seq_res {
+                            Matched(pos, opt_params) => {
+                                {
+                                    let seq_res = match parse_parser_name::<F>(input, state, pos) {
+                                        Matched(newpos, value) => {
+                                            Matched(newpos, Some(value))
+                                        }
+                                        Failed => {
+                                            Matched(pos, None)
+                                        }
+                                    };
+                                    match seq_res {
+                                        Matched(pos, name) => {
+                                            {
+                                                let match_str = &input[start_pos..pos];
+                                                Matched(pos, {
+                                                    F::new_hex(name, opt_params)
+                                                })
                                             }
                                         }
                                         Failed => Failed,
@@ -636,91 +1139,2106 @@ fn parse_parser_GREEDY<'input, F: ParserFactory>(input: &'input str,
         }
     }
 }
-fn parse_parser_BASE_optional_param<'input, F: ParserFactory>
-                                                              (input: &'input str,
-                                                               state: &mut ParseState<'input>,
-                                                               pos: usize)
-                                                               -> RuleResult<OptionalParameter<'input>> {
+fn parse_parser_IPV4<'input, F: ParserFactory>(input: &'input str,
+                                               state: &mut ParseState<'input>,
+                                               pos: usize)
+                                               -> RuleResult<Box<Parser>> {
+    {
+        let start_pos = pos;
+        {
+            let seq_res = parse_IPV4::<F>(input, state, pos);
+            match seq_res {
+                Matched(pos, _) => {
+                    {
+                        let seq_res = match parse_parser_name::<F>(input, state, pos) {
+                            Matched(newpos, value) => {
+                                Matched(newpos, Some(value))
+                            }
+                            Failed => {
+                                Matched(pos, None)
+                            }
+                        };
+                        match seq_res {
+                            Matched(pos, name) => {
+                                {
+                                    let match_str = &input[start_pos..pos];
+                                    Matched(pos, {
+                                        F::new_ipv4(name)
+                                    })
+                                }
+                            }
+                            Failed => Failed,
+                        }
+                    }
+                }
+                Failed => Failed,
+            }
+        }
+    }
+}
+fn parse_parser_IPV6<'input, F: ParserFactory>(input: &'input str,
+                                               state: &mut ParseState<'input>,
+                                               pos: usize)
+                                               -> RuleResult<Box<Parser>> {
+    {
+        let start_pos = pos;
+        {
+            let seq_res = parse_IPV6::<F>(input, state, pos);
+            match seq_res {
+                Matched(pos, _) => {
+                    {
+                        let seq_res = match parse_parser_name::<F>(input, state, pos) {
+                            Matched(newpos, value) => {
+                                Matched(newpos, Some(value))
+                            }
+                            Failed => {
+                                Matched(pos, None)
+                            }
+                        };
+                        match seq_res {
+                            Matched(pos, name) => {
+                                {
+                                    let match_str = &input[start_pos..pos];
+                                    Matched(pos, {
+                                        F::new_ipv6(name)
+                                    })
+                                }
+                            }
+                            Failed => Failed,
+                        }
+                    }
+                }
+                Failed => Failed,
+            }
+        }
+    }
+}
+fn parse_parser_IPADDR<'input, F: ParserFactory>(input: &'input str,
+                                                 state: &mut ParseState<'input>,
+                                                 pos: usize)
+                                                 -> RuleResult<Box<Parser>> {
+    {
+        let start_pos = pos;
+        {
+            let seq_res = parse_IPADDR::<F>(input, state, pos);
+            match seq_res {
+                Matched(pos, _) => {
+                    {
+                        let seq_res = match parse_parser_name::<F>(input, state, pos) {
+                            Matched(newpos, value) => {
+                                Matched(newpos, Some(value))
+                            }
+                            Failed => {
+                                Matched(pos, None)
+                            }
+                        };
+                        match seq_res {
+                            Matched(pos, name) => {
+                                {
+                                    let match_str = &input[start_pos..pos];
+                                    Matched(pos, {
+                                        F::new_ipaddr(name)
+                                    })
+                                }
+                            }
+                            Failed => Failed,
+                        }
+                    }
+                }
+                Failed => Failed,
+            }
+        }
+    }
+}
+fn parse_parser_MACADDR<'input, F: ParserFactory>(input: &'input str,
+                                                  state: &mut ParseState<'input>,
+                                                  pos: usize)
+                                                  -> RuleResult<Box<Parser>> {
+    {
+        let start_pos = pos;
+        {
+            let seq_res = parse_MACADDR::<F>(input, state, pos);
+            match seq_res {
+                Matched(pos, _) => {
+                    {
+                        let seq_res = match parse_parser_name::<F>(input, state, pos) {
+                            Matched(newpos, value) => {
+                                Matched(newpos, Some(value))
+                            }
+                            Failed => {
+                                Matched(pos, None)
+                            }
+                        };
+                        match seq_res {
+                            Matched(pos, name) => {
+                                {
+                                    let match_str = &input[start_pos..pos];
+                                    Matched(pos, {
+                                        F::new_macaddr(name)
+                                    })
+                                }
+                            }
+                            Failed => Failed,
+                        }
+                    }
+                }
+                Failed => Failed,
+            }
+        }
+    }
+}
+fn parse_parser_HOSTNAME<'input, F: ParserFactory>(input: &'input str,
+                                                   state: &mut ParseState<'input>,
+                                                   pos: usize)
+                                                   -> RuleResult<Box<Parser>> {
+    {
+        let start_pos = pos;
+        {
+            let seq_res = parse_HOSTNAME::<F>(input, state, pos);
+            match seq_res {
+                Matched(pos, _) => {
+                    {
+                        let seq_res = match parse_parser_name::<F>(input, state, pos) {
+                            Matched(newpos, value) => {
+                                Matched(newpos, Some(value))
+                            }
+                            Failed => {
+                                Matched(pos, None)
+                            }
+                        };
+                        match seq_res {
+                            Matched(pos, name) => {
+                                {
+                                    let match_str = &input[start_pos..pos];
+                                    Matched(pos, {
+                                        F::new_hostname(name)
+                                    })
+                                }
+                            }
+                            Failed => Failed,
+                        }
+                    }
+                }
+                Failed => Failed,
+            }
+        }
+    }
+}
+fn parse_parser_EMAIL<'input, F: ParserFactory>(input: &'input str,
+                                                state: &mut ParseState<'input>,
+                                                pos: usize)
+                                                -> RuleResult<Box<Parser>> {
+    {
+        let start_pos = pos;
+        {
+            let seq_res = parse_EMAIL::<F>(input, state, pos);
+            match seq_res {
+                Matched(pos, _) => {
+                    {
+                        let seq_res = match parse_parser_name::<F>(input, state, pos) {
+                            Matched(newpos, value) => {
+                                Matched(newpos, Some(value))
+                            }
+                            Failed => {
+                                Matched(pos, None)
+                            }
+                        };
+                        match seq_res {
+                            Matched(pos, name) => {
+                                {
+                                    let match_str = &input[start_pos..pos];
+                                    Matched(pos, {
+                                        F::new_email(name)
+                                    })
+                                }
+                            }
+                            Failed => Failed,
+                        }
+                    }
+                }
+                Failed => Failed,
+            }
+        }
+    }
+}
+fn parse_parser_TIMESTAMP<'input, F: ParserFactory>(input: &'input str,
+                                                    state: &mut ParseState<'input>,
+                                                    pos: usize)
+                                                    -> RuleResult<Box<Parser>> {
+    {
+        let start_pos = pos;
+        {
+            let seq_res = parse_TIMESTAMP::<F>(input, state, pos);
+            match seq_res {
+                Matched(pos, _) => {
+                    {
+                        let seq_res = match parse_parser_TIMESTAMP_optional_params::<F>(input,
+                                                                                        state,
+                                                                                        pos) {
+                            Matched(newpos, value) => {
+                                Matched(newpos, Some(value))
+                            }
+                            Failed => {
+                                Matched(pos, None)
+                            }
+                        };
+                        match seq_res {
+                            Matched(pos, opt_params) => {
+                                {
+                                    let seq_res = match parse_parser_name::<F>(input, state, pos) {
+                                        Matched(newpos, value) => {
+                                            Matched(newpos, Some(value))
+                                        }
+                                        Failed => {
+                                            Matched(pos, None)
+                                        }
+                                    };
+                                    match seq_res {
+                                        Matched(pos, name) => {
+                                            {
+                                                let match_str = &input[start_pos..pos];
+                                                Matched(pos, {
+                                                    F::new_timestamp(name, opt_params)
+                                                })
+                                            }
+                                        }
+                                        Failed => Failed,
+                                    }
+                                }
+                            }
+                            Failed => Failed,
+                        }
+                    }
+                }
+                Failed => Failed,
+            }
+        }
+    }
+}
+fn parse_parser_TIMESTAMP_optional_params<'input, F: ParserFactory>
+                                                                    (input: &'input str,
+                                                                     state: &mut ParseState<'input>,
+                                                                     pos: usize)
+                                                                     -> RuleResult<Vec<OptionalParameter<'input>>> {
+    {
+        let start_pos = pos;
+        {
+            let seq_res = parse_PARSER_PARAMS_BEGIN::<F>(input, state, pos);
+            match seq_res {
+                Matched(pos, _) => {
+                    {
+                        let seq_res = {
+                            let mut repeat_pos = pos;
+                            let mut repeat_value = vec![];
+                            loop {
+                                let pos = repeat_pos;
+                                let pos = if !repeat_value.is_empty() {
+                                    let sep_res = parse_comma::<F>(input, state, pos);
+                                    match sep_res {
+                                        Matched(newpos, _) => {
+                                            newpos
+                                        }
+                                        Failed => break,
+                                    }
+                                } else {
+                                    pos
+                                };
+                                let step_res = parse_parser_TIMESTAMP_optional_param::<F>(input,
+                                                                                          state,
+                                                                                          pos);
+                                match step_res {
+                                    Matched(newpos, value) => {
+                                        repeat_pos = newpos;
+                                        repeat_value.push(value);
+                                    }
+                                    Failed => {
+                                        break;
+                                    }
+                                }
+                            }
+                            Matched(repeat_pos, repeat_value)
+                        };
+                        match seq_res {
+                            Matched(pos, params) => {
+                                {
+                                    let seq_res = parse_PARSER_PARAMS_END::<F>(input, state, pos);
+                                    match seq_res {
+                                        Matched(pos, _) => {
+                                            {
+                                                let match_str = &input[start_pos..pos];
+                                                Matched(pos, {
+                                                    params
+                                                })
+                                            }
+                                        }
+                                        Failed => Failed,
+                                    }
+                                }
+                            }
+                            Failed => Failed,
+                        }
+                    }
+                }
+                Failed => Failed,
+            }
+        }
+    }
+}
+fn parse_parser_TIMESTAMP_optional_param<'input, F: ParserFactory>
+                                                                   (input: &'input str,
+                                                                    state: &mut ParseState<'input>,
+                                                                    pos: usize)
+                                                                    -> RuleResult<OptionalParameter<'input>> {
+    {
+        let choice_res = {
+            let start_pos = pos;
+            {
+                let seq_res = parse_FORMAT::<F>(input, state, pos);
+                match seq_res {
+                    Matched(pos, name) => {
+                        {
+                            let seq_res = slice_eq(input, state, pos, "=");
+                            match seq_res {
+                                Matched(pos, _) => {
+                                    {
+                                        let seq_res = parse_timestamp_format::<F>(input,
+                                                                                  state,
+                                                                                  pos);
+                                        match seq_res {
+                                            Matched(pos, value) => {
+                                                {
+                                                    let match_str = &input[start_pos..pos];
+                                                    Matched(pos, {
+                                                        OptionalParameter::Str(name, value)
+                                                    })
+                                                }
+                                            }
+                                            Failed => Failed,
+                                        }
+                                    }
+                                }
+                                Failed => Failed,
+                            }
+                        }
+                    }
+                    Failed => Failed,
+                }
+            }
+        };
+        match choice_res {
+            Matched(pos, value) => Matched(pos, value),
+            Failed => {
+                let start_pos = pos;
+                {
+                    let seq_res = parse_PRESET::<F>(input, state, pos);
+                    match seq_res {
+                        Matched(pos, name) => {
+                            {
+                                let seq_res = slice_eq(input, state, pos, "=");
+                                match seq_res {
+                                    Matched(pos, _) => {
+                                        {
+                                            let seq_res = slice_eq(input, state, pos, "\"");
+                                            match seq_res {
+                                                Matched(pos, _) => {
+                                                    {
+                                                        let seq_res =
+                                                            parse_timestamp_preset::<F>(input,
+                                                                                        state,
+                                                                                        pos);
+                                                        match seq_res {
+                                                            Matched(pos, value) => {
+                                                                {
+                                                                    let seq_res = slice_eq(input,
+                                                                                           state,
+                                                                                           pos,
+                                                                                           "\"");
+                                                                    match seq_res {
+                                                                        Matched(pos, _) => {
+                                                                            {
+                                                                                let match_str =
+                                                                                    &input[start_pos..pos];
+                                                                                Matched(pos,
+                                                                                        {
+                                                                                            OptionalParameter::Str(name,
+                                                                                                                   value.to_owned())
+                                                                                        })
+                                                                            }
+                                                                        }
+                                                                        Failed => Failed,
+                                                                    }
+                                                                }
+                                                            }
+                                                            Failed => Failed,
+                                                        }
+                                                    }
+                                                }
+                                                Failed => Failed,
+                                            }
+                                        }
+                                    }
+                                    Failed => Failed,
+                                }
+                            }
+                        }
+                        Failed => Failed,
+                    }
+                }
+            }
+        }
+    }
+}
+fn parse_timestamp_format<'input, F: ParserFactory>(input: &'input str,
+                                                    state: &mut ParseState<'input>,
+                                                    pos: usize)
+                                                    -> RuleResult<String> {
+    {
+        let start_pos = pos;
+        {
+            let seq_res = slice_eq(input, state, pos, "\"");
+            match seq_res {
+                Matched(pos, _) => {
+                    {
+                        let seq_res = {
+                            let mut repeat_pos = pos;
+                            let mut repeat_value = vec![];
+                            loop {
+                                let pos = repeat_pos;
+                                let step_res = parse_timestamp_format_part::<F>(input, state, pos);
+                                match step_res {
+                                    Matched(newpos, value) => {
+                                        repeat_pos = newpos;
+                                        repeat_value.push(value);
+                                    }
+                                    Failed => {
+                                        break;
+                                    }
+                                }
+                            }
+                            Matched(repeat_pos, repeat_value)
+                        };
+                        match seq_res {
+                            Matched(pos, parts) => {
+                                {
+                                    let seq_res = slice_eq(input, state, pos, "\"");
+                                    match seq_res {
+                                        Matched(pos, _) => {
+                                            {
+                                                let match_str = &input[start_pos..pos];
+                                                match {
+                                                    if parts.is_empty() {
+                                                        Err("non-empty timestamp format")
+                                                    } else {
+                                                        Ok(parts.concat())
+                                                    }
+                                                } {
+                                                    Ok(res) => Matched(pos, res),
+                                                    Err(expected) => {
+                                                        state.mark_failure(pos, expected);
+                                                        Failed
+                                                    }
+                                                }
+                                            }
+                                        }
+                                        Failed => Failed,
+                                    }
+                                }
+                            }
+                            Failed => Failed,
+                        }
+                    }
+                }
+                Failed => Failed,
+            }
+        }
+    }
+}
+fn parse_timestamp_format_part<'input, F: ParserFactory>(input: &'input str,
+                                                         state: &mut ParseState<'input>,
+                                                         pos: usize)
+                                                         -> RuleResult<&'input str> {
+    {
+        let choice_res = {
+            let start_pos = pos;
+            {
+                let seq_res = slice_eq(input, state, pos, "%");
+                match seq_res {
+                    Matched(pos, _) => {
+                        {
+                            let seq_res = if input.len() > pos {
+                                let (ch, next) = char_range_at(input, pos);
+                                match ch {
+                                    'Y' |
+                                    'y' |
+                                    'm' |
+                                    'd' |
+                                    'e' |
+                                    'H' |
+                                    'M' |
+                                    'S' |
+                                    'f' |
+                                    's' |
+                                    'b' |
+                                    'a' |
+                                    'z' |
+                                    '%' => Matched(next, ()),
+                                    _ => state.mark_failure(pos, "[YymdeHMSfsbaz%]"),
+                                }
+                            } else {
+                                state.mark_failure(pos, "[YymdeHMSfsbaz%]")
+                            };
+                            match seq_res {
+                                Matched(pos, _) => {
+                                    {
+                                        let match_str = &input[start_pos..pos];
+                                        Matched(pos, {
+                                            match_str
+                                        })
+                                    }
+                                }
+                                Failed => Failed,
+                            }
+                        }
+                    }
+                    Failed => Failed,
+                }
+            }
+        };
+        match choice_res {
+            Matched(pos, value) => Matched(pos, value),
+            Failed => {
+                let choice_res = {
+                    let start_pos = pos;
+                    {
+                        let seq_res = slice_eq(input, state, pos, "\\");
+                        match seq_res {
+                            Matched(pos, _) => {
+                                {
+                                    let seq_res = parse_escaped_char::<F>(input, state, pos);
+                                    match seq_res {
+                                        Matched(pos, c) => {
+                                            {
+                                                let match_str = &input[start_pos..pos];
+                                                Matched(pos, {
+                                                    if c == "%" {
+                                                        "%%"
+                                                    } else {
+                                                        c
+                                                    }
+                                                })
+                                            }
+                                        }
+                                        Failed => Failed,
+                                    }
+                                }
+                            }
+                            Failed => Failed,
+                        }
+                    }
+                };
+                match choice_res {
+                    Matched(pos, value) => Matched(pos, value),
+                    Failed => {
+                        let start_pos = pos;
+                        {
+                            let seq_res = {
+                                let seq_res = {
+                                    let assert_res = slice_eq(input, state, pos, "\"");
+                                    match assert_res {
+                                        Failed => Matched(pos, ()),
+                                        Matched(..) => Failed,
+                                    }
+                                };
+                                match seq_res {
+                                    Matched(pos, _) => {
+                                        {
+                                            let seq_res = {
+                                                let assert_res = slice_eq(input, state, pos, "\\");
+                                                match assert_res {
+                                                    Failed => Matched(pos, ()),
+                                                    Matched(..) => Failed,
+                                                }
+                                            };
+                                            match seq_res {
+                                                Matched(pos, _) => {
+                                                    {
+                                                        let seq_res = {
+                                                            let assert_res = slice_eq(input,
+                                                                                      state,
+                                                                                      pos,
+                                                                                      "%");
+                                                            match assert_res {
+                                                                Failed => Matched(pos, ()),
+                                                                Matched(..) => Failed,
+                                                            }
+                                                        };
+                                                        match seq_res {
+                                                            Matched(pos, _) => {
+                                                                any_char(input, state, pos)
+                                                            }
+                                                            Failed => Failed,
+                                                        }
+                                                    }
+                                                }
+                                                Failed => Failed,
+                                            }
+                                        }
+                                    }
+                                    Failed => Failed,
+                                }
+                            };
+                            match seq_res {
+                                Matched(pos, _) => {
+                                    {
+                                        let match_str = &input[start_pos..pos];
+                                        Matched(pos, {
+                                            match_str
+                                        })
+                                    }
+                                }
+                                Failed => Failed,
+                            }
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
+fn parse_timestamp_preset<'input, F: ParserFactory>(input: &'input str,
+                                                    state: &mut ParseState<'input>,
+                                                    pos: usize)
+                                                    -> RuleResult<&'input str> {
+    {
+        let start_pos = pos;
+        {
+            let seq_res = {
+                let choice_res = slice_eq(input, state, pos, "ISO8601");
+                match choice_res {
+                    Matched(pos, value) => Matched(pos, value),
+                    Failed => {
+                        let choice_res = slice_eq(input, state, pos, "RFC3164");
+                        match choice_res {
+                            Matched(pos, value) => Matched(pos, value),
+                            Failed => slice_eq(input, state, pos, "epoch"),
+                        }
+                    }
+                }
+            };
+            match seq_res {
+                Matched(pos, _) => {
+                    {
+                        let match_str = &input[start_pos..pos];
+                        Matched(pos, {
+                            match_str
+                        })
+                    }
+                }
+                Failed => Failed,
+            }
+        }
+    }
+}
+fn parse_parser_KVPAIRS<'input, F: ParserFactory>(input: &'input str,
+                                                  state: &mut ParseState<'input>,
+                                                  pos: usize)
+                                                  -> RuleResult<Box<Parser>> {
+    {
+        let start_pos = pos;
+        {
+            let seq_res = parse_KVPAIRS::<F>(input, state, pos);
+            match seq_res {
+                Matched(pos, _) => {
+                    {
+                        let seq_res = match parse_parser_KVPAIRS_optional_params::<F>(input,
+                                                                                      state,
+                                                                                      pos) {
+                            Matched(newpos, value) => {
+                                Matched(newpos, Some(value))
+                            }
+                            Failed => {
+                                Matched(pos, None)
+                            }
+                        };
+                        match seq_res {
+                            Matched(pos, opt_params) => {
+                                {
+                                    let seq_res = parse_parser_optional_name::<F>(input,
+                                                                                  state,
+                                                                                  pos);
+                                    match seq_res {
+                                        Matched(pos, name) => {
+                                            {
+                                                let match_str = &input[start_pos..pos];
+                                                Matched(pos, {
+                                                    F::new_kvpairs(name, opt_params)
+                                                })
+                                            }
+                                        }
+                                        Failed => Failed,
+                                    }
+                                }
+                            }
+                            Failed => Failed,
+                        }
+                    }
+                }
+                Failed => Failed,
+            }
+        }
+    }
+}
+fn parse_parser_KVPAIRS_optional_params<'input, F: ParserFactory>
+                                                                  (input: &'input str,
+                                                                   state: &mut ParseState<'input>,
+                                                                   pos: usize)
+                                                                   -> RuleResult<Vec<OptionalParameter<'input>>> {
+    {
+        let start_pos = pos;
+        {
+            let seq_res = parse_PARSER_PARAMS_BEGIN::<F>(input, state, pos);
+            match seq_res {
+                Matched(pos, _) => {
+                    {
+                        let seq_res = {
+                            let mut repeat_pos = pos;
+                            let mut repeat_value = vec![];
+                            loop {
+                                let pos = repeat_pos;
+                                let pos = if !repeat_value.is_empty() {
+                                    let sep_res = parse_comma::<F>(input, state, pos);
+                                    match sep_res {
+                                        Matched(newpos, _) => {
+                                            newpos
+                                        }
+                                        Failed => break,
+                                    }
+                                } else {
+                                    pos
+                                };
+                                let step_res = parse_parser_KVPAIRS_optional_param::<F>(input,
+                                                                                        state,
+                                                                                        pos);
+                                match step_res {
+                                    Matched(newpos, value) => {
+                                        repeat_pos = newpos;
+                                        repeat_value.push(value);
+                                    }
+                                    Failed => {
+                                        break;
+                                    }
+                                }
+                            }
+                            Matched(repeat_pos, repeat_value)
+                        };
+                        match seq_res {
+                            Matched(pos, params) => {
+                                {
+                                    let seq_res = parse_PARSER_PARAMS_END::<F>(input, state, pos);
+                                    match seq_res {
+                                        Matched(pos, _) => {
+                                            {
+                                                let match_str = &input[start_pos..pos];
+                                                Matched(pos, {
+                                                    params
+                                                })
+                                            }
+                                        }
+                                        Failed => Failed,
+                                    }
+                                }
+                            }
+                            Failed => Failed,
+                        }
+                    }
+                }
+                Failed => Failed,
+            }
+        }
+    }
+}
+fn parse_parser_KVPAIRS_optional_param<'input, F: ParserFactory>
+                                                                 (input: &'input str,
+                                                                  state: &mut ParseState<'input>,
+                                                                  pos: usize)
+                                                                  -> RuleResult<OptionalParameter<'input>> {
+    {
+        let choice_res = {
+            let start_pos = pos;
+            {
+                let seq_res = parse_SEPARATOR::<F>(input, state, pos);
+                match seq_res {
+                    Matched(pos, name) => {
+                        {
+                            let seq_res = slice_eq(input, state, pos, "=");
+                            match seq_res {
+                                Matched(pos, _) => {
+                                    {
+                                        let seq_res = parse_escaped_string::<F>(input, state, pos);
+                                        match seq_res {
+                                            Matched(pos, value) => {
+                                                {
+                                                    let match_str = &input[start_pos..pos];
+                                                    Matched(pos, {
+                                                        OptionalParameter::Str(name, value)
+                                                    })
+                                                }
+                                            }
+                                            Failed => Failed,
+                                        }
+                                    }
+                                }
+                                Failed => Failed,
+                            }
+                        }
+                    }
+                    Failed => Failed,
+                }
+            }
+        };
+        match choice_res {
+            Matched(pos, value) => Matched(pos, value),
+            Failed => {
+                let choice_res = {
+                    let start_pos = pos;
+                    {
+                        let seq_res = parse_DELIMITER::<F>(input, state, pos);
+                        match seq_res {
+                            Matched(pos, name) => {
+                                {
+                                    let seq_res = slice_eq(input, state, pos, "=");
+                                    match seq_res {
+                                        Matched(pos, _) => {
+                                            {
+                                                let seq_res = parse_escaped_string::<F>(input,
+                                                                                        state,
+                                                                                        pos);
+                                                match seq_res {
+                                                    Matched(pos, value) => {
+                                                        {
+                                                            let match_str = &input[start_pos..pos];
+                                                            Matched(pos, {
+                                                                OptionalParameter::Str(name, value)
+                                                            })
+                                                        }
+                                                    }
+                                                    Failed => Failed,
+                                                }
+                                            }
+                                        }
+                                        Failed => Failed,
+                                    }
+                                }
+                            }
+                            Failed => Failed,
+                        }
+                    }
+                };
+                match choice_res {
+                    Matched(pos, value) => Matched(pos, value),
+                    Failed => {
+                        let start_pos = pos;
+                        {
+                            let seq_res = parse_PREFIX::<F>(input, state, pos);
+                            match seq_res {
+                                Matched(pos, name) => {
+                                    {
+                                        let seq_res = slice_eq(input, state, pos, "=");
+                                        match seq_res {
+                                            Matched(pos, _) => {
+                                                {
+                                                    let seq_res = parse_escaped_string::<F>(input,
+                                                                                            state,
+                                                                                            pos);
+                                                    match seq_res {
+                                                        Matched(pos, value) => {
+                                                            {
+                                                                let match_str =
+                                                                    &input[start_pos..pos];
+                                                                Matched(pos,
+                                                                        {
+                                                                            OptionalParameter::Str(name, value)
+                                                                        })
+                                                            }
+                                                        }
+                                                        Failed => Failed,
+                                                    }
+                                                }
+                                            }
+                                            Failed => Failed,
+                                        }
+                                    }
+                                }
+                                Failed => Failed,
+                            }
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
+fn parse_parser_JSON<'input, F: ParserFactory>(input: &'input str,
+                                               state: &mut ParseState<'input>,
+                                               pos: usize)
+                                               -> RuleResult<Box<Parser>> {
+    {
+        let start_pos = pos;
+        {
+            let seq_res = parse_JSON::<F>(input, state, pos);
+            match seq_res {
+                Matched(pos, _) => {
+                    {
+                        let seq_res = match parse_parser_JSON_optional_params::<F>(input,
+                                                                                   state,
+                                                                                   pos) {
+                            Matched(newpos, value) => {
+                                Matched(newpos, Some(value))
+                            }
+                            Failed => {
+                                Matched(pos, None)
+                            }
+                        };
+                        match seq_res {
+                            Matched(pos, opt_params) => {
+                                {
+                                    let seq_res = parse_parser_optional_name::<F>(input,
+                                                                                  state,
+                                                                                  pos);
+                                    match seq_res {
+                                        Matched(pos, name) => {
+                                            {
+                                                let match_str = &input[start_pos..pos];
+                                                Matched(pos, {
+                                                    F::new_json(name, opt_params)
+                                                })
+                                            }
+                                        }
+                                        Failed => Failed,
+                                    }
+                                }
+                            }
+                            Failed => Failed,
+                        }
+                    }
+                }
+                Failed => Failed,
+            }
+        }
+    }
+}
+fn parse_parser_JSON_optional_params<'input, F: ParserFactory>
+                                                               (input: &'input str,
+                                                                state: &mut ParseState<'input>,
+                                                                pos: usize)
+                                                                -> RuleResult<Vec<OptionalParameter<'input>>> {
+    {
+        let start_pos = pos;
+        {
+            let seq_res = parse_PARSER_PARAMS_BEGIN::<F>(input, state, pos);
+            match seq_res {
+                Matched(pos, _) => {
+                    {
+                        let seq_res = {
+                            let mut repeat_pos = pos;
+                            let mut repeat_value = vec![];
+                            loop {
+                                let pos = repeat_pos;
+                                let pos = if !repeat_value.is_empty() {
+                                    let sep_res = parse_comma::<F>(input, state, pos);
+                                    match sep_res {
+                                        Matched(newpos, _) => {
+                                            newpos
+                                        }
+                                        Failed => break,
+                                    }
+                                } else {
+                                    pos
+                                };
+                                let step_res = parse_parser_JSON_optional_param::<F>(input,
+                                                                                     state,
+                                                                                     pos);
+                                match step_res {
+                                    Matched(newpos, value) => {
+                                        repeat_pos = newpos;
+                                        repeat_value.push(value);
+                                    }
+                                    Failed => {
+                                        break;
+                                    }
+                                }
+                            }
+                            Matched(repeat_pos, repeat_value)
+                        };
+                        match seq_res {
+                            Matched(pos, params) => {
+                                {
+                                    let seq_res = parse_PARSER_PARAMS_END::<F>(input, state, pos);
+                                    match seq_res {
+                                        Matched(pos, _) => {
+                                            {
+                                                let match_str = &input[start_pos..pos];
+                                                Matched(pos, {
+                                                    params
+                                                })
+                                            }
+                                        }
+                                        Failed => Failed,
+                                    }
+                                }
+                            }
+                            Failed => Failed,
+                        }
+                    }
+                }
+                Failed => Failed,
+            }
+        }
+    }
+}
+fn parse_parser_JSON_optional_param<'input, F: ParserFactory>
+                                                              (input: &'input str,
+                                                               state: &mut ParseState<'input>,
+                                                               pos: usize)
+                                                               -> RuleResult<OptionalParameter<'input>> {
+    {
+        let start_pos = pos;
+        {
+            let seq_res = parse_PREFIX::<F>(input, state, pos);
+            match seq_res {
+                Matched(pos, name) => {
+                    {
+                        let seq_res = slice_eq(input, state, pos, "=");
+                        match seq_res {
+                            Matched(pos, _) => {
+                                {
+                                    let seq_res = parse_escaped_string::<F>(input, state, pos);
+                                    match seq_res {
+                                        Matched(pos, value) => {
+                                            {
+                                                let match_str = &input[start_pos..pos];
+                                                Matched(pos, {
+                                                    OptionalParameter::Str(name, value)
+                                                })
+                                            }
+                                        }
+                                        Failed => Failed,
+                                    }
+                                }
+                            }
+                            Failed => Failed,
+                        }
+                    }
+                }
+                Failed => Failed,
+            }
+        }
+    }
+}
+fn parse_parser_QSTRING<'input, F: ParserFactory>(input: &'input str,
+                                                  state: &mut ParseState<'input>,
+                                                  pos: usize)
+                                                  -> RuleResult<Box<Parser>> {
+    {
+        let start_pos = pos;
+        {
+            let seq_res = parse_QSTRING::<F>(input, state, pos);
+            match seq_res {
+                Matched(pos, _) => {
+                    {
+                        let seq_res = match parse_parser_QSTRING_optional_params::<F>(input,
+                                                                                      state,
+                                                                                      pos) {
+                            Matched(newpos, value) => {
+                                Matched(newpos, Some(value))
+                            }
+                            Failed => {
+                                Matched(pos, None)
+                            }
+                        };
+                        match seq_res {
+                            Matched(pos, opt_params) => {
+                                {
+                                    let seq_res = match parse_parser_name::<F>(input, state, pos) {
+                                        Matched(newpos, value) => {
+                                            Matched(newpos, Some(value))
+                                        }
+                                        Failed => {
+                                            Matched(pos, None)
+                                        }
+                                    };
+                                    match seq_res {
+                                        Matched(pos, name) => {
+                                            {
+                                                let match_str = &input[start_pos..pos];
+                                                Matched(pos, {
+                                                    F::new_qstring(name, opt_params)
+                                                })
+                                            }
+                                        }
+                                        Failed => Failed,
+                                    }
+                                }
+                            }
+                            Failed => Failed,
+                        }
+                    }
+                }
+                Failed => Failed,
+            }
+        }
+    }
+}
+fn parse_parser_QSTRING_optional_params<'input, F: ParserFactory>
+                                                                  (input: &'input str,
+                                                                   state: &mut ParseState<'input>,
+                                                                   pos: usize)
+                                                                   -> RuleResult<Vec<OptionalParameter<'input>>> {
+    {
+        let start_pos = pos;
+        {
+            let seq_res = parse_PARSER_PARAMS_BEGIN::<F>(input, state, pos);
+            match seq_res {
+                Matched(pos, _) => {
+                    {
+                        let seq_res = {
+                            let mut repeat_pos = pos;
+                            let mut repeat_value = vec![];
+                            loop {
+                                let pos = repeat_pos;
+                                let pos = if !repeat_value.is_empty() {
+                                    let sep_res = parse_comma::<F>(input, state, pos);
+                                    match sep_res {
+                                        Matched(newpos, _) => {
+                                            newpos
+                                        }
+                                        Failed => break,
+                                    }
+                                } else {
+                                    pos
+                                };
+                                let step_res = parse_parser_QSTRING_optional_param::<F>(input,
+                                                                                        state,
+                                                                                        pos);
+                                match step_res {
+                                    Matched(newpos, value) => {
+                                        repeat_pos = newpos;
+                                        repeat_value.push(value);
+                                    }
+                                    Failed => {
+                                        break;
+                                    }
+                                }
+                            }
+                            Matched(repeat_pos, repeat_value)
+                        };
+                        match seq_res {
+                            Matched(pos, params) => {
+                                {
+                                    let seq_res = parse_PARSER_PARAMS_END::<F>(input, state, pos);
+                                    match seq_res {
+                                        Matched(pos, _) => {
+                                            {
+                                                let match_str = &input[start_pos..pos];
+                                                Matched(pos, {
+                                                    params
+                                                })
+                                            }
+                                        }
+                                        Failed => Failed,
+                                    }
+                                }
+                            }
+                            Failed => Failed,
+                        }
+                    }
+                }
+                Failed => Failed,
+            }
+        }
+    }
+}
+fn parse_parser_QSTRING_optional_param<'input, F: ParserFactory>
+                                                                 (input: &'input str,
+                                                                  state: &mut ParseState<'input>,
+                                                                  pos: usize)
+                                                                  -> RuleResult<OptionalParameter<'input>> {
+    {
+        let choice_res = {
+            let start_pos = pos;
+            {
+                let seq_res = parse_QUOTES::<F>(input, state, pos);
+                match seq_res {
+                    Matched(pos, name) => {
+                        {
+                            let seq_res = slice_eq(input, state, pos, "=");
+                            match seq_res {
+                                Matched(pos, _) => {
+                                    {
+                                        let seq_res = parse_escaped_string::<F>(input, state, pos);
+                                        match seq_res {
+                                            Matched(pos, value) => {
+                                                {
+                                                    let match_str = &input[start_pos..pos];
+                                                    match {
+                                                        if value.is_empty() {
+                                                            Err("non-empty quotes")
+                                                        } else {
+                                                            Ok(OptionalParameter::Str(name, value))
+                                                        }
+                                                    } {
+                                                        Ok(res) => Matched(pos, res),
+                                                        Err(expected) => {
+                                                            state.mark_failure(pos, expected);
+                                                            Failed
+                                                        }
+                                                    }
+                                                }
+                                            }
+                                            Failed => Failed,
+                                        }
+                                    }
+                                }
+                                Failed => Failed,
+                            }
+                        }
+                    }
+                    Failed => Failed,
+                }
+            }
+        };
+        match choice_res {
+            Matched(pos, value) => Matched(pos, value),
+            Failed => {
+                let start_pos = pos;
+                {
+                    let seq_res = parse_PAIRS::<F>(input, state, pos);
+                    match seq_res {
+                        Matched(pos, name) => {
+                            {
+                                let seq_res = slice_eq(input, state, pos, "=");
+                                match seq_res {
+                                    Matched(pos, _) => {
+                                        {
+                                            let seq_res = parse_escaped_string::<F>(input, state, pos);
+                                            match seq_res {
+                                                Matched(pos, value) => {
+                                                    {
+                                                        let match_str = &input[start_pos..pos];
+                                                        match {
+                                                            if value.is_empty() || value.chars().count() % 2 != 0 {
+                                                                Err("pairs of opening and closing quotes")
+                                                            } else {
+                                                                Ok(OptionalParameter::Str(name, value))
+                                                            }
+                                                        } {
+                                                            Ok(res) => Matched(pos, res),
+                                                            Err(expected) => {
+                                                                state.mark_failure(pos, expected);
+                                                                Failed
+                                                            }
+                                                        }
+                                                    }
+                                                }
+                                                Failed => Failed,
+                                            }
+                                        }
+                                    }
+                                    Failed => Failed,
+                                }
+                            }
+                        }
+                        Failed => Failed,
+                    }
+                }
+            }
+        }
+    }
+}
+fn parse_parser_ESTRING<'input, F: ParserFactory>(input: &'input str,
+                                                  state: &mut ParseState<'input>,
+                                                  pos: usize)
+                                                  -> RuleResult<Box<Parser>> {
+    {
+        let start_pos = pos;
+        {
+            let seq_res = parse_ESTRING::<F>(input, state, pos);
+            match seq_res {
+                Matched(pos, _) => {
+                    {
+                        let seq_res = parse_PARSER_PARAMS_BEGIN::<F>(input, state, pos);
+                        match seq_res {
+                            Matched(pos, _) => {
+                                {
+                                    let seq_res = parse_parser_ESTRING_end::<F>(input, state, pos);
+                                    match seq_res {
+                                        Matched(pos, end_string) => {
+                                            {
+                                                let seq_res = parse_PARSER_PARAMS_END::<F>(input,
+                                                                                           state,
+                                                                                           pos);
+                                                match seq_res {
+                                                    Matched(pos, _) => {
+                                                        {
+                                                            let seq_res =
+                                                                match parse_parser_name::<F>(input,
+                                                                                             state,
+                                                                                             pos) {
+                                                                    Matched(newpos, value) => {
+                                                                        Matched(newpos, Some(value))
+                                                                    }
+                                                                    Failed => {
+                                                                        Matched(pos, None)
+                                                                    }
+                                                                };
+                                                            match seq_res {
+                                                                Matched(pos, name) => {
+                                                                    {
+                                                                        let match_str =
+                                                                            &input[start_pos..pos];
+                                                                        Matched(pos, {
+                                                                            F::new_estring(name, &end_string)
+                                                                        })
+                                                                    }
+                                                                }
+                                                                Failed => Failed,
+                                                            }
+                                                        }
+                                                    }
+                                                    Failed => Failed,
+                                                }
+                                            }
+                                        }
+                                        Failed => Failed,
+                                    }
+                                }
+                            }
+                            Failed => Failed,
+                        }
+                    }
+                }
+                Failed => Failed,
+            }
+        }
+    }
+}
+fn parse_parser_ESTRING_end<'input, F: ParserFactory>(input: &'input str,
+                                                      state: &mut ParseState<'input>,
+                                                      pos: usize)
+                                                      -> RuleResult<String> {
+    {
+        let start_pos = pos;
+        {
+            let seq_res = parse_END::<F>(input, state, pos);
+            match seq_res {
+                Matched(pos, _) => {
+                    {
+                        let seq_res = slice_eq(input, state, pos, "=");
+                        match seq_res {
+                            Matched(pos, _) => {
+                                {
+                                    let seq_res = parse_escaped_string::<F>(input, state, pos);
+                                    match seq_res {
+                                        Matched(pos, value) => {
+                                            {
+                                                let match_str = &input[start_pos..pos];
+                                                match {
+                                                    if value.is_empty() {
+                                                        Err("non-empty end string")
+                                                    } else {
+                                                        Ok(value)
+                                                    }
+                                                } {
+                                                    Ok(res) => Matched(pos, res),
+                                                    Err(expected) => {
+                                                        state.mark_failure(pos, expected);
+                                                        Failed
+                                                    }
+                                                }
+                                            }
+                                        }
+                                        Failed => Failed,
+                                    }
+                                }
+                            }
+                            Failed => Failed,
+                        }
+                    }
+                }
+                Failed => Failed,
+            }
+        }
+    }
+}
+fn parse_parser_REGEX<'input, F: ParserFactory>(input: &'input str,
+                                                state: &mut ParseState<'input>,
+                                                pos: usize)
+                                                -> RuleResult<Box<Parser>> {
+    {
+        let start_pos = pos;
+        {
+            let seq_res = parse_REGEX::<F>(input, state, pos);
+            match seq_res {
+                Matched(pos, _) => {
+                    {
+                        let seq_res = parse_PARSER_PARAMS_BEGIN::<F>(input, state, pos);
+                        match seq_res {
+                            Matched(pos, _) => {
+                                {
+                                    let seq_res = parse_regex_string::<F>(input, state, pos);
+                                    match seq_res {
+                                        Matched(pos, expression) => {
+                                            {
+                                                let seq_res = parse_PARSER_PARAMS_END::<F>(input,
+                                                                                           state,
+                                                                                           pos);
+                                                match seq_res {
+                                                    Matched(pos, _) => {
+                                                        {
+                                                            let seq_res =
+                                                                match parse_parser_name::<F>(input,
+                                                                                             state,
+                                                                                             pos) {
+                                                                    Matched(newpos, value) => {
+                                                                        Matched(newpos, Some(value))
+                                                                    }
+                                                                    Failed => {
+                                                                        Matched(pos, None)
+                                                                    }
+                                                                };
+                                                            match seq_res {
+                                                                Matched(pos, name) => {
+                                                                    {
+                                                                        let match_str =
+                                                                            &input[start_pos..pos];
+                                                                        match {
+                                                                            F::new_regex(name, &expression).map_err(|error| {
+                                                                              grammar::parser::set_regex_error(error);
+                                                                              "valid regular expression"
+                                                                            })
+                                                                        } {
+                                                                            Ok(res) => Matched(pos, res),
+                                                                            Err(expected) => {
+                                                                                state.mark_failure(pos, expected);
+                                                                                Failed
+                                                                            }
+                                                                        }
+                                                                    }
+                                                                }
+                                                                Failed => Failed,
+                                                            }
+                                                        }
+                                                    }
+                                                    Failed => Failed,
+                                                }
+                                            }
+                                        }
+                                        Failed => Failed,
+                                    }
+                                }
+                            }
+                            Failed => Failed,
+                        }
+                    }
+                }
+                Failed => Failed,
+            }
+        }
+    }
+}
+fn parse_parser_GREEDY<'input, F: ParserFactory>(input: &'input str,
+                                                 state: &mut ParseState<'input>,
+                                                 pos: usize)
+                                                 -> RuleResult<Vec<TokenType>> {
+    {
+        let start_pos = pos;
+        {
+            let seq_res = parse_PARSER_BEGIN::<F>(input, state, pos);
+            match seq_res {
+                Matched(pos, _) => {
+                    {
+                        let seq_res = parse_GREEDY::<F>(input, state, pos);
+                        match seq_res {
+                            Matched(pos, _) => {
+                                {
+                                    let seq_res = match parse_parser_name::<F>(input, state, pos) {
+                                        Matched(newpos, value) => {
+                                            Matched(newpos, Some(value))
+                                        }
+                                        Failed => {
+                                            Matched(pos, None)
+                                        }
+                                    };
+                                    match seq_res {
+                                        Matched(pos, name) => {
+                                            {
+                                                let seq_res = parse_PARSER_END::<F>(input,
+                                                                                    state,
+                                                                                    pos);
+                                                match seq_res {
+                                                    Matched(pos, _) => {
+                                                        {
+                                                            let seq_res =
+                                                                match parse_literal::<F>(input,
+                                                                                         state,
+                                                                                         pos) {
+                                                                    Matched(newpos, value) => {
+                                                                        Matched(newpos, Some(value))
+                                                                    }
+                                                                    Failed => {
+                                                                        Matched(pos, None)
+                                                                    }
+                                                                };
+                                                            match seq_res {
+                                                                Matched(pos, end_string) => {
+                                                                    {
+                                                                        let match_str =
+                                                                            &input[start_pos..pos];
+                                                                        Matched(pos,
+                                                                                {
+                                                                                    let mut tokens =
+                                                                                        Vec::new();
+                                                                                    let parser =
+                                                                                        F::new_greedy(name,
+                                                                                                      end_string);
+                                                                                    tokens.push(TokenType::Parser(parser));
+                                                                                    let end_string =
+                                                                                        end_string.map(|string|
+                                                                                                           string.to_owned());
+                                                                                    if let Some(end_string)
+                                                                                           =
+                                                                                           end_string
+                                                                                           {
+                                                                                        tokens.push(TokenType::Literal(end_string));
+                                                                                    }
+                                                                                    tokens
+                                                                                })
+                                                                    }
+                                                                }
+                                                                Failed => Failed,
+                                                            }
+                                                        }
+                                                    }
+                                                    Failed => Failed,
+                                                }
+                                            }
+                                        }
+                                        Failed => Failed,
+                                    }
+                                }
+                            }
+                            Failed => Failed,
+                        }
+                    }
+                }
+                Failed => Failed,
+            }
+        }
+    }
+}
+fn parse_parser_BASE_optional_param<'input, F: ParserFactory>
+                                                              (input: &'input str,
+                                                               state: &mut ParseState<'input>,
+                                                               pos: usize)
+                                                               -> RuleResult<OptionalParameter<'input>> {
+    {
+        let choice_res = {
+            let start_pos = pos;
+            {
+                let seq_res = parse_MIN_LEN::<F>(input, state, pos);
+                match seq_res {
+                    Matched(pos, name) => {
+                        {
+                            let seq_res = slice_eq(input, state, pos, "=");
+                            match seq_res {
+                                Matched(pos, _) => {
+                                    {
+                                        let seq_res = parse_int::<F>(input, state, pos);
+                                        match seq_res {
+                                            Matched(pos, value) => {
+                                                {
+                                                    let match_str = &input[start_pos..pos];
+                                                    Matched(pos, {
+                                                        OptionalParameter::Int(name, value)
+                                                    })
+                                                }
+                                            }
+                                            Failed => Failed,
+                                        }
+                                    }
+                                }
+                                Failed => Failed,
+                            }
+                        }
+                    }
+                    Failed => Failed,
+                }
+            }
+        };
+        match choice_res {
+            Matched(pos, value) => Matched(pos, value),
+            Failed => {
+                let start_pos = pos;
+                {
+                    let seq_res = parse_MAX_LEN::<F>(input, state, pos);
+                    match seq_res {
+                        Matched(pos, name) => {
+                            {
+                                let seq_res = slice_eq(input, state, pos, "=");
+                                match seq_res {
+                                    Matched(pos, _) => {
+                                        {
+                                            let seq_res = parse_int::<F>(input, state, pos);
+                                            match seq_res {
+                                                Matched(pos, value) => {
+                                                    {
+                                                        let match_str = &input[start_pos..pos];
+                                                        Matched(pos, {
+                                                            OptionalParameter::Int(name, value)
+                                                        })
+                                                    }
+                                                }
+                                                Failed => Failed,
+                                            }
+                                        }
+                                    }
+                                    Failed => Failed,
+                                }
+                            }
+                        }
+                        Failed => Failed,
+                    }
+                }
+            }
+        }
+    }
+}
+fn parse_MIN_LEN<'input, F: ParserFactory>(input: &'input str,
+                                           state: &mut ParseState<'input>,
+                                           pos: usize)
+                                           -> RuleResult<&'input str> {
+    {
+        let start_pos = pos;
+        {
+            let seq_res = slice_eq(input, state, pos, "min_len");
+            match seq_res {
+                Matched(pos, _) => {
+                    {
+                        let match_str = &input[start_pos..pos];
+                        Matched(pos, {
+                            match_str
+                        })
+                    }
+                }
+                Failed => Failed,
+            }
+        }
+    }
+}
+fn parse_MAX_LEN<'input, F: ParserFactory>(input: &'input str,
+                                           state: &mut ParseState<'input>,
+                                           pos: usize)
+                                           -> RuleResult<&'input str> {
+    {
+        let start_pos = pos;
+        {
+            let seq_res = slice_eq(input, state, pos, "max_len");
+            match seq_res {
+                Matched(pos, _) => {
+                    {
+                        let match_str = &input[start_pos..pos];
+                        Matched(pos, {
+                            match_str
+                        })
+                    }
+                }
+                Failed => Failed,
+            }
+        }
+    }
+}
+fn parse_FORMAT<'input, F: ParserFactory>(input: &'input str,
+                                          state: &mut ParseState<'input>,
+                                          pos: usize)
+                                          -> RuleResult<&'input str> {
+    {
+        let start_pos = pos;
+        {
+            let seq_res = slice_eq(input, state, pos, "format");
+            match seq_res {
+                Matched(pos, _) => {
+                    {
+                        let match_str = &input[start_pos..pos];
+                        Matched(pos, {
+                            match_str
+                        })
+                    }
+                }
+                Failed => Failed,
+            }
+        }
+    }
+}
+fn parse_PRESET<'input, F: ParserFactory>(input: &'input str,
+                                          state: &mut ParseState<'input>,
+                                          pos: usize)
+                                          -> RuleResult<&'input str> {
+    {
+        let start_pos = pos;
+        {
+            let seq_res = slice_eq(input, state, pos, "preset");
+            match seq_res {
+                Matched(pos, _) => {
+                    {
+                        let match_str = &input[start_pos..pos];
+                        Matched(pos, {
+                            match_str
+                        })
+                    }
+                }
+                Failed => Failed,
+            }
+        }
+    }
+}
+fn parse_SEPARATOR<'input, F: ParserFactory>(input: &'input str,
+                                             state: &mut ParseState<'input>,
+                                             pos: usize)
+                                             -> RuleResult<&'input str> {
+    {
+        let start_pos = pos;
+        {
+            let seq_res = slice_eq(input, state, pos, "separator");
+            match seq_res {
+                Matched(pos, _) => {
+                    {
+                        let match_str = &input[start_pos..pos];
+                        Matched(pos, {
+                            match_str
+                        })
+                    }
+                }
+                Failed => Failed,
+            }
+        }
+    }
+}
+fn parse_DELIMITER<'input, F: ParserFactory>(input: &'input str,
+                                             state: &mut ParseState<'input>,
+                                             pos: usize)
+                                             -> RuleResult<&'input str> {
+    {
+        let start_pos = pos;
+        {
+            let seq_res = slice_eq(input, state, pos, "delimiter");
+            match seq_res {
+                Matched(pos, _) => {
+                    {
+                        let match_str = &input[start_pos..pos];
+                        Matched(pos, {
+                            match_str
+                        })
+                    }
+                }
+                Failed => Failed,
+            }
+        }
+    }
+}
+fn parse_PREFIX<'input, F: ParserFactory>(input: &'input str,
+                                          state: &mut ParseState<'input>,
+                                          pos: usize)
+                                          -> RuleResult<&'input str> {
+    {
+        let start_pos = pos;
+        {
+            let seq_res = slice_eq(input, state, pos, "prefix");
+            match seq_res {
+                Matched(pos, _) => {
+                    {
+                        let match_str = &input[start_pos..pos];
+                        Matched(pos, {
+                            match_str
+                        })
+                    }
+                }
+                Failed => Failed,
+            }
+        }
+    }
+}
+fn parse_QUOTES<'input, F: ParserFactory>(input: &'input str,
+                                          state: &mut ParseState<'input>,
+                                          pos: usize)
+                                          -> RuleResult<&'input str> {
+    {
+        let start_pos = pos;
+        {
+            let seq_res = slice_eq(input, state, pos, "quotes");
+            match seq_res {
+                Matched(pos, _) => {
+                    {
+                        let match_str = &input[start_pos..pos];
+                        Matched(pos, {
+                            match_str
+                        })
+                    }
+                }
+                Failed => Failed,
+            }
+        }
+    }
+}
+fn parse_PAIRS<'input, F: ParserFactory>(input: &'input str,
+                                         state: &mut ParseState<'input>,
+                                         pos: usize)
+                                         -> RuleResult<&'input str> {
+    {
+        let start_pos = pos;
+        {
+            let seq_res = slice_eq(input, state, pos, "pairs");
+            match seq_res {
+                Matched(pos, _) => {
+                    {
+                        let match_str = &input[start_pos..pos];
+                        Matched(pos, {
+                            match_str
+                        })
+                    }
+                }
+                Failed => Failed,
+            }
+        }
+    }
+}
+fn parse_END<'input, F: ParserFactory>(input: &'input str,
+                                       state: &mut ParseState<'input>,
+                                       pos: usize)
+                                       -> RuleResult<&'input str> {
+    {
+        let start_pos = pos;
+        {
+            let seq_res = slice_eq(input, state, pos, "end");
+            match seq_res {
+                Matched(pos, _) => {
+                    {
+                        let match_str = &input[start_pos..pos];
+                        Matched(pos, {
+                            match_str
+                        })
+                    }
+                }
+                Failed => Failed,
+            }
+        }
+    }
+}
+fn parse_INT<'input, F: ParserFactory>(input: &'input str,
+                                       state: &mut ParseState<'input>,
+                                       pos: usize)
+                                       -> RuleResult<&'input str> {
+    {
+        let start_pos = pos;
+        {
+            let seq_res = slice_eq(input, state, pos, "INT");
+            match seq_res {
+                Matched(pos, _) => {
+                    {
+                        let match_str = &input[start_pos..pos];
+                        Matched(pos, {
+                            match_str
+                        })
+                    }
+                }
+                Failed => Failed,
+            }
+        }
+    }
+}
+fn parse_SET<'input, F: ParserFactory>(input: &'input str,
+                                       state: &mut ParseState<'input>,
+                                       pos: usize)
+                                       -> RuleResult<&'input str> {
+    {
+        let start_pos = pos;
+        {
+            let seq_res = slice_eq(input, state, pos, "SET");
+            match seq_res {
+                Matched(pos, _) => {
+                    {
+                        let match_str = &input[start_pos..pos];
+                        Matched(pos, {
+                            match_str
+                        })
+                    }
+                }
+                Failed => Failed,
+            }
+        }
+    }
+}
+fn parse_GREEDY<'input, F: ParserFactory>(input: &'input str,
+                                          state: &mut ParseState<'input>,
+                                          pos: usize)
+                                          -> RuleResult<&'input str> {
+    {
+        let start_pos = pos;
+        {
+            let seq_res = slice_eq(input, state, pos, "GREEDY");
+            match seq_res {
+                Matched(pos, _) => {
+                    {
+                        let match_str = &input[start_pos..pos];
+                        Matched(pos, {
+                            match_str
+                        })
+                    }
+                }
+                Failed => Failed,
+            }
+        }
+    }
+}
+fn parse_FLOAT<'input, F: ParserFactory>(input: &'input str,
+                                         state: &mut ParseState<'input>,
+                                         pos: usize)
+                                         -> RuleResult<&'input str> {
+    {
+        let start_pos = pos;
+        {
+            let seq_res = slice_eq(input, state, pos, "FLOAT");
+            match seq_res {
+                Matched(pos, _) => {
+                    {
+                        let match_str = &input[start_pos..pos];
+                        Matched(pos, {
+                            match_str
+                        })
+                    }
+                }
+                Failed => Failed,
+            }
+        }
+    }
+}
+fn parse_NUMBER<'input, F: ParserFactory>(input: &'input str,
+                                          state: &mut ParseState<'input>,
+                                          pos: usize)
+                                          -> RuleResult<&'input str> {
+    {
+        let start_pos = pos;
+        {
+            let seq_res = slice_eq(input, state, pos, "NUMBER");
+            match seq_res {
+                Matched(pos, _) => {
+                    {
+                        let match_str = &input[start_pos..pos];
+                        Matched(pos, {
+                            match_str
+                        })
+                    }
+                }
+                Failed => Failed,
+            }
+        }
+    }
+}
+fn parse_HEX<'input, F: ParserFactory>(input: &'input str,
+                                       state: &mut ParseState<'input>,
+                                       pos: usize)
+                                       -> RuleResult<&'input str> {
+    {
+        let start_pos = pos;
+        {
+            let seq_res = slice_eq(input, state, pos, "HEX");
+            match seq_res {
+                Matched(pos, _) => {
+                    {
+                        let match_str = &input[start_pos..pos];
+                        Matched(pos, {
+                            match_str
+                        })
+                    }
+                }
+                Failed => Failed,
+            }
+        }
+    }
+}
+fn parse_IPV4<'input, F: ParserFactory>(input: &'input str,
+                                        state: &mut ParseState<'input>,
+                                        pos: usize)
+                                        -> RuleResult<&'input str> {
     {
-        let choice_res = {
-            let start_pos = pos;
-            {
-                let seq_res = parse_MIN_LEN::<F>(input, state, pos);
-                match seq_res {
-                    Matched(pos, name) => {
-                        {
-                            let seq_res = slice_eq(input, state, pos, "=");
-                            match seq_res {
-                                Matched(pos, _) => {
-                                    {
-                                        let seq_res = parse_int::<F>(input, state, pos);
-                                        match seq_res {
-                                            Matched(pos, value) => {
-                                                {
-                                                    let match_str = &input[start_pos..pos];
-                                                    Matched(pos, {
-                                                        OptionalParameter::Int(name, value)
-                                                    })
-                                                }
-                                            }
-                                            Failed => Failed,
-                                        }
-                                    }
-                                }
-                                Failed => Failed,
-                            }
-                        }
+        let start_pos = pos;
+        {
+            let seq_res = slice_eq(input, state, pos, "IPV4");
+            match seq_res {
+                Matched(pos, _) => {
+                    {
+                        let match_str = &input[start_pos..pos];
+                        Matched(pos, {
+                            match_str
+                        })
                     }
-                    Failed => Failed,
                 }
+                Failed => Failed,
             }
-        };
-        match choice_res {
-            Matched(pos, value) => Matched(pos, value),
-            Failed => {
-                let start_pos = pos;
-                {
-                    let seq_res = parse_MAX_LEN::<F>(input, state, pos);
-                    match seq_res {
-                        Matched(pos, name) => {
-                            {
-                                let seq_res = slice_eq(input, state, pos, "=");
-                                match seq_res {
-                                    Matched(pos, _) => {
-                                        {
-                                            let seq_res = parse_int::<F>(input, state, pos);
-                                            match seq_res {
-                                                Matched(pos, value) => {
-                                                    {
-                                                        let match_str = &input[start_pos..pos];
-                                                        Matched(pos, {
-                                                            OptionalParameter::Int(name, value)
-                                                        })
-                                                    }
-                                                }
-                                                Failed => Failed,
-                                            }
-                                        }
-                                    }
-                                    Failed => Failed,
-                                }
-                            }
-                        }
-                        Failed => Failed,
+        }
+    }
+}
+fn parse_IPV6<'input, F: ParserFactory>(input: &'input str,
+                                        state: &mut ParseState<'input>,
+                                        pos: usize)
+                                        -> RuleResult<&'input str> {
+    {
+        let start_pos = pos;
+        {
+            let seq_res = slice_eq(input, state, pos, "IPV6");
+            match seq_res {
+                Matched(pos, _) => {
+                    {
+                        let match_str = &input[start_pos..pos];
+                        Matched(pos, {
+                            match_str
+                        })
                     }
                 }
+                Failed => Failed,
             }
         }
     }
 }
-fn parse_MIN_LEN<'input, F: ParserFactory>(input: &'input str,
+fn parse_IPADDR<'input, F: ParserFactory>(input: &'input str,
+                                          state: &mut ParseState<'input>,
+                                          pos: usize)
+                                          -> RuleResult<&'input str> {
+    {
+        let start_pos = pos;
+        {
+            let seq_res = slice_eq(input, state, pos, "IPADDR");
+            match seq_res {
+                Matched(pos, _) => {
+                    {
+                        let match_str = &input[start_pos..pos];
+                        Matched(pos, {
+                            match_str
+                        })
+                    }
+                }
+                Failed => Failed,
+            }
+        }
+    }
+}
+fn parse_MACADDR<'input, F: ParserFactory>(input: &'input str,
                                            state: &mut ParseState<'input>,
                                            pos: usize)
                                            -> RuleResult<&'input str> {
     {
         let start_pos = pos;
         {
-            let seq_res = slice_eq(input, state, pos, "min_len");
+            let seq_res = slice_eq(input, state, pos, "MACADDR");
             match seq_res {
                 Matched(pos, _) => {
                     {
@@ -735,14 +3253,80 @@ fn parse_MIN_LEN<'input, F: ParserFactory>(input: &'input str,
         }
     }
 }
-fn parse_MAX_LEN<'input, F: ParserFactory>(input: &'input str,
+fn parse_HOSTNAME<'input, F: ParserFactory>(input: &'input str,
+                                            state: &mut ParseState<'input>,
+                                            pos: usize)
+                                            -> RuleResult<&'input str> {
+    {
+        let start_pos = pos;
+        {
+            let seq_res = slice_eq(input, state, pos, "HOSTNAME");
+            match seq_res {
+                Matched(pos, _) => {
+                    {
+                        let match_str = &input[start_pos..pos];
+                        Matched(pos, {
+                            match_str
+                        })
+                    }
+                }
+                Failed => Failed,
+            }
+        }
+    }
+}
+fn parse_EMAIL<'input, F: ParserFactory>(input: &'input str,
+                                         state: &mut ParseState<'input>,
+                                         pos: usize)
+                                         -> RuleResult<&'input str> {
+    {
+        let start_pos = pos;
+        {
+            let seq_res = slice_eq(input, state, pos, "EMAIL");
+            match seq_res {
+                Matched(pos, _) => {
+                    {
+                        let match_str = &input[start_pos..pos];
+                        Matched(pos, {
+                            match_str
+                        })
+                    }
+                }
+                Failed => Failed,
+            }
+        }
+    }
+}
+fn parse_TIMESTAMP<'input, F: ParserFactory>(input: &'input str,
+                                             state: &mut ParseState<'input>,
+                                             pos: usize)
+                                             -> RuleResult<&'input str> {
+    {
+        let start_pos = pos;
+        {
+            let seq_res = slice_eq(input, state, pos, "TIMESTAMP");
+            match seq_res {
+                Matched(pos, _) => {
+                    {
+                        let match_str = &input[start_pos..pos];
+                        Matched(pos, {
+                            match_str
+                        })
+                    }
+                }
+                Failed => Failed,
+            }
+        }
+    }
+}
+fn parse_KVPAIRS<'input, F: ParserFactory>(input: &'input str,
                                            state: &mut ParseState<'input>,
                                            pos: usize)
                                            -> RuleResult<&'input str> {
     {
         let start_pos = pos;
         {
-            let seq_res = slice_eq(input, state, pos, "max_len");
+            let seq_res = slice_eq(input, state, pos, "KVPAIRS");
             match seq_res {
                 Matched(pos, _) => {
                     {
@@ -757,14 +3341,14 @@ fn parse_MAX_LEN<'input, F: ParserFactory>(input: &'input str,
         }
     }
 }
-fn parse_INT<'input, F: ParserFactory>(input: &'input str,
-                                       state: &mut ParseState<'input>,
-                                       pos: usize)
-                                       -> RuleResult<&'input str> {
+fn parse_JSON<'input, F: ParserFactory>(input: &'input str,
+                                        state: &mut ParseState<'input>,
+                                        pos: usize)
+                                        -> RuleResult<&'input str> {
     {
         let start_pos = pos;
         {
-            let seq_res = slice_eq(input, state, pos, "INT");
+            let seq_res = slice_eq(input, state, pos, "JSON");
             match seq_res {
                 Matched(pos, _) => {
                     {
@@ -779,14 +3363,14 @@ fn parse_INT<'input, F: ParserFactory>(input: &'input str,
         }
     }
 }
-fn parse_SET<'input, F: ParserFactory>(input: &'input str,
-                                       state: &mut ParseState<'input>,
-                                       pos: usize)
-                                       -> RuleResult<&'input str> {
+fn parse_QSTRING<'input, F: ParserFactory>(input: &'input str,
+                                           state: &mut ParseState<'input>,
+                                           pos: usize)
+                                           -> RuleResult<&'input str> {
     {
         let start_pos = pos;
         {
-            let seq_res = slice_eq(input, state, pos, "SET");
+            let seq_res = slice_eq(input, state, pos, "QSTRING");
             match seq_res {
                 Matched(pos, _) => {
                     {
@@ -801,14 +3385,58 @@ fn parse_SET<'input, F: ParserFactory>(input: &'input str,
         }
     }
 }
-fn parse_GREEDY<'input, F: ParserFactory>(input: &'input str,
-                                          state: &mut ParseState<'input>,
-                                          pos: usize)
-                                          -> RuleResult<&'input str> {
+fn parse_ESTRING<'input, F: ParserFactory>(input: &'input str,
+                                           state: &mut ParseState<'input>,
+                                           pos: usize)
+                                           -> RuleResult<&'input str> {
     {
         let start_pos = pos;
         {
-            let seq_res = slice_eq(input, state, pos, "GREEDY");
+            let seq_res = slice_eq(input, state, pos, "ESTRING");
+            match seq_res {
+                Matched(pos, _) => {
+                    {
+                        let match_str = &input[start_pos..pos];
+                        Matched(pos, {
+                            match_str
+                        })
+                    }
+                }
+                Failed => Failed,
+            }
+        }
+    }
+}
+fn parse_REGEX<'input, F: ParserFactory>(input: &'input str,
+                                         state: &mut ParseState<'input>,
+                                         pos: usize)
+                                         -> RuleResult<&'input str> {
+    {
+        let start_pos = pos;
+        {
+            let seq_res = slice_eq(input, state, pos, "REGEX");
+            match seq_res {
+                Matched(pos, _) => {
+                    {
+                        let match_str = &input[start_pos..pos];
+                        Matched(pos, {
+                            match_str
+                        })
+                    }
+                }
+                Failed => Failed,
+            }
+        }
+    }
+}
+fn parse_ANY<'input, F: ParserFactory>(input: &'input str,
+                                       state: &mut ParseState<'input>,
+                                       pos: usize)
+                                       -> RuleResult<&'input str> {
+    {
+        let start_pos = pos;
+        {
+            let seq_res = slice_eq(input, state, pos, "ANY");
             match seq_res {
                 Matched(pos, _) => {
                     {
@@ -829,6 +3457,12 @@ fn parse_PARSER_BEGIN<'input, F: ParserFactory>(input: &'input str,
                                                 -> RuleResult<()> {
     slice_eq(input, state, pos, "%{")
 }
+fn parse_OPTIONAL_BEGIN<'input, F: ParserFactory>(input: &'input str,
+                                                  state: &mut ParseState<'input>,
+                                                  pos: usize)
+                                                  -> RuleResult<()> {
+    slice_eq(input, state, pos, "%{?")
+}
 fn parse_PARSER_END<'input, F: ParserFactory>(input: &'input str,
                                               state: &mut ParseState<'input>,
                                               pos: usize)
@@ -868,11 +3502,70 @@ fn parse_parser_name<'input, F: ParserFactory>(input: &'input str,
                                     })
                                 }
                             }
-                            Failed => Failed,
+                            Failed => Failed,
+                        }
+                    }
+                }
+                Failed => Failed,
+            }
+        }
+    }
+}
+fn parse_parser_optional_name<'input, F: ParserFactory>(input: &'input str,
+                                                        state: &mut ParseState<'input>,
+                                                        pos: usize)
+                                                        -> RuleResult<Option<&'input str>> {
+    {
+        let choice_res = {
+            let start_pos = pos;
+            {
+                let seq_res = slice_eq(input, state, pos, ":");
+                match seq_res {
+                    Matched(pos, _) => {
+                        {
+                            let seq_res = match parse_identifier::<F>(input, state, pos) {
+                                Matched(newpos, value) => {
+                                    Matched(newpos, Some(value))
+                                }
+                                Failed => {
+                                    Matched(pos, None)
+                                }
+                            };
+                            match seq_res {
+                                Matched(pos, name) => {
+                                    {
+                                        let match_str = &input[start_pos..pos];
+                                        Matched(pos, {
+                                            name
+                                        })
+                                    }
+                                }
+                                Failed => Failed,
+                            }
                         }
                     }
+                    Failed => Failed,
+                }
+            }
+        };
+        match choice_res {
+            Matched(pos, value) => Matched(pos, value),
+            Failed => {
+                let start_pos = pos;
+                {
+                    let seq_res = slice_eq(input, state, pos, "");
+                    match seq_res {
+                        Matched(pos, _) => {
+                            {
+                                let match_str = &input[start_pos..pos];
+                                Matched(pos, {
+                                    None
+                                })
+                            }
+                        }
+                        Failed => Failed,
+                    }
                 }
-                Failed => Failed,
             }
         }
     }
@@ -1003,6 +3696,306 @@ fn parse_string<'input, F: ParserFactory>(input: &'input str,
         }
     }
 }
+fn parse_escaped_string<'input, F: ParserFactory>(input: &'input str,
+                                                  state: &mut ParseState<'input>,
+                                                  pos: usize)
+                                                  -> RuleResult<String> {
+    {
+        let start_pos = pos;
+        {
+            let seq_res = slice_eq(input, state, pos, "\"");
+            match seq_res {
+                Matched(pos, _) => {
+                    {
+                        let seq_res = {
+                            let mut repeat_pos = pos;
+                            let mut repeat_value = vec![];
+                            loop {
+                                let pos = repeat_pos;
+                                let step_res = parse_escaped_string_char::<F>(input, state, pos);
+                                match step_res {
+                                    Matched(newpos, value) => {
+                                        repeat_pos = newpos;
+                                        repeat_value.push(value);
+                                    }
+                                    Failed => {
+                                        break;
+                                    }
+                                }
+                            }
+                            Matched(repeat_pos, repeat_value)
+                        };
+                        match seq_res {
+                            Matched(pos, chars) => {
+                                {
+                                    let seq_res = slice_eq(input, state, pos, "\"");
+                                    match seq_res {
+                                        Matched(pos, _) => {
+                                            {
+                                                let match_str = &input[start_pos..pos];
+                                                Matched(pos, {
+                                                    chars.concat()
+                                                })
+                                            }
+                                        }
+                                        Failed => Failed,
+                                    }
+                                }
+                            }
+                            Failed => Failed,
+                        }
+                    }
+                }
+                Failed => Failed,
+            }
+        }
+    }
+}
+fn parse_escaped_string_char<'input, F: ParserFactory>(input: &'input str,
+                                                       state: &mut ParseState<'input>,
+                                                       pos: usize)
+                                                       -> RuleResult<&'input str> {
+    {
+        let choice_res = {
+            let start_pos = pos;
+            {
+                let seq_res = slice_eq(input, state, pos, "\\");
+                match seq_res {
+                    Matched(pos, _) => {
+                        {
+                            let seq_res = parse_escaped_char::<F>(input, state, pos);
+                            match seq_res {
+                                Matched(pos, c) => {
+                                    {
+                                        let match_str = &input[start_pos..pos];
+                                        Matched(pos, {
+                                            c
+                                        })
+                                    }
+                                }
+                                Failed => Failed,
+                            }
+                        }
+                    }
+                    Failed => Failed,
+                }
+            }
+        };
+        match choice_res {
+            Matched(pos, value) => Matched(pos, value),
+            Failed => {
+                let start_pos = pos;
+                {
+                    let seq_res = {
+                        let seq_res = {
+                            let assert_res = slice_eq(input, state, pos, "\"");
+                            match assert_res {
+                                Failed => Matched(pos, ()),
+                                Matched(..) => Failed,
+                            }
+                        };
+                        match seq_res {
+                            Matched(pos, _) => {
+                                {
+                                    let seq_res = {
+                                        let assert_res = slice_eq(input, state, pos, "\\");
+                                        match assert_res {
+                                            Failed => Matched(pos, ()),
+                                            Matched(..) => Failed,
+                                        }
+                                    };
+                                    match seq_res {
+                                        Matched(pos, _) => {
+                                            any_char(input, state, pos)
+                                        }
+                                        Failed => Failed,
+                                    }
+                                }
+                            }
+                            Failed => Failed,
+                        }
+                    };
+                    match seq_res {
+                        Matched(pos, _) => {
+                            {
+                                let match_str = &input[start_pos..pos];
+                                Matched(pos, {
+                                    match_str
+                                })
+                            }
+                        }
+                        Failed => Failed,
+                    }
+                }
+            }
+        }
+    }
+}
+fn parse_regex_string<'input, F: ParserFactory>(input: &'input str,
+                                                state: &mut ParseState<'input>,
+                                                pos: usize)
+                                                -> RuleResult<String> {
+    {
+        let start_pos = pos;
+        {
+            let seq_res = slice_eq(input, state, pos, "\"");
+            match seq_res {
+                Matched(pos, _) => {
+                    {
+                        let seq_res = {
+                            let mut repeat_pos = pos;
+                            let mut repeat_value = vec![];
+                            loop {
+                                let pos = repeat_pos;
+                                let step_res = parse_regex_string_char::<F>(input, state, pos);
+                                match step_res {
+                                    Matched(newpos, value) => {
+                                        repeat_pos = newpos;
+                                        repeat_value.push(value);
+                                    }
+                                    Failed => {
+                                        break;
+                                    }
+                                }
+                            }
+                            Matched(repeat_pos, repeat_value)
+                        };
+                        match seq_res {
+                            Matched(pos, parts) => {
+                                {
+                                    let seq_res = slice_eq(input, state, pos, "\"");
+                                    match seq_res {
+                                        Matched(pos, _) => {
+                                            {
+                                                let match_str = &input[start_pos..pos];
+                                                Matched(pos, {
+                                                    parts.concat()
+                                                })
+                                            }
+                                        }
+                                        Failed => Failed,
+                                    }
+                                }
+                            }
+                            Failed => Failed,
+                        }
+                    }
+                }
+                Failed => Failed,
+            }
+        }
+    }
+}
+fn parse_regex_string_char<'input, F: ParserFactory>(input: &'input str,
+                                                     state: &mut ParseState<'input>,
+                                                     pos: usize)
+                                                     -> RuleResult<&'input str> {
+    {
+        let choice_res = {
+            let start_pos = pos;
+            {
+                let seq_res = slice_eq(input, state, pos, "\\\"");
+                match seq_res {
+                    Matched(pos, _) => {
+                        {
+                            let match_str = &input[start_pos..pos];
+                            Matched(pos, {
+                                "\""
+                            })
+                        }
+                    }
+                    Failed => Failed,
+                }
+            }
+        };
+        match choice_res {
+            Matched(pos, value) => Matched(pos, value),
+            Failed => {
+                let choice_res = {
+                    let start_pos = pos;
+                    {
+                        let seq_res = slice_eq(input, state, pos, "\\");
+                        match seq_res {
+                            Matched(pos, _) => {
+                                {
+                                    let seq_res = any_char(input, state, pos);
+                                    match seq_res {
+                                        Matched(pos, _) => {
+                                            {
+                                                let match_str = &input[start_pos..pos];
+                                                Matched(pos, {
+                                                    match_str
+                                                })
+                                            }
+                                        }
+                                        Failed => Failed,
+                                    }
+                                }
+                            }
+                            Failed => Failed,
+                        }
+                    }
+                };
+                match choice_res {
+                    Matched(pos, value) => Matched(pos, value),
+                    Failed => {
+                        let start_pos = pos;
+                        {
+                            let seq_res = {
+                                let seq_res = {
+                                    let assert_res = slice_eq(input, state, pos, "\"");
+                                    match assert_res {
+                                        Failed => Matched(pos, ()),
+                                        Matched(..) => Failed,
+                                    }
+                                };
+                                match seq_res {
+                                    Matched(pos, _) => {
+                                        any_char(input, state, pos)
+                                    }
+                                    Failed => Failed,
+                                }
+                            };
+                            match seq_res {
+                                Matched(pos, _) => {
+                                    {
+                                        let match_str = &input[start_pos..pos];
+                                        Matched(pos, {
+                                            match_str
+                                        })
+                                    }
+                                }
+                                Failed => Failed,
+                            }
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
+fn parse_escaped_char<'input, F: ParserFactory>(input: &'input str,
+                                                state: &mut ParseState<'input>,
+                                                pos: usize)
+                                                -> RuleResult<&'input str> {
+    {
+        let start_pos = pos;
+        {
+            let seq_res = any_char(input, state, pos);
+            match seq_res {
+                Matched(pos, _) => {
+                    {
+                        let match_str = &input[start_pos..pos];
+                        Matched(pos, {
+                            match_str
+                        })
+                    }
+                }
+                Failed => Failed,
+            }
+        }
+    }
+}
 fn parse_literal<'input, F: ParserFactory>(input: &'input str,
                                            state: &mut ParseState<'input>,
                                            pos: usize)
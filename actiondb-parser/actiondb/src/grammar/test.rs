@@ -1,5 +1,8 @@
-use matcher::compiled_pattern::TokenType;
-use parsers::{SetParser, Parser, IntParser, GreedyParser, HasLengthConstraint};
+use matcher::compiled_pattern::{self, TokenType};
+use parsers::{SetParser, Parser, IntParser, GreedyParser, HasLengthConstraint, Ipv4Parser, Ipv6Parser,
+              IpAddrParser, QStringParser, EStringParser, FloatParser, NumberParser, HexParser,
+              TimestampParser, MacAddrParser, HostnameParser, EmailParser, KeyValuePairsParser,
+              JsonParser, RegexParser};
 
 fn assert_parser_name_equals(item: Option<&TokenType>, expected_name: Option<&str>) {
     if let Some(&TokenType::Parser(ref parser)) = item {
@@ -112,6 +115,19 @@ fn test_given_set_parser_with_character_set_parameter_when_we_parse_it_then_we_g
     assert_parser_equals(vec.get(0), &expected_parser);
 }
 
+#[test]
+fn test_given_set_parser_when_its_character_set_contains_backslashes_then_they_are_part_of_the_set() {
+    let expected_parser = SetParser::from_str("test_set", r"a\b");
+    let vec = ::grammar::parser::pattern(r#"%{SET("a\b"):test_set}"#).ok().unwrap();
+    assert_eq!(vec.len(), 1);
+    assert_parser_equals(vec.get(0), &expected_parser);
+}
+
+#[test]
+fn test_given_set_parser_with_empty_character_set_when_we_parse_it_then_we_get_an_error() {
+    assert!(::grammar::parser::pattern(r#"%{SET(""):test_set}"#).is_err());
+}
+
 #[test]
 fn test_given_set_parser_with_optional_parameters_when_we_parse_it_then_we_get_the_right_parser
                                                                                                 () {
@@ -210,3 +226,269 @@ fn test_given_valid_pattern_when_it_does_not_have_a_name_then_we_can_parse_the_p
     println!("{:?}", &vec);
     assert_parser_name_equals(vec.get(0), None);
 }
+
+#[test]
+fn test_given_ip_address_parsers_when_we_parse_them_then_we_get_the_right_parsers() {
+    let vec = ::grammar::parser::pattern("from %{IPV4:src} to %{IPV6:dst} via %{IPADDR:gw}")
+                  .expect("Failed to parse a pattern with IP address parsers");
+
+    assert_eq!(vec.len(), 6);
+    assert_parser_equals(vec.get(1), &Ipv4Parser::with_name("src"));
+    assert_parser_equals(vec.get(3), &Ipv6Parser::with_name("dst"));
+    assert_parser_equals(vec.get(5), &IpAddrParser::with_name("gw"));
+}
+
+#[test]
+fn test_given_ip_address_parser_when_it_does_not_have_a_name_then_we_can_parse_it() {
+    let vec = ::grammar::parser::pattern("%{IPADDR}").expect("Failed to parse an unnamed IPADDR parser");
+    assert_parser_name_equals(vec.get(0), None);
+}
+
+#[test]
+fn test_given_qstring_parser_with_escaped_quotes_parameter_when_we_parse_it_then_we_get_the_right_parser
+    () {
+    let vec = ::grammar::parser::pattern(r#"%{QSTRING(quotes="\"'"):name}"#)
+                  .expect("Failed to parse a QSTRING parser with a quotes parameter");
+    let mut expected = QStringParser::with_name("name");
+    expected.set_quotes("\"'");
+
+    assert_eq!(vec.len(), 1);
+    assert_parser_equals(vec.get(0), &expected);
+    if let Some(&TokenType::Parser(ref parser)) = vec.get(0) {
+        assert_eq!(parser.parse("\"foo 'bar\" baz").unwrap().value(), "foo 'bar");
+        assert_eq!(parser.parse("'foo \"bar' baz").unwrap().value(), "foo \"bar");
+    }
+}
+
+#[test]
+fn test_given_qstring_parser_with_pairs_parameter_when_we_parse_it_then_only_the_pairs_are_accepted() {
+    let vec = ::grammar::parser::pattern(r#"%{QSTRING(pairs="<>[]"):name}"#)
+                  .expect("Failed to parse a QSTRING parser with a pairs parameter");
+    let mut expected = QStringParser::with_name("name");
+    expected.set_quotes("");
+    expected.set_pairs("<>[]");
+
+    assert_eq!(vec.len(), 1);
+    assert_parser_equals(vec.get(0), &expected);
+    if let Some(&TokenType::Parser(ref parser)) = vec.get(0) {
+        assert_eq!(parser.parse("[foo] baz").unwrap().value(), "foo");
+        assert!(parser.parse("\"foo\" baz").is_none());
+    }
+}
+
+#[test]
+fn test_given_estring_parser_with_end_parameter_when_we_parse_it_then_we_get_the_right_parser() {
+    let vec = ::grammar::parser::pattern(r#"key=%{ESTRING(end=";"):value} %{QSTRING}"#)
+                  .expect("Failed to parse an ESTRING parser with an end parameter");
+
+    assert_eq!(vec.len(), 4);
+    assert_parser_equals(vec.get(1), &EStringParser::from_str("value", ";"));
+    assert_parser_equals(vec.get(3), &QStringParser::new());
+}
+
+#[test]
+fn test_given_estring_parser_when_its_string_parameter_is_not_terminated_then_we_get_an_error() {
+    let res = ::grammar::parser::pattern(r#"%{ESTRING(end=";):value}"#);
+    assert_eq!(res.is_err(), true);
+}
+
+#[test]
+fn test_given_estring_parser_when_its_end_string_is_empty_then_we_get_an_error() {
+    let error = ::grammar::parser::pattern(r#"%{ESTRING(end=""):value}"#)
+                    .err()
+                    .expect("Successfully parsed an ESTRING parser with an empty end string");
+    assert_eq!(error.expected.contains("non-empty end string"), true);
+}
+
+#[test]
+fn test_given_estring_parser_when_it_has_no_end_string_then_we_get_an_error() {
+    for pattern in &[r#"%{ESTRING:value}"#, r#"%{ESTRING}"#, r#"%{ESTRING():value}"#] {
+        let _ = ::grammar::parser::pattern(pattern)
+                    .err()
+                    .expect("Successfully parsed an ESTRING parser without an end string");
+    }
+}
+
+#[test]
+fn test_given_qstring_parser_when_its_quotes_are_empty_then_we_get_an_error() {
+    let error = ::grammar::parser::pattern(r#"%{QSTRING(quotes=""):value}"#)
+                    .err()
+                    .expect("Successfully parsed a QSTRING parser with empty quotes");
+    assert_eq!(error.expected.contains("non-empty quotes"), true);
+}
+
+#[test]
+fn test_given_qstring_parser_when_its_pairs_are_not_pairs_then_we_get_an_error() {
+    for pattern in &[r#"%{QSTRING(pairs=""):value}"#, r#"%{QSTRING(pairs="<>'"):value}"#] {
+        let error = ::grammar::parser::pattern(pattern)
+                        .err()
+                        .expect("Successfully parsed a QSTRING parser with unpaired quotes");
+        assert_eq!(error.expected.contains("pairs of opening and closing quotes"), true);
+    }
+}
+
+#[test]
+fn test_given_number_parsers_with_length_constraints_when_we_parse_them_then_we_get_the_right_parsers
+    () {
+    let vec = ::grammar::parser::pattern("%{FLOAT(max_len=8):latency} %{NUMBER:bytes} %{HEX(min_len=2)}")
+                  .expect("Failed to parse a pattern with number parsers");
+    let mut float = FloatParser::with_name("latency");
+    float.set_max_length(Some(8));
+    let mut hex = HexParser::new();
+    hex.set_min_length(Some(2));
+
+    assert_eq!(vec.len(), 5);
+    assert_parser_equals(vec.get(0), &float);
+    assert_parser_equals(vec.get(2), &NumberParser::with_name("bytes"));
+    assert_parser_equals(vec.get(4), &hex);
+}
+
+#[test]
+fn test_given_timestamp_parser_with_format_and_preset_when_we_parse_it_then_the_formats_are_collected
+    () {
+    let pattern = r#"%{TIMESTAMP(format="%d/%b/%Y:%H:%M:%S %z", preset="epoch"):ts}"#;
+    let vec = ::grammar::parser::pattern(pattern)
+                  .expect("Failed to parse a TIMESTAMP parser with format and preset parameters");
+    let mut expected = TimestampParser::with_name("ts");
+    expected.set_formats(vec!["%d/%b/%Y:%H:%M:%S %z".to_owned(),
+                              "%s".to_owned(),
+                              "%s.%f".to_owned()]);
+
+    assert_eq!(vec.len(), 1);
+    assert_parser_equals(vec.get(0), &expected);
+}
+
+#[test]
+fn test_given_timestamp_parser_without_parameters_when_we_parse_it_then_it_uses_the_iso8601_preset
+    () {
+    let vec = ::grammar::parser::pattern("%{TIMESTAMP}").expect("Failed to parse a TIMESTAMP parser");
+    assert_parser_equals(vec.get(0), &TimestampParser::new());
+}
+
+#[test]
+fn test_given_timestamp_parser_when_its_format_contains_escaped_percent_signs_then_they_are_literals() {
+    for &(pattern, format) in &[(r#"%{TIMESTAMP(format="\%Y"):ts}"#, "%%Y"),
+                                (r#"%{TIMESTAMP(format="%H:%M\%"):ts}"#, "%H:%M%%")] {
+        let vec = ::grammar::parser::pattern(pattern)
+                      .expect("Failed to parse a TIMESTAMP parser with an escaped percent sign");
+        let mut expected = TimestampParser::with_name("ts");
+        expected.set_formats(vec![format.to_owned()]);
+        assert_parser_equals(vec.get(0), &expected);
+    }
+    let vec = ::grammar::parser::pattern(r#"%{TIMESTAMP(format="\%Y"):ts}"#).unwrap();
+    if let Some(&TokenType::Parser(ref parser)) = vec.get(0) {
+        assert_eq!(parser.parse("%Y").unwrap().value(), "%Y");
+        assert!(parser.parse("2016").is_none());
+    } else {
+        unreachable!();
+    }
+}
+
+#[test]
+fn test_given_timestamp_parser_when_its_format_or_preset_is_invalid_then_we_get_an_error() {
+    assert_eq!(::grammar::parser::pattern(r#"%{TIMESTAMP(format="%Q")}"#).is_err(), true);
+    assert_eq!(::grammar::parser::pattern(r#"%{TIMESTAMP(preset="RFC5424")}"#).is_err(), true);
+    assert_eq!(::grammar::parser::pattern(r#"%{TIMESTAMP(format="")}"#).is_err(), true);
+}
+
+#[test]
+fn test_given_network_parsers_when_we_parse_them_then_we_get_the_right_parsers() {
+    let vec = ::grammar::parser::pattern("%{MACADDR:mac} %{HOSTNAME:host} %{EMAIL}")
+                  .expect("Failed to parse a pattern with MACADDR, HOSTNAME and EMAIL parsers");
+
+    assert_eq!(vec.len(), 5);
+    assert_parser_equals(vec.get(0), &MacAddrParser::with_name("mac"));
+    assert_parser_equals(vec.get(2), &HostnameParser::with_name("host"));
+    assert_parser_equals(vec.get(4), &EmailParser::new());
+}
+
+#[test]
+fn test_given_kvpairs_parser_when_its_name_is_left_empty_then_we_can_parse_it() {
+    let vec = ::grammar::parser::pattern(r#"msg: %{KVPAIRS(separator=":", delimiter=";", prefix="kv."):}"#)
+                  .expect("Failed to parse a KVPAIRS parser with an empty name");
+    let mut expected = KeyValuePairsParser::new();
+    expected.set_separator(":".to_owned());
+    expected.set_delimiter(";".to_owned());
+    expected.set_prefix("kv.".to_owned());
+
+    assert_eq!(vec.len(), 2);
+    assert_parser_equals(vec.get(1), &expected);
+    assert_parser_name_equals(vec.get(1), None);
+}
+
+#[test]
+fn test_given_json_parser_with_prefix_when_we_parse_it_then_we_get_the_right_parser() {
+    let vec = ::grammar::parser::pattern(r#"request done: %{JSON(prefix="req."):}"#)
+                  .expect("Failed to parse a JSON parser with a prefix");
+    let mut expected = JsonParser::new();
+    expected.set_prefix("req.".to_owned());
+
+    assert_eq!(vec.len(), 2);
+    assert_parser_equals(vec.get(1), &expected);
+}
+
+#[test]
+fn test_given_regex_parser_when_we_parse_it_then_the_backslashes_in_the_expression_are_kept() {
+    let vec = ::grammar::parser::pattern(r#"ticket %{REGEX("[A-Z]{3}-\d{4}\"?"):ticket} closed"#)
+                  .expect("Failed to parse a REGEX parser");
+    let expected = RegexParser::from_str("ticket", r#"[A-Z]{3}-\d{4}"?"#).unwrap();
+
+    assert_eq!(vec.len(), 3);
+    assert_parser_equals(vec.get(1), &expected);
+}
+
+#[test]
+fn test_given_regex_parser_when_its_expression_is_invalid_then_we_get_an_error() {
+    let err = ::grammar::parser::pattern(r#"ticket %{REGEX("[A-Z"):ticket}"#)
+                  .err()
+                  .expect("Failed to get an error for an invalid regular expression");
+    assert_eq!(err.expected.contains("valid regular expression"), true);
+    assert_eq!(err.regex_error.expect("Failed to get the error of the regular expression").contains("unclosed"),
+               true);
+}
+
+#[test]
+fn test_given_pattern_with_optional_group_when_we_parse_it_then_the_group_contains_its_tokens() {
+    let vec = ::grammar::parser::pattern(r#"sshd%{? (pid %{INT:pid})}: session closed"#)
+                  .expect("Failed to parse a pattern with an optional group");
+
+    assert_eq!(vec.len(), 3);
+    if let Some(&TokenType::Optional(ref group)) = vec.get(1) {
+        assert_eq!(group.len(), 3);
+        assert_literal_equals(group.get(0), " (pid ");
+        assert_parser_equals(group.get(1), &IntParser::with_name("pid"));
+        assert_literal_equals(group.get(2), ")");
+    } else {
+        unreachable!();
+    }
+    assert_literal_equals(vec.get(2), ": session closed");
+}
+
+#[test]
+fn test_given_optional_group_when_it_contains_an_escaped_closing_brace_then_it_is_unescaped() {
+    let vec = ::grammar::parser::pattern(r#"a%{? {b\}}"#).expect("Failed to parse an optional group");
+    let variants = compiled_pattern::expand(vec);
+
+    assert_eq!(variants.len(), 2);
+    assert_literal_equals(variants[1].get(0), "a {b}");
+}
+
+#[test]
+fn test_given_pattern_with_literal_alternatives_when_we_parse_it_then_we_get_every_alternative() {
+    let vec = ::grammar::parser::pattern(r#"%{ANY("accepted", "Accepted")} publickey"#)
+                  .expect("Failed to parse literal alternatives");
+
+    assert_eq!(vec.len(), 2);
+    if let Some(&TokenType::Alternatives(ref literals)) = vec.get(0) {
+        assert_eq!(literals, &["accepted".to_owned(), "Accepted".to_owned()]);
+    } else {
+        unreachable!();
+    }
+}
+
+#[test]
+fn test_given_optional_group_when_it_is_not_closed_or_contains_greedy_then_we_get_an_error() {
+    assert_eq!(::grammar::parser::pattern("a%{? b").is_err(), true);
+    assert_eq!(::grammar::parser::pattern("a%{? %{GREEDY:b} c}").is_err(), true);
+    assert_eq!(::grammar::parser::pattern("a %{ANY()}").is_err(), true);
+}
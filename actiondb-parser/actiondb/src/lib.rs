@@ -8,6 +8,10 @@ extern crate uuid;
 extern crate serde;
 extern crate serde_json;
 extern crate serde_yaml;
+extern crate yaml_rust;
+extern crate regex;
+extern crate rand;
+extern crate xml;
 
 pub mod parsers;
 pub mod utils;
@@ -0,0 +1,198 @@
+use uuid::Uuid;
+
+use super::Error;
+
+use std::str;
+
+// Every number is written in little endian byte order, the lengths of the
+// strings and the collections precede them.
+#[derive(Default)]
+pub struct Encoder {
+    buffer: Vec<u8>,
+}
+
+impl Encoder {
+    pub fn new() -> Encoder {
+        Encoder::default()
+    }
+
+    pub fn into_bytes(self) -> Vec<u8> {
+        self.buffer
+    }
+
+    pub fn write_bytes(&mut self, bytes: &[u8]) {
+        self.write_usize(bytes.len());
+        self.buffer.extend_from_slice(bytes);
+    }
+
+    pub fn write_raw(&mut self, bytes: &[u8]) {
+        self.buffer.extend_from_slice(bytes);
+    }
+
+    pub fn write_bool(&mut self, value: bool) {
+        self.buffer.push(value as u8);
+    }
+
+    pub fn write_u32(&mut self, value: u32) {
+        for i in 0..4 {
+            self.buffer.push((value >> (i * 8)) as u8);
+        }
+    }
+
+    pub fn write_u64(&mut self, value: u64) {
+        for i in 0..8 {
+            self.buffer.push((value >> (i * 8)) as u8);
+        }
+    }
+
+    pub fn write_i32(&mut self, value: i32) {
+        self.write_u32(value as u32);
+    }
+
+    pub fn write_usize(&mut self, value: usize) {
+        self.write_u64(value as u64);
+    }
+
+    pub fn write_str(&mut self, value: &str) {
+        self.write_bytes(value.as_bytes());
+    }
+
+    pub fn write_opt_str(&mut self, value: Option<&str>) {
+        self.write_bool(value.is_some());
+        if let Some(value) = value {
+            self.write_str(value);
+        }
+    }
+
+    pub fn write_opt_usize(&mut self, value: Option<usize>) {
+        self.write_bool(value.is_some());
+        if let Some(value) = value {
+            self.write_usize(value);
+        }
+    }
+
+    pub fn write_uuid(&mut self, uuid: &Uuid) {
+        self.write_raw(uuid.as_bytes());
+    }
+}
+
+pub struct Decoder<'a> {
+    buffer: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> Decoder<'a> {
+    pub fn new(buffer: &'a [u8]) -> Decoder<'a> {
+        Decoder {
+            buffer: buffer,
+            pos: 0,
+        }
+    }
+
+    pub fn is_at_end(&self) -> bool {
+        self.pos == self.buffer.len()
+    }
+
+    pub fn read_raw(&mut self, len: usize) -> Result<&'a [u8], Error> {
+        if self.buffer.len() - self.pos < len {
+            return Err(Error::InvalidFormat("unexpected end of file"));
+        }
+        let bytes = &self.buffer[self.pos..self.pos + len];
+        self.pos += len;
+        Ok(bytes)
+    }
+
+    pub fn read_bytes(&mut self) -> Result<&'a [u8], Error> {
+        let len = try!(self.read_usize());
+        self.read_raw(len)
+    }
+
+    pub fn read_bool(&mut self) -> Result<bool, Error> {
+        match try!(self.read_raw(1))[0] {
+            0 => Ok(false),
+            1 => Ok(true),
+            _ => Err(Error::InvalidFormat("invalid boolean value")),
+        }
+    }
+
+    pub fn read_u32(&mut self) -> Result<u32, Error> {
+        let bytes = try!(self.read_raw(4));
+        Ok(bytes.iter().rev().fold(0, |value, byte| (value << 8) | *byte as u32))
+    }
+
+    pub fn read_u64(&mut self) -> Result<u64, Error> {
+        let bytes = try!(self.read_raw(8));
+        Ok(bytes.iter().rev().fold(0, |value, byte| (value << 8) | *byte as u64))
+    }
+
+    pub fn read_i32(&mut self) -> Result<i32, Error> {
+        self.read_u32().map(|value| value as i32)
+    }
+
+    pub fn read_usize(&mut self) -> Result<usize, Error> {
+        self.read_u64().map(|value| value as usize)
+    }
+
+    pub fn read_str(&mut self) -> Result<&'a str, Error> {
+        let bytes = try!(self.read_bytes());
+        str::from_utf8(bytes).map_err(|_| Error::InvalidFormat("invalid UTF-8 string"))
+    }
+
+    pub fn read_string(&mut self) -> Result<String, Error> {
+        self.read_str().map(|value| value.to_owned())
+    }
+
+    pub fn read_opt_string(&mut self) -> Result<Option<String>, Error> {
+        if try!(self.read_bool()) {
+            self.read_string().map(Some)
+        } else {
+            Ok(None)
+        }
+    }
+
+    pub fn read_opt_usize(&mut self) -> Result<Option<usize>, Error> {
+        if try!(self.read_bool()) {
+            self.read_usize().map(Some)
+        } else {
+            Ok(None)
+        }
+    }
+
+    pub fn read_uuid(&mut self) -> Result<Uuid, Error> {
+        let bytes = try!(self.read_raw(16));
+        Uuid::from_bytes(bytes).map_err(|_| Error::InvalidFormat("invalid uuid"))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{Encoder, Decoder};
+    use uuid::Uuid;
+
+    #[test]
+    fn test_given_encoder_when_values_are_written_then_the_decoder_reads_them_back() {
+        let uuid = Uuid::new_v4();
+        let mut encoder = Encoder::new();
+        encoder.write_u32(0xdeadbeef);
+        encoder.write_i32(-5);
+        encoder.write_u64(0xfffffffffffffffe);
+        encoder.write_str("árvíztűrő");
+        encoder.write_opt_str(None);
+        encoder.write_opt_usize(Some(42));
+        encoder.write_bool(true);
+        encoder.write_uuid(&uuid);
+        let bytes = encoder.into_bytes();
+
+        let mut decoder = Decoder::new(&bytes);
+        assert_eq!(decoder.read_u32().unwrap(), 0xdeadbeef);
+        assert_eq!(decoder.read_i32().unwrap(), -5);
+        assert_eq!(decoder.read_u64().unwrap(), 0xfffffffffffffffe);
+        assert_eq!(decoder.read_str().unwrap(), "árvíztűrő");
+        assert_eq!(decoder.read_opt_string().unwrap(), None);
+        assert_eq!(decoder.read_opt_usize().unwrap(), Some(42));
+        assert_eq!(decoder.read_bool().unwrap(), true);
+        assert_eq!(decoder.read_uuid().unwrap(), uuid);
+        assert_eq!(decoder.is_at_end(), true);
+        assert_eq!(decoder.read_u32().is_err(), true);
+    }
+}
@@ -0,0 +1,74 @@
+use std::fmt;
+use std::error;
+use std::io;
+
+#[derive(Debug)]
+pub enum Error {
+    Io(io::Error),
+    InvalidFormat(&'static str),
+    UnsupportedVersion(u32),
+    UnknownParser(String),
+    InvalidParser(String),
+    // The source file or the files of a directory or a glob have changed
+    // since the cache was compiled.
+    Stale(String),
+    // The source file can't be checked for changes.
+    UnreadableSource(String, io::Error),
+    NotSupported,
+}
+
+impl From<io::Error> for Error {
+    fn from(error: io::Error) -> Error {
+        Error::Io(error)
+    }
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> Result<(), fmt::Error> {
+        match *self {
+            Error::Io(ref error) => error.fmt(formatter),
+            Error::InvalidFormat(reason) => write!(formatter, "Invalid matcher cache: {}", reason),
+            Error::UnsupportedVersion(version) => {
+                write!(formatter, "Unsupported matcher cache version: version={}", version)
+            }
+            Error::UnknownParser(ref kind) => write!(formatter, "Unknown parser in matcher cache: kind={}", kind),
+            Error::InvalidParser(ref error) => write!(formatter, "Invalid parser in matcher cache: {}", error),
+            Error::Stale(ref source) => {
+                write!(formatter,
+                       "The matcher cache is older than its source file, please compile it again: \
+                        source={}",
+                       source)
+            }
+            Error::UnreadableSource(ref source, ref error) => {
+                write!(formatter,
+                       "The source file of the matcher cache can't be read, please compile it again: \
+                        source={} error={}",
+                       source,
+                       error)
+            }
+            Error::NotSupported => formatter.write_str("The matcher doesn't support caching"),
+        }
+    }
+}
+
+impl error::Error for Error {
+    fn description(&self) -> &str {
+        match *self {
+            Error::Io(ref error) => error.description(),
+            Error::InvalidFormat(_) => "Invalid matcher cache",
+            Error::UnsupportedVersion(_) => "Unsupported matcher cache version",
+            Error::UnknownParser(_) => "Unknown parser in matcher cache",
+            Error::InvalidParser(_) => "Invalid parser in matcher cache",
+            Error::Stale(_) => "The matcher cache is older than its source file",
+            Error::UnreadableSource(..) => "The source file of the matcher cache can't be read",
+            Error::NotSupported => "The matcher doesn't support caching",
+        }
+    }
+
+    fn cause(&self) -> Option<&error::Error> {
+        match *self {
+            Error::Io(ref error) => error.cause(),
+            _ => None,
+        }
+    }
+}
@@ -0,0 +1,214 @@
+use uuid::Uuid;
+
+use matcher::Pattern;
+use matcher::pattern_loader::{self, Sources};
+
+use std::collections::BTreeMap;
+use std::env;
+use std::fs::{self, File};
+use std::io::{self, Read, Write};
+use std::path::Path;
+
+pub use self::codec::{Encoder, Decoder};
+pub use self::error::Error;
+
+mod codec;
+mod error;
+
+pub const EXTENSION: &'static str = "adbc";
+pub const VERSION: u32 = 1;
+const MAGIC: &'static [u8] = b"ADBCACHE";
+
+// A matcher which can be written into a precompiled cache file, so it can be
+// loaded without compiling its patterns and checking their test messages.
+pub trait Cacheable: Sized {
+    fn encode(&self, encoder: &mut Encoder);
+    fn decode(decoder: &mut Decoder) -> Result<Self, Error>;
+}
+
+pub fn is_cache_file(path: &str) -> bool {
+    Path::new(path).extension().map_or(false, |extension| extension == EXTENSION)
+}
+
+// 64 bit FNV-1a, its value doesn't depend on the version of the compiler or
+// the standard library, so the checksums of the older caches remain valid.
+pub fn checksum(content: &[u8]) -> u64 {
+    content.iter().fold(0xcbf29ce484222325, |hash, byte| (hash ^ *byte as u64).wrapping_mul(0x100000001b3))
+}
+
+fn read(path: &str) -> io::Result<Vec<u8>> {
+    let mut buffer = Vec::new();
+    let mut file = try!(File::open(path));
+    try!(file.read_to_end(&mut buffer));
+    Ok(buffer)
+}
+
+fn canonical_path(path: &Path) -> String {
+    fs::canonicalize(path)
+        .ok()
+        .and_then(|path| path.to_str().map(|path| path.to_owned()))
+        .unwrap_or_else(|| path.to_string_lossy().into_owned())
+}
+
+// The globs can't be canonicalized, so the relative paths are only joined to
+// the current directory.
+fn absolute_path(path: &str) -> String {
+    env::current_dir()
+        .ok()
+        .and_then(|directory| directory.join(path).to_str().map(|path| path.to_owned()))
+        .unwrap_or_else(|| path.to_owned())
+}
+
+// The expanded paths (the given path and the includes) with the files they
+// were expanded to and the checksums of the loaded files are stored in the
+// cache. When the cache is loaded, the paths are expanded again, so the files
+// which were added to a directory or a glob since then are detected too.
+pub fn compile<M: Cacheable>(matcher: &M, sources: &Sources, cache_path: &str) -> Result<(), Error> {
+    let mut encoder = Encoder::new();
+    encoder.write_raw(MAGIC);
+    encoder.write_u32(VERSION);
+    encoder.write_usize(sources.inputs.len());
+    for &(ref input, ref file_paths) in &sources.inputs {
+        encoder.write_str(&absolute_path(input));
+        encoder.write_usize(file_paths.len());
+        for file_path in file_paths {
+            encoder.write_str(&canonical_path(file_path));
+        }
+    }
+    encoder.write_usize(sources.files.len());
+    for &(ref source_path, _) in &sources.files {
+        let source = try!(read(source_path));
+        encoder.write_str(&canonical_path(Path::new(source_path)));
+        encoder.write_usize(source.len());
+        encoder.write_u64(checksum(&source));
+    }
+    matcher.encode(&mut encoder);
+
+    let mut file = try!(File::create(cache_path));
+    try!(file.write_all(&encoder.into_bytes()));
+    Ok(())
+}
+
+pub fn load<M: Cacheable>(cache_path: &str) -> Result<M, Error> {
+    let cache = try!(read(cache_path));
+    let mut decoder = Decoder::new(&cache);
+
+    if try!(decoder.read_raw(MAGIC.len())) != MAGIC {
+        return Err(Error::InvalidFormat("missing header"));
+    }
+    let version = try!(decoder.read_u32());
+    if version != VERSION {
+        return Err(Error::UnsupportedVersion(version));
+    }
+    for _ in 0..try!(decoder.read_usize()) {
+        let input = try!(decoder.read_string());
+        let mut file_paths = Vec::new();
+        for _ in 0..try!(decoder.read_usize()) {
+            file_paths.push(try!(decoder.read_string()));
+        }
+        let expanded = pattern_loader::expand(&input)
+                           .map(|paths| paths.iter().map(|path| canonical_path(path)).collect::<Vec<String>>());
+        if expanded.ok() != Some(file_paths) {
+            return Err(Error::Stale(input));
+        }
+    }
+    for _ in 0..try!(decoder.read_usize()) {
+        let source_path = try!(decoder.read_string());
+        let source_len = try!(decoder.read_usize());
+        let source_checksum = try!(decoder.read_u64());
+        let source = match read(&source_path) {
+            Ok(source) => source,
+            Err(error) => return Err(Error::UnreadableSource(source_path, error)),
+        };
+        if source.len() != source_len || checksum(&source) != source_checksum {
+            return Err(Error::Stale(source_path));
+        }
+    }
+
+    let matcher = try!(M::decode(&mut decoder));
+    if decoder.is_at_end() {
+        Ok(matcher)
+    } else {
+        Err(Error::InvalidFormat("trailing data"))
+    }
+}
+
+// The test messages and the compiled patterns are not needed by the
+// matchers, so they aren't stored.
+pub fn encode_pattern(pattern: &Pattern, encoder: &mut Encoder) {
+    encoder.write_uuid(pattern.uuid());
+    encoder.write_opt_str(pattern.name());
+    encoder.write_i32(pattern.priority());
+    encoder.write_bool(pattern.values().is_some());
+    if let Some(values) = pattern.values() {
+        encoder.write_usize(values.len());
+        for (key, value) in values {
+            encoder.write_str(key);
+            encoder.write_str(value);
+        }
+    }
+    encoder.write_bool(pattern.tags().is_some());
+    if let Some(tags) = pattern.tags() {
+        encoder.write_usize(tags.len());
+        for tag in tags {
+            encoder.write_str(tag);
+        }
+    }
+}
+
+pub fn encode_opt_pattern(pattern: Option<&Pattern>, encoder: &mut Encoder) {
+    encoder.write_bool(pattern.is_some());
+    if let Some(pattern) = pattern {
+        encode_pattern(pattern, encoder);
+    }
+}
+
+pub fn decode_opt_pattern(decoder: &mut Decoder) -> Result<Option<Pattern>, Error> {
+    if try!(decoder.read_bool()) {
+        decode_pattern(decoder).map(Some)
+    } else {
+        Ok(None)
+    }
+}
+
+pub fn decode_pattern(decoder: &mut Decoder) -> Result<Pattern, Error> {
+    let uuid: Uuid = try!(decoder.read_uuid());
+    let name = try!(decoder.read_opt_string());
+    let priority = try!(decoder.read_i32());
+    let values = if try!(decoder.read_bool()) {
+        let mut values = BTreeMap::new();
+        for _ in 0..try!(decoder.read_usize()) {
+            let key = try!(decoder.read_string());
+            let value = try!(decoder.read_string());
+            values.insert(key, value);
+        }
+        Some(values)
+    } else {
+        None
+    };
+    let tags = if try!(decoder.read_bool()) {
+        let mut tags = Vec::new();
+        for _ in 0..try!(decoder.read_usize()) {
+            tags.push(try!(decoder.read_string()));
+        }
+        Some(tags)
+    } else {
+        None
+    };
+
+    let mut pattern = Pattern::new(name, uuid, Vec::new(), None, values, tags);
+    pattern.set_priority(priority);
+    Ok(pattern)
+}
+
+#[cfg(test)]
+mod test {
+    use super::checksum;
+
+    #[test]
+    fn test_given_content_when_its_checksum_is_calculated_then_it_is_the_fnv_1a_hash() {
+        assert_eq!(checksum(b""), 0xcbf29ce484222325);
+        assert_eq!(checksum(b"a"), 0xaf63dc4c8601ec8c);
+        assert_eq!(checksum(b"foobar"), 0x85944171f73967e8);
+    }
+}
@@ -1,4 +1,5 @@
-use parsers::Parser;
+use grammar;
+use parsers::{OptionalParameter, Parser};
 
 pub type CompiledPattern = Vec<TokenType>;
 
@@ -6,6 +7,12 @@ pub type CompiledPattern = Vec<TokenType>;
 pub enum TokenType {
     Parser(Box<Parser>),
     Literal(String),
+    Optional(CompiledPattern),
+    Alternatives(Vec<String>),
+    // The patterns of a patterndb rule. They can't be written in the pattern
+    // language, so it's only the single token of a pattern which is read from
+    // the `patterns` field of a pattern file.
+    Choice(Vec<CompiledPattern>),
 }
 
 impl Clone for TokenType {
@@ -17,10 +24,168 @@ impl Clone for TokenType {
             TokenType::Literal(ref literal) => {
                 TokenType::Literal(literal.clone())
             }
+            TokenType::Optional(ref pattern) => {
+                TokenType::Optional(pattern.clone())
+            }
+            TokenType::Alternatives(ref literals) => {
+                TokenType::Alternatives(literals.clone())
+            }
+            TokenType::Choice(ref patterns) => {
+                TokenType::Choice(patterns.clone())
+            }
+        }
+    }
+}
+
+// Every optional group doubles the number of the variants, so it's limited to
+// keep the expansion cheap.
+pub const MAX_VARIANTS: usize = 1024;
+
+// The number of the patterns `expand()` returns, saturated at `usize::MAX`.
+pub fn variant_count(pattern: &[TokenType]) -> usize {
+    pattern.iter().fold(1usize, |count, token| {
+        let tails = match *token {
+            TokenType::Optional(ref group) => variant_count(group).saturating_add(1),
+            TokenType::Alternatives(ref literals) => literals.len(),
+            TokenType::Choice(ref patterns) => {
+                patterns.iter().fold(0usize, |count, pattern| count.saturating_add(variant_count(pattern)))
+            }
+            _ => 1,
+        };
+        count.saturating_mul(tails)
+    })
+}
+
+// A pattern can be inserted into a matcher only if it contains nothing but
+// parsers and literals.
+pub fn is_expanded(pattern: &[TokenType]) -> bool {
+    pattern.iter().all(|token| {
+        match *token {
+            TokenType::Parser(_) | TokenType::Literal(_) => true,
+            TokenType::Optional(_) | TokenType::Alternatives(_) | TokenType::Choice(_) => false,
+        }
+    })
+}
+
+// Returns the patterns of a patterndb rule if the pattern is a choice between
+// them.
+pub fn choices(pattern: &[TokenType]) -> Option<&[CompiledPattern]> {
+    match pattern.first() {
+        Some(&TokenType::Choice(ref patterns)) if pattern.len() == 1 => Some(patterns),
+        _ => None,
+    }
+}
+
+// Expands the optional groups, the literal alternatives and the choices, so
+// every returned pattern contains only parsers and literals. The adjacent
+// literals are joined.
+pub fn expand(pattern: CompiledPattern) -> Vec<CompiledPattern> {
+    let mut variants = vec![Vec::new()];
+
+    for token in pattern {
+        let tails = match token {
+            TokenType::Optional(group) => {
+                let mut tails = expand(group);
+                tails.insert(0, Vec::new());
+                tails
+            }
+            TokenType::Alternatives(literals) => {
+                literals.into_iter().map(|literal| vec![TokenType::Literal(literal)]).collect()
+            }
+            TokenType::Choice(patterns) => patterns.into_iter().flat_map(expand).collect(),
+            token => vec![vec![token]],
+        };
+
+        let mut expanded = Vec::with_capacity(variants.len() * tails.len());
+        for variant in &variants {
+            for tail in &tails {
+                let mut variant = variant.clone();
+                for token in tail {
+                    push_token(&mut variant, token.clone());
+                }
+                expanded.push(variant);
+            }
+        }
+        variants = expanded;
+    }
+    variants
+}
+
+fn push_token(pattern: &mut CompiledPattern, token: TokenType) {
+    if let TokenType::Literal(ref literal) = token {
+        if let Some(&mut TokenType::Literal(ref mut last)) = pattern.last_mut() {
+            last.push_str(literal);
+            return;
+        }
+    }
+    pattern.push(token);
+}
+
+// Writes the pattern back in the pattern language, so it can be parsed again
+// into the same pattern.
+pub fn to_string(pattern: &[TokenType]) -> String {
+    let mut string = String::new();
+    write_tokens(pattern, false, &mut string);
+    string
+}
+
+// Writes every pattern of a choice separately, the other patterns are written
+// as one string.
+pub fn to_strings(pattern: &[TokenType]) -> Vec<String> {
+    match choices(pattern) {
+        Some(patterns) => patterns.iter().map(|pattern| to_string(pattern)).collect(),
+        None => vec![to_string(pattern)],
+    }
+}
+
+fn write_tokens(pattern: &[TokenType], optional: bool, string: &mut String) {
+    for token in pattern {
+        match *token {
+            TokenType::Parser(ref parser) => write_parser(&**parser, string),
+            TokenType::Literal(ref literal) if optional => string.push_str(&grammar::escape_optional_literal(literal)),
+            TokenType::Literal(ref literal) => string.push_str(&grammar::escape_literal(literal)),
+            TokenType::Optional(ref group) => {
+                string.push_str("%{?");
+                write_tokens(group, true, string);
+                string.push('}');
+            }
+            TokenType::Alternatives(ref literals) => {
+                let literals: Vec<String> = literals.iter().map(|literal| grammar::quote_string(literal)).collect();
+                string.push_str(&format!("%{{ANY({})}}", literals.join(",")));
+            }
+            TokenType::Choice(_) => unreachable!("the choices are written by to_strings()"),
         }
     }
 }
 
+fn write_parser(parser: &Parser, string: &mut String) {
+    let parameters: Vec<String> = parser.parameters()
+                                        .iter()
+                                        .map(|parameter| {
+                                            match *parameter {
+                                                OptionalParameter::Int(name, value) => format!("{}={}", name, value),
+                                                OptionalParameter::Str("set", ref set) => format!("\"{}\"", set),
+                                                OptionalParameter::Str("expression", ref expression) => {
+                                                    grammar::quote_regex(expression)
+                                                }
+                                                OptionalParameter::Str(name, ref value) => {
+                                                    format!("{}={}", name, grammar::quote_string(value))
+                                                }
+                                            }
+                                        })
+                                        .collect();
+    string.push_str("%{");
+    string.push_str(parser.kind());
+    if !parameters.is_empty() {
+        string.push_str(&format!("({})", parameters.join(",")));
+    }
+    if let Some(name) = parser.name() {
+        string.push(':');
+        string.push_str(name);
+    }
+    string.push('}');
+}
+
 pub struct CompiledPatternBuilder {
     pattern: CompiledPattern,
 }
@@ -42,6 +207,16 @@ impl CompiledPatternBuilder {
         self
     }
 
+    pub fn optional(&mut self, pattern: CompiledPattern) -> &mut CompiledPatternBuilder {
+        self.pattern.push(TokenType::Optional(pattern));
+        self
+    }
+
+    pub fn alternatives(&mut self, literals: Vec<String>) -> &mut CompiledPatternBuilder {
+        self.pattern.push(TokenType::Alternatives(literals));
+        self
+    }
+
     pub fn build(&self) -> CompiledPattern {
         self.pattern.clone()
     }
@@ -52,3 +227,94 @@ impl Default for CompiledPatternBuilder {
         CompiledPatternBuilder { pattern: Vec::new() }
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::{expand, to_string, variant_count, CompiledPatternBuilder, TokenType};
+    use grammar::parser;
+    use parsers::IntParser;
+
+    fn literals(pattern: &[TokenType]) -> Vec<&str> {
+        pattern.iter()
+               .map(|token| {
+                   match *token {
+                       TokenType::Literal(ref literal) => literal.as_str(),
+                       TokenType::Parser(_) => "<parser>",
+                       _ => unreachable!(),
+                   }
+               })
+               .collect()
+    }
+
+    #[test]
+    fn test_given_pattern_with_optional_group_and_alternatives_when_it_is_expanded_then_every_variant_is_created
+        () {
+        let group = CompiledPatternBuilder::new()
+                        .literal(" (pid ")
+                        .parser(Box::new(IntParser::with_name("pid")))
+                        .literal(")")
+                        .build();
+        let pattern = CompiledPatternBuilder::new()
+                          .alternatives(vec!["accepted".to_owned(), "Accepted".to_owned()])
+                          .literal(" login")
+                          .optional(group)
+                          .literal(" from")
+                          .build();
+
+        let variants = expand(pattern);
+        let variants: Vec<Vec<&str>> = variants.iter().map(|variant| literals(variant)).collect();
+        assert_eq!(variants,
+                   vec![vec!["accepted login from"],
+                        vec!["accepted login (pid ", "<parser>", ") from"],
+                        vec!["Accepted login from"],
+                        vec!["Accepted login (pid ", "<parser>", ") from"]]);
+    }
+
+    #[test]
+    fn test_given_nested_optional_groups_when_they_are_expanded_then_the_inner_group_requires_the_outer_one
+        () {
+        let inner = CompiledPatternBuilder::new().literal("c").build();
+        let outer = CompiledPatternBuilder::new().literal("b").optional(inner).build();
+        let pattern = CompiledPatternBuilder::new().literal("a").optional(outer).build();
+
+        assert_eq!(variant_count(&pattern), 3);
+        let variants = expand(pattern);
+        let variants: Vec<Vec<&str>> = variants.iter().map(|variant| literals(variant)).collect();
+        assert_eq!(variants, vec![vec!["a"], vec!["ab"], vec!["abc"]]);
+    }
+
+    #[test]
+    fn test_given_pattern_with_many_optional_groups_when_its_variants_are_counted_then_it_doesnt_overflow() {
+        let mut builder = CompiledPatternBuilder::new();
+        for _ in 0..100 {
+            builder.optional(CompiledPatternBuilder::new().literal("a").build());
+        }
+        builder.alternatives(vec!["b".to_owned(), "c".to_owned()]);
+        assert_eq!(variant_count(&builder.build()), usize::MAX);
+        assert_eq!(variant_count(&CompiledPatternBuilder::new().literal("a").build()), 1);
+    }
+
+    #[test]
+    fn test_given_compiled_pattern_when_it_is_written_back_then_the_same_pattern_is_parsed_from_it() {
+        let patterns = [r#"Accepted %{ESTRING(end=" "):method}for %{SET("abc",min_len=2):user}%{GREEDY:rest}"#,
+                        r#"%{ANY("accepted","Acc\\epted")} login%{? (pid %{INT(max_len=5):pid}\})} from"#,
+                        r#"%{REGEX("[a-z]+\\d\""):re} \%\{ %{QSTRING(quotes="'",pairs="<>"):q}"#,
+                        r#"%{KVPAIRS(separator=":")}"#,
+                        r#"%{TIMESTAMP(format="%H:%M"):time} %{INT} %{JSON(prefix="json."):}"#];
+
+        for pattern in &patterns {
+            let compiled = parser::pattern(pattern).expect("Failed to parse a valid pattern");
+            let written = to_string(&compiled);
+            let reparsed = parser::pattern(&written).expect("Failed to parse a written pattern");
+            assert_eq!(to_string(&reparsed), written);
+        }
+    }
+
+    #[test]
+    fn test_given_compiled_pattern_when_it_is_written_back_then_the_parameters_are_in_the_pattern_language() {
+        let compiled = parser::pattern(r#"%{ESTRING(end="\""):a} %{SET("ab", max_len=3):b} %{ANY("x\"y","z")}"#)
+                           .unwrap();
+        assert_eq!(to_string(&compiled),
+                   r#"%{ESTRING(end="\""):a} %{SET("ab",max_len=3):b} %{ANY("x\"y","z")}"#);
+    }
+}
@@ -0,0 +1,197 @@
+use uuid::Uuid;
+
+use parsers::Parser;
+
+use std::collections::BTreeSet;
+use std::fmt;
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum Step {
+    // A literal was consumed from the message.
+    Literal {
+        depth: usize,
+        position: usize,
+        literal: String,
+    },
+    // A parser was tried at the position. The value is None if it failed.
+    Parser {
+        depth: usize,
+        position: usize,
+        kind: &'static str,
+        name: Option<String>,
+        value: Option<String>,
+    },
+    // The literals led to a dead end, so the matcher went back to the position
+    // to try the parsers.
+    Backtrack {
+        depth: usize,
+        position: usize,
+    },
+    // The pattern found by the matcher was overridden by an other one with
+    // higher priority.
+    Priority {
+        uuid: Uuid,
+        priority: i32,
+    },
+}
+
+// The matchers report the path they take while parsing a message into a
+// trace. Parsing uses `()` which ignores everything, so explaining a message
+// takes the same path as parsing it.
+pub trait Trace {
+    // `remaining` is the not yet consumed suffix of the message
+    fn push_literal(&mut self, depth: usize, remaining: &[u8], literal: &str);
+    fn push_parser(&mut self, depth: usize, remaining: &[u8], parser: &Parser, value: Option<&[u8]>);
+    fn push_backtrack(&mut self, depth: usize, remaining: &[u8]);
+    fn push_priority(&mut self, uuid: &Uuid, priority: i32);
+    // Records that the matcher got to the remaining part of the message.
+    fn reached<F>(&mut self, remaining: &[u8], collect_candidates: F) where F: FnOnce(&mut BTreeSet<Uuid>);
+}
+
+impl Trace for () {
+    fn push_literal(&mut self, _: usize, _: &[u8], _: &str) {}
+    fn push_parser(&mut self, _: usize, _: &[u8], _: &Parser, _: Option<&[u8]>) {}
+    fn push_backtrack(&mut self, _: usize, _: &[u8]) {}
+    fn push_priority(&mut self, _: &Uuid, _: i32) {}
+    fn reached<F>(&mut self, _: &[u8], _: F) where F: FnOnce(&mut BTreeSet<Uuid>) {}
+}
+
+// The path taken by a matcher while it was looking for the pattern of a
+// message. Every position is a byte offset in the message.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Explanation {
+    message: Vec<u8>,
+    steps: Vec<Step>,
+    matched: Option<Uuid>,
+    longest_partial_match: usize,
+    candidates: Vec<Uuid>,
+}
+
+impl Explanation {
+    pub fn new(message: &[u8]) -> Explanation {
+        Explanation {
+            message: message.to_owned(),
+            steps: Vec::new(),
+            matched: None,
+            longest_partial_match: 0,
+            candidates: Vec::new(),
+        }
+    }
+
+    pub fn message(&self) -> &[u8] {
+        &self.message
+    }
+
+    pub fn steps(&self) -> &[Step] {
+        &self.steps
+    }
+
+    pub fn matched(&self) -> Option<&Uuid> {
+        self.matched.as_ref()
+    }
+
+    pub fn set_matched(&mut self, matched: Option<Uuid>) {
+        self.matched = matched;
+    }
+
+    // The longest prefix of the message which was consumed by the matcher.
+    pub fn longest_partial_match(&self) -> &[u8] {
+        &self.message[..self.longest_partial_match]
+    }
+
+    // The patterns which were reachable after the longest partial match.
+    pub fn candidates(&self) -> &[Uuid] {
+        &self.candidates
+    }
+
+    fn position(&self, remaining: &[u8]) -> usize {
+        self.message.len() - remaining.len()
+    }
+}
+
+impl Trace for Explanation {
+    fn push_literal(&mut self, depth: usize, remaining: &[u8], literal: &str) {
+        let position = self.position(remaining);
+        self.steps.push(Step::Literal {
+            depth: depth,
+            position: position,
+            literal: literal.to_owned(),
+        });
+    }
+
+    // The values which aren't valid UTF-8 are stored lossily.
+    fn push_parser(&mut self, depth: usize, remaining: &[u8], parser: &Parser, value: Option<&[u8]>) {
+        let position = self.position(remaining);
+        self.steps.push(Step::Parser {
+            depth: depth,
+            position: position,
+            kind: parser.kind(),
+            name: parser.name().map(|name| name.to_owned()),
+            value: value.map(|value| String::from_utf8_lossy(value).into_owned()),
+        });
+    }
+
+    fn push_backtrack(&mut self, depth: usize, remaining: &[u8]) {
+        let position = self.position(remaining);
+        self.steps.push(Step::Backtrack {
+            depth: depth,
+            position: position,
+        });
+    }
+
+    fn push_priority(&mut self, uuid: &Uuid, priority: i32) {
+        self.steps.push(Step::Priority {
+            uuid: *uuid,
+            priority: priority,
+        });
+    }
+
+    // The candidates are collected only if it's further than anything before.
+    fn reached<F>(&mut self, remaining: &[u8], collect_candidates: F)
+        where F: FnOnce(&mut BTreeSet<Uuid>)
+    {
+        let position = self.position(remaining);
+        if position > self.longest_partial_match || self.candidates.is_empty() {
+            let mut candidates = BTreeSet::new();
+            collect_candidates(&mut candidates);
+            self.longest_partial_match = position;
+            self.candidates = candidates.into_iter().collect();
+        }
+    }
+}
+
+impl fmt::Display for Explanation {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> Result<(), fmt::Error> {
+        try!(writeln!(formatter, "message: {:?}", String::from_utf8_lossy(&self.message)));
+        for step in &self.steps {
+            let (depth, position, description) = match *step {
+                Step::Literal { depth, position, ref literal } => {
+                    (depth, position, format!("literal {:?}", literal))
+                }
+                Step::Parser { depth, position, kind, ref name, ref value } => {
+                    let name = name.as_ref().map_or("", |name| name);
+                    let description = match *value {
+                        Some(ref value) => format!("parser %{{{}:{}}} matched {:?}", kind, name, value),
+                        None => format!("parser %{{{}:{}}} failed", kind, name),
+                    };
+                    (depth, position, description)
+                }
+                Step::Backtrack { depth, position } => (depth, position, "backtrack".to_owned()),
+                Step::Priority { ref uuid, priority } => {
+                    let description = format!("chosen by priority: uuid={} priority={}", uuid.hyphenated(), priority);
+                    (0, self.message.len(), description)
+                }
+            };
+            try!(writeln!(formatter, "{:4} {}{}", position, "  ".repeat(depth), description));
+        }
+        match self.matched {
+            Some(ref uuid) => try!(writeln!(formatter, "matched: uuid={}", uuid.hyphenated())),
+            None => try!(writeln!(formatter, "no match")),
+        }
+        let candidates: Vec<String> = self.candidates.iter().map(|uuid| uuid.hyphenated().to_string()).collect();
+        write!(formatter,
+               "longest partial match: {:?} candidates={}",
+               String::from_utf8_lossy(self.longest_partial_match()),
+               candidates.join(","))
+    }
+}
@@ -1,6 +1,14 @@
-use matcher::Matcher;
+use matcher::{Matcher, BuildError};
+use matcher::cache;
 
 pub trait MatcherFactory {
     type Matcher: Matcher;
     fn new_matcher() -> Self::Matcher;
+    // Loads a matcher from a file created by cache::compile(). The matchers
+    // which don't implement cache::Cacheable can't be loaded from a cache, so
+    // by default it's an error.
+    fn from_cache(cache_path: &str) -> Result<Self::Matcher, BuildError> {
+        let _ = cache_path;
+        Err(BuildError::Cache(cache::Error::NotSupported))
+    }
 }
@@ -0,0 +1,533 @@
+use uuid::Uuid;
+
+use matcher::{BuildError, Matcher, MatcherFactory, Pattern};
+use matcher::compiled_pattern::TokenType;
+
+use std::collections::{BTreeSet, HashMap};
+use std::fmt;
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum Warning {
+    // The pattern can match the same messages as an other pattern. The
+    // message is the test message of the pattern which showed it, the overlaps
+    // found by comparing the shapes of the patterns don't have one.
+    Overlap {
+        uuid: Uuid,
+        other: Uuid,
+        message: Option<String>,
+    },
+    // A test message of the pattern is matched by an other pattern when every
+    // pattern is loaded.
+    ClaimedTestMessage {
+        uuid: Uuid,
+        claimed_by: Uuid,
+        message: String,
+    },
+    // Every test message of the pattern is claimed by other patterns. The
+    // pattern may still match other messages.
+    AllTestMessagesClaimed {
+        uuid: Uuid,
+        claimed_by: Vec<Uuid>,
+    },
+    // Every message of the pattern is matched by other patterns which are
+    // preferred over it, so the pattern can never match.
+    NeverMatches {
+        uuid: Uuid,
+        shadowed_by: Vec<Uuid>,
+    },
+    // The patterns differ only in the names of their parsers, so only one of
+    // them can ever match.
+    SameShape {
+        uuid: Uuid,
+        other: Uuid,
+    },
+}
+
+impl fmt::Display for Warning {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> Result<(), fmt::Error> {
+        match *self {
+            Warning::Overlap { ref uuid, ref other, message: Some(ref message) } => {
+                write!(formatter,
+                       "Patterns can match the same message: uuid={} other_uuid={} message={:?}",
+                       uuid.hyphenated(),
+                       other.hyphenated(),
+                       message)
+            }
+            Warning::Overlap { ref uuid, ref other, message: None } => {
+                write!(formatter,
+                       "Patterns can match the same messages: uuid={} other_uuid={}",
+                       uuid.hyphenated(),
+                       other.hyphenated())
+            }
+            Warning::ClaimedTestMessage { ref uuid, ref claimed_by, ref message } => {
+                write!(formatter,
+                       "A test message is claimed by an other pattern: uuid={} claimed_by={} \
+                        message={:?}",
+                       uuid.hyphenated(),
+                       claimed_by.hyphenated(),
+                       message)
+            }
+            Warning::AllTestMessagesClaimed { ref uuid, ref claimed_by } => {
+                let claimed_by: Vec<String> = claimed_by.iter()
+                                                        .map(|uuid| uuid.hyphenated().to_string())
+                                                        .collect();
+                write!(formatter,
+                       "Every test message is claimed by other patterns: uuid={} claimed_by={}",
+                       uuid.hyphenated(),
+                       claimed_by.join(","))
+            }
+            Warning::NeverMatches { ref uuid, ref shadowed_by } => {
+                let shadowed_by: Vec<String> = shadowed_by.iter()
+                                                          .map(|uuid| uuid.hyphenated().to_string())
+                                                          .collect();
+                write!(formatter,
+                       "The pattern can never match, other patterns are preferred: uuid={} shadowed_by={}",
+                       uuid.hyphenated(),
+                       shadowed_by.join(","))
+            }
+            Warning::SameShape { ref uuid, ref other } => {
+                write!(formatter,
+                       "Patterns differ only in their parser names: uuid={} other_uuid={}",
+                       uuid.hyphenated(),
+                       other.hyphenated())
+            }
+        }
+    }
+}
+
+// The parsers are compared without their names. A GREEDY parser without an
+// end string at the end of the pattern matches the rest of any message.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+enum ShapeToken {
+    Literal(String),
+    Parser(u64),
+    Rest,
+}
+
+fn shapes(pattern: &Pattern) -> Result<Vec<Vec<ShapeToken>>, BuildError> {
+    let variants = try!(pattern.clone().expand());
+    Ok(variants.iter().map(|variant| shape(variant, false)).collect())
+}
+
+// The named shapes tell apart the parsers which differ only in their names,
+// because they are different nodes in the matchers.
+fn shape(variant: &Pattern, with_names: bool) -> Vec<ShapeToken> {
+    let last = variant.pattern().len().saturating_sub(1);
+    variant.pattern()
+           .iter()
+           .enumerate()
+           .map(|(position, token)| {
+               match *token {
+                   TokenType::Literal(ref literal) => ShapeToken::Literal(literal.clone()),
+                   TokenType::Parser(ref parser) if with_names => ShapeToken::Parser(parser.hash_os()),
+                   TokenType::Parser(ref parser) if position == last && parser.kind() == "GREEDY" &&
+                                                    parser.parameters().is_empty() => ShapeToken::Rest,
+                   TokenType::Parser(ref parser) => {
+                       let mut parser = parser.boxed_clone();
+                       parser.set_name(None);
+                       ShapeToken::Parser(parser.hash_os())
+                   }
+                   TokenType::Optional(_) | TokenType::Alternatives(_) | TokenType::Choice(_) => {
+                       unreachable!("expanded patterns contain only literals and parsers")
+                   }
+               }
+           })
+           .collect()
+}
+
+// Returns true if the winner matches every message which is matched by the
+// loser. It's decided only by the shapes, so some covering patterns are missed
+// (e.g. an INT parser is not known to match the values of a literal).
+fn covers(winner: &[ShapeToken], loser: &[ShapeToken]) -> bool {
+    match (winner.split_first(), loser.split_first()) {
+        (None, None) => true,
+        (Some((&ShapeToken::Rest, _)), Some(_)) => true,
+        (Some((&ShapeToken::Literal(ref literal), winner_rest)),
+         Some((&ShapeToken::Literal(ref other), loser_rest))) => {
+            if other == literal {
+                covers(winner_rest, loser_rest)
+            } else if other.starts_with(&literal[..]) {
+                let mut loser = vec![ShapeToken::Literal(other[literal.len()..].to_owned())];
+                loser.extend_from_slice(loser_rest);
+                covers(winner_rest, &loser)
+            } else {
+                false
+            }
+        }
+        (Some((token, winner_rest)), Some((other, loser_rest))) => token == other && covers(winner_rest, loser_rest),
+        _ => false,
+    }
+}
+
+// Returns true if the earlier inserted winner is tried first. The literals are
+// tried before the parsers, so the winner is preferred only if its first
+// difference from the loser is a parser against a parser of the loser.
+fn is_tried_first(winner: &[ShapeToken], loser: &[ShapeToken]) -> bool {
+    match (winner.split_first(), loser.split_first()) {
+        (Some((token, winner_rest)), Some((other, loser_rest))) if token == other => {
+            is_tried_first(winner_rest, loser_rest)
+        }
+        (Some((&ShapeToken::Literal(_), _)), _) | (_, Some((&ShapeToken::Literal(_), _))) => false,
+        (Some(_), Some(_)) => true,
+        _ => false,
+    }
+}
+
+// A variant of a pattern with its shapes and the position of the pattern.
+struct Variant<'a> {
+    pattern: &'a Pattern,
+    position: usize,
+    shape: Vec<ShapeToken>,
+    named_shape: Vec<ShapeToken>,
+}
+
+fn shadows(winner: &Variant, loser: &Variant) -> bool {
+    if winner.pattern.uuid() == loser.pattern.uuid() || !covers(&winner.shape, &loser.shape) {
+        return false;
+    }
+    let (winner_priority, loser_priority) = (winner.pattern.priority(), loser.pattern.priority());
+    winner_priority > loser_priority ||
+    (winner_priority == loser_priority && winner.position < loser.position &&
+     is_tried_first(&winner.named_shape, &loser.named_shape))
+}
+
+fn variants(patterns: &[Pattern]) -> Result<Vec<Variant>, BuildError> {
+    let mut variants = Vec::new();
+    for (position, pattern) in patterns.iter().enumerate() {
+        for variant in try!(pattern.clone().expand()) {
+            variants.push(Variant {
+                pattern: pattern,
+                position: position,
+                shape: shape(&variant, false),
+                named_shape: shape(&variant, true),
+            });
+        }
+    }
+    Ok(variants)
+}
+
+// The pairs are unordered, so a pair is reported only once.
+fn pair(uuid: &Uuid, other: &Uuid) -> (Uuid, Uuid) {
+    if uuid < other {
+        (*uuid, *other)
+    } else {
+        (*other, *uuid)
+    }
+}
+
+// The pairs of patterns which are already reported by an other warning.
+fn reported_pairs(warnings: &[Warning]) -> BTreeSet<(Uuid, Uuid)> {
+    let mut pairs = BTreeSet::new();
+    for warning in warnings {
+        match *warning {
+            Warning::Overlap { ref uuid, ref other, .. } |
+            Warning::SameShape { ref uuid, ref other } => {
+                pairs.insert(pair(uuid, other));
+            }
+            Warning::NeverMatches { ref uuid, ref shadowed_by } => {
+                for other in shadowed_by {
+                    pairs.insert(pair(uuid, other));
+                }
+            }
+            Warning::ClaimedTestMessage { .. } |
+            Warning::AllTestMessagesClaimed { .. } => (),
+        }
+    }
+    pairs
+}
+
+fn lint_never_matches(patterns: &[Pattern], warnings: &mut Vec<Warning>) -> Result<(), BuildError> {
+    let variants = try!(variants(patterns));
+
+    for (position, pattern) in patterns.iter().enumerate() {
+        let mut shadowed_by = BTreeSet::new();
+        let mut never_matches = true;
+        for loser in variants.iter().filter(|variant| variant.position == position) {
+            match variants.iter().find(|winner| shadows(winner, loser)) {
+                Some(winner) => {
+                    shadowed_by.insert(*winner.pattern.uuid());
+                }
+                None => {
+                    never_matches = false;
+                    break;
+                }
+            }
+        }
+        if never_matches {
+            warnings.push(Warning::NeverMatches {
+                uuid: *pattern.uuid(),
+                shadowed_by: shadowed_by.into_iter().collect(),
+            });
+        }
+    }
+    Ok(())
+}
+
+// A pattern overlaps with an other one if a variant of the other pattern
+// covers one of its variants. Only the pairs which aren't reported as shadowed
+// or having the same shape are reported, so a pair is reported only once.
+fn lint_overlaps(patterns: &[Pattern], warnings: &mut Vec<Warning>) -> Result<(), BuildError> {
+    let variants = try!(variants(patterns));
+    let mut reported = reported_pairs(warnings);
+
+    for (position, pattern) in patterns.iter().enumerate() {
+        for other in patterns.iter().skip(position + 1) {
+            if pattern.uuid() == other.uuid() || reported.contains(&pair(pattern.uuid(), other.uuid())) {
+                continue;
+            }
+            let covered_by = |winner: &Uuid, loser: &Uuid| {
+                variants.iter().filter(|variant| variant.pattern.uuid() == winner).any(|winner| {
+                    variants.iter()
+                            .filter(|variant| variant.pattern.uuid() == loser)
+                            .any(|loser| covers(&winner.shape, &loser.shape))
+                })
+            };
+            let overlap = if covered_by(pattern.uuid(), other.uuid()) {
+                Some((other.uuid(), pattern.uuid()))
+            } else if covered_by(other.uuid(), pattern.uuid()) {
+                Some((pattern.uuid(), other.uuid()))
+            } else {
+                None
+            };
+            if let Some((uuid, other)) = overlap {
+                reported.insert(pair(uuid, other));
+                warnings.push(Warning::Overlap {
+                    uuid: *uuid,
+                    other: *other,
+                    message: None,
+                });
+            }
+        }
+    }
+    Ok(())
+}
+
+fn lint_shapes(patterns: &[Pattern], warnings: &mut Vec<Warning>) -> Result<(), BuildError> {
+    let mut seen: HashMap<Vec<ShapeToken>, Uuid> = HashMap::new();
+
+    for pattern in patterns {
+        let mut others = BTreeSet::new();
+        for shape in try!(shapes(pattern)) {
+            match seen.get(&shape) {
+                Some(other) if other != pattern.uuid() => {
+                    others.insert(*other);
+                }
+                Some(_) => (),
+                None => {
+                    seen.insert(shape, *pattern.uuid());
+                }
+            }
+        }
+        for other in others {
+            warnings.push(Warning::SameShape {
+                uuid: *pattern.uuid(),
+                other: other,
+            });
+        }
+    }
+    Ok(())
+}
+
+fn lint_test_messages<F: MatcherFactory>(patterns: &[Pattern], warnings: &mut Vec<Warning>) -> Result<(), BuildError> {
+    let mut matcher = F::new_matcher();
+    let mut reported = reported_pairs(warnings);
+
+    for pattern in patterns {
+        try!(matcher.add_pattern(pattern.clone()));
+    }
+
+    for pattern in patterns {
+        let uuid = *pattern.uuid();
+        let test_messages = pattern.test_messages().unwrap_or(&[]);
+        let mut all_claimed_by = BTreeSet::new();
+        let mut claimed_count = 0;
+
+        for test_message in test_messages {
+            let message = test_message.message();
+            let claimed_by = matcher.parse(message)
+                                    .map(|result| *result.pattern().uuid())
+                                    .and_then(|claimed_by| {
+                                        if claimed_by != uuid {
+                                            Some(claimed_by)
+                                        } else {
+                                            None
+                                        }
+                                    });
+
+            if let Some(claimed_by) = claimed_by {
+                claimed_count += 1;
+                all_claimed_by.insert(claimed_by);
+                warnings.push(Warning::ClaimedTestMessage {
+                    uuid: uuid,
+                    claimed_by: claimed_by,
+                    message: message.to_owned(),
+                });
+            }
+
+            let others: BTreeSet<Uuid> = matcher.parse_all(message)
+                                                .iter()
+                                                .map(|result| *result.pattern().uuid())
+                                                .filter(|other| *other != uuid && Some(*other) != claimed_by)
+                                                .collect();
+            for other in others {
+                if reported.insert(pair(&uuid, &other)) {
+                    warnings.push(Warning::Overlap {
+                        uuid: uuid,
+                        other: other,
+                        message: Some(message.to_owned()),
+                    });
+                }
+            }
+        }
+
+        if !test_messages.is_empty() && claimed_count == test_messages.len() {
+            warnings.push(Warning::AllTestMessagesClaimed {
+                uuid: uuid,
+                claimed_by: all_claimed_by.into_iter().collect(),
+            });
+        }
+    }
+    Ok(())
+}
+
+// Reports the patterns which can match the same messages. The checks based on
+// matching use the test messages of the patterns, so the patterns without test
+// messages are checked only by their shapes: whether they have the same shape
+// as an other pattern, whether other patterns always win over them and whether
+// an other pattern matches every message they match.
+pub fn lint<F: MatcherFactory>(patterns: &[Pattern]) -> Result<Vec<Warning>, BuildError> {
+    let mut warnings = Vec::new();
+    try!(lint_shapes(patterns, &mut warnings));
+    try!(lint_never_matches(patterns, &mut warnings));
+    try!(lint_overlaps(patterns, &mut warnings));
+    try!(lint_test_messages::<F>(patterns, &mut warnings));
+    Ok(warnings)
+}
+
+#[cfg(test)]
+mod test {
+    use super::{lint, Warning};
+    use matcher::{Matcher, MatcherFactory, Pattern};
+    use uuid::Uuid;
+    use matcher::suffix_array::SuffixArrayMatcherFactory;
+    use matcher::trie::factory::TrieMatcherFactory;
+
+    fn pattern(uuid: &str, pattern: &str, message: Option<&str>) -> Pattern {
+        let test_messages = match message {
+            Some(message) => format!(r#", "test_messages": [{{"message": "{}"}}]"#, message),
+            None => String::new(),
+        };
+        let doc = format!(r#"{{"uuid": "{}", "pattern": "{}"{}}}"#, uuid, pattern, test_messages);
+        Pattern::from_json(&doc).unwrap()
+    }
+
+    #[test]
+    fn test_given_patterns_when_they_differ_only_in_parser_names_then_they_have_the_same_shape() {
+        let patterns = vec![pattern("9a49c47d-29e9-4072-be84-3b76c6814743", "pid=%{INT:pid}", None),
+                            pattern("fa8bdbcb-e0fd-4da1-9fa4-15ecfec28ad2", "pid=%{INT:id}", None),
+                            pattern("c11c806a-766d-4a09-9f24-7de1fe02e51e", "uid=%{INT:id}", None)];
+        let warnings = lint::<TrieMatcherFactory>(&patterns).expect("Failed to lint the patterns");
+
+        assert_eq!(warnings,
+                   vec![Warning::SameShape {
+                            uuid: *patterns[1].uuid(),
+                            other: *patterns[0].uuid(),
+                        },
+                        Warning::NeverMatches {
+                            uuid: *patterns[1].uuid(),
+                            shadowed_by: vec![*patterns[0].uuid()],
+                        }]);
+    }
+
+    fn uuid_of_match<F: MatcherFactory>(patterns: &[Pattern], message: &str) -> Option<Uuid> {
+        let mut matcher = F::new_matcher();
+        for pattern in patterns {
+            matcher.add_pattern(pattern.clone()).unwrap();
+        }
+        matcher.parse(message).map(|result| *result.pattern().uuid())
+    }
+
+    #[test]
+    fn test_given_patterns_when_an_earlier_parser_matches_the_rest_of_the_messages_then_the_later_never_matches() {
+        let patterns = vec![pattern("9a49c47d-29e9-4072-be84-3b76c6814743", "pid=%{GREEDY:value}", None),
+                            pattern("fa8bdbcb-e0fd-4da1-9fa4-15ecfec28ad2", "pid=%{INT:pid}", None),
+                            pattern("c11c806a-766d-4a09-9f24-7de1fe02e51e", "ppid=%{INT:pid}", None)];
+        let warnings = lint::<TrieMatcherFactory>(&patterns).expect("Failed to lint the patterns");
+
+        assert_eq!(warnings,
+                   vec![Warning::NeverMatches {
+                            uuid: *patterns[1].uuid(),
+                            shadowed_by: vec![*patterns[0].uuid()],
+                        }]);
+        assert_eq!(uuid_of_match::<TrieMatcherFactory>(&patterns, "pid=42"), Some(*patterns[0].uuid()));
+        assert_eq!(uuid_of_match::<SuffixArrayMatcherFactory>(&patterns, "pid=42"),
+                   Some(*patterns[0].uuid()));
+        assert_eq!(uuid_of_match::<TrieMatcherFactory>(&patterns, "ppid=42"), Some(*patterns[2].uuid()));
+    }
+
+    #[test]
+    fn test_given_patterns_when_a_covering_pattern_has_higher_priority_then_the_other_never_matches() {
+        let mut patterns = vec![pattern("9a49c47d-29e9-4072-be84-3b76c6814743",
+                                        "user root %{GREEDY:action}",
+                                        None),
+                                pattern("fa8bdbcb-e0fd-4da1-9fa4-15ecfec28ad2",
+                                        "user %{GREEDY:user}",
+                                        None)];
+        assert_eq!(lint::<TrieMatcherFactory>(&patterns).unwrap(),
+                   vec![Warning::Overlap {
+                            uuid: *patterns[0].uuid(),
+                            other: *patterns[1].uuid(),
+                            message: None,
+                        }]);
+
+        patterns[1].set_priority(5);
+        let warnings = lint::<TrieMatcherFactory>(&patterns).expect("Failed to lint the patterns");
+
+        assert_eq!(warnings,
+                   vec![Warning::NeverMatches {
+                            uuid: *patterns[0].uuid(),
+                            shadowed_by: vec![*patterns[1].uuid()],
+                        }]);
+        assert_eq!(uuid_of_match::<SuffixArrayMatcherFactory>(&patterns, "user root logged in"),
+                   Some(*patterns[1].uuid()));
+    }
+
+    #[test]
+    fn test_given_patterns_when_a_test_message_is_claimed_by_an_other_pattern_then_it_is_reported() {
+        let patterns = vec![pattern("9a49c47d-29e9-4072-be84-3b76c6814743",
+                                    "user %{GREEDY:user}",
+                                    Some("user root logged in")),
+                            pattern("fa8bdbcb-e0fd-4da1-9fa4-15ecfec28ad2",
+                                    "user root %{GREEDY:action}",
+                                    Some("user root logged in"))];
+        let warnings = lint::<SuffixArrayMatcherFactory>(&patterns).expect("Failed to lint the patterns");
+
+        assert_eq!(warnings,
+                   vec![Warning::Overlap {
+                            uuid: *patterns[1].uuid(),
+                            other: *patterns[0].uuid(),
+                            message: None,
+                        },
+                        Warning::ClaimedTestMessage {
+                            uuid: *patterns[0].uuid(),
+                            claimed_by: *patterns[1].uuid(),
+                            message: "user root logged in".to_owned(),
+                        },
+                        Warning::AllTestMessagesClaimed {
+                            uuid: *patterns[0].uuid(),
+                            claimed_by: vec![*patterns[1].uuid()],
+                        }]);
+    }
+
+    #[test]
+    fn test_given_patterns_when_they_dont_match_each_others_test_messages_then_there_are_no_warnings
+        () {
+        let patterns = vec![pattern("9a49c47d-29e9-4072-be84-3b76c6814743",
+                                    "user %{GREEDY:user} logged in",
+                                    Some("user root logged in")),
+                            pattern("fa8bdbcb-e0fd-4da1-9fa4-15ecfec28ad2",
+                                    "user %{GREEDY:user} logged out",
+                                    Some("user root logged out"))];
+        assert_eq!(lint::<TrieMatcherFactory>(&patterns).unwrap(), vec![]);
+    }
+}
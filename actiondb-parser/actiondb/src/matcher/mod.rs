@@ -7,6 +7,13 @@ pub mod pattern_loader;
 pub mod suite;
 pub mod compiled_pattern;
 pub mod suffix_array;
+pub mod lint;
+pub mod explain;
+mod priority;
+pub mod cache;
+pub mod stats;
+pub mod suggest;
+pub mod ruleset;
 
 pub use self::pattern::Pattern;
 pub use self::pattern_loader::PatternLoader;
@@ -15,10 +22,50 @@ pub use self::suite::MatcherSuite;
 pub use self::pattern_source::{FromPatternSource, BuildError};
 
 use matcher::result::MatchResult;
+use matcher::explain::Explanation;
+use uuid::Uuid;
+use std::collections::BTreeSet;
 use std::fmt;
 
 pub trait Matcher: fmt::Debug {
-    fn parse<'a, 'b>(&'a self, text: &'b str) -> Option<MatchResult<'a, 'b>>;
-    fn add_pattern(&mut self, pattern: Pattern);
+    // The matchers work on bytes, so the messages don't have to be valid
+    // UTF-8. The values are slices of the text.
+    fn parse_bytes<'a, 'b>(&'a self, text: &'b [u8]) -> Option<MatchResult<'a, 'b, [u8]>>;
+    fn parse<'a, 'b>(&'a self, text: &'b str) -> Option<MatchResult<'a, 'b>> {
+        self.parse_bytes(text.as_bytes()).and_then(|result| result.into_str())
+    }
+    // Every successful match in precedence order: the literals are preferred
+    // over the parsers, the longer literals over the shorter ones and the
+    // parsers are tried in their insertion order.
+    fn parse_all_bytes<'a, 'b>(&'a self, text: &'b [u8]) -> Vec<MatchResult<'a, 'b, [u8]>>;
+    fn parse_all<'a, 'b>(&'a self, text: &'b str) -> Vec<MatchResult<'a, 'b>> {
+        self.parse_all_bytes(text.as_bytes())
+            .into_iter()
+            .filter_map(|result| result.into_str())
+            .collect()
+    }
+    // Records the path taken while looking for the pattern of the text.
+    fn explain_bytes(&self, text: &[u8]) -> Explanation;
+    fn explain(&self, text: &str) -> Explanation {
+        self.explain_bytes(text.as_bytes())
+    }
+    fn add_pattern(&mut self, pattern: Pattern) -> Result<(), BuildError>;
+    // Removes every variant of the pattern and prunes the nodes which became
+    // empty. Returns false if there wasn't such pattern.
+    fn remove_pattern(&mut self, uuid: &Uuid) -> bool;
+    // The new pattern is expanded before the old one is removed, so an invalid
+    // replacement leaves the old pattern in the matcher. The expanded variants
+    // can always be inserted.
+    fn replace_pattern(&mut self, pattern: Pattern) -> Result<(), BuildError> {
+        let uuid = *pattern.uuid();
+        let variants = try!(pattern.expand());
+        self.remove_pattern(&uuid);
+        for variant in variants {
+            try!(self.add_pattern(variant));
+        }
+        Ok(())
+    }
+    // The uuids of every pattern in the matcher.
+    fn uuids(&self) -> BTreeSet<Uuid>;
     fn boxed_clone(&self) -> Box<Matcher>;
 }
@@ -1,5 +1,6 @@
 use super::Pattern;
 use super::testmessage::TestMessage;
+use matcher::compiled_pattern::TokenType;
 
 use serde;
 use uuid::Uuid;
@@ -18,9 +19,13 @@ enum Field {
     NAME,
     UUID,
     PATTERN,
+    PATTERNS,
     VALUES,
     TAGS,
     TESTMESSAGES,
+    IGNORECASE,
+    COLLAPSEWHITESPACE,
+    PRIORITY,
 }
 
 impl serde::Deserialize for Field {
@@ -39,9 +44,13 @@ impl serde::Deserialize for Field {
                     "name" => Ok(Field::NAME),
                     "uuid" => Ok(Field::UUID),
                     "pattern" => Ok(Field::PATTERN),
+                    "patterns" => Ok(Field::PATTERNS),
                     "values" => Ok(Field::VALUES),
                     "tags" => Ok(Field::TAGS),
                     "test_messages" => Ok(Field::TESTMESSAGES),
+                    "ignore_case" => Ok(Field::IGNORECASE),
+                    "collapse_whitespace" => Ok(Field::COLLAPSEWHITESPACE),
+                    "priority" => Ok(Field::PRIORITY),
                     _ => Err(serde::de::Error::custom(format!("Unexpected field: {}", value))),
                 }
             }
@@ -63,46 +72,78 @@ impl serde::de::Visitor for PatternVisitor {
         let mut name = None;
         let mut uuid: Option<Uuid> = None;
         let mut pattern: Option<String> = None;
+        let mut patterns: Option<Vec<String>> = None;
         let mut values: Option<BTreeMap<String, String>> = None;
         let mut tags: Option<Vec<String>> = None;
         let mut test_messages: Option<Vec<TestMessage>> = None;
+        let mut ignore_case: Option<bool> = None;
+        let mut collapse_whitespace: Option<bool> = None;
+        let mut priority: Option<i32> = None;
 
         while let Some(field) = try!(visitor.visit_key()) {
             match field {
                 Field::NAME => name = Some(try!(visitor.visit_value())),
                 Field::UUID => uuid = Some(try!(visitor.visit_value())),
                 Field::PATTERN => pattern = Some(try!(visitor.visit_value())),
+                Field::PATTERNS => patterns = Some(try!(visitor.visit_value())),
                 Field::VALUES => values = Some(try!(visitor.visit_value())),
                 Field::TAGS => tags = Some(try!(visitor.visit_value())),
                 Field::TESTMESSAGES => test_messages = Some(try!(visitor.visit_value())),
+                Field::IGNORECASE => ignore_case = Some(try!(visitor.visit_value())),
+                Field::COLLAPSEWHITESPACE => collapse_whitespace = Some(try!(visitor.visit_value())),
+                Field::PRIORITY => priority = Some(try!(visitor.visit_value())),
             }
         }
 
-        let pattern = match pattern {
-            Some(pattern) => {
-                match ::grammar::parser::pattern(&pattern) {
-                    Ok(pattern) => pattern,
-                    Err(err) => {
-                        error!("Invalid field 'pattern': pattern={:?} name={:?} uuid={:?} \
-                                error={}",
-                               pattern,
-                               name,
-                               uuid,
-                               err);
-                        try!(Err(serde::de::Error::custom("Invalid field 'pattern'")))
-                    }
+        // `patterns: [...]` lists the patterns of a patterndb rule, they share
+        // the uuid and the other fields of the pattern.
+        let texts = match (pattern, patterns) {
+            (Some(pattern), None) => vec![pattern],
+            (None, Some(patterns)) => {
+                if patterns.is_empty() {
+                    return Err(serde::de::Error::custom("The field 'patterns' can't be empty".to_owned()));
                 }
+                patterns
             }
-            None => {
+            (Some(_), Some(_)) => {
+                return Err(serde::de::Error::custom("A pattern can't have both pattern and patterns".to_owned()))
+            }
+            (None, None) => {
                 error!("Missing field 'pattern': name={:?} uuid={:?}", name, uuid);
                 try!(Err(serde::de::Error::missing_field("pattern")))
             }
         };
 
+        let mut compiled = Vec::with_capacity(texts.len());
+        for text in &texts {
+            match ::grammar::parser::pattern(text) {
+                Ok(pattern) => compiled.push(pattern),
+                Err(err) => {
+                    // it's reported by the loader of the pattern file
+                    let uuid = uuid.map(|uuid| uuid.to_string()).unwrap_or_default();
+                    try!(Err(serde::de::Error::custom(format!("Invalid field 'pattern': \
+                                                               uuid={} column={} error={}",
+                                                              uuid,
+                                                              err.column,
+                                                              err))))
+                }
+            }
+        }
+        let pattern = if compiled.len() == 1 {
+            compiled.remove(0)
+        } else {
+            vec![TokenType::Choice(compiled)]
+        };
+
         let uuid = try!(uuid.ok_or(serde::de::Error::missing_field("pattern")));
 
         try!(visitor.end());
 
-        Ok(Pattern::new(name, uuid, pattern, test_messages, values, tags))
+        let mut pattern = Pattern::new(name, uuid, pattern, test_messages, values, tags);
+        pattern.set_default_flags(ignore_case, collapse_whitespace);
+        if let Some(priority) = priority {
+            pattern.set_priority(priority);
+        }
+        Ok(pattern)
     }
 }
@@ -2,6 +2,7 @@ use serde;
 
 use super::PatternFile;
 use matcher::pattern::Pattern;
+use matcher::ruleset::Ruleset;
 
 impl serde::de::Deserialize for PatternFile {
     fn deserialize<D>(deserializer: &mut D) -> Result<PatternFile, D::Error>
@@ -13,6 +14,10 @@ impl serde::de::Deserialize for PatternFile {
 
 enum Field {
     PATTERNS,
+    RULESETS,
+    INCLUDE,
+    IGNORECASE,
+    COLLAPSEWHITESPACE,
 }
 
 impl serde::Deserialize for Field {
@@ -29,6 +34,10 @@ impl serde::Deserialize for Field {
             {
                 match value {
                     "patterns" => Ok(Field::PATTERNS),
+                    "rulesets" => Ok(Field::RULESETS),
+                    "include" => Ok(Field::INCLUDE),
+                    "ignore_case" => Ok(Field::IGNORECASE),
+                    "collapse_whitespace" => Ok(Field::COLLAPSEWHITESPACE),
                     _ => Err(serde::de::Error::custom(format!("Unexpected field: {}", value))),
                 }
             }
@@ -47,20 +56,43 @@ impl serde::de::Visitor for FileVisitor {
         where V: serde::de::MapVisitor
     {
         let mut patterns: Option<Vec<Pattern>> = None;
+        let mut rulesets: Option<Vec<Ruleset>> = None;
+        let mut includes: Option<Vec<String>> = None;
+        let mut ignore_case: Option<bool> = None;
+        let mut collapse_whitespace: Option<bool> = None;
 
         while let Some (field) = try!(visitor.visit_key()) {
             match field {
-                Field::PATTERNS => patterns = Some(try!(visitor.visit_value()))
+                Field::PATTERNS => patterns = Some(try!(visitor.visit_value())),
+                Field::RULESETS => rulesets = Some(try!(visitor.visit_value())),
+                Field::INCLUDE => includes = Some(try!(visitor.visit_value())),
+                Field::IGNORECASE => ignore_case = Some(try!(visitor.visit_value())),
+                Field::COLLAPSEWHITESPACE => collapse_whitespace = Some(try!(visitor.visit_value())),
             }
         }
 
-        let patterns_final = match patterns {
-            Some(patterns) => patterns,
-            None => try!(visitor.missing_field("patterns")),
+        // A file which has only rulesets or includes other files doesn't need
+        // fallback patterns.
+        let mut patterns_final = match (patterns, rulesets.is_some() || includes.is_some()) {
+            (Some(patterns), _) => patterns,
+            (None, true) => Vec::new(),
+            (None, false) => try!(visitor.missing_field("patterns")),
         };
 
+        for pattern in &mut patterns_final {
+            pattern.set_default_flags(ignore_case, collapse_whitespace);
+        }
+        let mut rulesets = rulesets.unwrap_or_default();
+        for ruleset in &mut rulesets {
+            ruleset.set_default_flags(ignore_case, collapse_whitespace);
+        }
+
         try!(visitor.end());
 
-        Ok(PatternFile { patterns: patterns_final })
+        Ok(PatternFile {
+            patterns: patterns_final,
+            rulesets: rulesets,
+            includes: includes.unwrap_or_default(),
+        })
     }
 }
@@ -1,14 +1,41 @@
 mod deser;
+mod ser;
 mod iter;
+pub mod patterndb;
 
 use matcher::pattern::Pattern;
+use matcher::ruleset::Ruleset;
+
+use serde_json;
+use serde_yaml;
 
 pub struct PatternFile {
+    // The fallback patterns, they match the messages which don't belong to
+    // any ruleset.
     pub patterns: Vec<Pattern>,
+    pub rulesets: Vec<Ruleset>,
+    // The paths (or globs) of other pattern files, relative to this file.
+    pub includes: Vec<String>,
 }
 
 impl PatternFile {
     pub fn patterns(&self) -> &Vec<Pattern> {
         &self.patterns
     }
+
+    pub fn rulesets(&self) -> &[Ruleset] {
+        &self.rulesets
+    }
+
+    pub fn includes(&self) -> &[String] {
+        &self.includes
+    }
+
+    pub fn to_json(&self) -> Result<String, serde_json::Error> {
+        serde_json::to_string_pretty(self)
+    }
+
+    pub fn to_yaml(&self) -> Result<String, serde_yaml::Error> {
+        serde_yaml::to_string(self)
+    }
 }
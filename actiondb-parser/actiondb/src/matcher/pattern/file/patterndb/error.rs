@@ -0,0 +1,65 @@
+use xml::{reader, writer};
+
+use std::fmt;
+use std::error;
+
+#[derive(Debug)]
+pub enum Error {
+    Xml(reader::Error),
+    XmlWriter(writer::Error),
+    MissingAttribute {
+        element: &'static str,
+        attribute: &'static str,
+    },
+    // The root element isn't <patterndb>.
+    NotPatterndb(String),
+}
+
+impl From<reader::Error> for Error {
+    fn from(error: reader::Error) -> Error {
+        Error::Xml(error)
+    }
+}
+
+impl From<writer::Error> for Error {
+    fn from(error: writer::Error) -> Error {
+        Error::XmlWriter(error)
+    }
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> Result<(), fmt::Error> {
+        match *self {
+            Error::Xml(ref error) => error.fmt(formatter),
+            Error::XmlWriter(ref error) => write!(formatter, "Failed to write a patterndb file: {}", error),
+            Error::MissingAttribute { element, attribute } => {
+                write!(formatter,
+                       "Missing attribute in patterndb file: element={} attribute={}",
+                       element,
+                       attribute)
+            }
+            Error::NotPatterndb(ref root) => {
+                write!(formatter, "The root element of a patterndb file must be <patterndb>: root={}", root)
+            }
+        }
+    }
+}
+
+impl error::Error for Error {
+    fn description(&self) -> &str {
+        match *self {
+            Error::Xml(ref error) => error.description(),
+            Error::XmlWriter(ref error) => error.description(),
+            Error::MissingAttribute { .. } => "Missing attribute in patterndb file",
+            Error::NotPatterndb(_) => "The root element of a patterndb file must be <patterndb>",
+        }
+    }
+
+    fn cause(&self) -> Option<&error::Error> {
+        match *self {
+            Error::Xml(ref error) => Some(error),
+            Error::XmlWriter(ref error) => Some(error),
+            _ => None,
+        }
+    }
+}
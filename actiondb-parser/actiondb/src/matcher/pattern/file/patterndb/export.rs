@@ -0,0 +1,343 @@
+// Writes actiondb patterns into a patterndb XML file. The patterns with the
+// same uuid become the patterns of one rule, the optional groups and the
+// literal alternatives are expanded into more patterns.
+
+use xml::writer::{self, EmitterConfig, EventWriter, XmlEvent};
+
+use matcher::compiled_pattern::{self, TokenType};
+use matcher::pattern::Pattern;
+use matcher::pattern::file::PatternFile;
+use matcher::ruleset;
+use parsers::{OptionalParameter, Parser};
+use super::{escape_literal, rule_uuid, Error, Unsupported};
+
+use std::collections::BTreeMap;
+
+pub const PROVIDER: &'static str = "actiondb";
+
+pub struct Export {
+    xml: String,
+    unsupported: Vec<Unsupported>,
+}
+
+impl Export {
+    pub fn xml(&self) -> &str {
+        &self.xml
+    }
+
+    pub fn unsupported(&self) -> &[Unsupported] {
+        &self.unsupported
+    }
+}
+
+struct Writer {
+    writer: EventWriter<Vec<u8>>,
+}
+
+impl Writer {
+    fn new() -> Writer {
+        Writer { writer: EmitterConfig::new().perform_indent(true).create_writer(Vec::new()) }
+    }
+
+    fn start(&mut self, name: &str, attributes: &[(&str, &str)]) -> writer::Result<()> {
+        let element = attributes.iter()
+                                .fold(XmlEvent::start_element(name),
+                                      |element, &(key, value)| element.attr(key, value));
+        self.writer.write(element)
+    }
+
+    fn end(&mut self) -> writer::Result<()> {
+        self.writer.write(XmlEvent::end_element())
+    }
+
+    fn text_element(&mut self, name: &str, attributes: &[(&str, &str)], text: &str) -> writer::Result<()> {
+        try!(self.start(name, attributes));
+        try!(self.writer.write(XmlEvent::characters(text)));
+        self.end()
+    }
+
+    fn into_string(self) -> String {
+        String::from_utf8(self.writer.into_inner()).expect("The XML writer created invalid UTF-8")
+    }
+}
+
+// The parameters of a patterndb parser can't contain `@`.
+fn string_parameter(parser: &Parser, name: &str) -> Result<String, String> {
+    let value = parser.parameters().into_iter().filter_map(|parameter| {
+        match parameter {
+            OptionalParameter::Str(key, value) if key == name => Some(value),
+            _ => None,
+        }
+    }).next();
+    match value {
+        Some(ref value) if value.contains('@') => {
+            Err(format!("the parameter can't contain '@' in patterndb: parser={} {}={}", parser.kind(), name, value))
+        }
+        Some(value) => Ok(value),
+        None => Err(format!("the parser has no {} parameter: parser={}", name, parser.kind())),
+    }
+}
+
+fn translate_parser(parser: &Parser, end_string: Option<&str>) -> Result<String, String> {
+    let has_length_constraint = parser.parameters().iter().any(|parameter| {
+        match *parameter {
+            OptionalParameter::Int(..) => true,
+            _ => false,
+        }
+    });
+    if has_length_constraint {
+        return Err(format!("patterndb doesn't support length constraints: parser={}", parser.kind()));
+    }
+
+    let (kind, parameter) = match parser.kind() {
+        "GREEDY" => {
+            match end_string {
+                Some(end_string) if end_string.contains('@') => {
+                    return Err(format!("the literal after GREEDY can't contain '@' in patterndb: literal={}",
+                                       end_string))
+                }
+                Some(end_string) => ("ESTRING", Some(end_string.to_owned())),
+                None => ("ANYSTRING", None),
+            }
+        }
+        "INT" | "NUMBER" => ("NUMBER", None),
+        "FLOAT" => ("FLOAT", None),
+        "IPV4" => ("IPv4", None),
+        "IPV6" => ("IPv6", None),
+        "IPADDR" => ("IPvANY", None),
+        "MACADDR" => ("MACADDR", None),
+        "HOSTNAME" => ("HOSTNAME", None),
+        "EMAIL" => ("EMAIL", None),
+        "ESTRING" => ("ESTRING", Some(try!(string_parameter(parser, "end")))),
+        "QSTRING" => {
+            let has_parameter = |name: &str| {
+                parser.parameters().iter().any(|parameter| {
+                    match *parameter {
+                        OptionalParameter::Str(key, _) => key == name,
+                        _ => false,
+                    }
+                })
+            };
+            let pairs = if has_parameter("pairs") {
+                try!(string_parameter(parser, "pairs"))
+            } else {
+                String::new()
+            };
+            let quotes = if has_parameter("quotes") {
+                try!(string_parameter(parser, "quotes"))
+            } else if pairs.is_empty() {
+                "\"".to_owned()
+            } else {
+                String::new()
+            };
+            // patterndb has either one quote or one pair of quotes
+            match (quotes.chars().count(), pairs.chars().count()) {
+                (1, 0) => ("QSTRING", Some(quotes)),
+                (0, 2) => ("QSTRING", Some(pairs)),
+                _ => {
+                    return Err(format!("patterndb supports only one quote or one pair of quotes: quotes={} pairs={}",
+                                       quotes,
+                                       pairs))
+                }
+            }
+        }
+        "SET" => ("SET", Some(try!(string_parameter(parser, "set")))),
+        "REGEX" => ("PCRE", Some(try!(string_parameter(parser, "expression")))),
+        kind => return Err(format!("patterndb doesn't support the parser: parser={}", kind)),
+    };
+
+    let name = parser.name().unwrap_or("");
+    match parameter {
+        Some(parameter) => Ok(format!("@{}:{}:{}@", kind, name, parameter)),
+        None if name.is_empty() => Ok(format!("@{}@", kind)),
+        None => Ok(format!("@{}:{}@", kind, name)),
+    }
+}
+
+// A GREEDY parser is followed by its end string, they are translated together
+// into an ESTRING.
+fn translate_variant(variant: &[TokenType]) -> Result<String, String> {
+    let mut translated = String::new();
+    let mut tokens = variant.iter().peekable();
+
+    while let Some(token) = tokens.next() {
+        match *token {
+            TokenType::Literal(ref literal) => translated.push_str(&escape_literal(literal)),
+            TokenType::Parser(ref parser) if parser.kind() == "GREEDY" => {
+                let end_string = match tokens.peek() {
+                    Some(&&TokenType::Literal(ref literal)) => Some(&literal[..]),
+                    Some(_) => return Err("GREEDY must be followed by a literal or be at the end".to_owned()),
+                    None => None,
+                };
+                translated.push_str(&try!(translate_parser(&**parser, end_string)));
+                if end_string.is_some() {
+                    tokens.next();
+                }
+            }
+            TokenType::Parser(ref parser) => translated.push_str(&try!(translate_parser(&**parser, None))),
+            TokenType::Optional(_) | TokenType::Alternatives(_) | TokenType::Choice(_) => {
+                unreachable!("The expanded patterns contain only literals and parsers")
+            }
+        }
+    }
+    Ok(translated)
+}
+
+fn write_rule(writer: &mut Writer, patterns: &[&Pattern], unsupported: &mut Vec<Unsupported>) -> writer::Result<()> {
+    let first = patterns[0];
+    let id = first.uuid().hyphenated().to_string();
+    let mut report = |reason: String| {
+        unsupported.push(Unsupported {
+            rule: id.clone(),
+            reason: reason,
+        })
+    };
+
+    let mut translated = Vec::new();
+    for pattern in patterns {
+        let variants = compiled_pattern::expand(pattern.pattern().clone());
+        let has_int = variants.iter().flat_map(|variant| variant.iter()).any(|token| {
+            match *token {
+                TokenType::Parser(ref parser) => parser.kind() == "INT",
+                _ => false,
+            }
+        });
+        for variant in variants {
+            match translate_variant(&variant) {
+                Ok(variant) => translated.push(variant),
+                Err(reason) => report(reason),
+            }
+        }
+        if has_int {
+            report("INT is written as NUMBER, which matches negative and hexadecimal numbers too".to_owned());
+        }
+        if pattern.ignore_case() || pattern.collapse_whitespace() {
+            report("patterndb doesn't support the ignore_case and collapse_whitespace flags".to_owned());
+        }
+        if let Some(name) = pattern.name() {
+            report(format!("patterndb doesn't support the names of the patterns: name={}", name));
+        }
+        if pattern.priority() != 0 {
+            report(format!("patterndb doesn't support priorities: priority={}", pattern.priority()));
+        }
+    }
+    if translated.is_empty() {
+        report("the rule has no pattern which can be written into patterndb".to_owned());
+        return Ok(());
+    }
+
+    try!(writer.start("rule", &[("provider", PROVIDER), ("id", &id[..])]));
+
+    try!(writer.start("patterns", &[]));
+    for pattern in &translated {
+        try!(writer.text_element("pattern", &[], pattern));
+    }
+    try!(writer.end());
+
+    let test_messages: Vec<_> = patterns.iter().flat_map(|pattern| pattern.test_messages().unwrap_or(&[])).collect();
+    if !test_messages.is_empty() {
+        try!(writer.start("examples", &[]));
+        for test_message in test_messages {
+            try!(writer.start("example", &[]));
+            try!(writer.text_element("test_message", &[], test_message.message()));
+            if !test_message.values().is_empty() {
+                try!(writer.start("test_values", &[]));
+                for (name, value) in test_message.values() {
+                    try!(writer.text_element("test_value", &[("name", name)], value));
+                }
+                try!(writer.end());
+            }
+            try!(writer.end());
+        }
+        try!(writer.end());
+    }
+
+    if let Some(values) = first.values() {
+        try!(writer.start("values", &[]));
+        for (name, value) in values {
+            try!(writer.text_element("value", &[("name", name)], value));
+        }
+        try!(writer.end());
+    }
+
+    if let Some(tags) = first.tags() {
+        try!(writer.start("tags", &[]));
+        for tag in tags {
+            try!(writer.text_element("tag", &[], tag));
+        }
+        try!(writer.end());
+    }
+
+    writer.end()
+}
+
+// Groups the patterns by their uuids, keeping the order of their first
+// occurrences.
+fn group_rules<'a, I>(patterns: I) -> Vec<Vec<&'a Pattern>>
+    where I: Iterator<Item = &'a Pattern>
+{
+    let mut rules: Vec<Vec<&Pattern>> = Vec::new();
+    let mut positions = BTreeMap::new();
+    for pattern in patterns {
+        let pos = *positions.entry(pattern.uuid()).or_insert(rules.len());
+        if pos == rules.len() {
+            rules.push(Vec::new());
+        }
+        rules[pos].push(pattern);
+    }
+    rules
+}
+
+fn write_ruleset(writer: &mut Writer,
+                 name: &str,
+                 programs: &[String],
+                 rules: &[Vec<&Pattern>],
+                 unsupported: &mut Vec<Unsupported>)
+                 -> writer::Result<()> {
+    let id = rule_uuid(name).hyphenated().to_string();
+    try!(writer.start("ruleset", &[("name", name), ("id", &id)]));
+    for program in programs {
+        try!(writer.text_element("pattern", &[], program));
+    }
+    try!(writer.start("rules", &[]));
+    for rule in rules {
+        try!(write_rule(writer, rule, unsupported));
+    }
+    try!(writer.end());
+    writer.end()
+}
+
+// The fallback patterns are written into a ruleset without program patterns,
+// the rulesets of the PROGRAM field into rulesets with the program names as
+// patterns.
+pub fn export(file: &PatternFile) -> Result<Export, Error> {
+    let mut writer = Writer::new();
+    let mut unsupported = Vec::new();
+    try!(writer.start("patterndb", &[("version", "4")]));
+    if !file.patterns().is_empty() || file.rulesets().is_empty() {
+        let rules = group_rules(file.patterns().iter());
+        try!(write_ruleset(&mut writer, PROVIDER, &[], &rules, &mut unsupported));
+    }
+    for ruleset in file.rulesets() {
+        let rules = group_rules(ruleset.patterns().iter());
+        if ruleset.field() != ruleset::DEFAULT_FIELD {
+            for rule in &rules {
+                unsupported.push(Unsupported {
+                    rule: rule[0].uuid().hyphenated().to_string(),
+                    reason: format!("patterndb rulesets can be selected only by {}: field={}",
+                                    ruleset::DEFAULT_FIELD,
+                                    ruleset.field()),
+                });
+            }
+            continue;
+        }
+        let name = format!("{}-{}", PROVIDER, ruleset.values().join("-"));
+        try!(write_ruleset(&mut writer, &name, ruleset.values(), &rules, &mut unsupported));
+    }
+    try!(writer.end());
+
+    Ok(Export {
+        xml: writer.into_string(),
+        unsupported: unsupported,
+    })
+}
@@ -0,0 +1,417 @@
+// Converts syslog-ng's patterndb XML files into actiondb patterns. The
+// patterns are converted into a document in the format of the JSON pattern
+// files, so they can be loaded or written out like any other pattern file.
+
+pub use self::error::Error;
+pub use self::export::{export, Export};
+
+#[cfg(test)]
+mod test;
+mod error;
+mod export;
+
+use serde_json::{self, Value};
+use uuid::Uuid;
+use xml::reader::{EventReader, XmlEvent};
+
+use grammar;
+use matcher::{Matcher, MatcherFactory, Pattern};
+use matcher::pattern::file::PatternFile;
+use matcher::suffix_array::SuffixArrayMatcherFactory;
+
+use std::collections::BTreeMap;
+use std::fmt;
+use std::hash::{SipHasher, Hash, Hasher};
+
+pub const EXTENSION: &'static str = "xml";
+
+const ALPHANUMERIC: &'static str = "abcdefghijklmnopqrstuvwxyzABCDEFGHIJKLMNOPQRSTUVWXYZ0123456789";
+
+// A part of a rule which couldn't be converted. The rest of the rule is
+// converted without it.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Unsupported {
+    rule: String,
+    reason: String,
+}
+
+impl Unsupported {
+    pub fn rule(&self) -> &str {
+        &self.rule
+    }
+
+    pub fn reason(&self) -> &str {
+        &self.reason
+    }
+}
+
+impl fmt::Display for Unsupported {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> Result<(), fmt::Error> {
+        write!(formatter,
+               "Unsupported patterndb rule: rule_id={} reason={}",
+               self.rule,
+               self.reason)
+    }
+}
+
+pub struct Conversion {
+    document: Value,
+    unsupported: Vec<Unsupported>,
+}
+
+impl Conversion {
+    pub fn document(&self) -> &Value {
+        &self.document
+    }
+
+    pub fn unsupported(&self) -> &[Unsupported] {
+        &self.unsupported
+    }
+
+    pub fn into_pattern_file(self) -> Result<PatternFile, serde_json::Error> {
+        serde_json::from_value(self.document)
+    }
+}
+
+#[derive(Debug, Default)]
+struct Element {
+    name: String,
+    attributes: Vec<(String, String)>,
+    children: Vec<Element>,
+    text: String,
+}
+
+impl Element {
+    fn attribute(&self, name: &str) -> Option<&str> {
+        self.attributes.iter().find(|attribute| attribute.0 == name).map(|attribute| &attribute.1[..])
+    }
+
+    fn child(&self, name: &str) -> Option<&Element> {
+        self.children.iter().find(|child| child.name == name)
+    }
+
+    fn children(&self, name: &str) -> Vec<&Element> {
+        self.children.iter().filter(|child| child.name == name).collect()
+    }
+
+    // The children of a wrapper element, e.g. the <tag>s in <tags>.
+    fn grandchildren(&self, wrapper: &str, name: &str) -> Vec<&Element> {
+        self.child(wrapper).map_or(Vec::new(), |wrapper| wrapper.children(name))
+    }
+}
+
+fn read_document(content: &str) -> Result<Element, Error> {
+    let mut stack = vec![Element::default()];
+
+    for event in EventReader::from_str(content) {
+        match try!(event) {
+            XmlEvent::StartElement { name, attributes, .. } => {
+                stack.push(Element {
+                    name: name.local_name,
+                    attributes: attributes.into_iter()
+                                          .map(|attribute| (attribute.name.local_name, attribute.value))
+                                          .collect(),
+                    children: Vec::new(),
+                    text: String::new(),
+                });
+            }
+            XmlEvent::EndElement { .. } => {
+                let element = stack.pop().expect("The XML reader checks that the elements are balanced");
+                stack.last_mut().expect("The root of the document is never popped").children.push(element);
+            }
+            XmlEvent::Characters(text) | XmlEvent::CData(text) | XmlEvent::Whitespace(text) => {
+                if let Some(element) = stack.last_mut() {
+                    element.text.push_str(&text);
+                }
+            }
+            _ => (),
+        }
+    }
+    let document = stack.pop().expect("The root of the document is never popped");
+    document.children.into_iter().next().ok_or(Error::NotPatterndb(String::new()))
+}
+
+// The rule ids are usually uuids. Otherwise an uuid is derived from the id, so
+// it's the same every time the file is converted.
+fn rule_uuid(id: &str) -> Uuid {
+    Uuid::parse_str(id).unwrap_or_else(|_| {
+        let mut bytes = Vec::with_capacity(16);
+        for part in &["high", "low"] {
+            let mut hasher = SipHasher::new();
+            part.hash(&mut hasher);
+            id.hash(&mut hasher);
+            let hash = hasher.finish();
+            bytes.extend((0..8).map(|i| (hash >> (i * 8)) as u8));
+        }
+        Uuid::from_bytes(&bytes).expect("An uuid can be created from 16 bytes")
+    })
+}
+
+fn is_valid_name(name: &str) -> bool {
+    let mut chars = name.chars();
+    chars.next().map_or(false, |c| c.is_ascii_alphabetic() || c == '_' || c == '.') &&
+    chars.all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '.')
+}
+
+// The set parameters can't be escaped in the actiondb grammar.
+fn set_parameter(set: &str) -> Result<String, String> {
+    if set.contains('"') {
+        Err(format!("a set can't contain '\"': set={}", set))
+    } else {
+        Ok(format!("(\"{}\")", set))
+    }
+}
+
+// Translates a parser like `ESTRING:name:end` into the actiondb grammar.
+fn translate_parser(spec: &str) -> Result<String, String> {
+    let mut parts = spec.splitn(3, ':');
+    let kind = parts.next().unwrap_or("");
+    let name = parts.next().unwrap_or("");
+    let parameter = parts.next().unwrap_or("");
+
+    if !name.is_empty() && !is_valid_name(name) {
+        return Err(format!("invalid parser name: name={}", name));
+    }
+    let (actiondb_kind, actiondb_parameter) = match kind {
+        "ANYSTRING" => ("GREEDY", String::new()),
+        "NUMBER" => ("NUMBER", String::new()),
+        "FLOAT" | "DOUBLE" => ("FLOAT", String::new()),
+        "IPv4" => ("IPV4", String::new()),
+        "IPv6" => ("IPV6", String::new()),
+        "IPvANY" => ("IPADDR", String::new()),
+        "MACADDR" => ("MACADDR", String::new()),
+        "HOSTNAME" => ("HOSTNAME", String::new()),
+        "EMAIL" if parameter.is_empty() => ("EMAIL", String::new()),
+        "ESTRING" if !parameter.is_empty() => ("ESTRING", format!("(end={})", grammar::quote_string(parameter))),
+        "QSTRING" => {
+            let mut quotes = parameter.chars();
+            match (quotes.next(), quotes.next(), quotes.next()) {
+                (Some(quote), None, None) => ("QSTRING", format!("(quotes={})", grammar::quote_string(parameter))),
+                (Some(open), Some(close), None) if open == close => {
+                    ("QSTRING", format!("(quotes={})", grammar::quote_string(&open.to_string())))
+                }
+                (Some(_), Some(_), None) => ("QSTRING", format!("(pairs={})", grammar::quote_string(parameter))),
+                _ => return Err(format!("QSTRING must have one quote or one pair of quotes: quotes={}", parameter)),
+            }
+        }
+        "STRING" => ("SET", try!(set_parameter(&format!("{}{}", ALPHANUMERIC, parameter)))),
+        "SET" if !parameter.is_empty() => ("SET", try!(set_parameter(parameter))),
+        "PCRE" if !parameter.is_empty() => {
+            ("REGEX", format!("(\"{}\")", parameter.replace(r#"\""#, "\"").replace('"', r#"\""#)))
+        }
+        _ => return Err(format!("unsupported parser: {}", spec)),
+    };
+
+    if name.is_empty() {
+        Ok(format!("%{{{}{}}}", actiondb_kind, actiondb_parameter))
+    } else {
+        Ok(format!("%{{{}{}:{}}}", actiondb_kind, actiondb_parameter, name))
+    }
+}
+
+// The parsers are between `@` characters, `@@` is a literal `@`.
+fn escape_literal(literal: &str) -> String {
+    literal.replace('@', "@@")
+}
+
+fn translate_pattern(pattern: &str) -> Result<String, String> {
+    let mut translated = String::new();
+    let mut literal = String::new();
+    let mut rest = pattern;
+
+    while let Some(pos) = rest.find('@') {
+        literal.push_str(&rest[..pos]);
+        rest = &rest[pos + 1..];
+        if rest.starts_with('@') {
+            literal.push('@');
+            rest = &rest[1..];
+            continue;
+        }
+        let end = match rest.find('@') {
+            Some(end) => end,
+            None => return Err(format!("unterminated parser: {}", rest)),
+        };
+        translated.push_str(&grammar::escape_literal(&literal));
+        literal.clear();
+        translated.push_str(&try!(translate_parser(&rest[..end])));
+        rest = &rest[end + 1..];
+    }
+    literal.push_str(rest);
+    translated.push_str(&grammar::escape_literal(&literal));
+
+    match grammar::parser::pattern(&translated) {
+        Ok(_) => Ok(translated),
+        Err(error) => Err(format!("the converted pattern is invalid: pattern={} error={}", translated, error)),
+    }
+}
+
+struct Example {
+    message: String,
+    values: BTreeMap<String, Value>,
+}
+
+impl Example {
+    fn to_value(&self) -> Value {
+        let mut object = BTreeMap::new();
+        object.insert("message".to_owned(), Value::String(self.message.clone()));
+        object.insert("values".to_owned(), Value::Object(self.values.clone()));
+        Value::Object(object)
+    }
+}
+
+fn read_examples(rule: &Element) -> Vec<Example> {
+    rule.grandchildren("examples", "example")
+        .iter()
+        .filter_map(|example| {
+            example.child("test_message").map(|message| {
+                let values = example.grandchildren("test_values", "test_value")
+                                    .iter()
+                                    .filter_map(|value| {
+                                        value.attribute("name")
+                                             .map(|name| (name.to_owned(), Value::String(value.text.clone())))
+                                    })
+                                    .collect();
+                Example {
+                    message: message.text.clone(),
+                    values: values,
+                }
+            })
+        })
+        .collect()
+}
+
+fn matches(pattern: &str, message: &str) -> bool {
+    match grammar::parser::pattern(pattern) {
+        Ok(compiled) => {
+            let mut matcher = SuffixArrayMatcherFactory::new_matcher();
+            let mut pattern = Pattern::with_random_uuid();
+            pattern.set_pattern(compiled);
+            matcher.add_pattern(pattern).is_ok() && matcher.parse(message).is_some()
+        }
+        Err(_) => false,
+    }
+}
+
+// The rule becomes an actiondb pattern with the uuid of the rule. If the rule
+// has more patterns, they are listed in the `patterns` field of the actiondb
+// pattern, so they share its uuid. The examples are the test messages of the
+// pattern if any of the patterns matches them.
+fn convert_rule(rule: &Element, patterns: &mut Vec<Value>, unsupported: &mut Vec<Unsupported>) -> Result<(), Error> {
+    let id = try!(rule.attribute("id").ok_or(Error::MissingAttribute {
+        element: "rule",
+        attribute: "id",
+    }));
+    let mut report = |reason: String| {
+        unsupported.push(Unsupported {
+            rule: id.to_owned(),
+            reason: reason,
+        })
+    };
+
+    let mut translated = Vec::new();
+    for pattern in rule.grandchildren("patterns", "pattern") {
+        match translate_pattern(&pattern.text) {
+            Ok(pattern) => translated.push(pattern),
+            Err(reason) => report(reason),
+        }
+    }
+
+    let mut test_messages = Vec::new();
+    for example in read_examples(rule) {
+        if translated.iter().any(|pattern| matches(pattern, &example.message)) {
+            test_messages.push(example.to_value());
+        } else {
+            report(format!("the example doesn't match the converted patterns: message={}", example.message));
+        }
+    }
+
+    let tags: Vec<Value> = rule.grandchildren("tags", "tag").iter().map(|tag| Value::String(tag.text.clone())).collect();
+    // the values are set as they are, so the ones with template references
+    // (e.g. `${HOST}`) would be wrong
+    let mut values = BTreeMap::new();
+    for value in rule.grandchildren("values", "value") {
+        if let Some(name) = value.attribute("name") {
+            if value.text.contains("${") {
+                report(format!("the values can't contain template references: name={} value={}",
+                               name,
+                               value.text));
+            } else {
+                values.insert(name.to_owned(), Value::String(value.text.clone()));
+            }
+        }
+    }
+
+    if translated.is_empty() {
+        return Ok(());
+    }
+    let mut object = BTreeMap::new();
+    object.insert("uuid".to_owned(), Value::String(rule_uuid(id).hyphenated().to_string()));
+    if translated.len() == 1 {
+        object.insert("pattern".to_owned(), Value::String(translated.remove(0)));
+    } else {
+        object.insert("patterns".to_owned(),
+                      Value::Array(translated.into_iter().map(Value::String).collect()));
+    }
+    if !values.is_empty() {
+        object.insert("values".to_owned(), Value::Object(values));
+    }
+    if !tags.is_empty() {
+        object.insert("tags".to_owned(), Value::Array(tags));
+    }
+    if !test_messages.is_empty() {
+        object.insert("test_messages".to_owned(), Value::Array(test_messages));
+    }
+    patterns.push(Value::Object(object));
+    Ok(())
+}
+
+// The program patterns of a ruleset select it by the PROGRAM field. If they
+// contain parsers, the rules of the ruleset become fallback patterns.
+fn read_programs(ruleset: &Element, unsupported: &mut Vec<Unsupported>) -> Vec<Value> {
+    let mut programs = ruleset.children("pattern");
+    programs.extend(ruleset.grandchildren("patterns", "pattern"));
+    if let Some(program) = programs.iter().find(|program| program.text.contains('@')) {
+        unsupported.push(Unsupported {
+            rule: ruleset.attribute("id").or_else(|| ruleset.attribute("name")).unwrap_or("").to_owned(),
+            reason: format!("the program patterns of a ruleset can't contain parsers: pattern={}", program.text),
+        });
+        return Vec::new();
+    }
+    programs.iter().map(|program| Value::String(program.text.clone())).collect()
+}
+
+pub fn convert(content: &str) -> Result<Conversion, Error> {
+    let root = try!(read_document(content));
+    if root.name != "patterndb" {
+        return Err(Error::NotPatterndb(root.name));
+    }
+
+    let mut patterns = Vec::new();
+    let mut rulesets = Vec::new();
+    let mut unsupported = Vec::new();
+    for ruleset in root.children("ruleset") {
+        let programs = read_programs(ruleset, &mut unsupported);
+        let mut ruleset_patterns = Vec::new();
+        for rule in ruleset.grandchildren("rules", "rule") {
+            try!(convert_rule(rule, &mut ruleset_patterns, &mut unsupported));
+        }
+        if programs.is_empty() {
+            patterns.extend(ruleset_patterns);
+        } else {
+            let mut object = BTreeMap::new();
+            object.insert("program".to_owned(), Value::Array(programs));
+            object.insert("patterns".to_owned(), Value::Array(ruleset_patterns));
+            rulesets.push(Value::Object(object));
+        }
+    }
+
+    let mut document = BTreeMap::new();
+    document.insert("patterns".to_owned(), Value::Array(patterns));
+    if !rulesets.is_empty() {
+        document.insert("rulesets".to_owned(), Value::Array(rulesets));
+    }
+    Ok(Conversion {
+        document: Value::Object(document),
+        unsupported: unsupported,
+    })
+}
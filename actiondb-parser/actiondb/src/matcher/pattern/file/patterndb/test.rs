@@ -0,0 +1,265 @@
+use super::{convert, export, translate_pattern, rule_uuid, Error};
+use grammar;
+use matcher::compiled_pattern;
+use matcher::pattern::file::PatternFile;
+use serde_json;
+use uuid::Uuid;
+
+const PATTERNDB: &'static str = r#"<?xml version="1.0" encoding="UTF-8"?>
+<patterndb version="4" pub_date="2016-01-01">
+  <ruleset name="sshd" id="a3e69e50-1d2b-4e32-9c86-0b8e1a0ab3c1">
+    <pattern>sshd</pattern>
+    <rules>
+      <rule provider="balabit" id="9a49c47d-29e9-4072-be84-3b76c6814743" class="system">
+        <patterns>
+          <pattern>Accepted @ESTRING:auth_method: @for @ESTRING:user: @from @IPv4:client_addr@ port @NUMBER:port@ ssh2</pattern>
+          <pattern>Failed @ESTRING:auth_method: @for @ESTRING:user: @from @IPv4:client_addr@ port @NUMBER:port@ ssh2</pattern>
+        </patterns>
+        <examples>
+          <example>
+            <test_message program="sshd">Failed password for root from 10.0.0.1 port 4242 ssh2</test_message>
+            <test_values>
+              <test_value name="user">root</test_value>
+              <test_value name="port">4242</test_value>
+            </test_values>
+          </example>
+        </examples>
+        <values>
+          <value name="usracct.type">login</value>
+          <value name="usracct.device">${HOST}</value>
+        </values>
+        <tags>
+          <tag>usracct</tag>
+          <tag>secinfo</tag>
+        </tags>
+      </rule>
+      <rule provider="balabit" id="ssh-unsupported" class="system">
+        <patterns>
+          <pattern>Connection from @LLADDR:addr@</pattern>
+          <pattern>Connection closed by @ANYSTRING:addr@</pattern>
+        </patterns>
+      </rule>
+    </rules>
+  </ruleset>
+</patterndb>
+"#;
+
+#[test]
+fn test_given_patterndb_parsers_when_they_are_translated_then_we_get_the_equivalent_actiondb_parsers() {
+    assert_eq!(translate_pattern("user @ESTRING:user: @logged in"),
+               Ok(r#"user %{ESTRING(end=" "):user}logged in"#.to_owned()));
+    assert_eq!(translate_pattern("@NUMBER:pid@ @QSTRING:msg:'@ @ANYSTRING@"),
+               Ok(r#"%{NUMBER:pid} %{QSTRING(quotes="'"):msg} %{GREEDY}"#.to_owned()));
+    assert_eq!(translate_pattern("@QSTRING:msg:<>@"),
+               Ok(r#"%{QSTRING(pairs="<>"):msg}"#.to_owned()));
+    assert_eq!(translate_pattern("@QSTRING:msg:''@"),
+               Ok(r#"%{QSTRING(quotes="'"):msg}"#.to_owned()));
+    assert_eq!(translate_pattern("@IPvANY:addr@ @STRING:id:-@"),
+               Ok(format!(r#"%{{IPADDR:addr}} %{{SET("{}-"):id}}"#, super::ALPHANUMERIC)));
+    assert_eq!(translate_pattern(r#"@PCRE:name:[a-z]+\"@"#),
+               Ok(r#"%{REGEX("[a-z]+\""):name}"#.to_owned()));
+}
+
+#[test]
+fn test_given_patterndb_pattern_when_it_contains_escaped_at_signs_or_percent_signs_then_they_remain_literals() {
+    assert_eq!(translate_pattern("mail to root@@localhost: %{foo}"),
+               Ok(r#"mail to root@localhost: \%\{foo}"#.to_owned()));
+}
+
+#[test]
+fn test_given_patterndb_pattern_when_it_contains_unsupported_parsers_then_it_is_not_translated() {
+    assert!(translate_pattern("@LLADDR:addr@").is_err());
+    assert!(translate_pattern("@QSTRING:msg:<>'@").is_err());
+    assert!(translate_pattern("@ESTRING:msg@").is_err());
+    assert!(translate_pattern("@NUMBER:pid").is_err());
+}
+
+#[test]
+fn test_given_rule_id_when_it_is_not_an_uuid_then_the_same_uuid_is_derived_from_it_every_time() {
+    assert_eq!(rule_uuid("9a49c47d-29e9-4072-be84-3b76c6814743"),
+               Uuid::parse_str("9a49c47d-29e9-4072-be84-3b76c6814743").unwrap());
+    assert_eq!(rule_uuid("ssh-unsupported"), rule_uuid("ssh-unsupported"));
+    assert!(rule_uuid("ssh-unsupported") != rule_uuid("ssh-supported"));
+}
+
+#[test]
+fn test_given_patterndb_file_when_it_is_converted_then_every_supported_pattern_is_in_the_pattern_file() {
+    let conversion = convert(PATTERNDB).expect("Failed to convert a valid patterndb file");
+    assert_eq!(conversion.unsupported().len(), 2);
+    assert_eq!(conversion.unsupported()[0].rule(), "9a49c47d-29e9-4072-be84-3b76c6814743");
+    assert_eq!(conversion.unsupported()[0].reason(),
+               "the values can't contain template references: name=usracct.device value=${HOST}");
+    assert_eq!(conversion.unsupported()[1].rule(), "ssh-unsupported");
+
+    let file = conversion.into_pattern_file().expect("Failed to load the converted patterns");
+    assert!(file.patterns().is_empty());
+    assert_eq!(file.rulesets().len(), 1);
+    assert_eq!(file.rulesets()[0].field(), "PROGRAM");
+    assert_eq!(file.rulesets()[0].values(), &["sshd".to_owned()]);
+    let patterns = file.rulesets()[0].patterns();
+    assert_eq!(patterns.len(), 2);
+    assert_eq!(patterns[0].uuid(), &Uuid::parse_str("9a49c47d-29e9-4072-be84-3b76c6814743").unwrap());
+    assert_eq!(compiled_pattern::to_strings(patterns[0].pattern()),
+               vec![r#"Accepted %{ESTRING(end=" "):auth_method}for %{ESTRING(end=" "):user}from "#.to_owned() +
+                    "%{IPV4:client_addr} port %{NUMBER:port} ssh2",
+                    r#"Failed %{ESTRING(end=" "):auth_method}for %{ESTRING(end=" "):user}from "#.to_owned() +
+                    "%{IPV4:client_addr} port %{NUMBER:port} ssh2"]);
+    assert_eq!(patterns[0].name(), None);
+    assert_eq!(patterns[0].tags(), Some(&["usracct".to_owned(), "secinfo".to_owned()][..]));
+    assert_eq!(patterns[0].values().unwrap().get("usracct.type").map(|value| &value[..]),
+               Some("login"));
+    assert_eq!(patterns[0].values().unwrap().get("usracct.device"), None);
+    let test_messages = patterns[0].test_messages().expect("The example wasn't attached to the pattern");
+    assert_eq!(test_messages.len(), 1);
+    assert_eq!(test_messages[0].message(),
+               "Failed password for root from 10.0.0.1 port 4242 ssh2");
+    assert_eq!(patterns[1].uuid(), &rule_uuid("ssh-unsupported"));
+    assert_eq!(compiled_pattern::to_strings(patterns[1].pattern()),
+               vec!["Connection closed by %{GREEDY:addr}".to_owned()]);
+}
+
+#[test]
+fn test_given_xml_file_when_its_root_is_not_patterndb_then_it_is_not_converted() {
+    match convert("<rules></rules>") {
+        Err(Error::NotPatterndb(ref root)) => assert_eq!(root, "rules"),
+        _ => unreachable!(),
+    }
+    assert!(convert("<patterndb>").is_err());
+}
+
+#[test]
+fn test_given_pattern_file_when_it_is_exported_then_the_patterns_are_translated_into_patterndb_syntax() {
+    let json = r#"
+{
+  "patterns": [
+    {
+      "uuid": "9a49c47d-29e9-4072-be84-3b76c6814743",
+      "name": "system",
+      "pattern": "%{ANY(\"Accepted\",\"Failed\")} %{GREEDY:method} for %{INT:uid}@%{HOSTNAME:host}%{? port %{INT:port}}",
+      "tags": ["usracct"]
+    },
+    {
+      "uuid": "fa8bdbcb-e0fd-4da1-9fa4-15ecfec28ad2",
+      "pattern": "session %{HEX:id} closed",
+      "priority": 5
+    }
+  ]
+}
+"#;
+    let file: PatternFile = serde_json::from_str(json).unwrap();
+    let export = export(&file).unwrap();
+    let xml = export.xml();
+
+    assert!(xml.contains("<pattern>Accepted @ESTRING:method: for @@NUMBER:uid@@@@HOSTNAME:host@</pattern>"));
+    assert!(xml.contains("<pattern>Failed @ESTRING:method: for @@NUMBER:uid@@@@HOSTNAME:host@ port \
+                          @NUMBER:port@</pattern>"));
+    assert!(xml.contains("<tag>usracct</tag>"));
+    assert!(!xml.contains("fa8bdbcb-e0fd-4da1-9fa4-15ecfec28ad2"));
+    assert!(!xml.contains("class="));
+
+    let reasons: Vec<&str> = export.unsupported().iter().map(|unsupported| unsupported.reason()).collect();
+    assert_eq!(export.unsupported()[0].rule(), "9a49c47d-29e9-4072-be84-3b76c6814743");
+    assert_eq!(export.unsupported()[1].rule(), "9a49c47d-29e9-4072-be84-3b76c6814743");
+    assert_eq!(reasons[0], "INT is written as NUMBER, which matches negative and hexadecimal numbers too");
+    assert_eq!(reasons[1], "patterndb doesn't support the names of the patterns: name=system");
+    for unsupported in &export.unsupported()[2..] {
+        assert_eq!(unsupported.rule(), "fa8bdbcb-e0fd-4da1-9fa4-15ecfec28ad2");
+    }
+    assert_eq!(reasons.len(), 5);
+    assert!(reasons[2].contains("HEX"));
+}
+
+#[test]
+fn test_given_patterndb_file_when_it_is_imported_and_exported_then_the_patterns_are_the_same() {
+    let file = convert(PATTERNDB).unwrap().into_pattern_file().unwrap();
+    let export = export(&file).unwrap();
+    assert!(export.unsupported().is_empty());
+    assert!(export.xml().contains("<pattern>Accepted @ESTRING:auth_method: @for @ESTRING:user: @from \
+                                   @IPv4:client_addr@ port @NUMBER:port@ ssh2</pattern>"));
+    assert!(export.xml().contains("<pattern>sshd</pattern>"));
+
+    let imported = convert(export.xml()).unwrap().into_pattern_file().unwrap();
+    assert_eq!(imported.rulesets().len(), 1);
+    assert_eq!(imported.rulesets()[0].values(), file.rulesets()[0].values());
+    let patterns = file.rulesets()[0].patterns();
+    let imported = imported.rulesets()[0].patterns();
+    assert_eq!(imported.len(), patterns.len());
+    for (pattern, imported) in patterns.iter().zip(imported) {
+        assert_eq!(imported.uuid(), pattern.uuid());
+        assert_eq!(compiled_pattern::to_strings(imported.pattern()),
+                   compiled_pattern::to_strings(pattern.pattern()));
+        assert_eq!(imported.values(), pattern.values());
+        assert_eq!(imported.tags(), pattern.tags());
+        assert_eq!(imported.test_messages().map(|messages| messages.len()),
+                   pattern.test_messages().map(|messages| messages.len()));
+    }
+    let test_message = &patterns[0].test_messages().unwrap()[0];
+    assert_eq!(imported[0].test_messages().unwrap()[0].message(), test_message.message());
+    assert_eq!(imported[0].test_messages().unwrap()[0].values(), test_message.values());
+}
+
+#[test]
+fn test_given_pattern_with_int_parser_when_it_is_exported_then_the_conversion_to_number_is_reported() {
+    let json = r#"{"patterns": [{"uuid": "9a49c47d-29e9-4072-be84-3b76c6814743", "pattern": "pid %{INT:pid}"}]}"#;
+    let file: PatternFile = serde_json::from_str(json).unwrap();
+    let export = export(&file).unwrap();
+    assert!(export.xml().contains("<pattern>pid @NUMBER:pid@</pattern>"));
+    assert_eq!(export.unsupported().len(), 1);
+    assert_eq!(export.unsupported()[0].reason(),
+               "INT is written as NUMBER, which matches negative and hexadecimal numbers too");
+}
+
+#[test]
+fn test_given_pattern_with_qstring_parser_when_it_is_exported_then_one_quote_or_one_pair_of_quotes_is_written() {
+    let json = r#"{"patterns": [
+        {"uuid": "9a49c47d-29e9-4072-be84-3b76c6814743", "pattern": "a %{QSTRING:a}"},
+        {"uuid": "d8ba7a64-1b35-4e43-8cb9-6c7bfb11b0a4", "pattern": "b %{QSTRING(pairs=\"<>\"):b}"},
+        {"uuid": "1c0c4e7e-4d5a-4b54-a7c0-28e5d2b0ba1b", "pattern": "c %{QSTRING(quotes=\"'\",pairs=\"<>\"):c}"}
+    ]}"#;
+    let file: PatternFile = serde_json::from_str(json).unwrap();
+    let export = export(&file).unwrap();
+    assert!(export.xml().contains("<pattern>a @QSTRING:a:\"@</pattern>"));
+    assert!(export.xml().contains("<pattern>b @QSTRING:b:&lt;&gt;@</pattern>"));
+    assert_eq!(export.unsupported().len(), 2);
+    assert_eq!(export.unsupported()[0].reason(),
+               "patterndb supports only one quote or one pair of quotes: quotes=' pairs=<>");
+}
+
+#[test]
+fn test_given_patterndb_set_when_it_contains_backslashes_then_they_are_kept() {
+    let translated = translate_pattern(r"@SET:chars:\ab@").unwrap();
+    assert_eq!(translated, r#"%{SET("\ab"):chars}"#);
+    let compiled = grammar::parser::pattern(&translated).unwrap();
+    assert_eq!(compiled_pattern::to_string(&compiled), translated);
+}
+
+#[test]
+fn test_given_pattern_with_non_ascii_set_when_it_is_exported_and_imported_then_the_set_is_the_same() {
+    let json = r#"{"patterns": [{"uuid": "9a49c47d-29e9-4072-be84-3b76c6814743", "pattern": "name %{SET(\"áé\"):name}"}]}"#;
+    let file: PatternFile = serde_json::from_str(json).unwrap();
+    let export = export(&file).unwrap();
+    assert!(export.unsupported().is_empty());
+    assert!(export.xml().contains("<pattern>name @SET:name:áé@</pattern>"));
+
+    let imported = convert(export.xml()).unwrap().into_pattern_file().unwrap();
+    let json = serde_json::to_string(&imported).unwrap();
+    let imported: PatternFile = serde_json::from_str(&json).unwrap();
+    assert_eq!(compiled_pattern::to_strings(imported.patterns()[0].pattern()),
+               vec![r#"name %{SET("áé"):name}"#.to_owned()]);
+}
+
+#[test]
+fn test_given_patterndb_set_when_it_contains_quotes_then_it_cant_be_translated() {
+    assert!(translate_pattern(r#"@SET:chars:"'@"#).is_err());
+    assert!(translate_pattern(r#"@STRING:id:"@"#).is_err());
+}
+
+#[test]
+fn test_given_patterndb_ruleset_when_its_program_pattern_contains_parsers_then_its_rules_are_fallback_patterns() {
+    let patterndb = PATTERNDB.replace("<pattern>sshd</pattern>", "<pattern>sshd@NUMBER@</pattern>");
+    let conversion = convert(&patterndb).unwrap();
+    assert_eq!(conversion.unsupported()[0].rule(), "a3e69e50-1d2b-4e32-9c86-0b8e1a0ab3c1");
+    let file = conversion.into_pattern_file().unwrap();
+    assert!(file.rulesets().is_empty());
+    assert_eq!(file.patterns().len(), 2);
+}
@@ -0,0 +1,45 @@
+use serde;
+
+use super::PatternFile;
+
+impl serde::Serialize for PatternFile {
+    fn serialize<S>(&self, serializer: &mut S) -> Result<(), S::Error>
+        where S: serde::Serializer
+    {
+        serializer.serialize_struct("File", FileMapVisitor { value: self, state: 0 })
+    }
+}
+
+struct FileMapVisitor<'a> {
+    value: &'a PatternFile,
+    state: u8,
+}
+
+impl<'a> serde::ser::MapVisitor for FileMapVisitor<'a> {
+    fn visit<S>(&mut self, serializer: &mut S) -> Result<Option<()>, S::Error>
+        where S: serde::Serializer
+    {
+        loop {
+            self.state += 1;
+            match self.state {
+                1 => {
+                    try!(serializer.serialize_struct_elt("patterns", &self.value.patterns));
+                    return Ok(Some(()));
+                }
+                2 => {
+                    if !self.value.rulesets.is_empty() {
+                        try!(serializer.serialize_struct_elt("rulesets", &self.value.rulesets));
+                        return Ok(Some(()));
+                    }
+                }
+                3 => {
+                    if !self.value.includes.is_empty() {
+                        try!(serializer.serialize_struct_elt("include", &self.value.includes));
+                        return Ok(Some(()));
+                    }
+                }
+                _ => return Ok(None),
+            }
+        }
+    }
+}
@@ -3,6 +3,7 @@ pub use self::source::PatternSource;
 #[cfg(test)]
 mod test;
 mod deser;
+mod ser;
 pub mod source;
 pub mod file;
 pub mod testmessage;
@@ -10,10 +11,13 @@ pub mod testmessage;
 use uuid::Uuid;
 use serde_json;
 
-use matcher::compiled_pattern::{CompiledPattern, TokenType};
+use matcher::BuildError;
+use matcher::compiled_pattern::{self, CompiledPattern, TokenType};
 use self::testmessage::TestMessage;
+use parsers::{LiteralParser, Parser};
 
 use std::borrow::Borrow;
+use std::mem;
 use std::collections::BTreeMap;
 
 #[derive(Clone, Debug)]
@@ -24,6 +28,9 @@ pub struct Pattern {
     values: Option<BTreeMap<String, String>>,
     tags: Option<Vec<String>>,
     test_messages: Option<Vec<TestMessage>>,
+    ignore_case: Option<bool>,
+    collapse_whitespace: Option<bool>,
+    priority: i32,
 }
 
 impl Pattern {
@@ -45,6 +52,9 @@ impl Pattern {
             values: values,
             tags: tags,
             test_messages: test_messages,
+            ignore_case: None,
+            collapse_whitespace: None,
+            priority: 0,
         }
     }
 
@@ -72,6 +82,43 @@ impl Pattern {
         self.tags.as_ref().map(|tags| tags.borrow())
     }
 
+    pub fn test_messages(&self) -> Option<&[TestMessage]> {
+        self.test_messages.as_ref().map(|test_messages| test_messages.borrow())
+    }
+
+    pub fn ignore_case(&self) -> bool {
+        self.ignore_case.unwrap_or(false)
+    }
+
+    pub fn set_ignore_case(&mut self, ignore_case: bool) {
+        self.ignore_case = Some(ignore_case);
+    }
+
+    pub fn collapse_whitespace(&self) -> bool {
+        self.collapse_whitespace.unwrap_or(false)
+    }
+
+    pub fn set_collapse_whitespace(&mut self, collapse_whitespace: bool) {
+        self.collapse_whitespace = Some(collapse_whitespace);
+    }
+
+    // When more patterns match a message, the one with the highest priority is
+    // chosen. The default priority is 0.
+    pub fn priority(&self) -> i32 {
+        self.priority
+    }
+
+    pub fn set_priority(&mut self, priority: i32) {
+        self.priority = priority;
+    }
+
+    // The flags of the pattern file are used only if the pattern doesn't set
+    // them.
+    pub fn set_default_flags(&mut self, ignore_case: Option<bool>, collapse_whitespace: Option<bool>) {
+        self.ignore_case = self.ignore_case.or(ignore_case);
+        self.collapse_whitespace = self.collapse_whitespace.or(collapse_whitespace);
+    }
+
     pub fn from_json(doc: &str) -> Result<Pattern, serde_json::error::Error> {
         serde_json::from_str::<Pattern>(doc)
     }
@@ -80,6 +127,54 @@ impl Pattern {
         self.pattern = pattern;
     }
 
+    // Returns one pattern for every variant of the optional groups and the
+    // literal alternatives. They share the uuid and the test messages. The
+    // literals which are affected by the `ignore_case` or the
+    // `collapse_whitespace` flags are replaced by parsers, so the exact
+    // literals of other patterns are tried before them.
+    pub fn expand(mut self) -> Result<Vec<Pattern>, BuildError> {
+        if compiled_pattern::variant_count(&self.pattern) > compiled_pattern::MAX_VARIANTS {
+            return Err(BuildError::TooManyVariants {
+                uuid: self.uuid,
+                max: compiled_pattern::MAX_VARIANTS,
+            });
+        }
+        let variants = compiled_pattern::expand(mem::replace(&mut self.pattern, Vec::new()));
+        let variants: Vec<Pattern> = variants.into_iter()
+                                             .map(|variant| {
+                                                 let mut pattern = self.clone();
+                                                 pattern.pattern = variant.into_iter()
+                                                                          .map(|token| self.loose_literal(token))
+                                                                          .collect();
+                                                 pattern
+                                             })
+                                             .collect();
+        if variants.iter().any(|variant| !compiled_pattern::is_expanded(&variant.pattern)) {
+            return Err(BuildError::UnexpandedPattern(self.uuid));
+        }
+        Ok(variants)
+    }
+
+    fn loose_literal(&self, token: TokenType) -> TokenType {
+        match token {
+            TokenType::Literal(literal) => {
+                let ignore_case = self.ignore_case() &&
+                                  literal.chars().any(|c| c.is_lowercase() || c.is_uppercase());
+                let collapse_whitespace = self.collapse_whitespace() &&
+                                          literal.chars().any(char::is_whitespace);
+                if ignore_case || collapse_whitespace {
+                    let mut parser = LiteralParser::new(literal);
+                    parser.set_ignore_case(ignore_case);
+                    parser.set_collapse_whitespace(collapse_whitespace);
+                    TokenType::Parser(Box::new(parser) as Box<Parser>)
+                } else {
+                    TokenType::Literal(literal)
+                }
+            }
+            token => token,
+        }
+    }
+
     pub fn pop_first_token(&mut self) -> Option<TokenType> {
         if self.pattern.is_empty() {
             None
@@ -0,0 +1,102 @@
+use super::Pattern;
+use grammar;
+use matcher::compiled_pattern;
+
+use serde;
+
+impl serde::Serialize for Pattern {
+    fn serialize<S>(&self, serializer: &mut S) -> Result<(), S::Error>
+        where S: serde::Serializer
+    {
+        let patterns = compiled_pattern::to_strings(&self.pattern);
+        for pattern in &patterns {
+            if let Err(err) = grammar::parser::pattern(pattern) {
+                return Err(serde::ser::Error::custom(format!("The pattern can't be written in the pattern \
+                                                              language: uuid={} pattern={:?} error={}",
+                                                             self.uuid,
+                                                             pattern,
+                                                             err)));
+            }
+        }
+
+        serializer.serialize_struct("Pattern",
+                                    PatternMapVisitor {
+                                        value: self,
+                                        patterns: patterns,
+                                        state: 0,
+                                    })
+    }
+}
+
+struct PatternMapVisitor<'a> {
+    value: &'a Pattern,
+    patterns: Vec<String>,
+    state: u8,
+}
+
+impl<'a> serde::ser::MapVisitor for PatternMapVisitor<'a> {
+    fn visit<S>(&mut self, serializer: &mut S) -> Result<Option<()>, S::Error>
+        where S: serde::Serializer
+    {
+        loop {
+            self.state += 1;
+            match self.state {
+                1 => {
+                    if let Some(ref name) = self.value.name {
+                        try!(serializer.serialize_struct_elt("name", name));
+                        return Ok(Some(()));
+                    }
+                }
+                2 => {
+                    try!(serializer.serialize_struct_elt("uuid", self.value.uuid));
+                    return Ok(Some(()));
+                }
+                3 => {
+                    if compiled_pattern::choices(&self.value.pattern).is_some() {
+                        try!(serializer.serialize_struct_elt("patterns", &self.patterns));
+                    } else {
+                        try!(serializer.serialize_struct_elt("pattern", &self.patterns[0]));
+                    }
+                    return Ok(Some(()));
+                }
+                4 => {
+                    if let Some(ref values) = self.value.values {
+                        try!(serializer.serialize_struct_elt("values", values));
+                        return Ok(Some(()));
+                    }
+                }
+                5 => {
+                    if let Some(ref tags) = self.value.tags {
+                        try!(serializer.serialize_struct_elt("tags", tags));
+                        return Ok(Some(()));
+                    }
+                }
+                6 => {
+                    if let Some(ref test_messages) = self.value.test_messages {
+                        try!(serializer.serialize_struct_elt("test_messages", test_messages));
+                        return Ok(Some(()));
+                    }
+                }
+                7 => {
+                    if let Some(ignore_case) = self.value.ignore_case {
+                        try!(serializer.serialize_struct_elt("ignore_case", ignore_case));
+                        return Ok(Some(()));
+                    }
+                }
+                8 => {
+                    if let Some(collapse_whitespace) = self.value.collapse_whitespace {
+                        try!(serializer.serialize_struct_elt("collapse_whitespace", collapse_whitespace));
+                        return Ok(Some(()));
+                    }
+                }
+                9 => {
+                    if self.value.priority != 0 {
+                        try!(serializer.serialize_struct_elt("priority", self.value.priority));
+                        return Ok(Some(()));
+                    }
+                }
+                _ => return Ok(None),
+            }
+        }
+    }
+}
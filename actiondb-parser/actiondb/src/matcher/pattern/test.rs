@@ -1,4 +1,5 @@
 use super::Pattern;
+use matcher::compiled_pattern::TokenType;
 use uuid::Uuid;
 
 #[test]
@@ -86,6 +87,23 @@ fn test_given_json_pattern_when_its_pattern_is_invalid_then_pattern_cannot_be_bu
                 .expect("We created a Pattern with an invalid pattern field");
 }
 
+#[test]
+fn test_given_json_pattern_when_its_regex_cannot_be_compiled_then_the_error_contains_the_uuid() {
+    let buffer = r#"
+{
+  "uuid": "9a49c47d-29e9-4072-be84-3b76c6814743",
+  "pattern": "ticket %{REGEX(\"[A-Z\"):ticket} closed"
+}
+"#;
+
+    let err = Pattern::from_json(buffer)
+                  .err()
+                  .expect("We created a Pattern with an invalid regular expression");
+    assert_eq!(err.to_string().contains("9a49c47d-29e9-4072-be84-3b76c6814743"), true);
+    assert_eq!(err.to_string().contains("valid regular expression"), true);
+    assert_eq!(err.to_string().contains("unclosed character class"), true);
+}
+
 #[test]
 fn test_given_json_pattern_when_test_messages_are_specified_then_they_are_parsed() {
     let buffer = r#"
@@ -163,3 +181,113 @@ fn test_invalid_uuid_wont_make_uuid_library_panic_during_deserialization() {
 
     let _ = Pattern::from_json(buffer).err().expect("We should not panic when 'uuid' field is invalid");
 }
+
+#[test]
+fn test_given_json_pattern_when_its_literal_flags_are_set_then_they_are_deserialized() {
+    let buffer = r#"
+{
+  "uuid": "9a49c47d-29e9-4072-be84-3b76c6814743",
+  "pattern": "Jun %{INT:day}",
+  "ignore_case": true,
+  "collapse_whitespace": false
+}
+"#;
+
+    let mut pattern = Pattern::from_json(buffer).expect("Failed to deserialize a JSON Pattern with flags");
+    assert_eq!(pattern.ignore_case(), true);
+    assert_eq!(pattern.collapse_whitespace(), false);
+
+    pattern.set_default_flags(Some(false), Some(true));
+    assert_eq!(pattern.ignore_case(), true);
+    assert_eq!(pattern.collapse_whitespace(), false);
+}
+
+#[test]
+fn test_given_pattern_with_ignore_case_when_it_is_expanded_then_only_the_affected_literals_are_replaced
+    () {
+    let buffer = r#"
+{
+  "uuid": "9a49c47d-29e9-4072-be84-3b76c6814743",
+  "pattern": "Jun %{INT:day}: 12",
+  "ignore_case": true
+}
+"#;
+
+    let pattern = Pattern::from_json(buffer).expect("Failed to deserialize a JSON Pattern");
+    let variants = pattern.expand().expect("Failed to expand a pattern");
+    assert_eq!(variants.len(), 1);
+    match variants[0].pattern()[..] {
+        [TokenType::Parser(_), TokenType::Parser(_), TokenType::Literal(ref literal)] => {
+            assert_eq!(literal, ": 12");
+        }
+        _ => unreachable!(),
+    }
+}
+
+#[test]
+fn test_given_json_pattern_when_its_priority_is_set_then_it_is_deserialized() {
+    let json = r#"
+{
+  "uuid": "9a49c47d-29e9-4072-be84-3b76c6814743",
+  "pattern": "%{GREEDY:message}",
+  "priority": -5
+}
+"#;
+    let pattern = serde_json::from_str::<Pattern>(json).expect("Failed to deserialize a pattern with priority");
+    assert_eq!(pattern.priority(), -5);
+    assert_eq!(Pattern::with_random_uuid().priority(), 0);
+}
+
+#[test]
+fn test_given_pattern_when_it_is_serialized_then_it_is_deserialized_into_the_same_pattern() {
+    let json = r#"
+{
+  "uuid": "9a49c47d-29e9-4072-be84-3b76c6814743",
+  "name": "SSH",
+  "pattern": "%{ANY(\"Accepted\",\"Failed\")} for %{ESTRING(end=\" \"):user}from%{? port %{INT:port}}",
+  "values": {"key": "value"},
+  "tags": ["tag1"],
+  "test_messages": [{"message": "Failed for root from port 22", "values": {"user": "root"}}],
+  "ignore_case": true,
+  "priority": 3
+}
+"#;
+    let pattern = Pattern::from_json(json).expect("Failed to deserialize a JSON Pattern");
+    let serialized = serde_json::to_string(&pattern).expect("Failed to serialize a pattern");
+    let deserialized = Pattern::from_json(&serialized).expect("Failed to deserialize a serialized pattern");
+
+    assert_eq!(serde_json::to_string(&deserialized).unwrap(), serialized);
+    assert_eq!(deserialized.uuid(), pattern.uuid());
+    assert_eq!(deserialized.name(), Some("SSH"));
+    assert_eq!(deserialized.ignore_case(), true);
+    assert_eq!(deserialized.priority(), 3);
+    assert!(serialized.contains(r#""pattern":"%{ANY(\"Accepted\",\"Failed\")} for %{ESTRING(end=\" \"):user}from%{? port %{INT:port}}""#));
+}
+
+#[test]
+fn test_given_pattern_when_it_cannot_be_written_in_the_pattern_language_then_it_is_not_serialized() {
+    let pattern = Pattern::with_random_uuid();
+    assert!(serde_json::to_string(&pattern).is_err());
+}
+
+#[test]
+fn test_given_pattern_with_more_patterns_when_it_is_expanded_then_every_pattern_is_a_variant_with_its_uuid() {
+    let json = r#"
+{
+  "uuid": "9a49c47d-29e9-4072-be84-3b76c6814743",
+  "patterns": ["Accepted for %{GREEDY:user}", "Failed for %{GREEDY:user}"]
+}
+"#;
+    let pattern = Pattern::from_json(json).expect("Failed to deserialize a pattern with more patterns");
+    let serialized = serde_json::to_string(&pattern).expect("Failed to serialize a pattern");
+    assert!(serialized.contains(r#""patterns":["Accepted for %{GREEDY:user}","Failed for %{GREEDY:user}"]"#));
+
+    let uuid = *pattern.uuid();
+    let variants = pattern.expand().unwrap();
+    assert_eq!(variants.len(), 2);
+    assert!(variants.iter().all(|variant| variant.uuid() == &uuid));
+    assert!(Pattern::from_json(r#"{"uuid": "9a49c47d-29e9-4072-be84-3b76c6814743", "patterns": []}"#).is_err());
+    assert!(Pattern::from_json(r#"{"uuid": "9a49c47d-29e9-4072-be84-3b76c6814743", "pattern": "a",
+                                   "patterns": ["b"]}"#)
+                .is_err());
+}
@@ -9,12 +9,14 @@ use super::TestMessage;
 pub enum Error {
     ValueNotMatch {
         pattern_uuid: String,
+        message: String,
         key: String,
         expected_value: String,
         got_value: String,
     },
     KeyNotFound {
         pattern_uuid: String,
+        message: String,
         key: String,
     },
     TestMessageDoesntMatch {
@@ -28,6 +30,7 @@ pub enum Error {
     },
     UnexpectedTags {
         pattern_uuid: String,
+        message: String,
         expected: Option<Vec<String>>,
         got: Option<Vec<String>>,
     },
@@ -35,21 +38,24 @@ pub enum Error {
 
 impl Error {
     pub fn value_not_match(pattern_uuid: &Uuid,
+                           test_message: &str,
                            key: &str,
                            expected_value: &str,
                            got_value: &str)
                            -> Error {
         Error::ValueNotMatch {
             pattern_uuid: pattern_uuid.hyphenated().to_string(),
+            message: test_message.to_owned(),
             key: key.to_owned(),
             expected_value: expected_value.to_owned(),
             got_value: got_value.to_owned(),
         }
     }
 
-    pub fn key_not_found(pattern_uuid: &Uuid, key: &str) -> Error {
+    pub fn key_not_found(pattern_uuid: &Uuid, test_message: &str, key: &str) -> Error {
         Error::KeyNotFound {
             pattern_uuid: pattern_uuid.hyphenated().to_string(),
+            message: test_message.to_owned(),
             key: key.to_owned(),
         }
     }
@@ -73,33 +79,61 @@ impl Error {
     }
 
     pub fn unexpected_tags(pattern_uuid: &Uuid,
+                           test_message: &str,
                            expected: Option<Vec<String>>,
                            got: Option<Vec<String>>)
                            -> Error {
         Error::UnexpectedTags {
             pattern_uuid: pattern_uuid.hyphenated().to_string(),
+            message: test_message.to_owned(),
             expected: expected,
             got: got,
         }
     }
 }
 
+impl Error {
+    // The uuid of the pattern which the test message belongs to.
+    pub fn pattern_uuid(&self) -> &str {
+        match *self {
+            Error::ValueNotMatch { ref pattern_uuid, .. } |
+            Error::KeyNotFound { ref pattern_uuid, .. } |
+            Error::TestMessageDoesntMatch { ref pattern_uuid, .. } |
+            Error::UnexpectedTags { ref pattern_uuid, .. } => pattern_uuid,
+            Error::MatchedToOtherPattern { ref expected_uuid, .. } => expected_uuid,
+        }
+    }
+
+    // The test message which caused the error.
+    pub fn message(&self) -> &str {
+        match *self {
+            Error::ValueNotMatch { ref message, .. } |
+            Error::KeyNotFound { ref message, .. } |
+            Error::TestMessageDoesntMatch { ref message, .. } |
+            Error::MatchedToOtherPattern { ref message, .. } |
+            Error::UnexpectedTags { ref message, .. } => message,
+        }
+    }
+}
+
 impl fmt::Display for Error {
     fn fmt(&self, fmt: &mut fmt::Formatter) -> Result<(), fmt::Error> {
         match *self {
-            Error::ValueNotMatch{ref pattern_uuid, ref key, ref expected_value, ref got_value} => {
+            Error::ValueNotMatch{ref pattern_uuid, ref message, ref key, ref expected_value, ref got_value} => {
                 fmt.write_fmt(format_args!("A parsed value does not equal to its expected value: \
-                                            uuid={} key={} expected={} got={}",
+                                            uuid={} key={} expected={} got={} test_message='{}'",
                                            pattern_uuid,
                                            key,
                                            expected_value,
-                                           got_value))
+                                           got_value,
+                                           message))
             }
-            Error::KeyNotFound{ref pattern_uuid, ref key} => {
+            Error::KeyNotFound{ref pattern_uuid, ref message, ref key} => {
                 fmt.write_fmt(format_args!("A parsed key in not found among the expected ones: \
-                                            uuid={} key={}",
+                                            uuid={} key={} test_message='{}'",
                                            pattern_uuid,
-                                           key))
+                                           key,
+                                           message))
             }
             Error::TestMessageDoesntMatch{ref pattern_uuid, ref message} => {
                 fmt.write_fmt(format_args!("A test message did not match its pattern: uuid={} \
@@ -114,13 +148,14 @@ impl fmt::Display for Error {
                                            got_uuid,
                                            message))
             }
-            Error::UnexpectedTags{ref pattern_uuid, ref expected, ref got} => {
+            Error::UnexpectedTags{ref pattern_uuid, ref message, ref expected, ref got} => {
                 fmt.write_fmt(format_args!("Unexpected tags found either in the parse result or \
                                             among the expected ones: uuid={} expected: {:?} \
-                                            got={:?}",
+                                            got={:?} test_message='{}'",
                                            pattern_uuid,
                                            expected,
-                                           got))
+                                           got,
+                                           message))
             }
         }
     }
@@ -44,12 +44,13 @@ impl TestMessage {
         let merged_values = TestMessage::merge_values(result);
 
         for (key, value) in self.values() {
-            try!(TestMessage::test_value(key, value, &merged_values, result));
+            try!(self.test_value(key, value, &merged_values, result));
         }
         Ok(())
     }
 
-    fn test_value(key: &str,
+    fn test_value(&self,
+                  key: &str,
                   value: &str,
                   values: &BTreeMap<&str, &str>,
                   result: &MatchResult)
@@ -59,10 +60,10 @@ impl TestMessage {
             if value == got_value {
                 Ok(())
             } else {
-                Err(Error::value_not_match(result.pattern().uuid(), key, value, got_value))
+                Err(Error::value_not_match(result.pattern().uuid(), self.message(), key, value, got_value))
             }
         } else {
-            Err(Error::key_not_found(result.pattern().uuid(), key))
+            Err(Error::key_not_found(result.pattern().uuid(), self.message(), key))
         }
     }
 
@@ -77,6 +78,9 @@ impl TestMessage {
         for (key, value) in result.values() {
             merged_values.insert(key, value);
         }
+        for (key, value) in result.pairs() {
+            merged_values.insert(key, value);
+        }
 
         merged_values
     }
@@ -110,6 +114,6 @@ impl TestMessage {
     fn report_unexpected_tags_error(&self, result: &MatchResult) -> Error {
         let expected = self.tags().map(|tags| tags.to_vec());
         let got = result.pattern().tags().map(|tags| tags.to_vec());
-        Error::unexpected_tags(result.pattern().uuid(), expected, got)
+        Error::unexpected_tags(result.pattern().uuid(), self.message(), expected, got)
     }
 }
@@ -4,5 +4,6 @@ pub use self::message::TestMessage;
 #[cfg(test)]
 mod test;
 mod deser;
+mod ser;
 mod error;
 mod message;
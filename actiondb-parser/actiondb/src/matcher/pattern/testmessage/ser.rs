@@ -0,0 +1,48 @@
+use super::TestMessage;
+use serde;
+
+impl serde::Serialize for TestMessage {
+    fn serialize<S>(&self, serializer: &mut S) -> Result<(), S::Error>
+        where S: serde::Serializer
+    {
+        serializer.serialize_struct("TestMessage",
+                                    TestMessageMapVisitor {
+                                        value: self,
+                                        state: 0,
+                                    })
+    }
+}
+
+struct TestMessageMapVisitor<'a> {
+    value: &'a TestMessage,
+    state: u8,
+}
+
+impl<'a> serde::ser::MapVisitor for TestMessageMapVisitor<'a> {
+    fn visit<S>(&mut self, serializer: &mut S) -> Result<Option<()>, S::Error>
+        where S: serde::Serializer
+    {
+        loop {
+            self.state += 1;
+            match self.state {
+                1 => {
+                    try!(serializer.serialize_struct_elt("message", self.value.message()));
+                    return Ok(Some(()));
+                }
+                2 => {
+                    if !self.value.values().is_empty() {
+                        try!(serializer.serialize_struct_elt("values", self.value.values()));
+                        return Ok(Some(()));
+                    }
+                }
+                3 => {
+                    if let Some(tags) = self.value.tags() {
+                        try!(serializer.serialize_struct_elt("tags", tags));
+                        return Ok(Some(()));
+                    }
+                }
+                _ => return Ok(None),
+            }
+        }
+    }
+}
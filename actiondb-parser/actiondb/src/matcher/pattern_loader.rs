@@ -1,17 +1,37 @@
 use super::pattern::file::PatternFile;
+use super::pattern::file::patterndb;
 use matcher::BuildError;
-use matcher::MatcherFactory;
+use matcher::{Matcher, MatcherFactory, Pattern};
 use matcher::FromPatternSource;
+use matcher::cache;
+use matcher::pattern::source::BuildResult;
+use matcher::pattern_source::{location, TestMessages};
+use matcher::ruleset::RulesetMatcher;
 
-use std::path;
-use std::fs::File;
+use uuid::Uuid;
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::collections::btree_map::Entry;
+use std::path::{self, Path, PathBuf};
+use std::fs::{self, File};
 use std::io::Read;
+use std::iter;
 
 use serde_json;
 use serde_yaml;
 
+const EXTENSIONS: &'static [&'static str] = &["json", "yaml", "yml", "YAML", "YML", patterndb::EXTENSION];
+
 pub struct PatternLoader;
 
+// The paths which were expanded while the pattern files were loaded (the given
+// path and the includes) with the files they were expanded to, and the loaded
+// files in loading order.
+pub struct Sources {
+    pub inputs: Vec<(String, Vec<PathBuf>)>,
+    pub files: Vec<(String, PatternFile)>,
+}
+
 impl PatternLoader {
     fn read(pattern_file_path: &str) -> Result<String, BuildError>
     {
@@ -24,15 +44,80 @@ impl PatternLoader {
     pub fn from_file_ignore_errors<F>(pattern_file_path: &str) -> Result<F::Matcher, BuildError>
         where F: MatcherFactory
     {
-        let file = try!(PatternLoader::load_file(pattern_file_path));
-        Ok(F::Matcher::from_source_ignore_errors::<F>(&mut file.into_iter()))
+        if cache::is_cache_file(pattern_file_path) {
+            return F::from_cache(pattern_file_path);
+        }
+        let files = try!(PatternLoader::load_files(pattern_file_path));
+        build_matcher::<F>(files, true)
     }
 
+    // Every pattern is loaded into one matcher, the pattern files with
+    // rulesets are rejected.
     pub fn from_file<F>(pattern_file_path: &str) -> Result<F::Matcher, BuildError>
         where F: MatcherFactory
     {
-        let file = try!(PatternLoader::load_file(pattern_file_path));
-        F::Matcher::from_source::<F>(&mut file.into_iter())
+        if cache::is_cache_file(pattern_file_path) {
+            return F::from_cache(pattern_file_path);
+        }
+        let files = try!(PatternLoader::load_files(pattern_file_path));
+        build_matcher::<F>(files, false)
+    }
+
+    pub fn rulesets_from_file_ignore_errors<F>(pattern_file_path: &str)
+                                               -> Result<RulesetMatcher<F::Matcher>, BuildError>
+        where F: MatcherFactory
+    {
+        if cache::is_cache_file(pattern_file_path) {
+            return F::from_cache(pattern_file_path).map(RulesetMatcher::new);
+        }
+        let files = try!(PatternLoader::load_files(pattern_file_path));
+        build_rulesets::<F>(files, true)
+    }
+
+    // The patterns outside of the rulesets become the fallback patterns. A
+    // cache file has only fallback patterns.
+    pub fn rulesets_from_file<F>(pattern_file_path: &str) -> Result<RulesetMatcher<F::Matcher>, BuildError>
+        where F: MatcherFactory
+    {
+        if cache::is_cache_file(pattern_file_path) {
+            return F::from_cache(pattern_file_path).map(RulesetMatcher::new);
+        }
+        let files = try!(PatternLoader::load_files(pattern_file_path));
+        build_rulesets::<F>(files, false)
+    }
+
+    // Loads the pattern files of a path and merges their patterns into one
+    // pattern file.
+    pub fn load(pattern_file_path: &str) -> Result<PatternFile, BuildError> {
+        let mut patterns = Vec::new();
+        let mut rulesets = Vec::new();
+        for (_, file) in try!(PatternLoader::load_files(pattern_file_path)) {
+            patterns.extend(file.patterns);
+            rulesets.extend(file.rulesets);
+        }
+        Ok(PatternFile {
+            patterns: patterns,
+            rulesets: rulesets,
+            includes: Vec::new(),
+        })
+    }
+
+    // The path can be a pattern file, a directory or a glob. The included
+    // files are loaded too, the errors contain the path of the file where they
+    // occurred.
+    pub fn load_files(pattern_file_path: &str) -> Result<Vec<(String, PatternFile)>, BuildError> {
+        PatternLoader::load_sources(pattern_file_path).map(|sources| sources.files)
+    }
+
+    // The same as load_files(), but the expanded paths are returned too, so a
+    // cache can check whether they are still expanded into the same files.
+    pub fn load_sources(pattern_file_path: &str) -> Result<Sources, BuildError> {
+        let mut files = Files::new();
+        try!(files.load_path(pattern_file_path));
+        Ok(Sources {
+            inputs: files.inputs,
+            files: files.files,
+        })
     }
 
     pub fn load_file(pattern_file_path: &str) -> Result<PatternFile, BuildError>
@@ -51,6 +136,15 @@ impl PatternLoader {
                         let file = try!(serde_yaml::from_str::<PatternFile>(&content));
                         Ok(file)
                     },
+                    patterndb::EXTENSION => {
+                        let content = try!(PatternLoader::read(pattern_file_path));
+                        let conversion = try!(patterndb::convert(&content));
+                        for unsupported in conversion.unsupported() {
+                            warn!("{}", unsupported);
+                        }
+                        let file = try!(conversion.into_pattern_file());
+                        Ok(file)
+                    },
                     _ => Err(BuildError::UnsupportedFileExtension),
                 }
             }
@@ -58,3 +152,304 @@ impl PatternLoader {
         }
     }
 }
+
+// The pattern file is read again to find the location of the error in it.
+fn locate_in_file(path: &str, error: BuildError) -> BuildError {
+    match PatternLoader::read(path) {
+        Ok(content) => {
+            let (error, location) = location::locate(&content, error);
+            BuildError::in_file(path, location, error)
+        }
+        Err(_) => BuildError::in_file(path, None, error),
+    }
+}
+
+// The test messages of the ignored patterns are not returned.
+fn check_pattern<M: Matcher>(matcher: &mut M,
+                             path: &str,
+                             pattern: BuildResult,
+                             ignore_errors: bool,
+                             test_messages: &mut Vec<TestMessages>)
+                             -> Result<(), BuildError> {
+    match M::check_pattern(matcher, pattern) {
+        Ok(messages) => {
+            test_messages.push(messages);
+            Ok(())
+        }
+        Err(error) if ignore_errors => {
+            error!("{}", locate_in_file(path, error));
+            Ok(())
+        }
+        Err(error) => Err(locate_in_file(path, error)),
+    }
+}
+
+fn build_matcher<F>(files: Vec<(String, PatternFile)>, ignore_errors: bool) -> Result<F::Matcher, BuildError>
+    where F: MatcherFactory
+{
+    // The test messages can be checked only against the patterns of their own
+    // ruleset, so the rulesets can't be loaded into one matcher.
+    if let Some(file) = files.iter().find(|file| !file.1.rulesets().is_empty()) {
+        return Err(BuildError::UnexpectedRulesets(file.0.clone()));
+    }
+    let mut matcher = F::new_matcher();
+    let mut test_messages = Vec::new();
+    for (path, file) in files {
+        for pattern in file {
+            try!(check_pattern(&mut matcher, &path, pattern, ignore_errors, &mut test_messages));
+        }
+    }
+    F::Matcher::check_ambiguities(&matcher, &test_messages);
+    Ok(matcher)
+}
+
+// The field, the values, the matcher and the test messages of a ruleset.
+type Ruleset<M> = (String, BTreeSet<String>, M, Vec<TestMessages>);
+
+// The rulesets with the same field and values are merged, even if they are in
+// different files.
+fn build_rulesets<F>(files: Vec<(String, PatternFile)>,
+                     ignore_errors: bool)
+                     -> Result<RulesetMatcher<F::Matcher>, BuildError>
+    where F: MatcherFactory
+{
+    let mut fallback = F::new_matcher();
+    let mut fallback_test_messages = Vec::new();
+    let mut rulesets: Vec<Ruleset<F::Matcher>> = Vec::new();
+    for (path, mut file) in files {
+        for ruleset in file.rulesets.drain(..) {
+            let values: BTreeSet<String> = ruleset.values().iter().cloned().collect();
+            let position = rulesets.iter().position(|other| other.0 == ruleset.field() && other.1 == values);
+            let position = match position {
+                Some(position) => position,
+                None => {
+                    rulesets.push((ruleset.field().to_owned(), values, F::new_matcher(), Vec::new()));
+                    rulesets.len() - 1
+                }
+            };
+            let (_, _, ref mut matcher, ref mut test_messages) = rulesets[position];
+            for pattern in ruleset.into_patterns() {
+                try!(check_pattern(matcher, &path, Ok(pattern), ignore_errors, test_messages));
+            }
+        }
+        for pattern in file {
+            try!(check_pattern(&mut fallback, &path, pattern, ignore_errors, &mut fallback_test_messages));
+        }
+    }
+
+    F::Matcher::check_ambiguities(&fallback, &fallback_test_messages);
+    let mut matcher = RulesetMatcher::new(fallback);
+    for (field, values, ruleset, test_messages) in rulesets {
+        F::Matcher::check_ambiguities(&ruleset, &test_messages);
+        let values: Vec<String> = values.into_iter().collect();
+        if !matcher.add_ruleset(&field, &values, ruleset) {
+            return Err(BuildError::OverlappingRulesets {
+                field: field,
+                values: values,
+            });
+        }
+    }
+    Ok(matcher)
+}
+
+// The loaded pattern files in loading order. A file is loaded only once, even
+// if it's included more than once. The same uuid can't be used by different
+// patterns, neither in the same file nor in more files.
+struct Files {
+    inputs: Vec<(String, Vec<PathBuf>)>,
+    files: Vec<(String, PatternFile)>,
+    loaded: BTreeSet<PathBuf>,
+    origins: BTreeMap<Uuid, String>,
+}
+
+impl Files {
+    fn new() -> Files {
+        Files {
+            inputs: Vec::new(),
+            files: Vec::new(),
+            loaded: BTreeSet::new(),
+            origins: BTreeMap::new(),
+        }
+    }
+
+    fn load_path(&mut self, path: &str) -> Result<(), BuildError> {
+        let file_paths = try!(expand(path));
+        self.inputs.push((path.to_owned(), file_paths.clone()));
+        for file_path in &file_paths {
+            try!(self.load_file(file_path));
+        }
+        Ok(())
+    }
+
+    fn load_file(&mut self, path: &Path) -> Result<(), BuildError> {
+        let canonical_path = fs::canonicalize(path).unwrap_or_else(|_| path.to_owned());
+        if !self.loaded.insert(canonical_path) {
+            return Ok(());
+        }
+
+        let name = try!(path.to_str().ok_or(BuildError::NotUtf8FileName));
+        let file = try!(PatternLoader::load_file(name).map_err(|error| locate_in_file(name, error)));
+        let ruleset_patterns = file.rulesets().iter().map(|ruleset| ruleset.patterns());
+        for patterns in iter::once(&file.patterns()[..]).chain(ruleset_patterns) {
+            try!(self.add_uuids(name, patterns));
+        }
+
+        let directory = path.parent().unwrap_or_else(|| Path::new(""));
+        let mut includes = Vec::new();
+        for include in file.includes() {
+            let include = directory.join(include);
+            includes.push(try!(include.to_str().ok_or(BuildError::NotUtf8FileName)).to_owned());
+        }
+        self.files.push((name.to_owned(), file));
+
+        for include in &includes {
+            try!(self.load_path(include).map_err(|error| BuildError::in_file(name, None, error)));
+        }
+        Ok(())
+    }
+
+    // Every pattern needs its own uuid. The patterns of a patterndb rule share
+    // the uuid of the rule, so they are listed in one pattern.
+    fn add_uuids(&mut self, name: &str, patterns: &[Pattern]) -> Result<(), BuildError> {
+        for pattern in patterns {
+            let uuid = *pattern.uuid();
+            match self.origins.entry(uuid) {
+                Entry::Occupied(entry) => {
+                    return Err(BuildError::DuplicateUuid {
+                        uuid: uuid,
+                        first_file: entry.get().clone(),
+                        second_file: name.to_owned(),
+                    });
+                }
+                Entry::Vacant(entry) => {
+                    entry.insert(name.to_owned());
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+fn is_glob(path: &str) -> bool {
+    path.contains('*') || path.contains('?')
+}
+
+fn has_pattern_file_extension(path: &Path) -> bool {
+    path.extension()
+        .and_then(|extension| extension.to_str())
+        .map_or(false, |extension| EXTENSIONS.contains(&extension))
+}
+
+// A directory is replaced with its files which have a pattern file extension,
+// its subdirectories are not read.
+pub fn expand(pattern_file_path: &str) -> Result<Vec<PathBuf>, BuildError> {
+    let paths = if is_glob(pattern_file_path) {
+        try!(glob(pattern_file_path))
+    } else {
+        vec![PathBuf::from(pattern_file_path)]
+    };
+
+    let mut files = Vec::new();
+    for path in paths {
+        if path.is_dir() {
+            let mut entries = try!(read_dir(&path));
+            entries.retain(|entry| entry.is_file() && has_pattern_file_extension(entry));
+            files.extend(entries);
+        } else {
+            files.push(path);
+        }
+    }
+
+    if files.is_empty() {
+        Err(BuildError::NoPatternFile(pattern_file_path.to_owned()))
+    } else {
+        Ok(files)
+    }
+}
+
+fn read_dir(directory: &Path) -> Result<Vec<PathBuf>, BuildError> {
+    let name = try!(directory.to_str().ok_or(BuildError::NotUtf8FileName));
+    let mut paths = Vec::new();
+    for entry in try!(fs::read_dir(directory).map_err(|error| BuildError::in_file(name, None, error.into()))) {
+        let entry = try!(entry.map_err(|error| BuildError::in_file(name, None, error.into())));
+        paths.push(entry.path());
+    }
+    paths.sort();
+    Ok(paths)
+}
+
+// Expands the `*` and `?` wildcards in every component of the path. The
+// matching paths are sorted in every directory.
+fn glob(pattern: &str) -> Result<Vec<PathBuf>, BuildError> {
+    let mut paths = vec![PathBuf::new()];
+    for component in Path::new(pattern).components() {
+        let component = component.as_os_str();
+        let wildcard = try!(component.to_str().ok_or(BuildError::NotUtf8FileName));
+        if !is_glob(wildcard) {
+            for path in &mut paths {
+                path.push(component);
+            }
+            continue;
+        }
+
+        let wildcard: Vec<char> = wildcard.chars().collect();
+        let mut expanded = Vec::new();
+        for path in &paths {
+            let directory = if path.as_os_str().is_empty() {
+                Path::new(".")
+            } else {
+                path.as_path()
+            };
+            if !directory.is_dir() {
+                continue;
+            }
+            for entry in try!(read_dir(directory)) {
+                let file_name = match entry.file_name().and_then(|name| name.to_str()) {
+                    Some(file_name) => file_name.to_owned(),
+                    None => continue,
+                };
+                let chars: Vec<char> = file_name.chars().collect();
+                // hidden files are matched only explicitly, like in shells
+                if chars[0] == '.' && wildcard[0] != '.' {
+                    continue;
+                }
+                if matches_wildcard(&wildcard, &chars) {
+                    expanded.push(path.join(file_name));
+                }
+            }
+        }
+        paths = expanded;
+    }
+    Ok(paths)
+}
+
+fn matches_wildcard(wildcard: &[char], name: &[char]) -> bool {
+    match wildcard.split_first() {
+        None => name.is_empty(),
+        Some((&'*', rest)) => (0..name.len() + 1).any(|i| matches_wildcard(rest, &name[i..])),
+        Some((&'?', rest)) => !name.is_empty() && matches_wildcard(rest, &name[1..]),
+        Some((c, rest)) => name.first() == Some(c) && matches_wildcard(rest, &name[1..]),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::matches_wildcard;
+
+    fn matches(wildcard: &str, name: &str) -> bool {
+        let wildcard: Vec<char> = wildcard.chars().collect();
+        let name: Vec<char> = name.chars().collect();
+        matches_wildcard(&wildcard, &name)
+    }
+
+    #[test]
+    fn test_given_wildcard_when_it_is_matched_against_file_names_then_star_and_question_mark_are_expanded() {
+        assert!(matches("*.json", "sshd.json"));
+        assert!(matches("*.json", ".json"));
+        assert!(matches("ssh?.y*ml", "sshd.yaml"));
+        assert!(matches("*", ""));
+        assert!(!matches("*.json", "sshd.yaml"));
+        assert!(!matches("ssh?.json", "ssh.json"));
+        assert!(!matches("sshd", "sshd.json"));
+    }
+}
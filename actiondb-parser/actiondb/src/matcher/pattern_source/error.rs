@@ -1,7 +1,12 @@
 use matcher::pattern::testmessage;
+use matcher::cache;
+use matcher::pattern::file::patterndb;
+use grammar::parser::ParseError;
+use super::Location;
 
 use serde_json;
 use serde_yaml;
+use uuid::Uuid;
 use std::fmt;
 use std::error;
 use std::io;
@@ -14,6 +19,59 @@ pub enum BuildError {
     DeserYaml(serde_yaml::Error),
     UnsupportedFileExtension,
     NotUtf8FileName,
+    Cache(cache::Error),
+    Patterndb(patterndb::Error),
+    NoPatternFile(String),
+    DuplicateUuid {
+        uuid: Uuid,
+        first_file: String,
+        second_file: String,
+    },
+    File {
+        path: String,
+        location: Option<Location>,
+        error: Box<BuildError>,
+    },
+    Pattern {
+        pattern: String,
+        error: ParseError,
+    },
+    OverlappingRulesets {
+        field: String,
+        values: Vec<String>,
+    },
+    TooManyVariants {
+        uuid: Uuid,
+        max: usize,
+    },
+    UnexpandedPattern(Uuid),
+    // A pattern file with rulesets was loaded into one matcher.
+    UnexpectedRulesets(String),
+}
+
+impl BuildError {
+    // Attaches the path of the pattern file and the location of the error in
+    // it to the error, unless it already has a path.
+    pub fn in_file(path: &str, location: Option<Location>, error: BuildError) -> BuildError {
+        match error {
+            error @ BuildError::File { .. } | error @ BuildError::DuplicateUuid { .. } => error,
+            error => {
+                BuildError::File {
+                    path: path.to_owned(),
+                    location: location,
+                    error: Box::new(error),
+                }
+            }
+        }
+    }
+
+    // The path of the pattern file and the location of the error in it.
+    pub fn location(&self) -> Option<(&str, Location)> {
+        match *self {
+            BuildError::File { ref path, location: Some(location), .. } => Some((path, location)),
+            _ => None,
+        }
+    }
 }
 
 impl From<testmessage::Error> for BuildError {
@@ -40,6 +98,18 @@ impl From<serde_yaml::Error> for BuildError {
     }
 }
 
+impl From<cache::Error> for BuildError {
+    fn from(error: cache::Error) -> BuildError {
+        BuildError::Cache(error)
+    }
+}
+
+impl From<patterndb::Error> for BuildError {
+    fn from(error: patterndb::Error) -> BuildError {
+        BuildError::Patterndb(error)
+    }
+}
+
 impl fmt::Display for BuildError {
     fn fmt(&self, formatter: &mut fmt::Formatter) -> Result<(), fmt::Error> {
         match *self {
@@ -47,10 +117,60 @@ impl fmt::Display for BuildError {
             BuildError::Io(ref error) => error.fmt(formatter),
             BuildError::DeserJson(ref error) => error.fmt(formatter),
             BuildError::DeserYaml(ref error) => error.fmt(formatter),
+            BuildError::Cache(ref error) => error.fmt(formatter),
+            BuildError::Patterndb(ref error) => error.fmt(formatter),
             BuildError::UnsupportedFileExtension =>
                 formatter.write_fmt(format_args!("The given file extension is not suppoted")),
             BuildError::NotUtf8FileName =>
                 formatter.write_str("The given filename contains non Utf-8 characters"),
+            BuildError::NoPatternFile(ref path) =>
+                formatter.write_fmt(format_args!("No pattern file was found: path={}", path)),
+            BuildError::DuplicateUuid { ref uuid, ref first_file, ref second_file } => {
+                formatter.write_fmt(format_args!("The same uuid is used by different patterns: uuid={} \
+                                                  first_file={} second_file={}",
+                                                 uuid.hyphenated(),
+                                                 first_file,
+                                                 second_file))
+            }
+            BuildError::File { ref path, location: Some(ref location), ref error } => {
+                formatter.write_fmt(format_args!("{}:{}: {}", path, location, error))
+            }
+            BuildError::File { ref path, location: None, ref error } => {
+                formatter.write_fmt(format_args!("{}: {}", path, error))
+            }
+            BuildError::Pattern { ref pattern, ref error } => {
+                let mut expected: Vec<&str> = error.expected.iter().cloned().collect();
+                expected.sort();
+                try!(formatter.write_fmt(format_args!("Failed to parse a pattern: column={} expected={} pattern={}",
+                                                      error.column,
+                                                      expected.join(","),
+                                                      pattern)));
+                match error.regex_error {
+                    Some(ref regex_error) => formatter.write_fmt(format_args!(" error={}", regex_error)),
+                    None => Ok(()),
+                }
+            }
+            BuildError::OverlappingRulesets { ref field, ref values } => {
+                formatter.write_fmt(format_args!("A value selects more rulesets: field={} values={}",
+                                                 field,
+                                                 values.join(",")))
+            }
+            BuildError::TooManyVariants { ref uuid, max } => {
+                formatter.write_fmt(format_args!("The optional groups and the alternatives of a pattern have too \
+                                                  many variants: uuid={} max={}",
+                                                 uuid.hyphenated(),
+                                                 max))
+            }
+            BuildError::UnexpandedPattern(ref uuid) => {
+                formatter.write_fmt(format_args!("A pattern was inserted without expanding its optional groups \
+                                                  and alternatives: uuid={}",
+                                                 uuid.hyphenated()))
+            }
+            BuildError::UnexpectedRulesets(ref path) => {
+                formatter.write_fmt(format_args!("The pattern file has rulesets, it can be loaded only with its \
+                                                  rulesets: path={}",
+                                                 path))
+            }
         }
     }
 }
@@ -62,8 +182,18 @@ impl error::Error for BuildError {
             BuildError::Io(ref error) => error.description(),
             BuildError::DeserJson(ref error) => error.description(),
             BuildError::DeserYaml(ref error) => error.description(),
+            BuildError::Cache(ref error) => error.description(),
+            BuildError::Patterndb(ref error) => error.description(),
             BuildError::UnsupportedFileExtension => "The given file extension is not supported",
             BuildError::NotUtf8FileName => "The given filename contains non Utf-8 characters",
+            BuildError::NoPatternFile(_) => "No pattern file was found",
+            BuildError::DuplicateUuid { .. } => "The same uuid is used by different patterns",
+            BuildError::File { ref error, .. } => error.description(),
+            BuildError::OverlappingRulesets { .. } => "A value selects more rulesets",
+            BuildError::Pattern { .. } => "Failed to parse a pattern",
+            BuildError::TooManyVariants { .. } => "A pattern has too many variants",
+            BuildError::UnexpandedPattern(_) => "A pattern was inserted without expanding it",
+            BuildError::UnexpectedRulesets(_) => "The pattern file has rulesets",
         }
     }
 
@@ -73,7 +203,18 @@ impl error::Error for BuildError {
             BuildError::Io(ref error) => error.cause(),
             BuildError::DeserJson(ref error) => error.cause(),
             BuildError::DeserYaml(ref error) => error.cause(),
-            BuildError::UnsupportedFileExtension | BuildError::NotUtf8FileName => None,
+            BuildError::Cache(ref error) => error.cause(),
+            BuildError::Patterndb(ref error) => error.cause(),
+            BuildError::File { ref error, .. } => Some(&**error),
+            BuildError::Pattern { ref error, .. } => Some(error),
+            BuildError::UnsupportedFileExtension |
+            BuildError::NotUtf8FileName |
+            BuildError::NoPatternFile(_) |
+            BuildError::DuplicateUuid { .. } |
+            BuildError::OverlappingRulesets { .. } |
+            BuildError::TooManyVariants { .. } |
+            BuildError::UnexpandedPattern(_) |
+            BuildError::UnexpectedRulesets(_) => None,
         }
     }
 }
@@ -0,0 +1,142 @@
+// The nodes of a pattern file with the locations of its scalars. JSON is a
+// subset of YAML, so the files of both formats are read with the event parser
+// of yaml-rust (the parser of serde_yaml), which reports the position of every
+// event.
+
+use serde_json;
+use yaml_rust::parser::{Event, MarkedEventReceiver, Parser};
+use yaml_rust::scanner::{Marker, TScalarStyle};
+
+use super::Location;
+
+pub enum Node {
+    Scalar {
+        value: String,
+        style: TScalarStyle,
+        location: Option<Location>,
+    },
+    Sequence(Vec<Node>),
+    Mapping(Vec<(Node, Node)>),
+    Alias,
+}
+
+impl Node {
+    // Returns None if the content can't be parsed.
+    pub fn parse(content: &str) -> Option<Node> {
+        let mut builder = Builder {
+            stack: Vec::new(),
+            root: None,
+        };
+        match Parser::new(content.chars()).load(&mut builder, false) {
+            Ok(()) => builder.root,
+            Err(_) => None,
+        }
+    }
+
+    pub fn as_str(&self) -> Option<&str> {
+        match *self {
+            Node::Scalar { ref value, .. } => Some(value),
+            _ => None,
+        }
+    }
+
+    pub fn items(&self) -> &[Node] {
+        match *self {
+            Node::Sequence(ref items) => items,
+            _ => &[],
+        }
+    }
+
+    pub fn entries(&self) -> &[(Node, Node)] {
+        match *self {
+            Node::Mapping(ref entries) => entries,
+            _ => &[],
+        }
+    }
+
+    pub fn get(&self, key: &str) -> Option<&Node> {
+        self.entries()
+            .iter()
+            .find(|entry| entry.0.as_str() == Some(key))
+            .map(|entry| &entry.1)
+    }
+
+    // The location of the character of a scalar at `index`. The characters
+    // are expected to be on the first line of the scalar, the block scalars
+    // are located at their indicator.
+    pub fn location_at(&self, index: usize) -> Option<Location> {
+        let (value, style, location) = match *self {
+            Node::Scalar { ref value, style, location: Some(location) } => (value, style, location),
+            _ => return None,
+        };
+        let prefix: String = value.chars().take(index).collect();
+        let offset = match style {
+            TScalarStyle::Plain | TScalarStyle::Any => index,
+            // the closing quote of the prefix is in the place of the character
+            TScalarStyle::DoubleQuoted => {
+                serde_json::to_string(&prefix).expect("Failed to quote a string").chars().count() - 1
+            }
+            TScalarStyle::SingleQuoted => 1 + index + prefix.matches('\'').count(),
+            TScalarStyle::Literal | TScalarStyle::Foled => 0,
+        };
+        Some(Location::new(location.line(), location.column() + offset))
+    }
+}
+
+enum Collection {
+    Sequence(Vec<Node>),
+    // the entries and the key of the next entry
+    Mapping(Vec<(Node, Node)>, Option<Node>),
+}
+
+struct Builder {
+    stack: Vec<Collection>,
+    root: Option<Node>,
+}
+
+impl Builder {
+    fn add(&mut self, node: Node) {
+        match self.stack.last_mut() {
+            Some(&mut Collection::Sequence(ref mut items)) => items.push(node),
+            Some(&mut Collection::Mapping(ref mut entries, ref mut key)) => {
+                match key.take() {
+                    Some(key) => entries.push((key, node)),
+                    None => *key = Some(node),
+                }
+            }
+            None => self.root = Some(node),
+        }
+    }
+}
+
+impl MarkedEventReceiver for Builder {
+    fn on_event(&mut self, event: &Event, mark: Marker) {
+        let node = match *event {
+            Event::Scalar(ref value, style, _, _) => {
+                Node::Scalar {
+                    value: value.clone(),
+                    style: style,
+                    location: Some(Location::from_marker(&mark)),
+                }
+            }
+            Event::Alias(_) => Node::Alias,
+            Event::SequenceStart(_) => {
+                self.stack.push(Collection::Sequence(Vec::new()));
+                return;
+            }
+            Event::MappingStart(_) => {
+                self.stack.push(Collection::Mapping(Vec::new(), None));
+                return;
+            }
+            Event::SequenceEnd | Event::MappingEnd => {
+                match self.stack.pop() {
+                    Some(Collection::Sequence(items)) => Node::Sequence(items),
+                    Some(Collection::Mapping(entries, _)) => Node::Mapping(entries),
+                    None => return,
+                }
+            }
+            _ => return,
+        };
+        self.add(node);
+    }
+}
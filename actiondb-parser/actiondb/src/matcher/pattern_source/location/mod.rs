@@ -0,0 +1,177 @@
+// Finds the positions of the errors in the content of the pattern files. The
+// deserializers don't know where the values were in the file, so the file is
+// read again as a tree of nodes with their locations and the failing values
+// (e.g. an invalid pattern or a test message) are looked up in it by the
+// structure of the file.
+
+use grammar::parser::{self, ParseError};
+use matcher::pattern::file::patterndb;
+use matcher::pattern::testmessage;
+use serde_json;
+use serde_yaml;
+use uuid::Uuid;
+use xml::common::Position;
+use yaml_rust::scanner::Marker;
+
+use std::fmt;
+
+use self::document::Node;
+use super::BuildError;
+
+mod document;
+#[cfg(test)]
+mod test;
+
+// The line and the column are counted from 1, the column is counted in
+// characters.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Location {
+    line: usize,
+    column: usize,
+}
+
+impl Location {
+    pub fn new(line: usize, column: usize) -> Location {
+        Location {
+            line: line,
+            column: column,
+        }
+    }
+
+    pub fn from_offset(content: &str, offset: usize) -> Location {
+        let before = &content[..offset];
+        let line_start = before.rfind('\n').map_or(0, |pos| pos + 1);
+        Location::new(before.matches('\n').count() + 1,
+                      before[line_start..].chars().count() + 1)
+    }
+
+    // The column of the marker is counted from 0.
+    pub fn from_marker(marker: &Marker) -> Location {
+        Location::new(marker.line(), marker.col() + 1)
+    }
+
+    pub fn line(&self) -> usize {
+        self.line
+    }
+
+    pub fn column(&self) -> usize {
+        self.column
+    }
+
+    // Renders the line of the location with a caret under its column.
+    pub fn render(&self, content: &str) -> Option<String> {
+        let line = match content.lines().nth(self.line - 1) {
+            Some(line) => line.trim_right_matches('\r'),
+            None => return None,
+        };
+        // the tabs are kept, so the caret is under the right character
+        let indentation: String = line.chars()
+                                      .take(self.column - 1)
+                                      .map(|c| if c == '\t' { '\t' } else { ' ' })
+                                      .collect();
+        Some(format!("{}\n{}^", line, indentation))
+    }
+}
+
+impl fmt::Display for Location {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> Result<(), fmt::Error> {
+        write!(formatter, "{}:{}", self.line, self.column)
+    }
+}
+
+// Collects the patterns of the file and of its rulesets in the order of the
+// file.
+fn collect_patterns<'a>(file: &'a Node, patterns: &mut Vec<&'a Node>) {
+    for &(ref key, ref value) in file.entries() {
+        match key.as_str() {
+            Some("patterns") => patterns.extend(value.items()),
+            Some("rulesets") => {
+                for ruleset in value.items() {
+                    collect_patterns(ruleset, patterns);
+                }
+            }
+            _ => (),
+        }
+    }
+}
+
+// The deserializer stops at the first invalid pattern, but it reports only the
+// text of the grammar error, so the patterns are parsed again in the order of
+// the file to get the error.
+fn find_invalid_pattern(file: &Node) -> Option<(String, ParseError, Option<Location>)> {
+    let mut patterns = Vec::new();
+    collect_patterns(file, &mut patterns);
+    patterns.into_iter()
+            .flat_map(|pattern| {
+                pattern.get("pattern")
+                       .into_iter()
+                       .chain(pattern.get("patterns").map_or(&[][..], Node::items))
+            })
+            .filter_map(|pattern| {
+                pattern.as_str().and_then(|text| {
+                    parser::pattern(text).err().map(|error| {
+                        let location = pattern.location_at(error.column - 1);
+                        (text.to_owned(), error, location)
+                    })
+                })
+            })
+            .next()
+}
+
+// The test message is looked up among the test messages of its own pattern,
+// so the same message can be used in more patterns.
+fn find_test_message(file: &Node, error: &testmessage::Error) -> Option<Location> {
+    let mut patterns = Vec::new();
+    collect_patterns(file, &mut patterns);
+    let pattern = patterns.into_iter().find(|pattern| {
+        pattern.get("uuid")
+               .and_then(Node::as_str)
+               .and_then(|uuid| Uuid::parse_str(uuid).ok())
+               .map_or(false, |uuid| uuid.hyphenated().to_string() == error.pattern_uuid())
+    });
+    pattern.and_then(|pattern| pattern.get("test_messages"))
+           .and_then(|test_messages| {
+               test_messages.items()
+                            .iter()
+                            .filter_map(|test_message| test_message.get("message"))
+                            .find(|message| message.as_str() == Some(error.message()))
+           })
+           .and_then(|message| message.location_at(0))
+}
+
+// Returns the error with its location in the content. The deserialization
+// errors which were caused by an invalid pattern are replaced with the error
+// of the pattern.
+pub fn locate(content: &str, error: BuildError) -> (BuildError, Option<Location>) {
+    let location = match error {
+        BuildError::DeserJson(_) |
+        BuildError::DeserYaml(_) => {
+            let invalid_pattern = Node::parse(content).and_then(|file| find_invalid_pattern(&file));
+            if let Some((pattern, error, location)) = invalid_pattern {
+                let error = BuildError::Pattern {
+                    pattern: pattern,
+                    error: error,
+                };
+                return (error, location);
+            }
+            locate_syntax_error(&error)
+        }
+        BuildError::TestMessage(ref error) => Node::parse(content).and_then(|file| find_test_message(&file, error)),
+        BuildError::Patterndb(patterndb::Error::Xml(ref error)) => {
+            let position = error.position();
+            Some(Location::new(position.row as usize + 1, position.column as usize + 1))
+        }
+        _ => None,
+    };
+    (error, location)
+}
+
+fn locate_syntax_error(error: &BuildError) -> Option<Location> {
+    match *error {
+        BuildError::DeserJson(serde_json::Error::Syntax(_, line, column)) if line > 0 => {
+            Some(Location::new(line, column))
+        }
+        BuildError::DeserYaml(serde_yaml::Error::Scan(ref error)) => Some(Location::from_marker(error.marker())),
+        _ => None,
+    }
+}
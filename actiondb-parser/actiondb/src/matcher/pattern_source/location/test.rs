@@ -0,0 +1,82 @@
+use super::{locate, Location};
+use super::document::Node;
+use matcher::pattern::testmessage;
+use matcher::pattern_source::BuildError;
+use serde_json;
+use uuid::Uuid;
+
+#[test]
+fn test_given_offset_when_it_is_converted_to_location_then_the_line_and_the_column_are_counted_from_one() {
+    let content = "first\nsecond\n";
+    assert_eq!(Location::from_offset(content, 0), Location::new(1, 1));
+    assert_eq!(Location::from_offset(content, 8), Location::new(2, 3));
+}
+
+#[test]
+fn test_given_location_when_it_is_rendered_then_a_caret_is_under_its_column() {
+    let content = "first\n\tsecond\n";
+    assert_eq!(Location::new(2, 3).render(content),
+               Some("\tsecond\n\t ^".to_owned()));
+    assert_eq!(Location::new(3, 1).render(content), None);
+    assert_eq!(Location::new(2, 3).to_string(), "2:3");
+}
+
+#[test]
+fn test_given_string_when_it_is_escaped_in_the_content_then_the_location_points_to_the_escaped_character() {
+    let content = "{\n  \"pattern\": \"say \\\"%{GREEDY\"\n}";
+    let file = Node::parse(content).unwrap();
+    assert_eq!(file.get("pattern").and_then(|pattern| pattern.location_at(5)), Some(Location::new(2, 21)));
+    let file = Node::parse("pattern: foo %{\nname: 'it''s %{'").unwrap();
+    assert_eq!(file.get("pattern").and_then(|pattern| pattern.location_at(4)), Some(Location::new(1, 14)));
+    assert_eq!(file.get("name").and_then(|name| name.location_at(5)), Some(Location::new(2, 14)));
+    assert!(file.get("uuid").is_none());
+}
+
+#[test]
+fn test_given_test_message_error_when_the_message_is_in_more_patterns_then_the_one_of_its_pattern_is_located() {
+    let content = r#"patterns:
+  - uuid: "9a49c47d-29e9-4072-be84-3b76c6814743"
+    pattern: "session closed for user %{GREEDY:user}"
+    test_messages:
+      - message: "session closed for user root"
+  - uuid: "fa8bdbcb-e0fd-4da1-9fa4-15ecfec28ad2"
+    pattern: "session closed for user %{GREEDY:user}"
+    test_messages:
+      - message: "session closed for user root"
+"#;
+    let uuid = Uuid::parse_str("fa8bdbcb-e0fd-4da1-9fa4-15ecfec28ad2").unwrap();
+    let error = testmessage::Error::key_not_found(&uuid, "session closed for user root", "name");
+    match locate(content, BuildError::from(error)) {
+        (BuildError::TestMessage(_), location) => assert_eq!(location, Some(Location::new(9, 19))),
+        _ => unreachable!(),
+    }
+}
+
+#[test]
+fn test_given_deserialization_error_when_it_is_caused_by_invalid_pattern_then_the_pattern_is_located() {
+    let content = r#"{
+  "patterns": [
+    {
+      "uuid": "9a49c47d-29e9-4072-be84-3b76c6814743",
+      "pattern": "user %{INT:uid"
+    }
+  ]
+}"#;
+    let error = serde_json::from_str::<::matcher::pattern::file::PatternFile>(content).err().unwrap();
+    match locate(content, BuildError::from(error)) {
+        (BuildError::Pattern { ref pattern, ref error }, Some(location)) => {
+            assert_eq!(pattern, "user %{INT:uid");
+            assert_eq!(error.column, 15);
+            assert_eq!(location, Location::new(5, 33));
+        }
+        _ => unreachable!(),
+    }
+}
+
+#[test]
+fn test_given_json_syntax_error_when_it_is_located_then_the_position_of_the_parser_is_used() {
+    let content = "{\n  \"patterns\": [,]\n}";
+    let error = serde_json::from_str::<::matcher::pattern::file::PatternFile>(content).err().unwrap();
+    let (_, location) = locate(content, BuildError::from(error));
+    assert_eq!(location.map(|location| location.line()), Some(2));
+}
@@ -4,38 +4,83 @@ use matcher::pattern::{Pattern, PatternSource};
 use matcher::pattern::testmessage::{self, TestMessage};
 use matcher::{Matcher, MatcherFactory};
 use matcher::result::MatchResult;
+use matcher::lint::Warning;
 use matcher::pattern::source::BuildResult;
+
+use std::collections::BTreeSet;
 pub use self::error::BuildError;
+pub use self::location::Location;
 
 mod error;
+pub mod location;
+
+// The uuid of a checked pattern and its test messages.
+pub type TestMessages = (Uuid, Vec<TestMessage>);
 
 pub trait FromPatternSource {
     fn from_source<F: MatcherFactory>(from: &mut PatternSource) -> Result<F::Matcher, BuildError> {
         let mut matcher = F::new_matcher();
+        let mut test_messages = Vec::new();
         for pattern in from {
-            try!(Self::check_pattern::<F::Matcher>(&mut matcher, pattern));
+            test_messages.push(try!(Self::check_pattern::<F::Matcher>(&mut matcher, pattern)));
         }
+        Self::check_ambiguities(&matcher, &test_messages);
         Ok(matcher)
     }
 
     fn from_source_ignore_errors<F: MatcherFactory>(from: &mut PatternSource) -> F::Matcher {
         let mut matcher = F::new_matcher();
+        let mut test_messages = Vec::new();
         for pattern in from {
-            let result = Self::check_pattern::<F::Matcher>(&mut matcher, pattern);
-            if let Err(error) = result {
-                error!("{}", error);
+            match Self::check_pattern::<F::Matcher>(&mut matcher, pattern) {
+                Ok(messages) => test_messages.push(messages),
+                Err(error) => error!("{}", error),
             }
         }
+        Self::check_ambiguities(&matcher, &test_messages);
         matcher
     }
 
-    fn check_pattern<M: Matcher>(matcher: &mut M, result: BuildResult) -> Result<(), BuildError> {
+    // The test messages are returned, so they can be checked for ambiguity
+    // once every pattern is loaded.
+    fn check_pattern<M: Matcher>(matcher: &mut M, result: BuildResult) -> Result<TestMessages, BuildError> {
         let mut pattern = try!(result);
         let uuid = pattern.uuid().to_owned();
         let test_messages = Self::extract_test_messages(&mut pattern);
-        matcher.add_pattern(pattern);
+        try!(matcher.add_pattern(pattern));
         debug!("validating pattern: {}", uuid.hyphenated().to_string());
-        Self::check_test_messages(matcher, &test_messages, &uuid)
+        try!(Self::check_test_messages(matcher, &test_messages, &uuid));
+        Ok((uuid, test_messages))
+    }
+
+    fn check_ambiguities<M: Matcher>(matcher: &M, test_messages: &[TestMessages]) {
+        for warning in Self::ambiguities(matcher, test_messages) {
+            warn!("Ambiguous test message: {}", warning);
+        }
+    }
+
+    // A test message is ambiguous if it can be matched by other patterns too.
+    // It's not an error, because the matcher always chooses the same pattern.
+    fn ambiguities<M: Matcher>(matcher: &M, test_messages: &[TestMessages]) -> Vec<Warning> {
+        let mut warnings = Vec::new();
+        for tested in test_messages {
+            let uuid = &tested.0;
+            for message in &tested.1 {
+                let others: BTreeSet<Uuid> = matcher.parse_all(message.message())
+                                                    .iter()
+                                                    .map(|result| *result.pattern().uuid())
+                                                    .filter(|other| other != uuid)
+                                                    .collect();
+                for other in others {
+                    warnings.push(Warning::Overlap {
+                        uuid: *uuid,
+                        other: other,
+                        message: Some(message.message().to_owned()),
+                    });
+                }
+            }
+        }
+        warnings
     }
 
     fn extract_test_messages(pattern: &mut Pattern) -> Vec<TestMessage> {
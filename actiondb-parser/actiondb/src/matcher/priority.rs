@@ -0,0 +1,115 @@
+use matcher::result::MatchResult;
+use matcher::explain::Trace;
+
+use std::cmp;
+use std::collections::BTreeSet;
+
+// Every node of the matchers keeps the highest priority of the patterns below
+// it, so the other matching patterns have to be looked up only if the found
+// one has lower priority and only below the nodes which have higher. It's None
+// if none of the patterns has a priority.
+pub fn highest(priorities: &BTreeSet<i32>) -> Option<i32> {
+    if priorities.iter().all(|priority| *priority == 0) {
+        None
+    } else {
+        priorities.iter().next_back().cloned()
+    }
+}
+
+// The patterns without priority have 0 priority, so it's a safe upper bound
+// when the first pattern with priority is added.
+pub fn add(highest: Option<i32>, priority: i32) -> Option<i32> {
+    if highest.is_none() && priority == 0 {
+        None
+    } else {
+        Some(cmp::max(highest.unwrap_or(0), priority))
+    }
+}
+
+// A node can have a pattern with higher priority than `above` only if its
+// highest priority is higher. The nodes without priorities have patterns with
+// 0 priority. There is no limit if `above` is None.
+pub fn may_exceed(highest: Option<i32>, above: Option<i32>) -> bool {
+    above.map_or(true, |above| highest.unwrap_or(0) > above)
+}
+
+pub fn exceeds(priority: i32, above: Option<i32>) -> bool {
+    above.map_or(true, |above| priority > above)
+}
+
+// The result with the highest priority is chosen. If there are more of them,
+// the one preferred by the matcher wins, so the ties are resolved as if there
+// weren't any priorities. `parse_all` returns the results with higher priority
+// than its argument, so only the subtrees which can contain them are searched.
+pub fn choose<'a, 'b, V, F, T>(result: Option<MatchResult<'a, 'b, V>>,
+                               highest: Option<i32>,
+                               parse_all: F,
+                               trace: &mut T)
+                               -> Option<MatchResult<'a, 'b, V>>
+    where V: ?Sized + ToOwned + AsRef<[u8]> + 'b,
+          F: FnOnce(Option<i32>) -> Vec<MatchResult<'a, 'b, V>>,
+          T: Trace
+{
+    let highest = match highest {
+        Some(highest) => highest,
+        None => return result,
+    };
+    let found = result.as_ref().map(|result| result.pattern().priority());
+    if found.map_or(false, |found| found >= highest) {
+        return result;
+    }
+
+    let results = parse_all(found);
+    let highest = match results.iter().map(|result| result.pattern().priority()).max() {
+        Some(highest) => highest,
+        None => return result,
+    };
+
+    let chosen = results.into_iter().find(|result| result.pattern().priority() == highest);
+    if let Some(ref chosen) = chosen {
+        trace.push_priority(chosen.pattern().uuid(), highest);
+    }
+    chosen
+}
+
+#[cfg(test)]
+mod test {
+    use super::{add, choose, exceeds, highest, may_exceed};
+    use matcher::Pattern;
+    use matcher::result::MatchResult;
+
+    use std::collections::BTreeSet;
+
+    #[test]
+    fn test_given_result_when_it_has_the_highest_priority_then_the_other_results_are_not_collected() {
+        let pattern = Pattern::from_json(r#"{"uuid": "9a49c47d-29e9-4072-be84-3b76c6814743",
+                                             "pattern": "user %{GREEDY:user}",
+                                             "priority": 5}"#)
+                          .unwrap();
+        let result: Option<MatchResult<[u8]>> = Some(MatchResult::new(&pattern));
+        let chosen = choose(result, Some(5), |_| panic!("the results shouldn't be collected"), &mut ());
+        assert_eq!(chosen.unwrap().pattern().uuid(), pattern.uuid());
+    }
+
+    #[test]
+    fn test_given_priorities_when_none_of_them_is_set_then_there_is_no_highest_priority() {
+        let priorities: BTreeSet<i32> = vec![0].into_iter().collect();
+        assert_eq!(highest(&priorities), None);
+        let priorities: BTreeSet<i32> = vec![-1, 0].into_iter().collect();
+        assert_eq!(highest(&priorities), Some(0));
+        assert_eq!(add(None, 0), None);
+        assert_eq!(add(None, -1), Some(0));
+        assert_eq!(add(Some(0), 3), Some(3));
+    }
+
+    #[test]
+    fn test_given_lower_limit_when_priorities_are_compared_to_it_then_only_the_higher_ones_exceed_it() {
+        assert_eq!(may_exceed(None, None), true);
+        assert_eq!(may_exceed(None, Some(0)), false);
+        assert_eq!(may_exceed(None, Some(-1)), true);
+        assert_eq!(may_exceed(Some(5), Some(5)), false);
+        assert_eq!(may_exceed(Some(5), Some(4)), true);
+        assert_eq!(exceeds(-3, None), true);
+        assert_eq!(exceeds(3, Some(3)), false);
+    }
+}
@@ -1,25 +1,60 @@
+use std::borrow::Cow;
 use std::collections::BTreeMap;
+use std::fmt::{self, Debug};
+use std::str;
 
-use parsers::ParseResult;
+use parsers::{self, ParseResult, ValueType};
 use matcher::Pattern;
 
-#[derive(Debug)]
-pub struct MatchResult<'a, 'b> {
+// The values are either string slices or byte slices of the matched text.
+// The values of the named parsers and the pairs extracted by the parsers
+// (e.g. by KVPAIRS) are stored separately, a key is in only one of them.
+pub struct MatchResult<'a, 'b, V: ?Sized + ToOwned + 'b = str> {
     pattern: &'a Pattern,
-    values: BTreeMap<&'a str, &'b str>,
+    values: BTreeMap<&'a str, &'b V>,
+    pairs: BTreeMap<String, Cow<'b, V>>,
+    types: BTreeMap<&'a str, ValueType>,
+    pair_types: BTreeMap<String, ValueType>,
 }
 
-impl <'a, 'b> MatchResult<'a, 'b> {
-    pub fn new(pattern: &'a Pattern) -> MatchResult<'a, 'b> {
+impl<'a, 'b, V> Debug for MatchResult<'a, 'b, V>
+    where V: ?Sized + ToOwned + Debug + 'b,
+          V::Owned: Debug
+{
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("MatchResult")
+            .field("pattern", &self.pattern)
+            .field("values", &self.values)
+            .field("pairs", &self.pairs)
+            .field("types", &self.types)
+            .field("pair_types", &self.pair_types)
+            .finish()
+    }
+}
+
+impl<'a, 'b, V: ?Sized + ToOwned + AsRef<[u8]> + 'b> MatchResult<'a, 'b, V> {
+    pub fn new(pattern: &'a Pattern) -> MatchResult<'a, 'b, V> {
         MatchResult {
             pattern: pattern,
             values: BTreeMap::new(),
+            pairs: BTreeMap::new(),
+            types: BTreeMap::new(),
+            pair_types: BTreeMap::new(),
         }
     }
 
-    pub fn insert(&mut self, result: ParseResult<'a, 'b>) {
+    pub fn insert(&mut self, result: ParseResult<'a, 'b, V>) {
         if let Some(name) = result.parser().name() {
+            self.pairs.remove(name);
+            self.pair_types.remove(name);
             self.values.insert(name, result.value());
+            self.types.insert(name, result.value_type());
+        }
+        for (key, value, value_type) in result.into_pairs() {
+            self.values.remove(&key[..]);
+            self.types.remove(&key[..]);
+            self.pair_types.insert(key.clone(), value_type);
+            self.pairs.insert(key, value);
         }
     }
 
@@ -27,14 +62,54 @@ impl <'a, 'b> MatchResult<'a, 'b> {
         self.pattern
     }
 
-    pub fn values(&self) -> &BTreeMap<&'a str, &'b str> {
+    pub fn values(&self) -> &BTreeMap<&'a str, &'b V> {
         &self.values
     }
+
+    pub fn pairs(&self) -> &BTreeMap<String, Cow<'b, V>> {
+        &self.pairs
+    }
+
+    // The type of a value or of a pair, the unknown keys are strings.
+    pub fn value_type(&self, key: &str) -> ValueType {
+        self.types
+            .get(key)
+            .or_else(|| self.pair_types.get(key))
+            .cloned()
+            .unwrap_or(ValueType::String)
+    }
+}
+
+impl<'a, 'b> MatchResult<'a, 'b, [u8]> {
+    // Returns None if a value isn't valid UTF-8.
+    pub fn into_str(self) -> Option<MatchResult<'a, 'b>> {
+        let mut values = BTreeMap::new();
+        for (key, value) in self.values {
+            match str::from_utf8(value) {
+                Ok(value) => values.insert(key, value),
+                Err(_) => return None,
+            };
+        }
+        let mut pairs = BTreeMap::new();
+        for (key, value) in self.pairs {
+            match parsers::into_str(value) {
+                Some(value) => pairs.insert(key, value),
+                None => return None,
+            };
+        }
+        Some(MatchResult {
+            pattern: self.pattern,
+            values: values,
+            pairs: pairs,
+            types: self.types,
+            pair_types: self.pair_types,
+        })
+    }
 }
 
 #[cfg(test)]
 mod test {
-    use parsers::{IntParser, ParseResult};
+    use parsers::{IntParser, GreedyParser, ParseResult, ValueType};
     use super::MatchResult;
     use matcher::Pattern;
     use std::collections::BTreeMap;
@@ -56,4 +131,38 @@ mod test {
         match_result.insert(ParseResult::new(&parser_with_name, "c"));
         assert_eq!(match_result.values(), &expected_values);
     }
+
+    #[test]
+    fn test_given_match_result_when_a_parse_result_with_pairs_is_inserted_then_every_pair_is_stored
+        () {
+        let parser = IntParser::with_name("name".to_owned());
+        let pattern = Pattern::with_random_uuid();
+        let mut match_result = MatchResult::new(&pattern);
+        let mut parse_result = ParseResult::new(&parser, "a=1");
+        parse_result.set_pairs(vec![("kv.a".to_owned(), "1".into(), ValueType::Int),
+                                    ("kv.b".to_owned(), "2".into(), ValueType::String)]);
+        match_result.insert(parse_result);
+
+        assert_eq!(match_result.values().len(), 1);
+        assert_eq!(match_result.values().get("name"), Some(&"a=1"));
+        assert_eq!(match_result.pairs().len(), 2);
+        assert_eq!(match_result.pairs().get("kv.a"), Some(&"1".into()));
+        assert_eq!(match_result.pairs().get("kv.b"), Some(&"2".into()));
+        assert_eq!(match_result.value_type("kv.a"), ValueType::Int);
+        assert_eq!(match_result.value_type("kv.b"), ValueType::String);
+    }
+
+    #[test]
+    fn test_given_match_result_when_typed_values_are_inserted_then_their_types_are_stored() {
+        let int_parser = IntParser::with_name("pid".to_owned());
+        let greedy_parser = GreedyParser::with_name("user".to_owned());
+        let pattern = Pattern::with_random_uuid();
+        let mut match_result = MatchResult::new(&pattern);
+        match_result.insert(ParseResult::new(&int_parser, "42"));
+        match_result.insert(ParseResult::new(&greedy_parser, "joe"));
+
+        assert_eq!(match_result.value_type("pid"), ValueType::Int);
+        assert_eq!(match_result.value_type("user"), ValueType::String);
+        assert_eq!(match_result.value_type("missing"), ValueType::String);
+    }
 }
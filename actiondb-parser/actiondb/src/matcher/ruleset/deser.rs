@@ -0,0 +1,89 @@
+use serde;
+
+use super::{Ruleset, DEFAULT_FIELD};
+use matcher::pattern::Pattern;
+
+impl serde::de::Deserialize for Ruleset {
+    fn deserialize<D>(deserializer: &mut D) -> Result<Ruleset, D::Error>
+        where D: serde::de::Deserializer
+    {
+        deserializer.deserialize_struct("Ruleset", &[], RulesetVisitor)
+    }
+}
+
+enum Field {
+    PROGRAM,
+    FIELD,
+    VALUES,
+    PATTERNS,
+}
+
+impl serde::Deserialize for Field {
+    fn deserialize<D>(deserializer: &mut D) -> Result<Field, D::Error>
+        where D: serde::de::Deserializer
+    {
+        struct FieldVisitor;
+
+        impl serde::de::Visitor for FieldVisitor {
+            type Value = Field;
+
+            fn visit_str<E>(&mut self, value: &str) -> Result<Field, E>
+                where E: serde::de::Error
+            {
+                match value {
+                    "program" => Ok(Field::PROGRAM),
+                    "field" => Ok(Field::FIELD),
+                    "values" => Ok(Field::VALUES),
+                    "patterns" => Ok(Field::PATTERNS),
+                    _ => Err(serde::de::Error::custom(format!("Unexpected field: {}", value))),
+                }
+            }
+        }
+
+        deserializer.deserialize(FieldVisitor)
+    }
+}
+
+struct RulesetVisitor;
+
+impl serde::de::Visitor for RulesetVisitor {
+    type Value = Ruleset;
+
+    // `program: [...]` is the short form of `field: PROGRAM` and `values: [...]`.
+    fn visit_map<V>(&mut self, mut visitor: V) -> Result<Ruleset, V::Error>
+        where V: serde::de::MapVisitor
+    {
+        let mut program: Option<Vec<String>> = None;
+        let mut field: Option<String> = None;
+        let mut values: Option<Vec<String>> = None;
+        let mut patterns: Option<Vec<Pattern>> = None;
+
+        while let Some(key) = try!(visitor.visit_key()) {
+            match key {
+                Field::PROGRAM => program = Some(try!(visitor.visit_value())),
+                Field::FIELD => field = Some(try!(visitor.visit_value())),
+                Field::VALUES => values = Some(try!(visitor.visit_value())),
+                Field::PATTERNS => patterns = Some(try!(visitor.visit_value())),
+            }
+        }
+
+        let (field, values) = match (program, field, values) {
+            (Some(program), None, None) => (DEFAULT_FIELD.to_owned(), program),
+            (None, field, Some(values)) => (field.unwrap_or_else(|| DEFAULT_FIELD.to_owned()), values),
+            (Some(_), _, _) => {
+                return Err(serde::de::Error::custom("A ruleset can't have both program and field or values"
+                                                        .to_owned()))
+            }
+            (None, _, None) => try!(visitor.missing_field("values")),
+        };
+
+        let patterns = match patterns {
+            Some(patterns) => patterns,
+            None => try!(visitor.missing_field("patterns")),
+        };
+
+        try!(visitor.end());
+
+        Ok(Ruleset::new(field, values, patterns))
+    }
+}
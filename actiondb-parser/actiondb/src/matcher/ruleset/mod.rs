@@ -0,0 +1,132 @@
+// The rulesets group the patterns by a field of the messages (the name of the
+// program which sent them by default), like the rulesets of patterndb. A
+// message is matched only against the patterns of the ruleset selected by its
+// field, so a pattern can't match the messages of other programs. The messages
+// which don't select any ruleset are matched against the fallback patterns.
+
+mod deser;
+mod ser;
+#[cfg(test)]
+mod test;
+
+use matcher::Matcher;
+use matcher::pattern::Pattern;
+use matcher::result::MatchResult;
+use uuid::Uuid;
+
+use std::collections::{BTreeMap, BTreeSet};
+
+pub const DEFAULT_FIELD: &'static str = "PROGRAM";
+
+#[derive(Clone, Debug)]
+pub struct Ruleset {
+    field: String,
+    values: Vec<String>,
+    patterns: Vec<Pattern>,
+}
+
+impl Ruleset {
+    pub fn new(field: String, values: Vec<String>, patterns: Vec<Pattern>) -> Ruleset {
+        Ruleset {
+            field: field,
+            values: values,
+            patterns: patterns,
+        }
+    }
+
+    pub fn field(&self) -> &str {
+        &self.field
+    }
+
+    pub fn values(&self) -> &[String] {
+        &self.values
+    }
+
+    pub fn patterns(&self) -> &[Pattern] {
+        &self.patterns
+    }
+
+    pub fn into_patterns(self) -> Vec<Pattern> {
+        self.patterns
+    }
+
+    pub fn set_default_flags(&mut self, ignore_case: Option<bool>, collapse_whitespace: Option<bool>) {
+        for pattern in &mut self.patterns {
+            pattern.set_default_flags(ignore_case, collapse_whitespace);
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct RulesetMatcher<M> {
+    // The rulesets are selected by the first field (in the order they were
+    // added) whose value has a ruleset.
+    selectors: Vec<(String, BTreeMap<Vec<u8>, usize>)>,
+    rulesets: Vec<M>,
+    fallback: M,
+}
+
+impl<M: Matcher> RulesetMatcher<M> {
+    pub fn new(fallback: M) -> RulesetMatcher<M> {
+        RulesetMatcher {
+            selectors: Vec::new(),
+            rulesets: Vec::new(),
+            fallback: fallback,
+        }
+    }
+
+    // Returns false if a value of the field already selects an other ruleset,
+    // in this case the ruleset isn't added.
+    pub fn add_ruleset(&mut self, field: &str, values: &[String], matcher: M) -> bool {
+        let position = match self.selectors.iter().position(|selector| selector.0 == field) {
+            Some(position) => position,
+            None => {
+                self.selectors.push((field.to_owned(), BTreeMap::new()));
+                self.selectors.len() - 1
+            }
+        };
+        let selector = &mut self.selectors[position].1;
+        if values.iter().any(|value| selector.contains_key(value.as_bytes())) {
+            return false;
+        }
+        for value in values {
+            selector.insert(value.as_bytes().to_vec(), self.rulesets.len());
+        }
+        self.rulesets.push(matcher);
+        true
+    }
+
+    pub fn fallback(&self) -> &M {
+        &self.fallback
+    }
+
+    pub fn rulesets(&self) -> &[M] {
+        &self.rulesets
+    }
+
+    // The lookup returns the value of a field of the message.
+    pub fn select<'a, 'c, L>(&'a self, lookup: L) -> &'a M
+        where L: Fn(&str) -> Option<&'c [u8]>
+    {
+        for selector in &self.selectors {
+            if let Some(index) = lookup(&selector.0).and_then(|value| selector.1.get(value)) {
+                return &self.rulesets[*index];
+            }
+        }
+        &self.fallback
+    }
+
+    pub fn parse_bytes<'a, 'b, 'c, L>(&'a self, lookup: L, text: &'b [u8]) -> Option<MatchResult<'a, 'b, [u8]>>
+        where L: Fn(&str) -> Option<&'c [u8]>
+    {
+        self.select(lookup).parse_bytes(text)
+    }
+
+    pub fn uuids(&self) -> BTreeSet<Uuid> {
+        let mut uuids = self.fallback.uuids();
+        for ruleset in &self.rulesets {
+            uuids.extend(ruleset.uuids());
+        }
+        uuids
+    }
+}
@@ -0,0 +1,53 @@
+use serde;
+
+use super::{Ruleset, DEFAULT_FIELD};
+
+impl serde::Serialize for Ruleset {
+    fn serialize<S>(&self, serializer: &mut S) -> Result<(), S::Error>
+        where S: serde::Serializer
+    {
+        serializer.serialize_struct("Ruleset",
+                                    RulesetMapVisitor {
+                                        value: self,
+                                        state: 0,
+                                    })
+    }
+}
+
+struct RulesetMapVisitor<'a> {
+    value: &'a Ruleset,
+    state: u8,
+}
+
+// The rulesets of the default field are written in the short form.
+impl<'a> serde::ser::MapVisitor for RulesetMapVisitor<'a> {
+    fn visit<S>(&mut self, serializer: &mut S) -> Result<Option<()>, S::Error>
+        where S: serde::Serializer
+    {
+        let is_program = self.value.field() == DEFAULT_FIELD;
+        loop {
+            self.state += 1;
+            match self.state {
+                1 => {
+                    if is_program {
+                        try!(serializer.serialize_struct_elt("program", self.value.values()));
+                    } else {
+                        try!(serializer.serialize_struct_elt("field", self.value.field()));
+                    }
+                    return Ok(Some(()));
+                }
+                2 => {
+                    if !is_program {
+                        try!(serializer.serialize_struct_elt("values", self.value.values()));
+                        return Ok(Some(()));
+                    }
+                }
+                3 => {
+                    try!(serializer.serialize_struct_elt("patterns", self.value.patterns()));
+                    return Ok(Some(()));
+                }
+                _ => return Ok(None),
+            }
+        }
+    }
+}
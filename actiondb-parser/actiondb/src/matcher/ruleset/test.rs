@@ -0,0 +1,58 @@
+use super::{Ruleset, RulesetMatcher, DEFAULT_FIELD};
+use matcher::{Matcher, MatcherFactory, Pattern};
+use matcher::suffix_array::SuffixArrayMatcherFactory;
+use serde_json;
+
+fn matcher_of(patterns: &[&str]) -> <SuffixArrayMatcherFactory as MatcherFactory>::Matcher {
+    let mut matcher = SuffixArrayMatcherFactory::new_matcher();
+    for pattern in patterns {
+        matcher.add_pattern(Pattern::from_json(pattern).unwrap()).unwrap();
+    }
+    matcher
+}
+
+const SSHD: &'static str = r#"{"uuid": "9a49c47d-29e9-4072-be84-3b76c6814743", "pattern": "session closed for %{GREEDY:user}"}"#;
+const CRON: &'static str = r#"{"uuid": "fa8bdbcb-e0fd-4da1-9fa4-15ecfec28ad2", "pattern": "session closed for %{GREEDY:user}"}"#;
+const OTHER: &'static str = r#"{"uuid": "c11c806a-766d-4a09-9f24-7de1fe02e51e", "pattern": "session %{GREEDY:rest}"}"#;
+
+#[test]
+fn test_given_ruleset_when_it_is_deserialized_then_program_is_the_short_form_of_field_and_values() {
+    let ruleset: Ruleset = serde_json::from_str(r#"{"program": ["sshd", "sshd-session"], "patterns": []}"#).unwrap();
+    assert_eq!(ruleset.field(), DEFAULT_FIELD);
+    assert_eq!(ruleset.values(), &["sshd".to_owned(), "sshd-session".to_owned()]);
+
+    let ruleset: Ruleset = serde_json::from_str(r#"{"field": "HOST", "values": ["fw1"], "patterns": []}"#).unwrap();
+    assert_eq!(ruleset.field(), "HOST");
+    assert_eq!(serde_json::to_string(&ruleset).unwrap(),
+               r#"{"field":"HOST","values":["fw1"],"patterns":[]}"#);
+
+    assert!(serde_json::from_str::<Ruleset>(r#"{"program": ["sshd"], "values": ["sshd"], "patterns": []}"#).is_err());
+    assert!(serde_json::from_str::<Ruleset>(r#"{"field": "HOST", "patterns": []}"#).is_err());
+}
+
+#[test]
+fn test_given_ruleset_matcher_when_the_field_of_the_message_selects_a_ruleset_then_only_its_patterns_are_used() {
+    let mut matcher = RulesetMatcher::new(matcher_of(&[OTHER]));
+    assert!(matcher.add_ruleset("PROGRAM", &["sshd".to_owned(), "sshd-session".to_owned()], matcher_of(&[SSHD])));
+    assert!(matcher.add_ruleset("PROGRAM", &["cron".to_owned()], matcher_of(&[CRON])));
+
+    let message = b"session closed for root";
+    let uuid_of = |program: Option<&'static [u8]>| {
+        matcher.parse_bytes(|field| if field == "PROGRAM" { program } else { None }, message)
+               .map(|result| result.pattern().uuid().hyphenated().to_string())
+    };
+    assert_eq!(uuid_of(Some(b"sshd-session")), Some("9a49c47d-29e9-4072-be84-3b76c6814743".to_owned()));
+    assert_eq!(uuid_of(Some(b"cron")), Some("fa8bdbcb-e0fd-4da1-9fa4-15ecfec28ad2".to_owned()));
+    assert_eq!(uuid_of(Some(b"postfix")), Some("c11c806a-766d-4a09-9f24-7de1fe02e51e".to_owned()));
+    assert_eq!(uuid_of(None), Some("c11c806a-766d-4a09-9f24-7de1fe02e51e".to_owned()));
+    assert_eq!(matcher.uuids().len(), 3);
+}
+
+#[test]
+fn test_given_ruleset_matcher_when_a_value_already_selects_a_ruleset_then_the_new_ruleset_is_not_added() {
+    let mut matcher = RulesetMatcher::new(matcher_of(&[]));
+    assert!(matcher.add_ruleset("PROGRAM", &["sshd".to_owned()], matcher_of(&[SSHD])));
+    assert!(!matcher.add_ruleset("PROGRAM", &["cron".to_owned(), "sshd".to_owned()], matcher_of(&[CRON])));
+    assert!(matcher.add_ruleset("HOST", &["sshd".to_owned()], matcher_of(&[CRON])));
+    assert_eq!(matcher.rulesets().len(), 2);
+}
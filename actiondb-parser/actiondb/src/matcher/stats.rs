@@ -0,0 +1,252 @@
+use rand::{self, Rng};
+use serde_json::{self, Value};
+use uuid::Uuid;
+
+use std::collections::BTreeMap;
+use std::fs::{self, File};
+use std::io::{self, Write};
+use std::mem;
+
+pub const DEFAULT_SAMPLE_SIZE: usize = 100;
+
+// Counts the matches of every pattern and the messages which didn't match
+// any of them. A fixed number of the unmatched messages is kept as a uniform
+// random sample (reservoir sampling), so the most common unmatched messages
+// are likely to be there without storing all of them.
+#[derive(Clone, Debug)]
+pub struct Statistics {
+    matches: BTreeMap<Uuid, u64>,
+    unmatched: u64,
+    samples: Vec<String>,
+    sample_size: usize,
+}
+
+impl Statistics {
+    pub fn new(sample_size: usize) -> Statistics {
+        Statistics {
+            matches: BTreeMap::new(),
+            unmatched: 0,
+            samples: Vec::new(),
+            sample_size: sample_size,
+        }
+    }
+
+    pub fn sample_size(&self) -> usize {
+        self.sample_size
+    }
+
+    // The sample is truncated if it's bigger than the new size.
+    pub fn set_sample_size(&mut self, sample_size: usize) {
+        self.sample_size = sample_size;
+        self.samples.truncate(sample_size);
+    }
+
+    // The patterns are registered with zero matches, so the ones which never
+    // match show up in the statistics too.
+    pub fn add_pattern(&mut self, uuid: Uuid) {
+        self.matches.entry(uuid).or_insert(0);
+    }
+
+    pub fn record_match(&mut self, uuid: &Uuid) {
+        *self.matches.entry(*uuid).or_insert(0) += 1;
+    }
+
+    // The messages which aren't valid UTF-8 are sampled lossily.
+    pub fn record_unmatched(&mut self, message: &[u8]) {
+        self.unmatched += 1;
+        if self.samples.len() < self.sample_size {
+            self.samples.push(String::from_utf8_lossy(message).into_owned());
+        } else if self.sample_size > 0 {
+            let pos = rand::thread_rng().gen_range(0, self.unmatched) as usize;
+            if pos < self.sample_size {
+                self.samples[pos] = String::from_utf8_lossy(message).into_owned();
+            }
+        }
+    }
+
+    // Adds the counters of the other statistics to these ones. The merged
+    // sample is drawn from the two samples in proportion to the number of
+    // unmatched messages they were taken from, so it remains uniform.
+    pub fn merge(&mut self, other: &Statistics) {
+        for (uuid, count) in &other.matches {
+            *self.matches.entry(*uuid).or_insert(0) += *count;
+        }
+
+        let mut rng = rand::thread_rng();
+        let mut mine = mem::replace(&mut self.samples, Vec::new());
+        let mut theirs = other.samples.clone();
+        rng.shuffle(&mut mine);
+        rng.shuffle(&mut theirs);
+        let (mut my_weight, mut their_weight) = (self.unmatched, other.unmatched);
+        while self.samples.len() < self.sample_size && (!mine.is_empty() || !theirs.is_empty()) {
+            let from_mine = theirs.is_empty() ||
+                            (!mine.is_empty() && rng.gen_range(0, my_weight + their_weight) < my_weight);
+            if from_mine {
+                self.samples.extend(mine.pop());
+                my_weight -= 1;
+            } else {
+                self.samples.extend(theirs.pop());
+                their_weight -= 1;
+            }
+        }
+        self.unmatched += other.unmatched;
+    }
+
+    pub fn matches(&self) -> &BTreeMap<Uuid, u64> {
+        &self.matches
+    }
+
+    pub fn unmatched(&self) -> u64 {
+        self.unmatched
+    }
+
+    pub fn samples(&self) -> &[String] {
+        &self.samples
+    }
+
+    // The patterns which haven't matched any message yet.
+    pub fn dead_patterns(&self) -> Vec<&Uuid> {
+        self.matches.iter().filter(|&(_, count)| *count == 0).map(|(uuid, _)| uuid).collect()
+    }
+
+    pub fn to_json(&self) -> String {
+        let matches = self.matches
+                          .iter()
+                          .map(|(uuid, count)| (uuid.hyphenated().to_string(), Value::U64(*count)))
+                          .collect();
+        let samples = self.samples.iter().map(|sample| Value::String(sample.clone())).collect();
+
+        let mut object = BTreeMap::new();
+        object.insert("matches".to_owned(), Value::Object(matches));
+        object.insert("unmatched".to_owned(), Value::U64(self.unmatched));
+        object.insert("unmatched_samples".to_owned(), Value::Array(samples));
+        serde_json::to_string_pretty(&Value::Object(object)).expect("Failed to serialize the statistics")
+    }
+
+    // The statistics are written into a temporary file first, so the readers
+    // never see a partially written file.
+    pub fn write_to_file(&self, path: &str) -> io::Result<()> {
+        let tmp_path = format!("{}.tmp", path);
+        {
+            let mut file = try!(File::create(&tmp_path));
+            try!(file.write_all(self.to_json().as_bytes()));
+        }
+        fs::rename(&tmp_path, path)
+    }
+}
+
+impl Default for Statistics {
+    fn default() -> Statistics {
+        Statistics::new(DEFAULT_SAMPLE_SIZE)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::Statistics;
+    use serde_json::{self, Value};
+    use uuid::Uuid;
+
+    #[test]
+    fn test_given_statistics_when_patterns_match_then_their_counters_are_incremented() {
+        let hot = Uuid::parse_str("9a49c47d-29e9-4072-be84-3b76c6814743").unwrap();
+        let dead = Uuid::parse_str("fa8bdbcb-e0fd-4da1-9fa4-15ecfec28ad2").unwrap();
+        let mut stats = Statistics::new(10);
+        stats.add_pattern(hot);
+        stats.add_pattern(dead);
+
+        stats.record_match(&hot);
+        stats.record_match(&hot);
+
+        assert_eq!(stats.matches().get(&hot), Some(&2));
+        assert_eq!(stats.matches().get(&dead), Some(&0));
+        assert_eq!(stats.dead_patterns(), vec![&dead]);
+    }
+
+    #[test]
+    fn test_given_statistics_when_more_messages_are_unmatched_than_the_sample_size_then_the_sample_is_bounded
+        () {
+        let mut stats = Statistics::new(3);
+        for i in 0..100 {
+            stats.record_unmatched(format!("message {}", i).as_bytes());
+        }
+
+        assert_eq!(stats.unmatched(), 100);
+        assert_eq!(stats.samples().len(), 3);
+        for sample in stats.samples() {
+            assert_eq!(sample.starts_with("message "), true);
+        }
+    }
+
+    #[test]
+    fn test_given_statistics_when_the_sample_size_is_zero_then_no_message_is_kept() {
+        let mut stats = Statistics::new(0);
+        stats.record_unmatched(b"foo");
+        assert_eq!(stats.unmatched(), 1);
+        assert_eq!(stats.samples().is_empty(), true);
+    }
+
+    #[test]
+    fn test_given_statistics_when_the_sample_size_is_decreased_then_the_sample_is_truncated() {
+        let mut stats = Statistics::new(3);
+        for i in 0..3 {
+            stats.record_unmatched(format!("message {}", i).as_bytes());
+        }
+        stats.set_sample_size(1);
+        assert_eq!(stats.samples().len(), 1);
+        assert_eq!(stats.unmatched(), 3);
+    }
+
+    #[test]
+    fn test_given_statistics_when_they_are_merged_then_the_counters_are_added_and_the_sample_is_bounded() {
+        let uuid = Uuid::parse_str("9a49c47d-29e9-4072-be84-3b76c6814743").unwrap();
+        let other_uuid = Uuid::parse_str("fa8bdbcb-e0fd-4da1-9fa4-15ecfec28ad2").unwrap();
+        let mut stats = Statistics::new(3);
+        stats.add_pattern(other_uuid);
+        stats.record_match(&uuid);
+        stats.record_unmatched(b"foo");
+        let mut other = Statistics::new(3);
+        other.record_match(&uuid);
+        for i in 0..10 {
+            other.record_unmatched(format!("message {}", i).as_bytes());
+        }
+
+        stats.merge(&other);
+
+        assert_eq!(stats.matches().get(&uuid), Some(&2));
+        assert_eq!(stats.matches().get(&other_uuid), Some(&0));
+        assert_eq!(stats.unmatched(), 11);
+        assert_eq!(stats.samples().len(), 3);
+        assert_eq!(other.unmatched(), 10);
+    }
+
+    #[test]
+    fn test_given_statistics_when_the_samples_are_smaller_than_the_sample_size_then_every_sample_is_kept() {
+        let mut stats = Statistics::new(10);
+        stats.record_unmatched(b"foo");
+        let mut other = Statistics::new(10);
+        other.record_unmatched(b"bar");
+
+        stats.merge(&other);
+
+        let mut samples = stats.samples().to_vec();
+        samples.sort();
+        assert_eq!(samples, vec!["bar".to_owned(), "foo".to_owned()]);
+    }
+
+    #[test]
+    fn test_given_statistics_when_they_are_converted_to_json_then_every_counter_is_there() {
+        let uuid = Uuid::parse_str("9a49c47d-29e9-4072-be84-3b76c6814743").unwrap();
+        let mut stats = Statistics::new(10);
+        stats.record_match(&uuid);
+        stats.record_unmatched(b"caf\xe9");
+
+        let json: Value = serde_json::from_str(&stats.to_json()).unwrap();
+        let object = json.as_object().unwrap();
+        let matches = object.get("matches").unwrap().as_object().unwrap();
+        assert_eq!(matches.get("9a49c47d-29e9-4072-be84-3b76c6814743").unwrap().as_u64(), Some(1));
+        assert_eq!(object.get("unmatched").unwrap().as_u64(), Some(1));
+        assert_eq!(object.get("unmatched_samples").unwrap().as_array().unwrap(),
+                   &vec![Value::String("caf\u{fffd}".to_owned())]);
+    }
+}
@@ -5,31 +5,38 @@ use super::interface::{
     ParserEntry
 };
 
-use parsers::{Parser, ParseResult};
+use parsers::{self, Parser, ParseResult};
 use matcher::{
+    BuildError,
     Matcher,
     Pattern
 };
 use matcher::compiled_pattern::TokenType;
 use matcher::result::MatchResult;
+use matcher::priority;
+use matcher::explain::{Explanation, Trace};
+use matcher::cache::{self, Cacheable, Encoder, Decoder};
 use utils::CommonPrefix;
+use uuid::Uuid;
 
-use std::borrow::Borrow;
+use std::collections::BTreeSet;
 
 #[derive(Debug, Clone)]
 pub struct SuffixTable {
     literal_entries: Vec<LiteralE>,
     parser_entries: Vec<ParserE>,
+    max_priority: Option<i32>,
 }
 
 impl SuffixTable {
-    fn longest_common_prefix_between_consecutive_entries(&self, value: &str, pos: usize) -> Option<&LiteralE> {
+    fn longest_common_prefix_between_consecutive_entries(&self, value: &[u8], pos: usize) -> Option<&LiteralE> {
         let first_opt = self.literal_entries.get(pos);
         let second_opt = self.literal_entries.get(pos + 1);
 
         first_opt.map_or(second_opt, |first| {
             second_opt.map_or(first_opt, |second| {
-                if first.literal().common_prefix_len(value) >= second.literal().common_prefix_len(value) {
+                if first.literal().as_bytes().common_prefix_len(value) >=
+                   second.literal().as_bytes().common_prefix_len(value) {
                     first_opt
                 } else {
                     second_opt
@@ -38,7 +45,7 @@ impl SuffixTable {
         })
     }
 
-    fn longest_common_prefix_around_pos(&self, value: &str, pos: usize) -> Option<&LiteralE> {
+    fn longest_common_prefix_around_pos(&self, value: &[u8], pos: usize) -> Option<&LiteralE> {
         if pos == 0 {
             self.literal_entries.get(pos)
         } else {
@@ -60,15 +67,148 @@ impl SuffixTable {
         }
     }
 
-    fn parse_with_parsers<'a, 'b>(&'a self, value: &'b str) -> Option<MatchResult<'a, 'b>> {
+    // The loose literals of the patterns which ignore case or collapse
+    // whitespace are parsers, they are tried when the exact literals lead to a
+    // dead end.
+    fn has_loose_literals(&self) -> bool {
+        self.parser_entries.iter().any(|entry| entry.parser.kind() == "LITERAL")
+    }
+
+    // The literal which the value starts with and the length of the value it
+    // covers.
+    fn lookup_literal(&self, value: &[u8]) -> Option<(&LiteralE, usize)> {
+        self.longest_common_prefix(value).and_then(|child| {
+            match child.determine_match_type(value) {
+                MatchType::Exact => Some((child, value.len())),
+                MatchType::Partial(common_prefix_len) => Some((child, common_prefix_len)),
+                MatchType::None => None,
+            }
+        })
+    }
+
+    // Both parse() and explain() take this path, the latter records it into
+    // the trace.
+    fn parse_traced<'a, 'b, T: Trace>(&'a self,
+                                      value: &'b [u8],
+                                      depth: usize,
+                                      trace: &mut T)
+                                      -> Option<MatchResult<'a, 'b, [u8]>> {
+        match self.lookup_literal(value) {
+            Some((child, len)) => {
+                trace.push_literal(depth, value, child.literal());
+                let rest = value.ltrunc(len);
+                trace.reached(rest, |uuids| child.collect_uuids(uuids));
+                let result = if rest.is_empty() {
+                    child.pattern().map(MatchResult::new)
+                } else {
+                    child.child().and_then(|child| child.parse_traced(rest, depth + 1, trace))
+                };
+                // the literals are preferred, the loose literals are tried
+                // only if the exact ones lead to a dead end, the other
+                // parsers aren't
+                if result.is_none() && self.has_loose_literals() {
+                    trace.push_backtrack(depth, value);
+                    self.parse_with_loose_literals(value, depth, trace)
+                } else {
+                    result
+                }
+            }
+            None => self.parse_with_parsers(value, depth, trace),
+        }
+    }
+
+    fn parse_with_parsers<'a, 'b, T: Trace>(&'a self,
+                                            value: &'b [u8],
+                                            depth: usize,
+                                            trace: &mut T)
+                                            -> Option<MatchResult<'a, 'b, [u8]>> {
         for parser in &self.parser_entries {
-            if let Some(result) = parser.parse(value) {
+            if let Some(result) = parser.parse_traced(value, depth, trace) {
+                return Some(result);
+            }
+        }
+        None
+    }
+
+    fn parse_with_loose_literals<'a, 'b, T: Trace>(&'a self,
+                                                   value: &'b [u8],
+                                                   depth: usize,
+                                                   trace: &mut T)
+                                                   -> Option<MatchResult<'a, 'b, [u8]>> {
+        for parser in self.parser_entries.iter().filter(|entry| entry.parser.kind() == "LITERAL") {
+            if let Some(result) = parser.parse_traced(value, depth, trace) {
                 return Some(result);
             }
         }
         None
     }
 
+    fn parse_with_priorities<'a, 'b, T: Trace>(&'a self,
+                                               value: &'b [u8],
+                                               trace: &mut T)
+                                               -> Option<MatchResult<'a, 'b, [u8]>> {
+        let result = self.parse_traced(value, 0, trace);
+        let parse_all = |above| {
+            let mut results = Vec::new();
+            self.parse_all_into(value, above, &mut results);
+            results
+        };
+        priority::choose(result, self.max_priority, parse_all, trace)
+    }
+
+    // Only the patterns with higher priority than `above` are collected, the
+    // tables which don't have such patterns are skipped.
+    fn parse_all_into<'a, 'b>(&'a self,
+                              value: &'b [u8],
+                              above: Option<i32>,
+                              results: &mut Vec<MatchResult<'a, 'b, [u8]>>) {
+        if !priority::may_exceed(self.max_priority, above) {
+            return;
+        }
+        let end = match self.literal_entries.binary_search_by(|probe| probe.literal().as_bytes().cmp(value)) {
+            Ok(pos) => pos + 1,
+            Err(pos) => pos,
+        };
+        // the literals which are prefixes of the value precede it, the longest is the last one
+        let candidates = self.literal_entries[..end]
+                             .iter()
+                             .rev()
+                             .take_while(|entry| entry.literal().as_bytes().common_prefix_len(value) > 0);
+        for entry in candidates {
+            // the child can match the empty rest of the value too, like in the trie
+            let common_prefix_len = match entry.determine_match_type(value) {
+                MatchType::Exact => {
+                    if let Some(pattern) = entry.pattern() {
+                        if priority::exceeds(pattern.priority(), above) {
+                            results.push(MatchResult::new(pattern));
+                        }
+                    }
+                    value.len()
+                }
+                MatchType::Partial(common_prefix_len) => common_prefix_len,
+                MatchType::None => continue,
+            };
+            if let Some(child) = entry.child() {
+                child.parse_all_into(value.ltrunc(common_prefix_len), above, results);
+            }
+        }
+        for parser in &self.parser_entries {
+            parser.parse_all_into(value, above, results);
+        }
+    }
+
+    // Every table knows the highest priority of the patterns below it. It's
+    // recomputed from the entries on removal, so below the root it's only an
+    // upper bound.
+    fn entries_max_priority(&self) -> Option<i32> {
+        let literal_entries = self.literal_entries.iter().map(|entry| (entry.pattern(), entry.child()));
+        let parser_entries = self.parser_entries.iter().map(|entry| (entry.pattern(), entry.child()));
+        literal_entries.chain(parser_entries).fold(None, |highest, (pattern, child)| {
+            let highest = pattern.map_or(highest, |pattern| priority::add(highest, pattern.priority()));
+            child.map_or(highest, |child| priority::add(highest, child.max_priority.unwrap_or(0)))
+        })
+    }
+
     fn insert_parser(&mut self, parser: Box<Parser>) -> &mut Entry<SA=SuffixTable> {
         let pos = self.parser_entries.iter().position(|x| {
             x.parser.hash_os() == parser.hash_os()
@@ -82,11 +222,8 @@ impl SuffixTable {
         }
     }
 
-    pub fn longest_common_prefix<'a, 'b>(&'a self, value: &'b str) -> Option<&'a LiteralE> {
-        let result = self.literal_entries.binary_search_by(|probe| {
-            let s: &str = probe.literal().borrow();
-            s.cmp(value)
-        });
+    pub fn longest_common_prefix<'a, 'b>(&'a self, value: &'b [u8]) -> Option<&'a LiteralE> {
+        let result = self.literal_entries.binary_search_by(|probe| probe.literal().as_bytes().cmp(value));
         match result {
             Ok(pos) => self.literal_entries.get(pos),
             Err(pos) =>self.longest_common_prefix_around_pos(value, pos)
@@ -98,7 +235,8 @@ impl Default for SuffixTable {
     fn default() -> Self {
         SuffixTable {
             literal_entries: Vec::new(),
-            parser_entries: Vec::new()
+            parser_entries: Vec::new(),
+            max_priority: None
         }
     }
 }
@@ -108,18 +246,62 @@ impl SuffixArray for SuffixTable {
         SuffixTable::default()
     }
 
-    fn insert(&mut self, mut pattern: Pattern) {
+    fn insert(&mut self, mut pattern: Pattern) -> Result<(), BuildError> {
+        let priority = pattern.priority();
         if let Some(token) = pattern.pop_first_token() {
-            let mut entry: &mut Entry<SA=SuffixTable> = match token {
-                TokenType::Literal(literal) => {
-                    self.insert_literal(literal)
-                },
-                TokenType::Parser(parser) => {
-                    self.insert_parser(parser)
-                }
-            };
-            entry.insert(pattern);
+            {
+                let mut entry: &mut Entry<SA=SuffixTable> = match token {
+                    TokenType::Literal(literal) => {
+                        self.insert_literal(literal)
+                    },
+                    TokenType::Parser(parser) => {
+                        self.insert_parser(parser)
+                    }
+                    TokenType::Optional(_) | TokenType::Alternatives(_) | TokenType::Choice(_) => {
+                        return Err(BuildError::UnexpandedPattern(*pattern.uuid()));
+                    }
+                };
+                try!(entry.insert(pattern));
+            }
+            self.max_priority = priority::add(self.max_priority, priority);
+        }
+        Ok(())
+    }
+
+    fn collect_uuids(&self, uuids: &mut BTreeSet<Uuid>) {
+        for entry in &self.literal_entries {
+            entry.collect_uuids(uuids);
+        }
+        for entry in &self.parser_entries {
+            entry.collect_uuids(uuids);
+        }
+    }
+
+    fn collect_priorities(&self, priorities: &mut BTreeSet<i32>) {
+        for entry in &self.literal_entries {
+            entry.collect_priorities(priorities);
+        }
+        for entry in &self.parser_entries {
+            entry.collect_priorities(priorities);
+        }
+    }
+
+    fn remove(&mut self, uuid: &Uuid) -> bool {
+        let mut removed = false;
+        for entry in &mut self.literal_entries {
+            removed |= entry.remove(uuid);
+        }
+        for entry in &mut self.parser_entries {
+            removed |= entry.remove(uuid);
         }
+        self.literal_entries.retain(|entry| !entry.is_empty());
+        self.parser_entries.retain(|entry| !entry.is_empty());
+        self.max_priority = self.entries_max_priority();
+        removed
+    }
+
+    fn is_empty(&self) -> bool {
+        self.literal_entries.is_empty() && self.parser_entries.is_empty()
     }
 }
 
@@ -149,7 +331,32 @@ impl ParserE {
         }
     }
 
-    fn create_match_result<'a, 'b>(&'a self, kvpair: ParseResult<'a, 'b>) -> Option<MatchResult<'a, 'b>> {
+    fn parse_all_into<'a, 'b>(&'a self,
+                              value: &'b [u8],
+                              above: Option<i32>,
+                              results: &mut Vec<MatchResult<'a, 'b, [u8]>>) {
+        if let Some(kvpair) = self.parser.parse_bytes(value) {
+            let value = value.ltrunc(kvpair.consumed_length());
+
+            if value.is_empty() {
+                if self.pattern().map_or(false, |pattern| priority::exceeds(pattern.priority(), above)) {
+                    results.extend(self.create_match_result(kvpair.clone()));
+                }
+            }
+            if let Some(child) = self.child() {
+                let mut child_results = Vec::new();
+                child.parse_all_into(value, above, &mut child_results);
+                for mut result in child_results {
+                    result.insert(kvpair.clone());
+                    results.push(result);
+                }
+            }
+        }
+    }
+
+    fn create_match_result<'a, 'b>(&'a self,
+                                   kvpair: ParseResult<'a, 'b, [u8]>)
+                                   -> Option<MatchResult<'a, 'b, [u8]>> {
         if let Some(pattern) = self.pattern() {
             let mut result = MatchResult::new(pattern);
             result.insert(kvpair);
@@ -180,24 +387,33 @@ impl Entry for ParserE {
     }
 }
 impl ParserEntry for ParserE {
-    fn parser(&self) -> &Box<Parser> {
-        &self.parser
-    }
-    fn parse<'a, 'b>(&'a self, value: &'b str) -> Option<MatchResult<'a, 'b>> {
-        self.parser.parse(value).and_then(|kvpair| {
-            let value = value.ltrunc(kvpair.value().len());
+    fn parse_traced<'a, 'b, T: Trace>(&'a self,
+                                      value: &'b [u8],
+                                      depth: usize,
+                                      trace: &mut T)
+                                      -> Option<MatchResult<'a, 'b, [u8]>> {
+        match self.parser.parse_bytes(value) {
+            Some(kvpair) => {
+                trace.push_parser(depth, value, &*self.parser, Some(kvpair.value()));
+                let value = value.ltrunc(kvpair.consumed_length());
+                trace.reached(value, |uuids| self.collect_uuids(uuids));
 
-            if value.is_empty() {
-                self.create_match_result(kvpair)
-            } else if let Some(child) = self.child() {
-                child.parse(value).and_then(|mut result| {
-                    result.insert(kvpair);
-                    Some(result)
-                })
-            } else {
+                if value.is_empty() {
+                    self.create_match_result(kvpair)
+                } else if let Some(child) = self.child() {
+                    child.parse_traced(value, depth + 1, trace).map(|mut result| {
+                        result.insert(kvpair);
+                        result
+                    })
+                } else {
+                    None
+                }
+            }
+            None => {
+                trace.push_parser(depth, value, &*self.parser, None);
                 None
             }
-        })
+        }
     }
 }
 
@@ -223,15 +439,17 @@ impl LiteralE {
         }
     }
 
-    pub fn determine_match_type(&self, value: &str) -> MatchType {
-        let common_prefix_len = self.literal().common_prefix_len(value);
+    // The value has to start with the whole literal, otherwise the rest of the
+    // value would be matched against the entries following the literal.
+    pub fn determine_match_type(&self, value: &[u8]) -> MatchType {
+        let common_prefix_len = self.literal().as_bytes().common_prefix_len(value);
 
-        if common_prefix_len == value.len() && common_prefix_len == self.literal().len() {
+        if common_prefix_len < self.literal().len() {
+            MatchType::None
+        } else if common_prefix_len == value.len() {
             MatchType::Exact
-        } else if common_prefix_len < value.len() {
-            MatchType::Partial(common_prefix_len)
         } else {
-            MatchType::None
+            MatchType::Partial(common_prefix_len)
         }
     }
 }
@@ -262,26 +480,109 @@ impl LiteralEntry for LiteralE {
 }
 
 impl Matcher for SuffixTable {
-    fn parse<'a, 'b>(&'a self, value: &'b str) -> Option<MatchResult<'a, 'b>> {
-        if let Some(child) = self.longest_common_prefix(value) {
-            match child.determine_match_type(value) {
-                MatchType::Exact => {
-                    child.pattern().and_then(|pattern| Some(MatchResult::new(pattern)))
-                },
-                MatchType::Partial(common_prefix_len) => {
-                    let value = value.ltrunc(common_prefix_len);
-                    child.child().and_then(|child| child.parse(value))
-                },
-                MatchType::None => None
-            }
-        } else {
-            self.parse_with_parsers(value)
+    fn parse_bytes<'a, 'b>(&'a self, value: &'b [u8]) -> Option<MatchResult<'a, 'b, [u8]>> {
+        self.parse_with_priorities(value, &mut ())
+    }
+    fn parse_all_bytes<'a, 'b>(&'a self, value: &'b [u8]) -> Vec<MatchResult<'a, 'b, [u8]>> {
+        let mut results = Vec::new();
+        self.parse_all_into(value, None, &mut results);
+        results
+    }
+    fn explain_bytes(&self, text: &[u8]) -> Explanation {
+        let mut explanation = Explanation::new(text);
+        explanation.reached(text, |uuids| SuffixArray::collect_uuids(self, uuids));
+        let matched = self.parse_with_priorities(text, &mut explanation).map(|result| *result.pattern().uuid());
+        explanation.set_matched(matched);
+        explanation
+    }
+    fn remove_pattern(&mut self, uuid: &Uuid) -> bool {
+        let removed = SuffixArray::remove(self, uuid);
+        if removed {
+            let mut priorities = BTreeSet::new();
+            self.collect_priorities(&mut priorities);
+            self.max_priority = priority::highest(&priorities);
         }
+        removed
     }
-    fn add_pattern(&mut self, pattern: Pattern) {
-        self.insert(pattern);
+    // Pattern::expand() checks every variant before any of them is inserted
+    // and an expanded variant can always be inserted, so a failed pattern
+    // doesn't leave a part of its variants in the table and doesn't raise its
+    // highest priority.
+    fn add_pattern(&mut self, pattern: Pattern) -> Result<(), BuildError> {
+        let variants = try!(pattern.expand());
+        for variant in variants {
+            try!(self.insert(variant));
+        }
+        Ok(())
+    }
+    fn uuids(&self) -> BTreeSet<Uuid> {
+        let mut uuids = BTreeSet::new();
+        SuffixArray::collect_uuids(self, &mut uuids);
+        uuids
     }
     fn boxed_clone(&self) -> Box<Matcher> {
         Box::new(self.clone())
     }
 }
+
+fn encode_child(child: Option<&SuffixTable>, encoder: &mut Encoder) {
+    encoder.write_bool(child.is_some());
+    if let Some(child) = child {
+        child.encode(encoder);
+    }
+}
+
+fn decode_child(decoder: &mut Decoder) -> Result<Option<SuffixTable>, cache::Error> {
+    if try!(decoder.read_bool()) {
+        Ok(Some(try!(SuffixTable::decode(decoder))))
+    } else {
+        Ok(None)
+    }
+}
+
+// The entries are written in their current order, so the literals remain
+// sorted and the parsers are tried in the same order after decoding.
+impl Cacheable for SuffixTable {
+    fn encode(&self, encoder: &mut Encoder) {
+        encoder.write_bool(self.max_priority.is_some());
+        encoder.write_i32(self.max_priority.unwrap_or(0));
+        encoder.write_usize(self.literal_entries.len());
+        for entry in &self.literal_entries {
+            encoder.write_str(&entry.literal);
+            cache::encode_opt_pattern(entry.pattern(), encoder);
+            encode_child(entry.child(), encoder);
+        }
+        encoder.write_usize(self.parser_entries.len());
+        for entry in &self.parser_entries {
+            parsers::encode(&*entry.parser, encoder);
+            cache::encode_opt_pattern(entry.pattern(), encoder);
+            encode_child(entry.child(), encoder);
+        }
+    }
+
+    fn decode(decoder: &mut Decoder) -> Result<SuffixTable, cache::Error> {
+        let mut table = SuffixTable::new();
+        let has_priorities = try!(decoder.read_bool());
+        let max_priority = try!(decoder.read_i32());
+        if has_priorities {
+            table.max_priority = Some(max_priority);
+        }
+        for _ in 0..try!(decoder.read_usize()) {
+            let literal = try!(decoder.read_string());
+            table.literal_entries.push(LiteralE {
+                literal: literal,
+                pattern: try!(cache::decode_opt_pattern(decoder)),
+                child: try!(decode_child(decoder)),
+            });
+        }
+        for _ in 0..try!(decoder.read_usize()) {
+            let parser = try!(parsers::decode(decoder));
+            table.parser_entries.push(ParserE {
+                parser: parser,
+                pattern: try!(cache::decode_opt_pattern(decoder)),
+                child: try!(decode_child(decoder)),
+            });
+        }
+        Ok(table)
+    }
+}
@@ -1,10 +1,17 @@
-use matcher::Pattern;
-use parsers::Parser;
+use matcher::{BuildError, Pattern};
 use matcher::result::MatchResult;
+use matcher::explain::Trace;
+use uuid::Uuid;
+
+use std::collections::BTreeSet;
 
 pub trait SuffixArray: Clone {
     fn new() -> Self;
-    fn insert(&mut self, pattern: Pattern);
+    fn insert(&mut self, pattern: Pattern) -> Result<(), BuildError>;
+    fn collect_uuids(&self, uuids: &mut BTreeSet<Uuid>);
+    fn collect_priorities(&self, priorities: &mut BTreeSet<i32>);
+    fn remove(&mut self, uuid: &Uuid) -> bool;
+    fn is_empty(&self) -> bool;
 }
 
 pub trait Entry {
@@ -14,9 +21,49 @@ pub trait Entry {
     fn child(&self) -> Option<&Self::SA>;
     fn child_mut(&mut self) -> Option<&mut Self::SA>;
     fn set_child(&mut self, child: Option<Self::SA>);
-    fn insert(&mut self, pattern: Pattern) {
+    fn collect_uuids(&self, uuids: &mut BTreeSet<Uuid>) {
+        if let Some(pattern) = self.pattern() {
+            uuids.insert(*pattern.uuid());
+        }
+        if let Some(child) = self.child() {
+            child.collect_uuids(uuids);
+        }
+    }
+    fn collect_priorities(&self, priorities: &mut BTreeSet<i32>) {
+        if let Some(pattern) = self.pattern() {
+            priorities.insert(pattern.priority());
+        }
+        if let Some(child) = self.child() {
+            child.collect_priorities(priorities);
+        }
+    }
+    // Removes the pattern from the entry and from its child. The child is
+    // dropped if it becomes empty.
+    fn remove(&mut self, uuid: &Uuid) -> bool {
+        let mut removed = false;
+        if self.pattern().map_or(false, |pattern| pattern.uuid() == uuid) {
+            self.set_pattern(None);
+            removed = true;
+        }
+        let child_is_empty = match self.child_mut() {
+            Some(child) => {
+                removed |= child.remove(uuid);
+                child.is_empty()
+            }
+            None => false,
+        };
+        if child_is_empty {
+            self.set_child(None);
+        }
+        removed
+    }
+    fn is_empty(&self) -> bool {
+        self.pattern().is_none() && self.child().is_none()
+    }
+    fn insert(&mut self, pattern: Pattern) -> Result<(), BuildError> {
         if pattern.pattern().is_empty() {
             self.set_pattern(Some(pattern));
+            Ok(())
         }
         else {
             if self.child().is_none() {
@@ -24,7 +71,7 @@ pub trait Entry {
                 self.set_child(Some(sa));
             }
 
-            self.child_mut().expect("Failed to get a child").insert(pattern);
+            self.child_mut().expect("Failed to get a child").insert(pattern)
         }
     }
 }
@@ -34,6 +81,10 @@ pub trait LiteralEntry: Entry + Clone {
 }
 
 pub trait ParserEntry: Entry + Clone {
-    fn parse<'a, 'b>(&'a self, value: &'b str) -> Option<MatchResult<'a, 'b>>;
-    fn parser(&self) -> &Box<Parser>;
+    // The path taken while parsing is recorded into the trace.
+    fn parse_traced<'a, 'b, T: Trace>(&'a self,
+                                      value: &'b [u8],
+                                      depth: usize,
+                                      trace: &mut T)
+                                      -> Option<MatchResult<'a, 'b, [u8]>>;
 }
@@ -1,5 +1,6 @@
 use matcher::trie::parser_factory::TrieParserFactory;
-use matcher::MatcherFactory;
+use matcher::{MatcherFactory, BuildError};
+use matcher::cache;
 
 use matcher::MatcherSuite;
 use self::impls::SuffixTable;
@@ -18,6 +19,10 @@ impl MatcherFactory for SuffixArrayMatcherFactory {
     fn new_matcher() -> Self::Matcher {
         SuffixTable::new()
     }
+
+    fn from_cache(cache_path: &str) -> Result<Self::Matcher, BuildError> {
+        Ok(try!(cache::load(cache_path)))
+    }
 }
 
 #[derive(Clone)]
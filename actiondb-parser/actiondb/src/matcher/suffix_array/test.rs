@@ -4,7 +4,11 @@ use parsers::IntParser;
 use matcher::compiled_pattern::CompiledPatternBuilder;
 use parsers::SetParser;
 use matcher::pattern::Pattern;
-use matcher::Matcher;
+use matcher::{BuildError, Matcher};
+use matcher::trie::SuffixTree;
+use matcher::explain::Step;
+use matcher::cache::{Cacheable, Encoder, Decoder};
+use parsers::LiteralParser;
 
 use std::iter::FromIterator;
 use std::collections::BTreeMap;
@@ -39,10 +43,10 @@ fn create_populated_suffix_table() -> SuffixTable {
     let mut pattern4 = Pattern::with_random_uuid();
     pattern4.set_pattern(cp_4);
 
-    root.insert(pattern_1);
-    root.insert(pattern_2);
-    root.insert(pattern_3);
-    root.insert(pattern4);
+    root.insert(pattern_1).unwrap();
+    root.insert(pattern_2).unwrap();
+    root.insert(pattern_3).unwrap();
+    root.insert(pattern4).unwrap();
 
     root
 }
@@ -54,7 +58,7 @@ fn assert_suffix_table_parse<'a, 'b>(patterns: &[&'a str], values: &[&'b str]) {
         let compiled_pattern = ::grammar::parser::pattern(pattern).expect("Failed to compile pattern");
         let mut pattern = Pattern::with_random_uuid();
         pattern.set_pattern(compiled_pattern);
-        root.insert(pattern);
+        root.insert(pattern).unwrap();
     }
 
     for value in values {
@@ -86,11 +90,11 @@ fn test_given_suffix_array_when_a_parser_entry_is_inserted_it_is_only_added_if_i
 
     let mut pattern_1 = Pattern::with_random_uuid();
     pattern_1.set_pattern(cp_1);
-    root.insert(pattern_1);
+    root.insert(pattern_1).unwrap();
 
     let mut pattern_2 = Pattern::with_random_uuid();
     pattern_2.set_pattern(cp_2);
-    root.insert(pattern_2);
+    root.insert(pattern_2).unwrap();
 
     assert_eq!(true, root.parse("42").is_some());
 }
@@ -104,7 +108,7 @@ fn test_given_suffix_array_when_there_is_no_match_then_the_parsing_is_unsuccessf
                 .build();
     let mut pattern = Pattern::with_random_uuid();
     pattern.set_pattern(cp_1);
-    root.insert(pattern);
+    root.insert(pattern).unwrap();
 
     assert_eq!(true, root.parse("XYZ").is_none());
 }
@@ -118,7 +122,7 @@ fn test_given_suffix_array_when_the_match_is_too_short_then_we_dont_panic() {
                 .build();
     let mut pattern = Pattern::with_random_uuid();
     pattern.set_pattern(cp_1);
-    root.insert(pattern);
+    root.insert(pattern).unwrap();
 
     assert_eq!(true, root.parse("XYZ").is_none());
 }
@@ -131,7 +135,7 @@ fn test_given_suffix_array_when_during_parsing_the_parsed_value_is_not_empty_but
                 .build();
     let mut pattern = Pattern::with_random_uuid();
     pattern.set_pattern(cp_1);
-    root.insert(pattern);
+    root.insert(pattern).unwrap();
 
     assert_eq!(true, root.parse("apple").is_none());
 }
@@ -144,7 +148,7 @@ fn test_given_suffix_array_when_a_literal_entry_is_found_then_it_is_returned() {
                 .build();
     let mut pattern = Pattern::with_random_uuid();
     pattern.set_pattern(cp_1);
-    root.insert(pattern);
+    root.insert(pattern).unwrap();
 
     assert_eq!(true, root.parse("app").is_some());
 }
@@ -157,9 +161,21 @@ fn test_given_suffix_array_when_literals_are_inserted_then_it_can_find_the_strin
                 .build();
     let mut pattern = Pattern::with_random_uuid();
     pattern.set_pattern(cp_1);
-    root.insert(pattern);
+    root.insert(pattern).unwrap();
 
-    assert_eq!("app", root.longest_common_prefix("app42").unwrap().literal());
+    assert_eq!("app", root.longest_common_prefix(b"app42").unwrap().literal());
+}
+
+#[test]
+fn test_given_suffix_array_when_the_value_starts_with_only_a_part_of_a_literal_then_it_doesnt_match() {
+    let mut pattern = Pattern::with_random_uuid();
+    pattern.set_pattern(::grammar::parser::pattern("abc%{INT:n}").unwrap());
+    let mut root = SuffixTable::new();
+    root.add_pattern(pattern.clone()).unwrap();
+
+    assert_eq!(root.parse("ab12").is_none(), true);
+    assert_eq!(root.parse("a").is_none(), true);
+    assert_eq!(root.parse("abc12").unwrap().pattern().uuid(), pattern.uuid());
 }
 
 #[test]
@@ -195,7 +211,492 @@ fn test_given_parser_when_it_receives_empty_message_and_there_is_no_matching_pat
     pattern.set_pattern(compiled_pattern);
 
     let mut root = SuffixTable::new();
-    root.insert(pattern);
+    root.insert(pattern).unwrap();
 
     assert_eq!(true, root.parse("").is_none());
 }
+
+#[test]
+fn test_given_suffix_array_when_a_pattern_contains_delimited_parsers_then_the_delimiters_are_not_extracted() {
+    let pattern = r#"msg=%{QSTRING:msg} key=%{ESTRING(end=";"):key} end"#;
+    let compiled_pattern = ::grammar::parser::pattern(pattern).unwrap();
+    let mut pattern = Pattern::with_random_uuid();
+    pattern.set_pattern(compiled_pattern);
+
+    let mut root = SuffixTable::new();
+    root.insert(pattern).unwrap();
+
+    let expected = BTreeMap::from_iter(vec![("msg", "foo bar"), ("key", "value")]);
+    let result = root.parse(r#"msg="foo bar" key=value; end"#)
+                     .expect("Failed to parse a message with delimited values");
+    assert_eq!(&expected, result.values());
+}
+
+#[test]
+fn test_given_suffix_array_when_a_pattern_contains_kvpairs_then_every_pair_is_extracted() {
+    let compiled_pattern = ::grammar::parser::pattern(r#"login %{KVPAIRS(prefix="kv."):}"#).unwrap();
+    let mut pattern = Pattern::with_random_uuid();
+    pattern.set_pattern(compiled_pattern);
+
+    let mut root = SuffixTable::new();
+    root.insert(pattern).unwrap();
+
+    let expected = BTreeMap::from_iter(vec![("kv.user".to_owned(), "john".into()), ("kv.port".to_owned(), "22".into())]);
+    let result = root.parse("login user=john port=22").expect("Failed to parse a message with kvpairs");
+    assert_eq!(&expected, result.pairs());
+}
+
+#[test]
+fn test_given_suffix_array_when_a_pattern_contains_json_then_its_fields_are_extracted() {
+    let compiled_pattern = ::grammar::parser::pattern(r#"request done: %{JSON(prefix="req."):}"#).unwrap();
+    let mut pattern = Pattern::with_random_uuid();
+    pattern.set_pattern(compiled_pattern);
+
+    let mut root = SuffixTable::new();
+    root.insert(pattern).unwrap();
+
+    let expected = BTreeMap::from_iter(vec![("req.status".to_owned(), "200".into()), ("req.user.id".to_owned(), "42".into())]);
+    let result = root.parse(r#"request done: {"status":200,"user":{"id":42}}"#)
+                     .expect("Failed to parse a message with a JSON payload");
+    assert_eq!(&expected, result.pairs());
+}
+
+#[test]
+fn test_given_suffix_array_when_a_pattern_has_optional_group_and_alternatives_then_every_variant_matches() {
+    let compiled_pattern = ::grammar::parser::pattern(r#"%{ANY("accepted","Accepted")} publickey%{? (pid %{INT:pid})} for %{GREEDY:user}"#)
+                               .unwrap();
+    let mut pattern = Pattern::with_random_uuid();
+    pattern.set_pattern(compiled_pattern);
+
+    let mut root = SuffixTable::new();
+    root.add_pattern(pattern).unwrap();
+
+    let expected = BTreeMap::from_iter(vec![("user", "joe")]);
+    let result = root.parse("accepted publickey for joe").expect("Failed to parse a message without the optional group");
+    assert_eq!(&expected, result.values());
+
+    let expected = BTreeMap::from_iter(vec![("pid", "123"), ("user", "joe")]);
+    let result = root.parse("Accepted publickey (pid 123) for joe")
+                     .expect("Failed to parse a message with the optional group");
+    assert_eq!(&expected, result.values());
+    assert_eq!(root.parse("ACCEPTED publickey for joe").is_none(), true);
+}
+
+#[test]
+fn test_given_suffix_array_when_a_pattern_ignores_case_and_whitespace_then_exact_literals_are_still_preferred() {
+    let mut loose = Pattern::with_random_uuid();
+    loose.set_pattern(::grammar::parser::pattern("session closed for %{GREEDY:user}").unwrap());
+    loose.set_ignore_case(true);
+    loose.set_collapse_whitespace(true);
+    let mut exact = Pattern::with_random_uuid();
+    exact.set_pattern(::grammar::parser::pattern("Session Closed for %{GREEDY:admin}").unwrap());
+
+    let mut root = SuffixTable::new();
+    root.add_pattern(loose.clone()).unwrap();
+    root.add_pattern(exact.clone()).unwrap();
+
+    let result = root.parse("SESSION   closed for joe").expect("Failed to parse a message with loose literals");
+    assert_eq!(result.pattern().uuid(), loose.uuid());
+    assert_eq!(&BTreeMap::from_iter(vec![("user", "joe")]), result.values());
+
+    let result = root.parse("Session Closed for root").expect("Failed to parse a message with exact literals");
+    assert_eq!(result.pattern().uuid(), exact.uuid());
+}
+
+#[test]
+fn test_given_suffix_array_when_the_exact_literals_lead_to_a_dead_end_then_only_the_loose_literals_are_tried() {
+    let mut loose = Pattern::with_random_uuid();
+    loose.set_pattern(::grammar::parser::pattern("session closed for %{GREEDY:user}").unwrap());
+    loose.set_ignore_case(true);
+    let mut exact = Pattern::with_random_uuid();
+    exact.set_pattern(::grammar::parser::pattern("Session Closed for %{INT:id}").unwrap());
+    let mut root = SuffixTable::new();
+    root.add_pattern(loose.clone()).unwrap();
+    root.add_pattern(exact.clone()).unwrap();
+    assert_eq!(root.parse("Session Closed for joe").unwrap().pattern().uuid(), loose.uuid());
+
+    let mut pid = Pattern::with_random_uuid();
+    pid.set_pattern(::grammar::parser::pattern("pid=%{INT:pid}").unwrap());
+    let mut any = Pattern::with_random_uuid();
+    any.set_pattern(::grammar::parser::pattern("%{GREEDY:any}").unwrap());
+    let mut root = SuffixTable::new();
+    root.add_pattern(pid).unwrap();
+    root.add_pattern(any).unwrap();
+    assert_eq!(root.parse("pid=abc").is_none(), true);
+}
+
+#[test]
+fn test_given_suffix_array_with_loose_literal_when_the_exact_literals_lead_to_a_dead_end_then_the_other_parsers_are_not_tried
+    () {
+    let mut pid = Pattern::with_random_uuid();
+    pid.set_pattern(::grammar::parser::pattern("pid=%{INT:pid}").unwrap());
+    let mut any = Pattern::with_random_uuid();
+    any.set_pattern(::grammar::parser::pattern("%{GREEDY:any}").unwrap());
+    let mut loose = Pattern::with_random_uuid();
+    loose.set_pattern(::grammar::parser::pattern("session closed").unwrap());
+    loose.set_ignore_case(true);
+    let mut root = SuffixTable::new();
+    root.add_pattern(pid.clone()).unwrap();
+    root.add_pattern(any).unwrap();
+    root.add_pattern(loose.clone()).unwrap();
+
+    assert_eq!(root.parse("pid=abc").is_none(), true);
+    assert_eq!(root.parse("pid=42").unwrap().pattern().uuid(), pid.uuid());
+    assert_eq!(root.parse("Session Closed").unwrap().pattern().uuid(), loose.uuid());
+}
+
+#[test]
+fn test_given_suffix_array_when_no_literal_matches_the_message_then_the_parsers_are_tried_like_in_the_trie() {
+    let mut literal = Pattern::with_random_uuid();
+    literal.set_pattern(::grammar::parser::pattern("foo %{INT:a}").unwrap());
+    let mut parser = Pattern::with_random_uuid();
+    parser.set_pattern(::grammar::parser::pattern("%{INT:b} bar").unwrap());
+    let mut root = SuffixTable::new();
+    let mut tree = SuffixTree::new();
+    for pattern in &[literal, parser.clone()] {
+        root.add_pattern(pattern.clone()).unwrap();
+        tree.add_pattern(pattern.clone()).unwrap();
+    }
+    assert_eq!(tree.parse("12 bar").unwrap().pattern().uuid(), parser.uuid());
+    assert_eq!(root.parse("12 bar").unwrap().pattern().uuid(), parser.uuid());
+
+    let mut pid = Pattern::with_random_uuid();
+    pid.set_pattern(::grammar::parser::pattern("pid=%{INT:pid}").unwrap());
+    let mut any = Pattern::with_random_uuid();
+    any.set_pattern(::grammar::parser::pattern("%{GREEDY:any}").unwrap());
+    let mut root = SuffixTable::new();
+    root.add_pattern(pid).unwrap();
+    root.add_pattern(any.clone()).unwrap();
+    assert_eq!(root.parse("ppid=abc").unwrap().pattern().uuid(), any.uuid());
+}
+
+#[test]
+fn test_given_suffix_array_when_the_value_ends_before_a_child_which_matches_empty_values_then_parse_all_finds_it
+    () {
+    let mut pid = Pattern::with_random_uuid();
+    pid.set_pattern(::grammar::parser::pattern("%{INT:pid}").unwrap());
+    let mut pid_rest = Pattern::with_random_uuid();
+    pid_rest.set_pattern(::grammar::parser::pattern("%{INT:pid}%{GREEDY:rest}").unwrap());
+    let mut user = Pattern::with_random_uuid();
+    user.set_pattern(::grammar::parser::pattern("user").unwrap());
+    let mut user_rest = Pattern::with_random_uuid();
+    user_rest.set_pattern(::grammar::parser::pattern("user%{GREEDY:rest}").unwrap());
+    let mut root = SuffixTable::new();
+    let mut tree = SuffixTree::new();
+    for pattern in &[pid.clone(), pid_rest.clone(), user.clone(), user_rest.clone()] {
+        root.add_pattern(pattern.clone()).unwrap();
+        tree.add_pattern(pattern.clone()).unwrap();
+    }
+
+    for &(message, ref expected) in &[("42", vec![*pid.uuid(), *pid_rest.uuid()]),
+                                       ("user", vec![*user.uuid(), *user_rest.uuid()])] {
+        let from_tree: Vec<_> = tree.parse_all(message).iter().map(|result| *result.pattern().uuid()).collect();
+        let from_table: Vec<_> = root.parse_all(message).iter().map(|result| *result.pattern().uuid()).collect();
+        assert_eq!(&from_tree, expected);
+        assert_eq!(&from_table, expected);
+    }
+}
+
+#[test]
+fn test_given_suffix_array_when_a_message_is_not_matched_then_the_explanation_contains_the_longest_partial_match() {
+    let mut port = Pattern::with_random_uuid();
+    port.set_pattern(::grammar::parser::pattern("login from %{IPV4:ip} port %{INT:port}").unwrap());
+    let mut failed = Pattern::with_random_uuid();
+    failed.set_pattern(::grammar::parser::pattern("login from %{IPV4:ip} failed").unwrap());
+
+    let mut root = SuffixTable::new();
+    root.add_pattern(port.clone()).unwrap();
+    root.add_pattern(failed.clone()).unwrap();
+
+    let explanation = root.explain("login from 10.0.0.1 port none");
+    assert_eq!(explanation.matched(), None);
+    assert_eq!(explanation.longest_partial_match(), b"login from 10.0.0.1 port ");
+    assert_eq!(explanation.candidates(), &[*port.uuid()]);
+
+    let explanation = root.explain("login from 10.0.0.1 failed");
+    assert_eq!(explanation.matched(), Some(failed.uuid()));
+    assert_eq!(explanation.steps()[0],
+               Step::Literal {
+                   depth: 0,
+                   position: 0,
+                   literal: "login from ".to_owned(),
+               });
+}
+
+#[test]
+fn test_given_suffix_array_when_a_pattern_has_higher_priority_then_the_explanation_matches_it_like_parse() {
+    let literal = Pattern::from_json(r#"{"uuid": "9a49c47d-29e9-4072-be84-3b76c6814743",
+                                         "pattern": "user root %{GREEDY:action}"}"#)
+                      .unwrap();
+    let high = Pattern::from_json(r#"{"uuid": "fa8bdbcb-e0fd-4da1-9fa4-15ecfec28ad2",
+                                      "pattern": "user %{GREEDY:user}",
+                                      "priority": 5}"#)
+                   .unwrap();
+    let mut root = SuffixTable::new();
+    root.add_pattern(literal).unwrap();
+    root.add_pattern(high.clone()).unwrap();
+
+    let message = "user root logged in";
+    let explanation = root.explain(message);
+    assert_eq!(explanation.matched(), Some(root.parse(message).unwrap().pattern().uuid()));
+    assert_eq!(explanation.matched(), Some(high.uuid()));
+    assert_eq!(explanation.steps().last(),
+               Some(&Step::Priority {
+                   uuid: *high.uuid(),
+                   priority: 5,
+               }));
+}
+
+#[test]
+fn test_given_suffix_array_when_more_patterns_match_a_message_then_parse_all_returns_all_of_them_in_precedence_order() {
+    let mut exact = Pattern::with_random_uuid();
+    exact.set_pattern(::grammar::parser::pattern("user root %{GREEDY:action}").unwrap());
+    let mut shorter = Pattern::with_random_uuid();
+    shorter.set_pattern(::grammar::parser::pattern("user %{GREEDY:user}").unwrap());
+    let mut greedy = Pattern::with_random_uuid();
+    greedy.set_pattern(::grammar::parser::pattern("%{GREEDY:message}").unwrap());
+    let mut other = Pattern::with_random_uuid();
+    other.set_pattern(::grammar::parser::pattern("group %{GREEDY:group}").unwrap());
+
+    let mut root = SuffixTable::new();
+    root.add_pattern(greedy.clone()).unwrap();
+    root.add_pattern(shorter.clone()).unwrap();
+    root.add_pattern(exact.clone()).unwrap();
+    root.add_pattern(other).unwrap();
+
+    let results = root.parse_all("user root logged in");
+    let uuids: Vec<_> = results.iter().map(|result| *result.pattern().uuid()).collect();
+    assert_eq!(uuids, vec![*exact.uuid(), *shorter.uuid(), *greedy.uuid()]);
+    assert_eq!(results[1].values().get("user"), Some(&"root logged in"));
+    assert_eq!(root.parse("user root logged in").unwrap().pattern().uuid(), exact.uuid());
+}
+
+#[test]
+fn test_given_suffix_array_when_a_pattern_is_removed_then_its_empty_entries_are_pruned() {
+    let mut port = Pattern::with_random_uuid();
+    port.set_pattern(::grammar::parser::pattern("login from %{IPV4:ip} port %{INT:port}").unwrap());
+    let mut failed = Pattern::with_random_uuid();
+    failed.set_pattern(::grammar::parser::pattern("login from %{IPV4:ip} failed").unwrap());
+    let mut logout = Pattern::with_random_uuid();
+    logout.set_pattern(::grammar::parser::pattern("logout %{GREEDY:user}").unwrap());
+
+    let mut root = SuffixTable::new();
+    root.add_pattern(port.clone()).unwrap();
+    root.add_pattern(failed.clone()).unwrap();
+    root.add_pattern(logout.clone()).unwrap();
+
+    assert_eq!(root.remove_pattern(logout.uuid()), true);
+    assert_eq!(root.remove_pattern(logout.uuid()), false);
+    assert_eq!(root.parse("logout joe").is_none(), true);
+    assert_eq!(root.longest_common_prefix(b"logout joe").unwrap().literal(), "login from ");
+
+    assert_eq!(root.remove_pattern(port.uuid()), true);
+    assert_eq!(root.parse("login from 10.0.0.1 port 22").is_none(), true);
+    assert_eq!(root.parse("login from 10.0.0.1 failed").unwrap().pattern().uuid(), failed.uuid());
+
+    assert_eq!(root.remove_pattern(failed.uuid()), true);
+    assert_eq!(root.longest_common_prefix(b"login from ").is_none(), true);
+}
+
+#[test]
+fn test_given_suffix_array_when_a_pattern_is_replaced_then_only_the_new_version_matches() {
+    let mut pattern = Pattern::with_random_uuid();
+    pattern.set_pattern(::grammar::parser::pattern("user %{GREEDY:user} logged in").unwrap());
+    let mut root = SuffixTable::new();
+    root.add_pattern(pattern.clone()).unwrap();
+
+    pattern.set_pattern(::grammar::parser::pattern("user %{GREEDY:user} logged out").unwrap());
+    root.replace_pattern(pattern.clone()).unwrap();
+
+    assert_eq!(root.parse("user joe logged in").is_none(), true);
+    assert_eq!(root.parse("user joe logged out").unwrap().pattern().uuid(), pattern.uuid());
+}
+
+#[test]
+fn test_given_suffix_array_when_a_pattern_is_replaced_by_an_invalid_one_then_the_old_version_still_matches() {
+    let mut pattern = Pattern::with_random_uuid();
+    pattern.set_pattern(::grammar::parser::pattern("user %{GREEDY:user} logged in").unwrap());
+    let mut root = SuffixTable::new();
+    root.add_pattern(pattern.clone()).unwrap();
+
+    pattern.set_pattern(::grammar::parser::pattern(&"a%{? b}".repeat(22)).unwrap());
+    match root.replace_pattern(pattern.clone()) {
+        Err(BuildError::TooManyVariants { uuid, .. }) => assert_eq!(&uuid, pattern.uuid()),
+        result => panic!("Unexpected result: {:?}", result),
+    }
+    assert_eq!(root.parse("user joe logged in").unwrap().pattern().uuid(), pattern.uuid());
+}
+
+fn encode_and_decode(root: &SuffixTable) -> SuffixTable {
+    let mut encoder = Encoder::new();
+    root.encode(&mut encoder);
+    let bytes = encoder.into_bytes();
+    let mut decoder = Decoder::new(&bytes);
+    let decoded = SuffixTable::decode(&mut decoder).unwrap();
+    assert_eq!(decoder.is_at_end(), true);
+    decoded
+}
+
+#[test]
+fn test_given_suffix_array_when_it_is_encoded_and_decoded_then_every_parser_kind_still_matches() {
+    let messages = vec![("email %{EMAIL:email}", "email joe@example.com"),
+                        ("estring %{ESTRING(end=\";\"):value} end", "estring abc; end"),
+                        ("float %{FLOAT(max_len=8):latency}", "float 1.5"),
+                        ("greedy %{GREEDY:greedy} end", "greedy a b end"),
+                        ("hex %{HEX(min_len=2):hex}", "hex 0a1f"),
+                        ("hostname %{HOSTNAME:host}", "hostname example.com"),
+                        ("int %{INT(min_len=2,max_len=5):int}", "int 123"),
+                        ("ipaddr %{IPADDR:gw}", "ipaddr 10.0.0.1"),
+                        ("ipv4 %{IPV4:src}", "ipv4 10.0.0.2"),
+                        ("ipv6 %{IPV6:dst}", "ipv6 ::1"),
+                        ("json %{JSON(prefix=\"req.\"):}", "json {\"a\": 1}"),
+                        ("kvpairs %{KVPAIRS(separator=\":\", delimiter=\";\", prefix=\"kv.\"):}",
+                         "kvpairs a:1;b:2"),
+                        ("macaddr %{MACADDR:mac}", "macaddr 00:11:22:33:44:55"),
+                        ("number %{NUMBER:bytes}", "number 0x1f"),
+                        ("qstring %{QSTRING(quotes=\"'\"):msg}", "qstring 'hello'"),
+                        ("regex %{REGEX(\"[A-Z]{3}-[0-9]{4}\"):ticket}", "regex ABC-1234"),
+                        ("set %{SET(\"abc\",min_len=2):set}", "set abcab"),
+                        ("timestamp %{TIMESTAMP(preset=\"ISO8601\"):ts}", "timestamp 2016-01-02T03:04:05Z")];
+    let mut root = SuffixTable::new();
+    for &(pattern, _) in &messages {
+        let mut p = Pattern::with_random_uuid();
+        p.set_pattern(::grammar::parser::pattern(pattern).unwrap());
+        root.add_pattern(p).unwrap();
+    }
+    let mut literal = LiteralParser::new("Hello");
+    literal.set_ignore_case(true);
+    let mut p = Pattern::with_random_uuid();
+    p.set_pattern(CompiledPatternBuilder::new().literal("literal ").parser(Box::new(literal)).build());
+    root.add_pattern(p).unwrap();
+
+    let decoded = encode_and_decode(&root);
+
+    for &(_, message) in messages.iter().chain([("", "literal HELLO")].iter()) {
+        let expected = root.parse(message).expect(message);
+        let result = decoded.parse(message).expect(message);
+        assert_eq!(result.pattern().uuid(), expected.pattern().uuid());
+        assert_eq!(result.values(), expected.values());
+    }
+    assert_eq!(decoded.parse("set a").is_none(), true);
+    assert_eq!(decoded.parse("literal Help").is_none(), true);
+}
+
+#[test]
+fn test_given_suffix_array_when_it_is_encoded_and_decoded_then_the_patterns_keep_their_metadata() {
+    let low = Pattern::from_json(r#"{"uuid": "9a49c47d-29e9-4072-be84-3b76c6814743",
+                                         "name": "LOGGEDIN",
+                                         "pattern": "user %{GREEDY:user} logged in",
+                                         "values": {"action": "login"},
+                                         "tags": ["auth", "login"]}"#)
+                      .unwrap();
+    let high = Pattern::from_json(r#"{"uuid": "fa8bdbcb-e0fd-4da1-9fa4-15ecfec28ad2",
+                                      "pattern": "user root %{GREEDY:action}",
+                                      "priority": 5}"#)
+                   .unwrap();
+    let mut root = SuffixTable::new();
+    root.add_pattern(high.clone()).unwrap();
+    root.add_pattern(low.clone()).unwrap();
+
+    let decoded = encode_and_decode(&root);
+
+    let result = decoded.parse("user joe logged in").unwrap();
+    assert_eq!(result.pattern().uuid(), low.uuid());
+    assert_eq!(result.pattern().name(), Some("LOGGEDIN"));
+    assert_eq!(result.pattern().values(), low.values());
+    assert_eq!(result.pattern().tags(), low.tags());
+    assert_eq!(decoded.parse("user root logged in").unwrap().pattern().uuid(), high.uuid());
+}
+
+#[test]
+fn test_given_suffix_array_when_the_message_is_not_valid_utf8_then_it_can_be_matched_as_bytes() {
+    let pattern = Pattern::from_json(r#"{"uuid": "9a49c47d-29e9-4072-be84-3b76c6814743",
+                                         "pattern": "user %{GREEDY:user} logged in"}"#)
+                      .unwrap();
+    let mut root = SuffixTable::new();
+    root.add_pattern(pattern.clone()).unwrap();
+
+    let message = b"user Andr\xe9 logged in";
+    let result = root.parse_bytes(message).unwrap();
+    assert_eq!(result.pattern().uuid(), pattern.uuid());
+    assert_eq!(result.values().get("user"), Some(&&b"Andr\xe9"[..]));
+    assert_eq!(root.parse_all_bytes(message).len(), 1);
+    assert_eq!(root.explain_bytes(message).matched(), Some(pattern.uuid()));
+}
+
+#[test]
+fn test_given_suffix_array_when_patterns_are_added_then_their_uuids_are_returned() {
+    let mut root = create_populated_suffix_table();
+    let mut expected = root.uuids();
+    assert_eq!(expected.len(), 4);
+
+    let mut pattern = Pattern::with_random_uuid();
+    pattern.set_pattern(CompiledPatternBuilder::new().literal("foo").build());
+    expected.insert(*pattern.uuid());
+    root.add_pattern(pattern).unwrap();
+    assert_eq!(root.uuids(), expected);
+}
+
+#[test]
+fn test_given_pattern_with_too_many_optional_groups_when_it_is_added_then_we_get_an_error() {
+    let mut pattern = Pattern::with_random_uuid();
+    pattern.set_pattern(::grammar::parser::pattern(&"a%{? b}".repeat(22)).unwrap());
+    let mut root = SuffixTable::new();
+    match root.add_pattern(pattern.clone()) {
+        Err(BuildError::TooManyVariants { uuid, max: 1024 }) => assert_eq!(&uuid, pattern.uuid()),
+        result => panic!("Unexpected result: {:?}", result),
+    }
+}
+
+#[test]
+fn test_given_suffix_array_when_the_patterns_with_priorities_are_in_nested_tables_then_the_highest_one_is_chosen() {
+    let low = Pattern::from_json(r#"{"uuid": "9a49c47d-29e9-4072-be84-3b76c6814743",
+                                     "pattern": "%{INT:pid} user root logged %{GREEDY:what}"}"#)
+                  .unwrap();
+    let middle = Pattern::from_json(r#"{"uuid": "d8ba7a64-1b35-4e43-8cb9-6c7bfb11b0a4",
+                                        "pattern": "%{INT:pid} user root %{GREEDY:action}",
+                                        "priority": 3}"#)
+                     .unwrap();
+    let high = Pattern::from_json(r#"{"uuid": "fa8bdbcb-e0fd-4da1-9fa4-15ecfec28ad2",
+                                      "pattern": "%{INT:pid} user %{GREEDY:user}",
+                                      "priority": 5}"#)
+                   .unwrap();
+    let mut root = SuffixTable::new();
+    root.add_pattern(low.clone()).unwrap();
+    root.add_pattern(middle.clone()).unwrap();
+    root.add_pattern(high.clone()).unwrap();
+
+    let message = "42 user root logged in";
+    assert_eq!(root.parse_all(message).len(), 3);
+    assert_eq!(root.parse(message).unwrap().pattern().uuid(), high.uuid());
+    assert_eq!(root.remove_pattern(high.uuid()), true);
+    assert_eq!(root.parse(message).unwrap().pattern().uuid(), middle.uuid());
+    assert_eq!(root.remove_pattern(middle.uuid()), true);
+    assert_eq!(root.parse(message).unwrap().pattern().uuid(), low.uuid());
+}
+
+#[test]
+fn test_given_pattern_with_priority_when_it_cant_be_added_then_the_table_remains_unchanged() {
+    let mut pattern = Pattern::with_random_uuid();
+    pattern.set_pattern(::grammar::parser::pattern(&"a%{? b}".repeat(22)).unwrap());
+    pattern.set_priority(5);
+    let mut root = SuffixTable::new();
+    assert!(root.add_pattern(pattern).is_err());
+
+    let mut encoder = Encoder::new();
+    root.encode(&mut encoder);
+    let mut empty = Encoder::new();
+    SuffixTable::new().encode(&mut empty);
+    assert_eq!(encoder.into_bytes(), empty.into_bytes());
+}
+
+#[test]
+fn test_given_pattern_with_optional_group_when_it_is_inserted_without_expanding_then_we_get_an_error() {
+    let mut pattern = Pattern::with_random_uuid();
+    pattern.set_pattern(::grammar::parser::pattern("a%{? b}").unwrap());
+    let mut root = SuffixTable::new();
+    match root.insert(pattern.clone()) {
+        Err(BuildError::UnexpandedPattern(uuid)) => assert_eq!(&uuid, pattern.uuid()),
+        result => panic!("Unexpected result: {:?}", result),
+    }
+}
@@ -0,0 +1,444 @@
+use uuid::Uuid;
+
+use grammar;
+use matcher::{Matcher, MatcherFactory, Pattern};
+use matcher::compiled_pattern::CompiledPattern;
+use matcher::pattern::file::PatternFile;
+use matcher::pattern::testmessage::TestMessage;
+use matcher::suffix_array::SuffixArrayMatcherFactory;
+use parsers::{Parser, IntParser, FloatParser, Ipv4Parser, Ipv6Parser, MacAddrParser, EmailParser, HostnameParser};
+
+use std::collections::{BTreeMap, HashMap};
+
+pub const DEFAULT_SIMILARITY: f64 = 0.5;
+// Only the first few lines of a cluster are used as test messages.
+pub const MAX_TEST_MESSAGES: usize = 3;
+
+// The characters which can separate a key or an opening bracket from a value
+// in a token, e.g. `pid=123` or `[123]`.
+const PREFIX_DELIMITERS: &'static str = "=:[(<\"'";
+const SUFFIX_DELIMITERS: &'static str = "])>\"',;:";
+
+// The lines with the same number of tokens are grouped into clusters. Every
+// position of a cluster's template is either a literal token which is the same
+// in all of its lines or a variable.
+#[derive(Debug)]
+struct Cluster {
+    template: Vec<Option<String>>,
+    lines: Vec<Vec<String>>,
+}
+
+// The tokens which contain digits are most likely variables.
+fn looks_variable(token: &str) -> bool {
+    token.chars().any(|c| c.is_digit(10))
+}
+
+impl Cluster {
+    fn new(tokens: Vec<String>) -> Cluster {
+        let template = tokens.iter()
+                             .map(|token| {
+                                 if looks_variable(token) {
+                                     None
+                                 } else {
+                                     Some(token.clone())
+                                 }
+                             })
+                             .collect();
+        Cluster {
+            template: template,
+            lines: vec![tokens],
+        }
+    }
+
+    fn similarity(&self, tokens: &[String]) -> f64 {
+        let same = self.template
+                       .iter()
+                       .zip(tokens)
+                       .filter(|&(template, token)| {
+                           match *template {
+                               Some(ref literal) => literal == token,
+                               None => looks_variable(token),
+                           }
+                       })
+                       .count();
+        same as f64 / tokens.len() as f64
+    }
+
+    fn merge(&mut self, tokens: Vec<String>) {
+        for (template, token) in self.template.iter_mut().zip(&tokens) {
+            if template.as_ref().map_or(false, |literal| literal != token) {
+                *template = None;
+            }
+        }
+        self.lines.push(tokens);
+    }
+}
+
+// A suggested pattern with the number of lines it was created from.
+#[derive(Clone, Debug)]
+pub struct Suggestion {
+    pattern: Pattern,
+    source: String,
+    line_count: usize,
+}
+
+impl Suggestion {
+    pub fn uuid(&self) -> &Uuid {
+        self.pattern.uuid()
+    }
+
+    // The pattern in the pattern language.
+    pub fn pattern(&self) -> &str {
+        &self.source
+    }
+
+    pub fn line_count(&self) -> usize {
+        self.line_count
+    }
+
+    pub fn test_messages(&self) -> &[TestMessage] {
+        self.pattern.test_messages().unwrap_or(&[])
+    }
+}
+
+// Clusters similar lines like Drain or LogMine do and suggests a pattern for
+// every cluster. Two lines are similar if they have the same number of tokens
+// and the ratio of their matching tokens is at least the given similarity.
+pub struct Suggester {
+    similarity: f64,
+    clusters: BTreeMap<usize, Vec<Cluster>>,
+}
+
+impl Suggester {
+    pub fn new(similarity: f64) -> Suggester {
+        Suggester {
+            similarity: similarity,
+            clusters: BTreeMap::new(),
+        }
+    }
+
+    pub fn add_line(&mut self, line: &str) {
+        if line.is_empty() {
+            return;
+        }
+        let tokens: Vec<String> = line.split(' ').map(|token| token.to_owned()).collect();
+        let similarity = self.similarity;
+        let clusters = self.clusters.entry(tokens.len()).or_insert_with(Vec::new);
+
+        let mut best: Option<(usize, f64)> = None;
+        for (pos, cluster) in clusters.iter().enumerate() {
+            let current = cluster.similarity(&tokens);
+            if current >= similarity && best.map_or(true, |(_, best)| current > best) {
+                best = Some((pos, current));
+            }
+        }
+
+        match best {
+            Some((pos, _)) => clusters[pos].merge(tokens),
+            None => clusters.push(Cluster::new(tokens)),
+        }
+    }
+
+    // The suggestions are ordered by the number of their lines, the most
+    // common shapes are the first ones.
+    pub fn suggestions(&self) -> Vec<Suggestion> {
+        let mut suggestions: Vec<Suggestion> = self.clusters
+                                                   .values()
+                                                   .flat_map(|clusters| clusters.iter())
+                                                   .filter_map(suggest)
+                                                   .collect();
+        suggestions.sort_by(|a, b| b.line_count.cmp(&a.line_count));
+        suggestions
+    }
+}
+
+impl Default for Suggester {
+    fn default() -> Suggester {
+        Suggester::new(DEFAULT_SIMILARITY)
+    }
+}
+
+// The suggestions can be written as a pattern file.
+pub fn to_pattern_file(suggestions: &[Suggestion]) -> PatternFile {
+    PatternFile {
+        patterns: suggestions.iter().map(|suggestion| suggestion.pattern.clone()).collect(),
+        rulesets: Vec::new(),
+        includes: Vec::new(),
+    }
+}
+
+pub fn to_json(suggestions: &[Suggestion]) -> String {
+    to_pattern_file(suggestions).to_json().expect("Failed to serialize the suggestions")
+}
+
+pub fn to_yaml(suggestions: &[Suggestion]) -> String {
+    to_pattern_file(suggestions).to_yaml().expect("Failed to serialize the suggestions")
+}
+
+fn common_prefix_len(values: &[&str]) -> usize {
+    let first = values[0];
+    let mut len = first.len();
+    for value in &values[1..] {
+        len = first.char_indices()
+                   .zip(value.chars())
+                   .take_while(|&((_, a), b)| a == b)
+                   .last()
+                   .map_or(0, |((pos, c), _)| pos + c.len_utf8())
+                   .min(len);
+    }
+    len
+}
+
+fn common_suffix_len(values: &[&str]) -> usize {
+    let first = values[0];
+    let mut len = first.len();
+    for value in &values[1..] {
+        len = first.char_indices()
+                   .rev()
+                   .zip(value.chars().rev())
+                   .take_while(|&((_, a), b)| a == b)
+                   .last()
+                   .map_or(0, |((pos, _), _)| first.len() - pos)
+                   .min(len);
+    }
+    len
+}
+
+// The common prefix and suffix of the values which are kept as literals, e.g.
+// `pid=` and `]` from `pid=123]` and `pid=42]`. They are cut at delimiters, so
+// they don't contain a part of the values.
+fn split_affixes(values: &[&str]) -> (usize, usize) {
+    let first = values[0];
+    let prefix = &first[..common_prefix_len(values)];
+    let suffix = &first[first.len() - common_suffix_len(values)..];
+
+    let prefix_len = prefix.rfind(|c| PREFIX_DELIMITERS.contains(c)).map_or(0, |pos| pos + 1);
+    let suffix_len = suffix.find(|c| SUFFIX_DELIMITERS.contains(c)).map_or(0, |pos| suffix.len() - pos);
+
+    if values.iter().all(|value| value.len() > prefix_len + suffix_len) {
+        (prefix_len, suffix_len)
+    } else if values.iter().all(|value| value.len() > prefix_len) {
+        (prefix_len, 0)
+    } else {
+        (0, 0)
+    }
+}
+
+// The most specific parser which consumes every value.
+fn choose_parser(values: &[&str]) -> &'static str {
+    let candidates: Vec<(&'static str, Box<Parser>)> = vec![("INT", Box::new(IntParser::new())),
+                                                            ("FLOAT", Box::new(FloatParser::new())),
+                                                            ("IPV4", Box::new(Ipv4Parser::new())),
+                                                            ("IPV6", Box::new(Ipv6Parser::new())),
+                                                            ("MACADDR", Box::new(MacAddrParser::new())),
+                                                            ("EMAIL", Box::new(EmailParser::new()))];
+    let parses_all = |parser: &Parser| {
+        values.iter().all(|value| parser.parse(value).map_or(false, |result| result.consumed_length() == value.len()))
+    };
+
+    for (kind, parser) in candidates {
+        if parses_all(&*parser) {
+            return kind;
+        }
+    }
+    // every word would be a hostname, so the values must contain dots
+    if values.iter().all(|value| value.contains('.')) && parses_all(&HostnameParser::new()) {
+        return "HOSTNAME";
+    }
+    "GREEDY"
+}
+
+fn is_valid_name(name: &str) -> bool {
+    let mut chars = name.chars();
+    chars.next().map_or(false, |c| c.is_ascii_alphabetic() || c == '_' || c == '.') &&
+    chars.all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '.')
+}
+
+// The key before the value (e.g. `pid` from `pid=`) or the previous word is
+// used as the name of the parser, otherwise it's named after its kind.
+fn choose_name(prefix: &str, previous: Option<&str>, kind: &str, names: &mut HashMap<String, usize>) -> String {
+    let key = if prefix.ends_with('=') || prefix.ends_with(':') {
+        let key = &prefix[..prefix.len() - 1];
+        let start = key.rfind(|c: char| !(c.is_ascii_alphanumeric() || c == '_' || c == '.')).map_or(0, |pos| pos + 1);
+        Some(&key[start..])
+    } else {
+        previous
+    };
+    let name = match key {
+        Some(key) if is_valid_name(key) => key.to_lowercase(),
+        _ => kind.to_lowercase(),
+    };
+
+    let count = names.entry(name.clone()).or_insert(0);
+    *count += 1;
+    if *count == 1 {
+        name
+    } else {
+        format!("{}{}", name, count)
+    }
+}
+
+fn build_pattern(cluster: &Cluster) -> String {
+    let mut pattern = String::new();
+    let mut literal = String::new();
+    let mut names = HashMap::new();
+
+    for (pos, template) in cluster.template.iter().enumerate() {
+        if pos > 0 {
+            literal.push(' ');
+        }
+        let values: Vec<&str> = cluster.lines.iter().map(|tokens| &tokens[pos][..]).collect();
+        let is_single_line = cluster.lines.len() == 1;
+        if template.is_some() || (!is_single_line && values.iter().all(|value| *value == values[0])) {
+            literal.push_str(values[0]);
+            continue;
+        }
+
+        let (prefix_len, suffix_len) = split_affixes(&values);
+        let first = values[0];
+        let prefix = &first[..prefix_len];
+        let suffix = &first[first.len() - suffix_len..];
+        let middles: Vec<&str> = values.iter().map(|value| &value[prefix_len..value.len() - suffix_len]).collect();
+        let kind = choose_parser(&middles);
+        // a single line shows only that the token contains digits, it's kept
+        // as a literal unless it has a more specific type
+        if is_single_line && kind == "GREEDY" {
+            literal.push_str(first);
+            continue;
+        }
+        let previous = match pos {
+            0 => None,
+            _ => cluster.template[pos - 1].as_ref().map(|token| &token[..]),
+        };
+        let name = choose_name(prefix, previous, kind, &mut names);
+
+        literal.push_str(prefix);
+        pattern.push_str(&grammar::escape_literal(&literal));
+        pattern.push_str(&format!("%{{{}:{}}}", kind, name));
+        literal = suffix.to_owned();
+    }
+    pattern.push_str(&grammar::escape_literal(&literal));
+    pattern
+}
+
+// The test messages are the first lines of the cluster which are matched by
+// the pattern, with the values extracted from them.
+fn collect_test_messages(uuid: Uuid, compiled_pattern: &CompiledPattern, cluster: &Cluster) -> Vec<TestMessage> {
+    let mut matcher = SuffixArrayMatcherFactory::new_matcher();
+    let mut pattern = Pattern::with_uuid(uuid);
+    pattern.set_pattern(compiled_pattern.clone());
+    if let Err(error) = matcher.add_pattern(pattern) {
+        warn!("Failed to compile a suggested pattern: uuid={} error={}", uuid, error);
+        return Vec::new();
+    }
+
+    let mut test_messages = Vec::new();
+    for tokens in &cluster.lines {
+        if test_messages.len() == MAX_TEST_MESSAGES {
+            break;
+        }
+        let line = tokens.join(" ");
+        let values = match matcher.parse(&line) {
+            Some(result) => {
+                result.values().iter().map(|(key, value)| (key.to_string(), value.to_string())).collect()
+            }
+            None => {
+                debug!("A line doesn't match its suggested pattern: uuid={} line={:?}", uuid, line);
+                continue;
+            }
+        };
+        test_messages.push(TestMessage::new(line, values, None));
+    }
+    test_messages
+}
+
+// The clusters whose pattern can't be compiled are skipped.
+fn suggest(cluster: &Cluster) -> Option<Suggestion> {
+    let source = build_pattern(cluster);
+    let compiled_pattern = match grammar::parser::pattern(&source) {
+        Ok(compiled_pattern) => compiled_pattern,
+        Err(error) => {
+            warn!("Failed to compile a suggested pattern: pattern={:?} error={}", source, error);
+            return None;
+        }
+    };
+    let uuid = Uuid::new_v4();
+    let test_messages = collect_test_messages(uuid, &compiled_pattern, cluster);
+    Some(Suggestion {
+        pattern: Pattern::new(None, uuid, compiled_pattern, Some(test_messages), None, None),
+        source: source,
+        line_count: cluster.lines.len(),
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use super::{Suggester, to_json};
+    use matcher::{Matcher, MatcherFactory};
+    use matcher::pattern::file::PatternFile;
+    use matcher::suffix_array::SuffixArrayMatcherFactory;
+    use serde_json;
+
+    fn suggest(lines: &[&str]) -> Vec<String> {
+        let mut suggester = Suggester::default();
+        for line in lines {
+            suggester.add_line(line);
+        }
+        suggester.suggestions().iter().map(|suggestion| suggestion.pattern().to_owned()).collect()
+    }
+
+    #[test]
+    fn test_given_similar_lines_when_patterns_are_suggested_then_the_variable_tokens_are_replaced_by_parsers() {
+        let patterns = suggest(&["Accepted password for root from 10.0.0.1 port 22",
+                                 "Accepted password for joe from 192.168.1.10 port 2201",
+                                 "Connection closed by 10.0.0.1"]);
+        assert_eq!(patterns,
+                   vec!["Accepted password for %{GREEDY:for} from %{IPV4:from} port %{INT:port}".to_owned(),
+                        "Connection closed by %{IPV4:by}".to_owned()]);
+    }
+
+    #[test]
+    fn test_given_lines_with_keys_and_brackets_when_patterns_are_suggested_then_they_are_kept_as_literals() {
+        let patterns = suggest(&["sshd[1234]: session opened uid=0",
+                                 "sshd[42]: session opened uid=1000"]);
+        assert_eq!(patterns, vec!["sshd[%{INT:int}]: session opened uid=%{INT:uid}".to_owned()]);
+    }
+
+    #[test]
+    fn test_given_tokens_which_contain_digits_when_they_are_the_same_in_every_line_then_they_are_literals() {
+        let patterns = suggest(&["ssh2 login ok", "ssh2 login ok"]);
+        assert_eq!(patterns, vec!["ssh2 login ok".to_owned()]);
+    }
+
+    #[test]
+    fn test_given_literal_with_parser_begin_when_it_is_suggested_then_it_is_escaped() {
+        let patterns = suggest(&["100%{ done", "42%{ done"]);
+        assert_eq!(patterns, vec![r#"%{GREEDY:greedy} done"#.to_owned()]);
+        let patterns = suggest(&["progress %{ 1", "progress %{ 2"]);
+        assert_eq!(patterns, vec![r#"progress \%\{ %{INT:int}"#.to_owned()]);
+    }
+
+    #[test]
+    fn test_given_suggestions_when_they_are_converted_to_json_then_they_can_be_loaded_as_a_pattern_file() {
+        let mut suggester = Suggester::default();
+        suggester.add_line("user root logged in from 10.0.0.1");
+        suggester.add_line("user joe logged in from 10.0.0.2");
+        suggester.add_line("disk full");
+
+        let suggestions = suggester.suggestions();
+        assert_eq!(suggestions[0].line_count(), 2);
+        assert_eq!(suggestions[0].test_messages().len(), 2);
+        assert_eq!(suggestions[0].test_messages()[1].values().get("from"),
+                   Some(&"10.0.0.2".to_owned()));
+
+        let file: PatternFile = serde_json::from_str(&to_json(&suggestions)).unwrap();
+        assert_eq!(file.patterns()[0].test_messages().unwrap().len(), 2);
+        let mut matcher = SuffixArrayMatcherFactory::new_matcher();
+        for pattern in file.patterns() {
+            matcher.add_pattern(pattern.clone()).unwrap();
+        }
+        assert_eq!(matcher.parse("user admin logged in from 10.0.0.3").unwrap().pattern().uuid(),
+                   suggestions[0].uuid());
+        assert_eq!(matcher.parse("disk full").unwrap().pattern().uuid(), suggestions[1].uuid());
+    }
+}
@@ -1,4 +1,5 @@
-use matcher::MatcherFactory;
+use matcher::{MatcherFactory, BuildError};
+use matcher::cache;
 use matcher::trie::SuffixTree;
 
 pub struct TrieMatcherFactory;
@@ -9,4 +10,8 @@ impl MatcherFactory for TrieMatcherFactory {
     fn new_matcher() -> Self::Matcher {
         SuffixTree::new()
     }
+
+    fn from_cache(cache_path: &str) -> Result<Self::Matcher, BuildError> {
+        Ok(try!(cache::load(cache_path)))
+    }
 }
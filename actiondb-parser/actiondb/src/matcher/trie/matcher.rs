@@ -1,15 +1,67 @@
-use matcher::Matcher;
+use matcher::{BuildError, Matcher};
 use super::SuffixTree;
 use matcher::result::MatchResult;
+use matcher::explain::{Explanation, Trace};
+use matcher::priority;
+use uuid::Uuid;
 use matcher::pattern::Pattern;
 use matcher::trie::node::interface::SuffixTree as STree;
 
+use std::collections::BTreeSet;
+
+fn parse_with_priorities<'a, 'b, T: Trace>(trie: &'a SuffixTree,
+                                           text: &'b [u8],
+                                           trace: &mut T)
+                                           -> Option<MatchResult<'a, 'b, [u8]>> {
+    let result = trie.parse_traced(text, 0, trace);
+    let parse_all = |above| {
+        let mut results = Vec::new();
+        trie.parse_all_into(text, above, &mut results);
+        results
+    };
+    priority::choose(result, trie.max_priority(), parse_all, trace)
+}
+
 impl Matcher for SuffixTree {
-    fn parse<'a, 'b>(&'a self, text: &'b str) -> Option<MatchResult<'a, 'b>> {
-        self.parse(text)
+    fn parse_bytes<'a, 'b>(&'a self, text: &'b [u8]) -> Option<MatchResult<'a, 'b, [u8]>> {
+        parse_with_priorities(self, text, &mut ())
+    }
+    fn parse_all_bytes<'a, 'b>(&'a self, text: &'b [u8]) -> Vec<MatchResult<'a, 'b, [u8]>> {
+        let mut results = Vec::new();
+        self.parse_all_into(text, None, &mut results);
+        results
+    }
+    fn explain_bytes(&self, text: &[u8]) -> Explanation {
+        let mut explanation = Explanation::new(text);
+        explanation.reached(text, |uuids| STree::collect_uuids(self, uuids));
+        let matched = parse_with_priorities(self, text, &mut explanation).map(|result| *result.pattern().uuid());
+        explanation.set_matched(matched);
+        explanation
+    }
+    fn remove_pattern(&mut self, uuid: &Uuid) -> bool {
+        let removed = STree::remove(self, uuid);
+        if removed {
+            let mut priorities = BTreeSet::new();
+            STree::collect_priorities(self, &mut priorities);
+            self.set_max_priority(priority::highest(&priorities));
+        }
+        removed
+    }
+    // Pattern::expand() checks every variant before any of them is inserted
+    // and an expanded variant can always be inserted, so a failed pattern
+    // doesn't leave a part of its variants in the trie and doesn't raise its
+    // highest priority.
+    fn add_pattern(&mut self, pattern: Pattern) -> Result<(), BuildError> {
+        let variants = try!(pattern.expand());
+        for variant in variants {
+            try!(self.insert(variant));
+        }
+        Ok(())
     }
-    fn add_pattern(&mut self, pattern: Pattern) {
-        self.insert(pattern);
+    fn uuids(&self) -> BTreeSet<Uuid> {
+        let mut uuids = BTreeSet::new();
+        STree::collect_uuids(self, &mut uuids);
+        uuids
     }
     fn boxed_clone(&self) -> Box<Matcher> {
         Box::new(self.clone())
@@ -48,7 +48,7 @@ impl LiteralNode {
         self.node.as_ref()
     }
 
-    pub fn cmp_str(&self, other: &str) -> Ordering {
+    pub fn cmp_bytes(&self, other: &[u8]) -> Ordering {
         if self.literal.is_empty() && other.is_empty() {
             Ordering::Equal
         } else if self.literal.is_empty() {
@@ -56,7 +56,7 @@ impl LiteralNode {
         } else if other.is_empty() {
             Ordering::Greater
         } else {
-            self.literal[0..1].cmp(&other[0..1])
+            self.literal.as_bytes()[0..1].cmp(&other[0..1])
         }
     }
 
@@ -95,8 +95,24 @@ impl LiteralNode {
         self.node.is_none()
     }
 
+    // Undoes a split: when a removal leaves a single literal child below a
+    // node which doesn't end a literal, the two literals are concatenated.
+    pub fn merge_single_child(&mut self) {
+        let can_merge = !self.has_value && self.pattern.is_none() &&
+                        self.node.as_ref().map_or(false, |node| {
+            node.literal_children.len() == 1 && node.parser_children.is_empty()
+        });
+        if can_merge {
+            let child = self.node.take().unwrap().literal_children.remove(0);
+            self.literal.push_str(&child.literal);
+            self.has_value = child.has_value;
+            self.pattern = child.pattern;
+            self.node = child.node;
+        }
+    }
+
     fn compare_first_chars(&self, other: &LiteralNode) -> Ordering {
-        self.cmp_str(other.literal())
+        self.cmp_bytes(other.literal().as_bytes())
     }
 }
 
@@ -176,7 +192,7 @@ mod test {
         assert_eq!(alpha.cmp(&empty), Ordering::Greater);
         assert_eq!(empty.cmp(&alpha), Ordering::Less);
         assert_eq!(empty.cmp(&a), Ordering::Less);
-        assert_eq!(empty.cmp_str("a"), Ordering::Less);
+        assert_eq!(empty.cmp_bytes(b"a"), Ordering::Less);
         assert_eq!(a.cmp(&empty), Ordering::Greater);
     }
 }
@@ -1,8 +1,17 @@
 use parsers::{Parser, ParseResult};
 use utils::{SortedVec, CommonPrefix};
+use matcher::BuildError;
 use matcher::pattern::Pattern;
 use matcher::result::MatchResult;
 use matcher::compiled_pattern::TokenType;
+use matcher::explain::Trace;
+use matcher::cache::{self, Cacheable, Encoder, Decoder};
+use matcher::priority;
+use parsers;
+use uuid::Uuid;
+
+use std::collections::BTreeSet;
+use std::ptr;
 
 mod literal;
 mod parser;
@@ -17,12 +26,15 @@ pub use self::parser::ParserNode;
 pub struct SuffixTree {
     literal_children: SortedVec<LiteralNode>,
     parser_children: Vec<ParserNode>,
+    max_priority: Option<i32>,
 }
 
+type LookupResult<'a> = Result<(&'a SuffixTree, usize), (&'a SuffixTree, usize)>;
+
 enum LiteralLookupResult<'a> {
     Found(usize),
     NotFound,
-    GoDown(usize, &'a str),
+    GoDown(usize, &'a [u8]),
 }
 
 impl SuffixTree {
@@ -38,6 +50,43 @@ impl SuffixTree {
         self.literal_children.is_empty() && self.parser_children.is_empty()
     }
 
+    // Every node knows the highest priority of the patterns below it. It's
+    // recomputed from the children on removal, so below the root it's only an
+    // upper bound.
+    pub fn max_priority(&self) -> Option<i32> {
+        self.max_priority
+    }
+
+    pub fn set_max_priority(&mut self, max_priority: Option<i32>) {
+        self.max_priority = max_priority;
+    }
+
+    fn children_max_priority(&self) -> Option<i32> {
+        let literal_children = self.literal_children.iter().map(|child| (child.pattern(), child.node()));
+        let parser_children = self.parser_children.iter().map(|child| (child.pattern(), child.node()));
+        literal_children.chain(parser_children).fold(None, |highest, (pattern, node)| {
+            let highest = pattern.map_or(highest, |pattern| priority::add(highest, pattern.priority()));
+            node.map_or(highest, |node| priority::add(highest, node.max_priority.unwrap_or(0)))
+        })
+    }
+
+    // Raises the highest priority of the nodes which the literal goes through.
+    fn raise_max_priority(&mut self, literal: &[u8], priority: i32) {
+        self.max_priority = priority::add(self.max_priority, priority);
+        if let LiteralLookupResult::GoDown(pos, truncated_literal) = self.search(literal) {
+            if let Some(node) = self.literal_children.get_mut(pos).and_then(|child| child.node_mut()) {
+                node.raise_max_priority(truncated_literal, priority);
+            }
+        }
+    }
+
+    // The loose literals of the patterns which ignore case or collapse
+    // whitespace are parsers, they are tried when the exact literals lead to a
+    // dead end.
+    fn has_loose_literals(&self) -> bool {
+        self.parser_children.iter().any(|child| child.parser().kind() == "LITERAL")
+    }
+
 
     // If a literal isn't found the last SuffixTree instance and the remaining
     // length of
@@ -45,7 +94,7 @@ impl SuffixTree {
     // if the literal is in the trie, we return the last SuffixTree instance and the
     // index of the LiteralNode which contains the literal
     pub fn lookup_literal_mut(&mut self,
-                              literal: &str)
+                              literal: &[u8])
                               -> Result<(&mut SuffixTree, usize), (&mut SuffixTree, usize)> {
         match self.search(literal) {
             LiteralLookupResult::Found(pos) => {
@@ -66,40 +115,48 @@ impl SuffixTree {
     }
 
     // It's the same as lookup_literal_mut() without the muts
-    pub fn lookup_literal(&self,
-                          literal: &str)
-                          -> Result<(&SuffixTree, usize), (&SuffixTree, usize)> {
+    pub fn lookup_literal(&self, literal: &[u8]) -> LookupResult {
+        self.lookup_literal_traced(literal, 0, &mut ()).0
+    }
+
+    // The depth of the returned node is returned too.
+    fn lookup_literal_traced<'a, T: Trace>(&'a self,
+                                           literal: &[u8],
+                                           depth: usize,
+                                           trace: &mut T)
+                                           -> (LookupResult<'a>, usize) {
         match self.search(literal) {
             LiteralLookupResult::Found(pos) => {
-                Ok((self, pos))
-            }
-            LiteralLookupResult::NotFound => {
-                Err((self, literal.len()))
+                let child = self.literal_children.get(pos).expect("Failed to get a looked up child");
+                trace.push_literal(depth, literal, child.literal());
+                trace.reached(literal.ltrunc(literal.len()), |uuids| child.collect_uuids(uuids));
+                (Ok((self, pos)), depth)
             }
+            LiteralLookupResult::NotFound => (Err((self, literal.len())), depth),
             LiteralLookupResult::GoDown(pos, truncated_literal) => {
-                self.literal_children
-                    .get(pos)
-                    .unwrap()
-                    .node()
-                    .unwrap()
-                    .lookup_literal(truncated_literal)
+                let child = self.literal_children.get(pos).expect("Failed to get a looked up child");
+                trace.push_literal(depth, literal, child.literal());
+                trace.reached(truncated_literal, |uuids| child.collect_uuids(uuids));
+                child.node()
+                     .expect("Failed to get the node of a literal child")
+                     .lookup_literal_traced(truncated_literal, depth + 1, trace)
             }
         }
     }
 
-    fn search<'a, 'b>(&'a self, literal: &'b str) -> LiteralLookupResult<'b> {
+    fn search<'a, 'b>(&'a self, literal: &'b [u8]) -> LiteralLookupResult<'b> {
         trace!("search(): stepped in");
         trace!("search(): #children = {}", self.literal_children.len());
         trace!("search(): #pchildren = {}", self.parser_children.len());
-        let cmp_str = |probe: &LiteralNode| probe.cmp_str(literal);
+        let cmp_bytes = |probe: &LiteralNode| probe.cmp_bytes(literal);
 
-        match self.literal_children.binary_search_by(&cmp_str) {
+        match self.literal_children.binary_search_by(&cmp_bytes) {
             Ok(pos) => {
                 self.search_prefix_is_found(literal, pos)
             }
             Err(_) => {
                 trace!("search(): there is no common prefix with this literal");
-                trace!("search(): literal = {}", literal);
+                trace!("search(): literal = {}", String::from_utf8_lossy(literal));
                 trace!("search(): #children = {}", self.literal_children.len());
                 trace!("search(): #pchildren = {}", self.parser_children.len());
                 LiteralLookupResult::NotFound
@@ -108,7 +165,7 @@ impl SuffixTree {
     }
 
     fn search_prefix_is_found<'a, 'b>(&'a self,
-                                      literal: &'b str,
+                                      literal: &'b [u8],
                                       pos: usize)
                                       -> LiteralLookupResult<'b> {
         if self.literal_children.get(pos).unwrap().is_leaf() {
@@ -119,11 +176,11 @@ impl SuffixTree {
     }
 
     fn search_prefix_is_found_and_node_is_leaf<'a, 'b>(&'a self,
-                                                       literal: &'b str,
+                                                       literal: &'b [u8],
                                                        pos: usize)
                                                        -> LiteralLookupResult<'b> {
         trace!("search(): we found a prefix, but it's a leaf");
-        if self.literal_children.get(pos).unwrap().literal() == literal {
+        if self.literal_children.get(pos).unwrap().literal().as_bytes() == literal {
             trace!("search(): we got it");
             LiteralLookupResult::Found(pos)
         } else {
@@ -133,11 +190,11 @@ impl SuffixTree {
     }
 
     fn search_prefix_is_found_and_node_is_not_leaf<'a, 'b>(&'a self,
-                                                           literal: &'b str,
+                                                           literal: &'b [u8],
                                                            pos: usize)
                                                            -> LiteralLookupResult<'b> {
         let literal_node = self.literal_children.get(pos).unwrap();
-        let common_prefix_len = literal_node.literal().common_prefix_len(literal);
+        let common_prefix_len = literal_node.literal().as_bytes().common_prefix_len(literal);
 
         if common_prefix_len < literal_node.literal().len() {
             return LiteralLookupResult::NotFound;
@@ -158,27 +215,80 @@ impl SuffixTree {
         }
     }
 
-    pub fn parse<'a, 'b>(&'a self, text: &'b str) -> Option<MatchResult<'a, 'b>> {
-        trace!("parse(): text = {}", text);
-        match self.lookup_literal(text) {
-            Ok((node, pos)) => {
+    // Both parse() and explain() take this path, the latter records it into
+    // the trace.
+    pub fn parse_traced<'a, 'b, T: Trace>(&'a self,
+                                          text: &'b [u8],
+                                          depth: usize,
+                                          trace: &mut T)
+                                          -> Option<MatchResult<'a, 'b, [u8]>> {
+        trace!("parse(): text = {}", String::from_utf8_lossy(text));
+        let (result, node) = match self.lookup_literal_traced(text, depth, trace) {
+            (Ok((node, pos)), _) => {
                 trace!("{:?}", node);
                 let child = node.literal_children
                                 .get(pos)
                                 .expect("Failed to get a looked up child");
-                SuffixTree::create_match_result_if_child_is_leaf(child)
+                (SuffixTree::create_match_result_if_child_is_leaf(child), node)
             }
-            Err((node, remaining_len)) => {
+            (Err((node, remaining_len)), node_depth) => {
                 let text = text.ltrunc(text.len() - remaining_len);
-                trace!("parse(): text = {}", text);
+                trace!("parse(): text = {}", String::from_utf8_lossy(text));
                 trace!("parse(): #parser_children = {}", node.parser_children.len());
-                node.parse_with_parsers(text)
+                (node.parse_with_parsers(text, node_depth, trace), node)
             }
+        };
+
+        // the literals are preferred, the loose literals of this node are
+        // tried only if the exact ones lead to a dead end, the other parsers
+        // aren't
+        if result.is_none() && !ptr::eq(node, self) && self.has_loose_literals() {
+            trace.push_backtrack(depth, text);
+            self.parse_with_loose_literals(text, depth, trace)
+        } else {
+            result
+        }
+    }
+
+    // Only the patterns with higher priority than `above` are collected, the
+    // nodes which don't have such patterns are skipped.
+    pub fn parse_all_into<'a, 'b>(&'a self,
+                                  text: &'b [u8],
+                                  above: Option<i32>,
+                                  results: &mut Vec<MatchResult<'a, 'b, [u8]>>) {
+        if !priority::may_exceed(self.max_priority, above) {
+            return;
+        }
+        let found = self.literal_children.binary_search_by(|probe| probe.cmp_bytes(text)).ok();
+        // a split literal can leave an empty literal child behind, it's a
+        // prefix of every text, so it's tried after the found one
+        let empty = match self.literal_children.get(0) {
+            Some(child) if child.literal().is_empty() && found != Some(0) => Some(0),
+            _ => None,
+        };
+        for pos in found.into_iter().chain(empty) {
+            let child = self.literal_children.get(pos).expect("Failed to get a looked up child");
+            if child.literal().as_bytes().common_prefix_len(text) == child.literal().len() {
+                let text = text.ltrunc(child.literal().len());
+                if text.is_empty() {
+                    if let Some(pattern) = child.pattern() {
+                        if priority::exceeds(pattern.priority(), above) {
+                            results.push(MatchResult::new(pattern));
+                        }
+                    }
+                }
+                if let Some(node) = child.node() {
+                    node.parse_all_into(text, above, results);
+                }
+            }
+        }
+        for i in &self.parser_children {
+            i.parse_all_into(text, above, results);
         }
     }
 
     fn create_match_result_if_child_is_leaf<'a, 'b>(child: &'a LiteralNode)
-                                                    -> Option<MatchResult<'a, 'b>> {
+                                                    -> Option<MatchResult<'a, 'b, [u8]>> {
         if let Some(pattern) = child.pattern() {
             let result = MatchResult::new(pattern);
             Some(result)
@@ -188,22 +298,41 @@ impl SuffixTree {
         }
     }
 
-    fn parse_with_parsers<'a, 'b>(&'a self, text: &'b str) -> Option<MatchResult<'a, 'b>> {
+    fn parse_with_parsers<'a, 'b, T: Trace>(&'a self,
+                                            text: &'b [u8],
+                                            depth: usize,
+                                            trace: &mut T)
+                                            -> Option<MatchResult<'a, 'b, [u8]>> {
         for i in &self.parser_children {
             trace!("parse(): testing parser");
 
-            if let Some(result) = i.parse(text) {
+            if let Some(result) = i.parse_traced(text, depth, trace) {
+                return Some(result);
+            }
+        }
+        None
+    }
+
+    fn parse_with_loose_literals<'a, 'b, T: Trace>(&'a self,
+                                                   text: &'b [u8],
+                                                   depth: usize,
+                                                   trace: &mut T)
+                                                   -> Option<MatchResult<'a, 'b, [u8]>> {
+        for i in self.parser_children.iter().filter(|child| child.parser().kind() == "LITERAL") {
+            if let Some(result) = i.parse_traced(text, depth, trace) {
                 return Some(result);
             }
         }
         None
     }
 
-    pub fn parse_then_push_kvpair<'a, 'b>(&'a self,
-                                          text: &'b str,
-                                          kvpair: ParseResult<'a, 'b>)
-                                          -> Option<MatchResult<'a, 'b>> {
-        if let Some(mut result) = self.parse(text) {
+    pub fn parse_then_push_kvpair<'a, 'b, T: Trace>(&'a self,
+                                                    text: &'b [u8],
+                                                    kvpair: ParseResult<'a, 'b, [u8]>,
+                                                    depth: usize,
+                                                    trace: &mut T)
+                                                    -> Option<MatchResult<'a, 'b, [u8]>> {
+        if let Some(mut result) = self.parse_traced(text, depth, trace) {
             result.insert(kvpair);
             Some(result)
         } else {
@@ -217,9 +346,9 @@ impl SuffixTree {
 
     fn insert_literal_tail(&mut self, tail: &str) -> &mut LiteralNode {
         trace!("insert_literal_tail(): tail = {}", tail);
-        let cmp_str = |probe: &LiteralNode| probe.cmp_str(tail);
+        let cmp_bytes = |probe: &LiteralNode| probe.cmp_bytes(tail.as_bytes());
 
-        match self.literal_children.binary_search_by(&cmp_str) {
+        match self.literal_children.binary_search_by(&cmp_bytes) {
             Ok(pos) => {
                 if let Some(common_prefix_len) = self.literal_children
                                                      .get(pos)
@@ -235,7 +364,9 @@ impl SuffixTree {
                     trace!("insert_literal_tail(): tail = {}", tail);
                     hit.split(common_prefix_len, tail);
                     let suffix = tail.ltrunc(common_prefix_len);
-                    hit.node_mut().expect("Failed to get").lookup_freshly_inserted_literal(suffix)
+                    let node = hit.node_mut().expect("Failed to get");
+                    node.max_priority = node.children_max_priority();
+                    node.lookup_freshly_inserted_literal(suffix)
                 } else {
                     unreachable!()
                 }
@@ -252,7 +383,7 @@ impl SuffixTree {
     }
 
     fn lookup_freshly_inserted_literal(&mut self, literal: &str) -> &mut LiteralNode {
-        let (node, pos) = self.lookup_literal_mut(literal)
+        let (node, pos) = self.lookup_literal_mut(literal.as_bytes())
                               .ok()
                               .unwrap();
         node.literal_children.get_mut(pos).unwrap()
@@ -261,7 +392,7 @@ impl SuffixTree {
     pub fn insert_literal(&mut self, literal: &str) -> &mut LiteralNode {
         trace!("inserting literal: '{}'", literal);
 
-        match self.lookup_literal_mut(literal) {
+        match self.lookup_literal_mut(literal.as_bytes()) {
             Ok((node, index)) => {
                 trace!("insert_literal(): it was already inserted");
                 node.literal_children.get_mut(index).unwrap()
@@ -290,6 +421,7 @@ impl Default for SuffixTree {
         SuffixTree {
             literal_children: SortedVec::new(),
             parser_children: Vec::new(),
+            max_priority: None,
         }
     }
 }
@@ -299,31 +431,141 @@ impl self::interface::SuffixTree for SuffixTree {
         SuffixTree {
             literal_children: SortedVec::new(),
             parser_children: Vec::new(),
+            max_priority: None,
         }
     }
-    fn insert(&mut self, mut pattern: Pattern) {
-        if let Some(token) = pattern.pop_first_token() {
-            let mut entry: &mut Entry<ST = Self> = match token {
-                TokenType::Literal(literal) => {
-                    self.insert_literal(&literal)
-                }
-                TokenType::Parser(parser) => {
-                    self.insert_parser(parser)
-                }
-            };
-            entry.insert(pattern);
+    // The highest priorities are raised after the pattern is inserted, as the
+    // literal can be split on the way.
+    fn insert(&mut self, mut pattern: Pattern) -> Result<(), BuildError> {
+        let priority = pattern.priority();
+        match pattern.pop_first_token() {
+            Some(TokenType::Literal(literal)) => {
+                try!(self.insert_literal(&literal).insert(pattern));
+                self.raise_max_priority(literal.as_bytes(), priority);
+            }
+            Some(TokenType::Parser(parser)) => {
+                try!(self.insert_parser(parser).insert(pattern));
+                self.max_priority = priority::add(self.max_priority, priority);
+            }
+            Some(TokenType::Optional(_)) |
+            Some(TokenType::Alternatives(_)) |
+            Some(TokenType::Choice(_)) => {
+                return Err(BuildError::UnexpandedPattern(*pattern.uuid()));
+            }
+            None => (),
+        }
+        Ok(())
+    }
+
+    fn collect_uuids(&self, uuids: &mut BTreeSet<Uuid>) {
+        for child in self.literal_children.iter() {
+            child.collect_uuids(uuids);
+        }
+        for child in &self.parser_children {
+            child.collect_uuids(uuids);
+        }
+    }
+
+    fn collect_priorities(&self, priorities: &mut BTreeSet<i32>) {
+        for child in self.literal_children.iter() {
+            child.collect_priorities(priorities);
+        }
+        for child in &self.parser_children {
+            child.collect_priorities(priorities);
+        }
+    }
+
+    fn remove(&mut self, uuid: &Uuid) -> bool {
+        let mut removed = false;
+        for child in self.literal_children.iter_mut() {
+            removed |= child.remove(uuid);
+        }
+        for child in &mut self.parser_children {
+            removed |= child.remove(uuid);
+        }
+        self.literal_children.retain(|child| !child.is_empty());
+        self.parser_children.retain(|child| !child.is_empty());
+        for child in self.literal_children.iter_mut() {
+            child.merge_single_child();
+        }
+        self.max_priority = self.children_max_priority();
+        removed
+    }
+
+    fn is_empty(&self) -> bool {
+        self.is_leaf()
+    }
+}
+
+fn encode_child(child: Option<&SuffixTree>, encoder: &mut Encoder) {
+    encoder.write_bool(child.is_some());
+    if let Some(child) = child {
+        child.encode(encoder);
+    }
+}
+
+fn decode_child(decoder: &mut Decoder) -> Result<Option<SuffixTree>, cache::Error> {
+    if try!(decoder.read_bool()) {
+        Ok(Some(try!(SuffixTree::decode(decoder))))
+    } else {
+        Ok(None)
+    }
+}
+
+// The split literals are written as they are, so the decoded trie has the
+// same shape as the encoded one.
+impl Cacheable for SuffixTree {
+    fn encode(&self, encoder: &mut Encoder) {
+        encoder.write_bool(self.max_priority.is_some());
+        encoder.write_i32(self.max_priority.unwrap_or(0));
+        encoder.write_usize(self.literal_children.len());
+        for child in self.literal_children.iter() {
+            encoder.write_str(child.literal());
+            encoder.write_bool(child.has_value());
+            cache::encode_opt_pattern(child.pattern(), encoder);
+            encode_child(child.node(), encoder);
+        }
+        encoder.write_usize(self.parser_children.len());
+        for child in &self.parser_children {
+            parsers::encode(child.parser(), encoder);
+            cache::encode_opt_pattern(child.pattern(), encoder);
+            encode_child(child.node(), encoder);
         }
     }
 
+    fn decode(decoder: &mut Decoder) -> Result<SuffixTree, cache::Error> {
+        let mut tree = SuffixTree::new();
+        let has_priorities = try!(decoder.read_bool());
+        let max_priority = try!(decoder.read_i32());
+        if has_priorities {
+            tree.max_priority = Some(max_priority);
+        }
+        for _ in 0..try!(decoder.read_usize()) {
+            let mut child = LiteralNode::new(try!(decoder.read_str()));
+            child.set_has_value(try!(decoder.read_bool()));
+            child.set_pattern(try!(cache::decode_opt_pattern(decoder)));
+            child.set_node(try!(decode_child(decoder)));
+            tree.add_literal_node(child);
+        }
+        for _ in 0..try!(decoder.read_usize()) {
+            let mut child = ParserNode::new(try!(parsers::decode(decoder)));
+            child.set_pattern(try!(cache::decode_opt_pattern(decoder)));
+            child.set_child(try!(decode_child(decoder)));
+            tree.parser_children.push(child);
+        }
+        Ok(tree)
+    }
 }
 
 #[cfg(test)]
 mod test {
     use matcher::trie::node::SuffixTree;
-    use parsers::{SetParser, IntParser, GreedyParser};
+    use parsers::{SetParser, IntParser, GreedyParser, QStringParser, EStringParser};
     use matcher::compiled_pattern::CompiledPatternBuilder;
     use matcher::pattern::Pattern;
     use matcher::trie::node::interface::SuffixTree as STree;
+    use matcher::{BuildError, Matcher};
+    use matcher::explain::Step;
 
     use std::iter::FromIterator;
     use std::collections::BTreeMap;
@@ -333,10 +575,10 @@ mod test {
         let mut node = SuffixTree::new();
 
         let _ = node.insert_literal("alma");
-        assert_eq!(node.lookup_literal("alma").is_ok(), true);
-        assert_eq!(node.lookup_literal("alm").is_err(), true);
+        assert_eq!(node.lookup_literal(b"alma").is_ok(), true);
+        assert_eq!(node.lookup_literal(b"alm").is_err(), true);
         let _ = node.insert_literal("alm");
-        assert_eq!(node.lookup_literal("alm").is_ok(), true);
+        assert_eq!(node.lookup_literal(b"alm").is_ok(), true);
         assert_eq!(node.literal_children.len(), 1);
     }
 
@@ -347,8 +589,8 @@ mod test {
         let _ = node.insert_literal("alma");
         let _ = node.insert_literal("alm");
         assert_eq!(node.literal_children.len(), 1);
-        assert_eq!(node.lookup_literal("alma").is_ok(), true);
-        assert_eq!(node.lookup_literal("alm").ok().unwrap().0.literal_children.len(),
+        assert_eq!(node.lookup_literal(b"alma").is_ok(), true);
+        assert_eq!(node.lookup_literal(b"alm").ok().unwrap().0.literal_children.len(),
                    2);
     }
 
@@ -360,10 +602,10 @@ mod test {
         let _ = node.insert_literal("alma");
         let _ = node.insert_literal("ai");
         assert_eq!(node.literal_children.len(), 1);
-        assert_eq!(node.lookup_literal("alma").is_ok(), true);
-        assert_eq!(node.lookup_literal("alm").ok().unwrap().0.literal_children.len(),
+        assert_eq!(node.lookup_literal(b"alma").is_ok(), true);
+        assert_eq!(node.lookup_literal(b"alm").ok().unwrap().0.literal_children.len(),
                    2);
-        assert_eq!(node.lookup_literal("ai").ok().unwrap().0.literal_children.len(),
+        assert_eq!(node.lookup_literal(b"ai").ok().unwrap().0.literal_children.len(),
                    2);
     }
 
@@ -374,7 +616,7 @@ mod test {
 
         let _ = node.insert_literal("alm");
         let _ = node.insert_literal("ala");
-        assert_eq!(node.lookup_literal("al").is_err(), true);
+        assert_eq!(node.lookup_literal(b"al").is_err(), true);
     }
 
     #[test]
@@ -418,9 +660,9 @@ mod test {
         let mut pattern_3 = Pattern::with_random_uuid();
         pattern_3.set_pattern(cp_3);
 
-        root.insert(pattern_1);
-        root.insert(pattern_2);
-        root.insert(pattern_3);
+        root.insert(pattern_1).unwrap();
+        root.insert(pattern_2).unwrap();
+        root.insert(pattern_3).unwrap();
 
         root
     }
@@ -492,10 +734,10 @@ mod test {
         let mut pattern_4 = Pattern::with_random_uuid();
         pattern_4.set_pattern(cp_4);
 
-        root.insert(pattern_1);
-        root.insert(pattern2);
-        root.insert(pattern_3);
-        root.insert(pattern_4);
+        root.insert(pattern_1).unwrap();
+        root.insert(pattern2).unwrap();
+        root.insert(pattern_3).unwrap();
+        root.insert(pattern_4).unwrap();
 
         root
     }
@@ -560,7 +802,7 @@ mod test {
 
         let mut pattern_1 = Pattern::with_random_uuid();
         pattern_1.set_pattern(cp_1);
-        root.insert(pattern_1);
+        root.insert(pattern_1).unwrap();
 
         let kvpairs = root.parse("app12x");
         assert_eq!(kvpairs.is_none(), true);
@@ -580,7 +822,7 @@ mod test {
         {
             let mut pattern = Pattern::with_random_uuid();
             pattern.set_pattern(cp_1);
-            trie.insert(pattern);
+            trie.insert(pattern).unwrap();
             println!("{:?}", &trie);
         }
         {
@@ -589,7 +831,7 @@ mod test {
                           .literal("appletree")
                           .build();
             pattern.set_pattern(cp_2);
-            trie.insert(pattern);
+            trie.insert(pattern).unwrap();
         }
     }
 
@@ -605,7 +847,7 @@ mod test {
         let mut pattern = Pattern::with_random_uuid();
         pattern.set_pattern(cp_1);
 
-        trie.insert(pattern);
+        trie.insert(pattern).unwrap();
         println!("{:?}", &trie);
         let expected = BTreeMap::from_iter(vec![("test", "23")].into_iter());
         match trie.parse("app23le") {
@@ -631,7 +873,7 @@ mod test {
         let mut pattern = Pattern::with_random_uuid();
         pattern.set_pattern(cp_1);
 
-        trie.insert(pattern);
+        trie.insert(pattern).unwrap();
         println!("{:?}", &trie);
 
         match trie.parse("appccc12le") {
@@ -643,4 +885,448 @@ mod test {
             None => unreachable!(),
         }
     }
+
+    #[test]
+    fn test_given_pattern_with_delimited_parsers_when_a_text_is_parsed_then_the_delimiters_are_consumed_but_not_extracted
+        () {
+        let mut trie = SuffixTree::new();
+        let expected = BTreeMap::from_iter(vec![("msg", "foo bar"), ("key", "value")].into_iter());
+        let cp_1 = CompiledPatternBuilder::new()
+                      .literal("msg=")
+                      .parser(Box::new(QStringParser::with_name("msg")))
+                      .literal(" key=")
+                      .parser(Box::new(EStringParser::from_str("key", ";")))
+                      .literal(" end")
+                      .build();
+        let mut pattern = Pattern::with_random_uuid();
+        pattern.set_pattern(cp_1);
+
+        trie.insert(pattern).unwrap();
+
+        match trie.parse("msg=\"foo bar\" key=value; end") {
+            Some(res) => assert_eq!(res.values(), &expected),
+            None => unreachable!(),
+        }
+    }
+
+    #[test]
+    fn test_given_pattern_with_optional_group_when_it_is_added_to_the_trie_then_both_variants_match
+        () {
+        let mut trie = SuffixTree::new();
+        let group = CompiledPatternBuilder::new()
+                        .literal(" (pid ")
+                        .parser(Box::new(IntParser::with_name("pid")))
+                        .literal(")")
+                        .build();
+        let cp_1 = CompiledPatternBuilder::new()
+                      .literal("sshd")
+                      .optional(group)
+                      .literal(": session ")
+                      .alternatives(vec!["opened".to_owned(), "closed".to_owned()])
+                      .build();
+        let mut pattern = Pattern::with_random_uuid();
+        pattern.set_pattern(cp_1);
+
+        trie.add_pattern(pattern).unwrap();
+
+        let expected = BTreeMap::from_iter(vec![("pid", "42")].into_iter());
+        match trie.parse("sshd (pid 42): session closed") {
+            Some(res) => assert_eq!(res.values(), &expected),
+            None => unreachable!(),
+        }
+        match trie.parse("sshd: session opened") {
+            Some(res) => assert_eq!(res.values().is_empty(), true),
+            None => unreachable!(),
+        }
+    }
+
+    #[test]
+    fn test_given_pattern_which_ignores_case_when_the_trie_has_the_exact_literal_too_then_the_exact_one_is_preferred
+        () {
+        let mut trie = SuffixTree::new();
+        let mut loose = Pattern::with_random_uuid();
+        loose.set_pattern(CompiledPatternBuilder::new()
+                              .literal("session closed ")
+                              .parser(Box::new(IntParser::with_name("id")))
+                              .build());
+        loose.set_ignore_case(true);
+        loose.set_collapse_whitespace(true);
+        let mut exact = Pattern::with_random_uuid();
+        exact.set_pattern(CompiledPatternBuilder::new()
+                              .literal("session closed ")
+                              .parser(Box::new(IntParser::with_name("exact_id")))
+                              .build());
+
+        trie.add_pattern(loose.clone()).unwrap();
+        trie.add_pattern(exact.clone()).unwrap();
+
+        match trie.parse("Session  CLOSED 42") {
+            Some(res) => assert_eq!(res.pattern().uuid(), loose.uuid()),
+            None => unreachable!(),
+        }
+        match trie.parse("session closed 42") {
+            Some(res) => assert_eq!(res.pattern().uuid(), exact.uuid()),
+            None => unreachable!(),
+        }
+    }
+
+    #[test]
+    fn test_given_trie_when_the_literals_lead_to_a_dead_end_then_the_parsers_are_tried_only_for_loose_literals() {
+        let mut trie = SuffixTree::new();
+        let mut pid = Pattern::with_random_uuid();
+        pid.set_pattern(CompiledPatternBuilder::new()
+                            .literal("pid=")
+                            .parser(Box::new(IntParser::with_name("pid")))
+                            .build());
+        let mut any = Pattern::with_random_uuid();
+        any.set_pattern(CompiledPatternBuilder::new()
+                            .parser(Box::new(GreedyParser::with_name("any".to_owned())))
+                            .build());
+
+        trie.add_pattern(pid.clone()).unwrap();
+        trie.add_pattern(any.clone()).unwrap();
+
+        assert_eq!(trie.parse("pid=abc").is_none(), true);
+        assert_eq!(trie.parse("ppid=abc").unwrap().pattern().uuid(), any.uuid());
+        assert_eq!(Matcher::explain(&trie, "pid=abc").matched(), None);
+    }
+
+    #[test]
+    fn test_given_trie_with_loose_literal_when_the_literals_lead_to_a_dead_end_then_the_other_parsers_are_not_tried
+        () {
+        let mut trie = SuffixTree::new();
+        let mut pid = Pattern::with_random_uuid();
+        pid.set_pattern(CompiledPatternBuilder::new()
+                            .literal("pid=")
+                            .parser(Box::new(IntParser::with_name("pid")))
+                            .build());
+        let mut any = Pattern::with_random_uuid();
+        any.set_pattern(CompiledPatternBuilder::new()
+                            .parser(Box::new(GreedyParser::with_name("any".to_owned())))
+                            .build());
+        let mut loose = Pattern::with_random_uuid();
+        loose.set_pattern(CompiledPatternBuilder::new()
+                              .literal("session closed")
+                              .build());
+        loose.set_ignore_case(true);
+
+        trie.add_pattern(pid.clone()).unwrap();
+        trie.add_pattern(any.clone()).unwrap();
+        trie.add_pattern(loose.clone()).unwrap();
+
+        assert_eq!(trie.parse("pid=abc").is_none(), true);
+        assert_eq!(trie.parse("pid=42").unwrap().pattern().uuid(), pid.uuid());
+        assert_eq!(trie.parse("Session Closed").unwrap().pattern().uuid(), loose.uuid());
+    }
+
+    #[test]
+    fn test_given_trie_when_the_literals_lead_to_a_dead_end_then_the_explanation_contains_the_backtracking() {
+        let mut trie = SuffixTree::new();
+        let mut pid = Pattern::with_random_uuid();
+        pid.set_pattern(CompiledPatternBuilder::new()
+                            .literal("pid=")
+                            .parser(Box::new(IntParser::with_name("pid")))
+                            .build());
+        let mut any = Pattern::with_random_uuid();
+        any.set_pattern(CompiledPatternBuilder::new()
+                            .literal("PID=")
+                            .parser(Box::new(GreedyParser::with_name("any".to_owned())))
+                            .build());
+        any.set_ignore_case(true);
+
+        trie.add_pattern(pid.clone()).unwrap();
+        trie.add_pattern(any.clone()).unwrap();
+
+        let explanation = Matcher::explain(&trie, "pid=abc");
+        assert_eq!(explanation.matched(), Some(any.uuid()));
+        assert_eq!(explanation.longest_partial_match(), b"pid=abc");
+        assert_eq!(explanation.steps(),
+                   &[Step::Literal {
+                         depth: 0,
+                         position: 0,
+                         literal: "pid=".to_owned(),
+                     },
+                     Step::Parser {
+                         depth: 1,
+                         position: 4,
+                         kind: "INT",
+                         name: Some("pid".to_owned()),
+                         value: None,
+                     },
+                     Step::Backtrack {
+                         depth: 0,
+                         position: 0,
+                     },
+                     Step::Parser {
+                         depth: 0,
+                         position: 0,
+                         kind: "LITERAL",
+                         name: None,
+                         value: Some("pid=".to_owned()),
+                     },
+                     Step::Parser {
+                         depth: 1,
+                         position: 4,
+                         kind: "GREEDY",
+                         name: Some("any".to_owned()),
+                         value: Some("abc".to_owned()),
+                     }]);
+    }
+
+    #[test]
+    fn test_given_trie_when_the_message_is_not_valid_utf8_then_it_can_be_matched_as_bytes() {
+        let mut trie = SuffixTree::new();
+        let mut pattern = Pattern::with_random_uuid();
+        pattern.set_pattern(CompiledPatternBuilder::new()
+                                .literal("user ")
+                                .parser(Box::new(GreedyParser::from_str("user", " logged in")))
+                                .literal(" logged in")
+                                .build());
+        trie.add_pattern(pattern.clone()).unwrap();
+
+        let result = Matcher::parse_bytes(&trie, b"user Andr\xe9 logged in").unwrap();
+        assert_eq!(result.pattern().uuid(), pattern.uuid());
+        assert_eq!(result.values().get("user"), Some(&&b"Andr\xe9"[..]));
+        // the same pattern matches the UTF-8 encoded message through the str API
+        assert_eq!(Matcher::parse(&trie, "user Andr\u{e9} logged in").is_some(), true);
+    }
+
+    #[test]
+    fn test_given_trie_when_more_patterns_match_a_message_then_parse_all_returns_all_of_them() {
+        let mut trie = SuffixTree::new();
+        let mut pid = Pattern::with_random_uuid();
+        pid.set_pattern(CompiledPatternBuilder::new()
+                            .literal("pid=")
+                            .parser(Box::new(IntParser::with_name("pid")))
+                            .build());
+        let mut any = Pattern::with_random_uuid();
+        any.set_pattern(CompiledPatternBuilder::new()
+                            .parser(Box::new(GreedyParser::with_name("any".to_owned())))
+                            .build());
+
+        trie.add_pattern(any.clone()).unwrap();
+        trie.add_pattern(pid.clone()).unwrap();
+
+        let results = trie.parse_all("pid=42");
+        let uuids: Vec<_> = results.iter().map(|result| *result.pattern().uuid()).collect();
+        assert_eq!(uuids, vec![*pid.uuid(), *any.uuid()]);
+        assert_eq!(results[0].values().get("pid"), Some(&"42"));
+        assert_eq!(trie.parse_all("pid=").len(), 1);
+    }
+
+    #[test]
+    fn test_given_trie_when_a_literal_is_split_then_parse_all_returns_the_matches_under_the_empty_literal_too() {
+        let mut trie = SuffixTree::new();
+        let mut root = Pattern::with_random_uuid();
+        root.set_pattern(CompiledPatternBuilder::new()
+                             .literal("user root ")
+                             .parser(Box::new(GreedyParser::with_name("action".to_owned())))
+                             .build());
+        let mut user = Pattern::with_random_uuid();
+        user.set_pattern(CompiledPatternBuilder::new()
+                             .literal("user ")
+                             .parser(Box::new(GreedyParser::with_name("user".to_owned())))
+                             .build());
+
+        trie.add_pattern(root.clone()).unwrap();
+        trie.add_pattern(user.clone()).unwrap();
+
+        let results = trie.parse_all("user root logged in");
+        let uuids: Vec<_> = results.iter().map(|result| *result.pattern().uuid()).collect();
+        assert_eq!(uuids, vec![*root.uuid(), *user.uuid()]);
+    }
+
+    // Inserting a pattern into an existing parser entry used to replace the
+    // child of the entry, so `42 foo` didn't match after `bar` was added. This
+    // doesn't depend on the removal of the patterns.
+    #[test]
+    fn test_given_trie_when_a_pattern_is_inserted_into_an_existing_parser_entry_then_the_earlier_patterns_below_it_are_kept
+        () {
+        let mut trie = SuffixTree::new();
+        let mut foo = Pattern::with_random_uuid();
+        foo.set_pattern(CompiledPatternBuilder::new()
+                            .parser(Box::new(IntParser::with_name("n")))
+                            .literal(" foo")
+                            .build());
+        let mut bar = Pattern::with_random_uuid();
+        bar.set_pattern(CompiledPatternBuilder::new()
+                            .parser(Box::new(IntParser::with_name("n")))
+                            .literal(" bar")
+                            .build());
+
+        trie.add_pattern(foo.clone()).unwrap();
+        trie.add_pattern(bar.clone()).unwrap();
+
+        assert_eq!(trie.parse("42 foo").unwrap().pattern().uuid(), foo.uuid());
+        assert_eq!(trie.parse("42 bar").unwrap().pattern().uuid(), bar.uuid());
+    }
+
+    #[test]
+    fn test_given_trie_when_patterns_are_removed_then_the_empty_nodes_are_pruned() {
+        let mut trie = SuffixTree::new();
+        let mut alma = Pattern::with_random_uuid();
+        alma.set_pattern(CompiledPatternBuilder::new()
+                             .literal("alma ")
+                             .parser(Box::new(IntParser::with_name("x")))
+                             .build());
+        let mut alfa = Pattern::with_random_uuid();
+        alfa.set_pattern(CompiledPatternBuilder::new()
+                             .literal("alfa ")
+                             .parser(Box::new(IntParser::with_name("x")))
+                             .build());
+
+        trie.add_pattern(alma.clone()).unwrap();
+        trie.add_pattern(alfa.clone()).unwrap();
+
+        assert_eq!(trie.remove_pattern(alfa.uuid()), true);
+        assert_eq!(trie.parse("alfa 42").is_none(), true);
+        assert_eq!(trie.parse("alma 42").unwrap().pattern().uuid(), alma.uuid());
+        assert_eq!(trie.literal_children.len(), 1);
+        assert_eq!(trie.literal_children.get(0).unwrap().literal(), "alma ");
+        assert_eq!(trie.literal_children.get(0).unwrap().node().unwrap().parser_children.len(), 1);
+
+        trie.replace_pattern(alfa.clone()).unwrap();
+        assert_eq!(trie.parse("alfa 42").unwrap().pattern().uuid(), alfa.uuid());
+
+        let mut invalid = alfa.clone();
+        invalid.set_pattern(::grammar::parser::pattern(&"a%{? b}".repeat(22)).unwrap());
+        match trie.replace_pattern(invalid) {
+            Err(BuildError::TooManyVariants { uuid, .. }) => assert_eq!(&uuid, alfa.uuid()),
+            result => panic!("Unexpected result: {:?}", result),
+        }
+        assert_eq!(trie.parse("alfa 42").unwrap().pattern().uuid(), alfa.uuid());
+
+        assert_eq!(trie.remove_pattern(alma.uuid()), true);
+        assert_eq!(trie.remove_pattern(alfa.uuid()), true);
+        assert_eq!(trie.remove_pattern(alfa.uuid()), false);
+        assert_eq!(trie.is_leaf(), true);
+    }
+
+    #[test]
+    fn test_given_trie_when_the_pattern_of_a_split_literal_is_removed_then_the_literals_are_merged_back() {
+        let mut trie = SuffixTree::new();
+        let mut alma = Pattern::with_random_uuid();
+        alma.set_pattern(CompiledPatternBuilder::new()
+                             .literal("alma ")
+                             .parser(Box::new(IntParser::with_name("x")))
+                             .build());
+        let mut al = Pattern::with_random_uuid();
+        al.set_pattern(CompiledPatternBuilder::new()
+                           .literal("al")
+                           .build());
+
+        trie.add_pattern(alma.clone()).unwrap();
+        trie.add_pattern(al.clone()).unwrap();
+        assert_eq!(trie.literal_children.get(0).unwrap().literal(), "al");
+        assert_eq!(trie.parse("al").unwrap().pattern().uuid(), al.uuid());
+
+        assert_eq!(trie.remove_pattern(al.uuid()), true);
+        assert_eq!(trie.literal_children.len(), 1);
+        assert_eq!(trie.literal_children.get(0).unwrap().literal(), "alma ");
+        assert_eq!(trie.literal_children.get(0).unwrap().has_value(), true);
+        assert_eq!(trie.parse("al").is_none(), true);
+        assert_eq!(trie.parse("alma 42").unwrap().pattern().uuid(), alma.uuid());
+
+        trie.add_pattern(al.clone()).unwrap();
+        assert_eq!(trie.parse("al").unwrap().pattern().uuid(), al.uuid());
+        assert_eq!(trie.parse("alma 42").unwrap().pattern().uuid(), alma.uuid());
+    }
+
+    #[test]
+    fn test_given_trie_when_a_pattern_has_higher_priority_then_the_explanation_matches_it_like_parse() {
+        let literal = Pattern::from_json(r#"{"uuid": "9a49c47d-29e9-4072-be84-3b76c6814743",
+                                             "pattern": "user root %{GREEDY:action}"}"#)
+                          .unwrap();
+        let high = Pattern::from_json(r#"{"uuid": "fa8bdbcb-e0fd-4da1-9fa4-15ecfec28ad2",
+                                          "pattern": "user %{GREEDY:user}",
+                                          "priority": 5}"#)
+                       .unwrap();
+        let mut trie = SuffixTree::new();
+        trie.add_pattern(literal).unwrap();
+        trie.add_pattern(high.clone()).unwrap();
+
+        let message = "user root logged in";
+        let explanation = trie.explain(message);
+        assert_eq!(explanation.matched(), Some(trie.parse(message).unwrap().pattern().uuid()));
+        assert_eq!(explanation.matched(), Some(high.uuid()));
+        assert_eq!(explanation.steps().last(),
+                   Some(&Step::Priority {
+                       uuid: *high.uuid(),
+                       priority: 5,
+                   }));
+    }
+    #[test]
+    fn test_given_trie_when_the_pattern_with_priority_is_removed_then_the_trie_has_no_priorities() {
+        let low = Pattern::from_json(r#"{"uuid": "9a49c47d-29e9-4072-be84-3b76c6814743",
+                                         "pattern": "user root %{GREEDY:action}"}"#)
+                      .unwrap();
+        let high = Pattern::from_json(r#"{"uuid": "fa8bdbcb-e0fd-4da1-9fa4-15ecfec28ad2",
+                                          "pattern": "user %{GREEDY:user}",
+                                          "priority": 5}"#)
+                       .unwrap();
+        let mut trie = SuffixTree::new();
+        trie.add_pattern(low.clone()).unwrap();
+        assert_eq!(trie.max_priority(), None);
+        trie.add_pattern(high.clone()).unwrap();
+        assert_eq!(trie.max_priority(), Some(5));
+
+        assert_eq!(trie.remove_pattern(high.uuid()), true);
+        assert_eq!(trie.max_priority(), None);
+        assert_eq!(trie.parse("user root logged in").unwrap().pattern().uuid(), low.uuid());
+    }
+    #[test]
+    fn test_given_trie_with_priorities_when_the_better_matches_are_collected_then_only_the_nodes_with_higher_priorities_are_searched
+        () {
+        let low = Pattern::from_json(r#"{"uuid": "9a49c47d-29e9-4072-be84-3b76c6814743",
+                                         "pattern": "user ro%{GREEDY:rest}"}"#)
+                      .unwrap();
+        let middle = Pattern::from_json(r#"{"uuid": "d8ba7a64-1b35-4e43-8cb9-6c7bfb11b0a4",
+                                            "pattern": "user root %{GREEDY:action}",
+                                            "priority": 3}"#)
+                         .unwrap();
+        let high = Pattern::from_json(r#"{"uuid": "fa8bdbcb-e0fd-4da1-9fa4-15ecfec28ad2",
+                                          "pattern": "user %{GREEDY:user}",
+                                          "priority": 5}"#)
+                       .unwrap();
+        let mut trie = SuffixTree::new();
+        trie.add_pattern(middle.clone()).unwrap();
+        trie.add_pattern(low.clone()).unwrap();
+        trie.add_pattern(high.clone()).unwrap();
+
+        let message = b"user root logged in";
+        let collect = |trie: &SuffixTree, above| {
+            let mut results = Vec::new();
+            trie.parse_all_into(message, above, &mut results);
+            results.iter().map(|result| *result.pattern().uuid()).collect::<Vec<_>>()
+        };
+        assert_eq!(collect(&trie, None).len(), 3);
+        assert_eq!(collect(&trie, Some(0)).len(), 2);
+        assert_eq!(collect(&trie, Some(3)), vec![*high.uuid()]);
+        assert_eq!(collect(&trie, Some(5)).is_empty(), true);
+
+        // the literals are split twice, the new nodes know the priorities below them
+        let (node, _) = trie.lookup_literal(b"user ").ok().unwrap();
+        assert_eq!(node.max_priority(), Some(5));
+        let (node, pos) = trie.lookup_literal(b"user ro").ok().unwrap();
+        assert_eq!(node.max_priority(), Some(3));
+        assert_eq!(node.literal_children.get(pos).unwrap().node().unwrap().max_priority(), None);
+
+        assert_eq!(trie.remove_pattern(high.uuid()), true);
+        assert_eq!(trie.max_priority(), Some(3));
+        assert_eq!(trie.parse("user root logged in").unwrap().pattern().uuid(), middle.uuid());
+        assert_eq!(collect(&trie, Some(0)), vec![*middle.uuid()]);
+        assert_eq!(trie.remove_pattern(middle.uuid()), true);
+        assert_eq!(collect(&trie, Some(0)).is_empty(), true);
+        assert_eq!(trie.parse("user root logged in").unwrap().pattern().uuid(), low.uuid());
+    }
+    #[test]
+    fn test_given_pattern_with_priority_when_it_cant_be_added_then_the_trie_remains_unchanged() {
+        let mut pattern = Pattern::with_random_uuid();
+        pattern.set_pattern(::grammar::parser::pattern(&"a%{? b}".repeat(22)).unwrap());
+        pattern.set_priority(5);
+        let mut trie = SuffixTree::new();
+        assert_eq!(trie.add_pattern(pattern).is_err(), true);
+        assert_eq!(trie.max_priority(), None);
+        assert_eq!(trie.is_leaf(), true);
+    }
 }
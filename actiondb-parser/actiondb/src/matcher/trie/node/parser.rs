@@ -2,6 +2,8 @@ use matcher::trie::node::SuffixTree;
 use matcher::trie::node::interface::{Entry, ParserEntry};
 use matcher::result::MatchResult;
 use matcher::Pattern;
+use matcher::explain::Trace;
+use matcher::priority;
 use parsers::{Parser, ParseResult};
 use utils::CommonPrefix;
 
@@ -33,27 +35,65 @@ impl ParserNode {
         self.node.as_ref()
     }
 
-    pub fn parse<'a, 'b>(&'a self, text: &'b str) -> Option<MatchResult<'a, 'b>> {
-        if let Some(parsed_kwpair) = self.parser.parse(text) {
+    pub fn parse<'a, 'b>(&'a self, text: &'b [u8]) -> Option<MatchResult<'a, 'b, [u8]>> {
+        self.parse_traced(text, 0, &mut ())
+    }
+
+    pub fn parse_traced<'a, 'b, T: Trace>(&'a self,
+                                          text: &'b [u8],
+                                          depth: usize,
+                                          trace: &mut T)
+                                          -> Option<MatchResult<'a, 'b, [u8]>> {
+        if let Some(parsed_kwpair) = self.parser.parse_bytes(text) {
             trace!("parse(): parsed_kwpair = {:?}", &parsed_kwpair);
-            let text = text.ltrunc(parsed_kwpair.value().len());
+            trace.push_parser(depth, text, self.parser(), Some(parsed_kwpair.value()));
+            let text = text.ltrunc(parsed_kwpair.consumed_length());
+            trace.reached(text, |uuids| self.collect_uuids(uuids));
 
             return match self.node() {
                 Some(node) => {
-                    node.parse_then_push_kvpair(text, parsed_kwpair)
+                    node.parse_then_push_kvpair(text, parsed_kwpair, depth + 1, trace)
                 }
                 None => {
                     self.push_last_kvpair(text, parsed_kwpair)
                 }
             };
         }
+        trace.push_parser(depth, text, self.parser(), None);
         None
     }
 
+    pub fn parse_all_into<'a, 'b>(&'a self,
+                                  text: &'b [u8],
+                                  above: Option<i32>,
+                                  results: &mut Vec<MatchResult<'a, 'b, [u8]>>) {
+        if let Some(parsed_kwpair) = self.parser.parse_bytes(text) {
+            let text = text.ltrunc(parsed_kwpair.consumed_length());
+
+            if text.is_empty() {
+                if let Some(pattern) = self.pattern() {
+                    if priority::exceeds(pattern.priority(), above) {
+                        let mut result = MatchResult::new(pattern);
+                        result.insert(parsed_kwpair.clone());
+                        results.push(result);
+                    }
+                }
+            }
+            if let Some(node) = self.node() {
+                let mut node_results = Vec::new();
+                node.parse_all_into(text, above, &mut node_results);
+                for mut result in node_results {
+                    result.insert(parsed_kwpair.clone());
+                    results.push(result);
+                }
+            }
+        }
+    }
+
     fn push_last_kvpair<'a, 'b>(&'a self,
-                                text: &'b str,
-                                kvpair: ParseResult<'a, 'b>)
-                                -> Option<MatchResult<'a, 'b>> {
+                                text: &'b [u8],
+                                kvpair: ParseResult<'a, 'b, [u8]>)
+                                -> Option<MatchResult<'a, 'b, [u8]>> {
         if text.is_empty() {
             let mut result = MatchResult::new(self.pattern().unwrap());
             result.insert(kvpair);
@@ -84,21 +124,8 @@ impl Entry for ParserNode {
 }
 
 impl ParserEntry for ParserNode {
-    fn parse<'a, 'b>(&'a self, value: &'b str) -> Option<MatchResult<'a, 'b>> {
-        if let Some(parsed_kwpair) = self.parser.parse(value) {
-            trace!("parse(): parsed_kwpair = {:?}", &parsed_kwpair);
-            let text = value.ltrunc(parsed_kwpair.value().len());
-
-            return match self.node() {
-                Some(node) => {
-                    node.parse_then_push_kvpair(text, parsed_kwpair)
-                }
-                None => {
-                    self.push_last_kvpair(text, parsed_kwpair)
-                }
-            };
-        }
-        None
+    fn parse<'a, 'b>(&'a self, value: &'b [u8]) -> Option<MatchResult<'a, 'b, [u8]>> {
+        self.parse_traced(value, 0, &mut ())
     }
     fn parser(&self) -> &Box<Parser> {
         &self.parser
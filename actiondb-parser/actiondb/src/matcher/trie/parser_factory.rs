@@ -1,5 +1,9 @@
 use parsers::{GreedyParser, IntParser, OptionalParameter, Parser, ParserFactory, SetParser,
-              HasLengthConstraint};
+              HasLengthConstraint, Ipv4Parser, Ipv6Parser, IpAddrParser, QStringParser,
+              EStringParser, FloatParser, NumberParser, HexParser, TimestampParser, MacAddrParser,
+              HostnameParser, EmailParser, KeyValuePairsParser, JsonParser, RegexParser};
+use parsers::timestamp;
+use regex;
 
 macro_rules! set_optinal_param {
     ($parser:expr, $param:expr) => {
@@ -15,6 +19,7 @@ macro_rules! set_optinal_param {
                     _ => ()
                 }
             }
+            OptionalParameter::Str(..) => ()
         }
     }
 }
@@ -59,4 +64,147 @@ impl ParserFactory for TrieParserFactory {
         parser.set_name(name);
         Box::new(parser)
     }
+    fn new_ipv4(name: Option<&str>) -> Box<Parser> {
+        let mut parser = Ipv4Parser::new();
+        let name = name.map(|name| name.to_owned());
+        parser.set_name(name);
+        Box::new(parser)
+    }
+    fn new_ipv6(name: Option<&str>) -> Box<Parser> {
+        let mut parser = Ipv6Parser::new();
+        let name = name.map(|name| name.to_owned());
+        parser.set_name(name);
+        Box::new(parser)
+    }
+    fn new_ipaddr(name: Option<&str>) -> Box<Parser> {
+        let mut parser = IpAddrParser::new();
+        let name = name.map(|name| name.to_owned());
+        parser.set_name(name);
+        Box::new(parser)
+    }
+    fn new_macaddr(name: Option<&str>) -> Box<Parser> {
+        let mut parser = MacAddrParser::new();
+        let name = name.map(|name| name.to_owned());
+        parser.set_name(name);
+        Box::new(parser)
+    }
+    fn new_hostname(name: Option<&str>) -> Box<Parser> {
+        let mut parser = HostnameParser::new();
+        let name = name.map(|name| name.to_owned());
+        parser.set_name(name);
+        Box::new(parser)
+    }
+    fn new_email(name: Option<&str>) -> Box<Parser> {
+        let mut parser = EmailParser::new();
+        let name = name.map(|name| name.to_owned());
+        parser.set_name(name);
+        Box::new(parser)
+    }
+    fn new_float(name: Option<&str>, opt_params: Option<Vec<OptionalParameter>>) -> Box<Parser> {
+        let mut parser = FloatParser::new();
+        set_optional_params!(&mut parser, opt_params);
+        let name = name.map(|name| name.to_owned());
+        parser.set_name(name);
+        Box::new(parser)
+    }
+    fn new_number(name: Option<&str>, opt_params: Option<Vec<OptionalParameter>>) -> Box<Parser> {
+        let mut parser = NumberParser::new();
+        set_optional_params!(&mut parser, opt_params);
+        let name = name.map(|name| name.to_owned());
+        parser.set_name(name);
+        Box::new(parser)
+    }
+    fn new_hex(name: Option<&str>, opt_params: Option<Vec<OptionalParameter>>) -> Box<Parser> {
+        let mut parser = HexParser::new();
+        set_optional_params!(&mut parser, opt_params);
+        let name = name.map(|name| name.to_owned());
+        parser.set_name(name);
+        Box::new(parser)
+    }
+    fn new_timestamp(name: Option<&str>,
+                     opt_params: Option<Vec<OptionalParameter>>)
+                     -> Box<Parser> {
+        let mut parser = TimestampParser::new();
+        let mut formats = Vec::new();
+        for param in opt_params.unwrap_or_default() {
+            match param {
+                OptionalParameter::Str("format", format) => formats.push(format),
+                OptionalParameter::Str("preset", preset) => {
+                    if let Some(preset_formats) = timestamp::preset_formats(&preset) {
+                        formats.extend(preset_formats.iter().map(|format| (*format).to_owned()));
+                    }
+                }
+                _ => (),
+            }
+        }
+        if !formats.is_empty() {
+            parser.set_formats(formats);
+        }
+        let name = name.map(|name| name.to_owned());
+        parser.set_name(name);
+        Box::new(parser)
+    }
+    fn new_kvpairs(name: Option<&str>, opt_params: Option<Vec<OptionalParameter>>) -> Box<Parser> {
+        let mut parser = KeyValuePairsParser::new();
+        for param in opt_params.unwrap_or_default() {
+            match param {
+                OptionalParameter::Str("separator", separator) => parser.set_separator(separator),
+                OptionalParameter::Str("delimiter", delimiter) => parser.set_delimiter(delimiter),
+                OptionalParameter::Str("prefix", prefix) => parser.set_prefix(prefix),
+                _ => (),
+            }
+        }
+        let name = name.map(|name| name.to_owned());
+        parser.set_name(name);
+        Box::new(parser)
+    }
+    fn new_json(name: Option<&str>, opt_params: Option<Vec<OptionalParameter>>) -> Box<Parser> {
+        let mut parser = JsonParser::new();
+        for param in opt_params.unwrap_or_default() {
+            if let OptionalParameter::Str("prefix", prefix) = param {
+                parser.set_prefix(prefix);
+            }
+        }
+        let name = name.map(|name| name.to_owned());
+        parser.set_name(name);
+        Box::new(parser)
+    }
+    fn new_qstring(name: Option<&str>, opt_params: Option<Vec<OptionalParameter>>) -> Box<Parser> {
+        let mut parser = QStringParser::new();
+        let mut has_quotes = false;
+        let mut has_pairs = false;
+        for param in opt_params.unwrap_or_default() {
+            match param {
+                OptionalParameter::Str("quotes", quotes) => {
+                    has_quotes = true;
+                    parser.set_quotes(&quotes);
+                }
+                OptionalParameter::Str("pairs", pairs) => {
+                    has_pairs = true;
+                    parser.set_pairs(&pairs);
+                }
+                _ => (),
+            }
+        }
+        // only the pairs are accepted if the quotes aren't given with them
+        if has_pairs && !has_quotes {
+            parser.set_quotes("");
+        }
+        let name = name.map(|name| name.to_owned());
+        parser.set_name(name);
+        Box::new(parser)
+    }
+    fn new_estring(name: Option<&str>, end_string: &str) -> Box<Parser> {
+        let mut parser = EStringParser::new(end_string);
+        let name = name.map(|name| name.to_owned());
+        parser.set_name(name);
+        Box::new(parser)
+    }
+
+    fn new_regex(name: Option<&str>, expression: &str) -> Result<Box<Parser>, regex::Error> {
+        let mut parser = try!(RegexParser::new(expression));
+        let name = name.map(|name| name.to_owned());
+        parser.set_name(name);
+        Ok(Box::new(parser))
+    }
 }
@@ -1,5 +1,7 @@
 use std::borrow::Borrow;
 
+use matcher::cache::{self, Encoder, Decoder};
+
 #[derive(Clone, Hash, Debug)]
 pub struct ParserBase {
     name: Option<String>,
@@ -21,6 +23,14 @@ impl ParserBase {
     pub fn set_name(&mut self, name: Option<String>) {
         self.name = name;
     }
+
+    pub fn encode(&self, encoder: &mut Encoder) {
+        encoder.write_opt_str(self.name());
+    }
+
+    pub fn decode(decoder: &mut Decoder) -> Result<ParserBase, cache::Error> {
+        Ok(ParserBase { name: try!(decoder.read_opt_string()) })
+    }
 }
 
 impl Default for ParserBase {
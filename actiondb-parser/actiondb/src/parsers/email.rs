@@ -0,0 +1,134 @@
+use std::hash::{SipHasher, Hash, Hasher};
+
+use parsers::{ParserBase, Parser, ObjectSafeHash, ParseResult};
+use super::hostname;
+use matcher::cache::{self, Encoder, Decoder};
+
+const MAX_LOCAL_PART_LENGTH: usize = 64;
+
+#[derive(Clone, Debug, Hash)]
+pub struct EmailParser {
+    base: ParserBase,
+}
+
+impl EmailParser {
+    pub fn with_name<S: Into<String>>(name: S) -> EmailParser {
+        EmailParser { base: ParserBase::with_name(name.into()) }
+    }
+
+    pub fn new() -> EmailParser {
+        EmailParser::default()
+    }
+
+    pub fn decode(decoder: &mut Decoder) -> Result<EmailParser, cache::Error> {
+        Ok(EmailParser {
+            base: try!(ParserBase::decode(decoder)),
+        })
+    }
+}
+
+impl Default for EmailParser {
+    fn default() -> Self {
+        EmailParser { base: ParserBase::new() }
+    }
+}
+
+fn is_atom_char(c: u8) -> bool {
+    c.is_ascii_alphanumeric() || b"!#$%&'*+/=?^_`{|}~-".contains(&c)
+}
+
+// The local part is a dot separated list of atoms, a dot which isn't
+// followed by an atom isn't part of it.
+fn local_part_len(bytes: &[u8]) -> usize {
+    let mut len = 0;
+
+    loop {
+        let atom_len = bytes[len..].iter().take_while(|c| is_atom_char(**c)).count();
+        if atom_len == 0 {
+            return if len == 0 { 0 } else { len - 1 };
+        }
+        len += atom_len;
+        if bytes.get(len) == Some(&b'.') {
+            len += 1;
+        } else {
+            return len;
+        }
+    }
+}
+
+pub fn match_len(bytes: &[u8]) -> Option<usize> {
+    let local_len = local_part_len(bytes);
+
+    if local_len == 0 || local_len > MAX_LOCAL_PART_LENGTH || bytes.get(local_len) != Some(&b'@') {
+        return None;
+    }
+
+    let domain_start = local_len + 1;
+    hostname::match_len(&bytes[domain_start..]).map(|domain_len| domain_start + domain_len)
+}
+
+impl Parser for EmailParser {
+    fn parse_bytes<'a, 'b>(&'a self, value: &'b [u8]) -> Option<ParseResult<'a, 'b, [u8]>> {
+        match_len(value).map(|len| ParseResult::new(self, &value[..len]))
+    }
+
+    fn name(&self) -> Option<&str> {
+        self.base.name()
+    }
+
+    fn set_name(&mut self, name: Option<String>) {
+        self.base.set_name(name);
+    }
+
+    fn kind(&self) -> &'static str {
+        "EMAIL"
+    }
+
+    fn encode(&self, encoder: &mut Encoder) {
+        self.base.encode(encoder);
+    }
+
+    fn boxed_clone(&self) -> Box<Parser> {
+        Box::new(self.clone())
+    }
+}
+
+impl ObjectSafeHash for EmailParser {
+    fn hash_os(&self) -> u64 {
+        let mut hasher = SipHasher::new();
+        "parser:email".hash(&mut hasher);
+        self.hash(&mut hasher);
+        hasher.finish()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use parsers::{EmailParser, Parser};
+
+    #[test]
+    fn test_given_email_parser_when_the_value_starts_with_an_address_then_it_matches() {
+        let parser = EmailParser::with_name("email");
+        let res = parser.parse("john.doe+logs@example.com, bar").unwrap();
+        assert_eq!(res.parser().name(), Some("email"));
+        assert_eq!(res.value(), "john.doe+logs@example.com");
+        assert_eq!(parser.parse("root@localhost>").unwrap().value(), "root@localhost");
+    }
+
+    #[test]
+    fn test_given_email_parser_when_the_local_part_is_invalid_then_it_does_not_match() {
+        let parser = EmailParser::new();
+        assert_eq!(parser.parse(".john@example.com").is_none(), true);
+        assert_eq!(parser.parse("john..doe@example.com").is_none(), true);
+        assert_eq!(parser.parse("john.@example.com").is_none(), true);
+        assert_eq!(parser.parse("@example.com").is_none(), true);
+    }
+
+    #[test]
+    fn test_given_email_parser_when_the_domain_is_missing_then_it_does_not_match() {
+        let parser = EmailParser::new();
+        assert_eq!(parser.parse("john@").is_none(), true);
+        assert_eq!(parser.parse("john@.com").is_none(), true);
+        assert_eq!(parser.parse("john").is_none(), true);
+    }
+}
@@ -0,0 +1,103 @@
+use std::hash::{SipHasher, Hash, Hasher};
+
+use utils;
+use parsers::{ParserBase, Parser, ObjectSafeHash, ParseResult, OptionalParameter};
+use matcher::cache::{self, Encoder, Decoder};
+
+// The end string is required, without it the parser would be the same as
+// GREEDY.
+#[derive(Clone, Debug, Hash)]
+pub struct EStringParser {
+    base: ParserBase,
+    end_string: String,
+}
+
+impl EStringParser {
+    pub fn new<S: Into<String>>(end_string: S) -> EStringParser {
+        EStringParser {
+            base: ParserBase::new(),
+            end_string: end_string.into(),
+        }
+    }
+
+    pub fn from_str(name: &str, end_string: &str) -> EStringParser {
+        let mut parser = EStringParser::new(end_string);
+        parser.set_name(Some(name.to_owned()));
+        parser
+    }
+
+    pub fn set_end_string(&mut self, end_string: String) {
+        self.end_string = end_string;
+    }
+
+    pub fn decode(decoder: &mut Decoder) -> Result<EStringParser, cache::Error> {
+        Ok(EStringParser {
+            base: try!(ParserBase::decode(decoder)),
+            end_string: try!(decoder.read_string()),
+        })
+    }
+}
+
+impl Parser for EStringParser {
+    fn parse_bytes<'a, 'b>(&'a self, value: &'b [u8]) -> Option<ParseResult<'a, 'b, [u8]>> {
+        utils::find_bytes(value, self.end_string.as_bytes()).map(|pos| {
+            let consumed_length = pos + self.end_string.len();
+            ParseResult::with_consumed_length(self, &value[..pos], consumed_length)
+        })
+    }
+
+    fn name(&self) -> Option<&str> {
+        self.base.name()
+    }
+
+    fn set_name(&mut self, name: Option<String>) {
+        self.base.set_name(name);
+    }
+
+    fn kind(&self) -> &'static str {
+        "ESTRING"
+    }
+
+    fn encode(&self, encoder: &mut Encoder) {
+        self.base.encode(encoder);
+        encoder.write_str(&self.end_string);
+    }
+
+    fn parameters(&self) -> Vec<OptionalParameter<'static>> {
+        vec![OptionalParameter::Str("end", self.end_string.clone())]
+    }
+
+    fn boxed_clone(&self) -> Box<Parser> {
+        Box::new(self.clone())
+    }
+}
+
+impl ObjectSafeHash for EStringParser {
+    fn hash_os(&self) -> u64 {
+        let mut hasher = SipHasher::new();
+        "parser:estring".hash(&mut hasher);
+        self.hash(&mut hasher);
+        hasher.finish()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use parsers::{Parser, EStringParser};
+
+    #[test]
+    fn test_given_estring_parser_when_the_end_string_is_found_then_it_is_consumed_but_not_extracted
+        () {
+        let parser = EStringParser::from_str("name", ";");
+        let res = parser.parse("foo bar;baz").unwrap();
+        assert_eq!(res.parser().name(), Some("name"));
+        assert_eq!(res.value(), "foo bar");
+        assert_eq!(res.consumed_length(), 8);
+    }
+
+    #[test]
+    fn test_given_estring_parser_when_the_end_string_is_not_found_then_it_doesnt_match() {
+        let parser = EStringParser::from_str("name", ";");
+        assert_eq!(parser.parse("foo bar baz").is_none(), true);
+    }
+}
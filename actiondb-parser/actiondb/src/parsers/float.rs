@@ -0,0 +1,190 @@
+use std::hash::{SipHasher, Hash, Hasher};
+
+use parsers::{ParserBase, Parser, ObjectSafeHash, ParseResult, HasLengthConstraint, ValueType, OptionalParameter};
+use matcher::cache::{self, Encoder, Decoder};
+
+#[derive(Clone, Debug, Hash)]
+pub struct FloatParser {
+    base: ParserBase,
+    min_length: Option<usize>,
+    max_length: Option<usize>,
+}
+
+impl FloatParser {
+    pub fn with_name<S: Into<String>>(name: S) -> FloatParser {
+        FloatParser {
+            base: ParserBase::with_name(name.into()),
+            min_length: None,
+            max_length: None,
+        }
+    }
+
+    pub fn new() -> FloatParser {
+        FloatParser::default()
+    }
+
+    pub fn decode(decoder: &mut Decoder) -> Result<FloatParser, cache::Error> {
+        Ok(FloatParser {
+            base: try!(ParserBase::decode(decoder)),
+            min_length: try!(decoder.read_opt_usize()),
+            max_length: try!(decoder.read_opt_usize()),
+        })
+    }
+}
+
+impl Default for FloatParser {
+    fn default() -> Self {
+        FloatParser {
+            base: ParserBase::new(),
+            min_length: None,
+            max_length: None,
+        }
+    }
+}
+
+pub fn sign_len(bytes: &[u8]) -> usize {
+    match bytes.first() {
+        Some(&b'+') | Some(&b'-') => 1,
+        _ => 0,
+    }
+}
+
+pub fn digits_len(bytes: &[u8]) -> usize {
+    bytes.iter().take_while(|c| c.is_ascii_digit()).count()
+}
+
+// Returns the length of the longest prefix of `bytes` which matches
+// `[+-]?([0-9]+(\.[0-9]+)?|\.[0-9]+)([eE][+-]?[0-9]+)?`. A dot or an exponent
+// marker which isn't followed by digits isn't part of the match.
+pub fn match_len(bytes: &[u8]) -> Option<usize> {
+    let mut pos = sign_len(bytes);
+
+    let integer_len = digits_len(&bytes[pos..]);
+    pos += integer_len;
+
+    let mut fraction_len = 0;
+    if bytes.get(pos) == Some(&b'.') {
+        fraction_len = digits_len(&bytes[pos + 1..]);
+        if fraction_len > 0 {
+            pos += 1 + fraction_len;
+        }
+    }
+
+    if integer_len == 0 && fraction_len == 0 {
+        return None;
+    }
+
+    if bytes.get(pos) == Some(&b'e') || bytes.get(pos) == Some(&b'E') {
+        let exponent_sign_len = sign_len(&bytes[pos + 1..]);
+        let exponent_len = digits_len(&bytes[pos + 1 + exponent_sign_len..]);
+        if exponent_len > 0 {
+            pos += 1 + exponent_sign_len + exponent_len;
+        }
+    }
+
+    Some(pos)
+}
+
+impl Parser for FloatParser {
+    fn parse_bytes<'a, 'b>(&'a self, value: &'b [u8]) -> Option<ParseResult<'a, 'b, [u8]>> {
+        match match_len(value) {
+            Some(len) if self.is_match_length_ok(len) => Some(ParseResult::new(self, &value[..len])),
+            _ => None,
+        }
+    }
+
+    fn name(&self) -> Option<&str> {
+        self.base.name()
+    }
+
+    fn set_name(&mut self, name: Option<String>) {
+        self.base.set_name(name);
+    }
+
+    fn kind(&self) -> &'static str {
+        "FLOAT"
+    }
+
+    fn encode(&self, encoder: &mut Encoder) {
+        self.base.encode(encoder);
+        encoder.write_opt_usize(self.min_length);
+        encoder.write_opt_usize(self.max_length);
+    }
+
+    fn parameters(&self) -> Vec<OptionalParameter<'static>> {
+        self.length_parameters()
+    }
+
+    fn boxed_clone(&self) -> Box<Parser> {
+        Box::new(self.clone())
+    }
+
+    fn value_type(&self) -> ValueType {
+        ValueType::Float
+    }
+}
+
+impl HasLengthConstraint for FloatParser {
+    fn min_length(&self) -> Option<usize> {
+        self.min_length
+    }
+    fn set_min_length(&mut self, length: Option<usize>) {
+        self.min_length = length;
+    }
+    fn max_length(&self) -> Option<usize> {
+        self.max_length
+    }
+    fn set_max_length(&mut self, length: Option<usize>) {
+        self.max_length = length;
+    }
+}
+
+impl ObjectSafeHash for FloatParser {
+    fn hash_os(&self) -> u64 {
+        let mut hasher = SipHasher::new();
+        "parser:float".hash(&mut hasher);
+        self.hash(&mut hasher);
+        hasher.finish()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use parsers::{FloatParser, Parser, HasLengthConstraint};
+
+    #[test]
+    fn test_given_float_parser_when_the_value_is_a_valid_float_then_it_matches() {
+        let parser = FloatParser::with_name("float");
+        assert_eq!(parser.parse("3.14 ms").unwrap().value(), "3.14");
+        assert_eq!(parser.parse("-12,").unwrap().value(), "-12");
+        assert_eq!(parser.parse("1e-3s").unwrap().value(), "1e-3");
+        assert_eq!(parser.parse("+.5E10").unwrap().value(), "+.5E10");
+    }
+
+    #[test]
+    fn test_given_float_parser_when_the_value_is_not_a_number_then_it_doesnt_match() {
+        let parser = FloatParser::new();
+        assert_eq!(parser.parse("--.").is_none(), true);
+        assert_eq!(parser.parse(".").is_none(), true);
+        assert_eq!(parser.parse("-").is_none(), true);
+        assert_eq!(parser.parse("").is_none(), true);
+    }
+
+    #[test]
+    fn test_given_float_parser_when_a_dot_or_exponent_is_not_followed_by_digits_then_it_is_not_consumed
+        () {
+        let parser = FloatParser::new();
+        assert_eq!(parser.parse("42.").unwrap().value(), "42");
+        assert_eq!(parser.parse("42e").unwrap().value(), "42");
+        assert_eq!(parser.parse("42e+x").unwrap().value(), "42");
+    }
+
+    #[test]
+    fn test_given_float_parser_with_maximum_length_when_the_match_is_longer_then_it_doesnt_match
+        () {
+        let mut parser = FloatParser::new();
+        parser.set_max_length(Some(3));
+        assert_eq!(parser.parse("1.5").is_some(), true);
+        assert_eq!(parser.parse("1.25").is_none(), true);
+    }
+}
@@ -1,5 +1,7 @@
 use std::hash::{SipHasher, Hash, Hasher};
+use utils;
 use super::{ParserBase, Parser, ObjectSafeHash, ParseResult};
+use matcher::cache::{self, Encoder, Decoder};
 
 #[derive(Clone, Debug, Hash)]
 pub struct GreedyParser {
@@ -29,6 +31,13 @@ impl GreedyParser {
     pub fn set_end_string(&mut self, end_string: Option<String>) {
         self.end_string = end_string;
     }
+
+    pub fn decode(decoder: &mut Decoder) -> Result<GreedyParser, cache::Error> {
+        Ok(GreedyParser {
+            base: try!(ParserBase::decode(decoder)),
+            end_string: try!(decoder.read_opt_string()),
+        })
+    }
 }
 
 impl Default for GreedyParser {
@@ -50,9 +59,9 @@ impl ObjectSafeHash for GreedyParser {
 }
 
 impl Parser for GreedyParser {
-    fn parse<'a, 'b>(&'a self, value: &'b str) -> Option<ParseResult<'a, 'b>> {
+    fn parse_bytes<'a, 'b>(&'a self, value: &'b [u8]) -> Option<ParseResult<'a, 'b, [u8]>> {
         if let Some(end_string) = self.end_string.as_ref() {
-            value.find(end_string).map(|pos| ParseResult::new(self, &value[..pos]))
+            utils::find_bytes(value, end_string.as_bytes()).map(|pos| ParseResult::new(self, &value[..pos]))
         } else {
             Some(ParseResult::new(self, &value[..]))
         }
@@ -66,6 +75,15 @@ impl Parser for GreedyParser {
         self.base.set_name(name);
     }
 
+    fn kind(&self) -> &'static str {
+        "GREEDY"
+    }
+
+    fn encode(&self, encoder: &mut Encoder) {
+        self.base.encode(encoder);
+        encoder.write_opt_str(self.end_string.as_ref().map(|end_string| &end_string[..]));
+    }
+
     fn boxed_clone(&self) -> Box<Parser> {
         Box::new(self.clone())
     }
@@ -90,4 +108,13 @@ mod test {
         assert_eq!(res.parser().name(), Some("name"));
         assert_eq!(res.value(), "qux ");
     }
+
+    #[test]
+    fn test_given_greedy_parser_when_the_value_is_not_valid_utf8_then_it_matches_only_as_bytes() {
+        let parser = GreedyParser::from_str("name", " bar");
+        let res = parser.parse_bytes(b"caf\xe9 bar").unwrap();
+        assert_eq!(res.value(), b"caf\xe9");
+        assert_eq!(res.consumed_length(), 4);
+        assert_eq!(parser.parse_bytes(b"caf\xe9 bar").unwrap().into_str().is_none(), true);
+    }
 }
@@ -0,0 +1,156 @@
+use std::hash::{SipHasher, Hash, Hasher};
+
+use parsers::{ParserBase, Parser, ObjectSafeHash, ParseResult, HasLengthConstraint, OptionalParameter};
+use matcher::cache::{self, Encoder, Decoder};
+
+#[derive(Clone, Debug, Hash)]
+pub struct HexParser {
+    base: ParserBase,
+    min_length: Option<usize>,
+    max_length: Option<usize>,
+}
+
+impl HexParser {
+    pub fn with_name<S: Into<String>>(name: S) -> HexParser {
+        HexParser {
+            base: ParserBase::with_name(name.into()),
+            min_length: None,
+            max_length: None,
+        }
+    }
+
+    pub fn new() -> HexParser {
+        HexParser::default()
+    }
+
+    pub fn decode(decoder: &mut Decoder) -> Result<HexParser, cache::Error> {
+        Ok(HexParser {
+            base: try!(ParserBase::decode(decoder)),
+            min_length: try!(decoder.read_opt_usize()),
+            max_length: try!(decoder.read_opt_usize()),
+        })
+    }
+}
+
+impl Default for HexParser {
+    fn default() -> Self {
+        HexParser {
+            base: ParserBase::new(),
+            min_length: None,
+            max_length: None,
+        }
+    }
+}
+
+fn hex_digits_len(bytes: &[u8]) -> usize {
+    bytes.iter().take_while(|c| c.is_ascii_hexdigit()).count()
+}
+
+// Returns the length of the `0[xX][0-9a-fA-F]+` prefix of `value`.
+pub fn prefixed_match_len(bytes: &[u8]) -> Option<usize> {
+    if bytes.len() > 2 && bytes[0] == b'0' && (bytes[1] == b'x' || bytes[1] == b'X') {
+        match hex_digits_len(&bytes[2..]) {
+            0 => None,
+            len => Some(2 + len),
+        }
+    } else {
+        None
+    }
+}
+
+// The `0x` prefix is optional.
+pub fn match_len(bytes: &[u8]) -> Option<usize> {
+    prefixed_match_len(bytes).or_else(|| {
+        match hex_digits_len(bytes) {
+            0 => None,
+            len => Some(len),
+        }
+    })
+}
+
+impl Parser for HexParser {
+    fn parse_bytes<'a, 'b>(&'a self, value: &'b [u8]) -> Option<ParseResult<'a, 'b, [u8]>> {
+        match match_len(value) {
+            Some(len) if self.is_match_length_ok(len) => Some(ParseResult::new(self, &value[..len])),
+            _ => None,
+        }
+    }
+
+    fn name(&self) -> Option<&str> {
+        self.base.name()
+    }
+
+    fn set_name(&mut self, name: Option<String>) {
+        self.base.set_name(name);
+    }
+
+    fn kind(&self) -> &'static str {
+        "HEX"
+    }
+
+    fn encode(&self, encoder: &mut Encoder) {
+        self.base.encode(encoder);
+        encoder.write_opt_usize(self.min_length);
+        encoder.write_opt_usize(self.max_length);
+    }
+
+    fn parameters(&self) -> Vec<OptionalParameter<'static>> {
+        self.length_parameters()
+    }
+
+    fn boxed_clone(&self) -> Box<Parser> {
+        Box::new(self.clone())
+    }
+}
+
+impl HasLengthConstraint for HexParser {
+    fn min_length(&self) -> Option<usize> {
+        self.min_length
+    }
+    fn set_min_length(&mut self, length: Option<usize>) {
+        self.min_length = length;
+    }
+    fn max_length(&self) -> Option<usize> {
+        self.max_length
+    }
+    fn set_max_length(&mut self, length: Option<usize>) {
+        self.max_length = length;
+    }
+}
+
+impl ObjectSafeHash for HexParser {
+    fn hash_os(&self) -> u64 {
+        let mut hasher = SipHasher::new();
+        "parser:hex".hash(&mut hasher);
+        self.hash(&mut hasher);
+        hasher.finish()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use parsers::{HexParser, Parser, HasLengthConstraint};
+
+    #[test]
+    fn test_given_hex_parser_when_the_value_is_hexadecimal_then_it_matches() {
+        let parser = HexParser::with_name("hex");
+        assert_eq!(parser.parse("0x7f ").unwrap().value(), "0x7f");
+        assert_eq!(parser.parse("DEADbeef:").unwrap().value(), "DEADbeef");
+    }
+
+    #[test]
+    fn test_given_hex_parser_when_the_prefix_is_not_followed_by_digits_then_only_the_zero_matches
+        () {
+        let parser = HexParser::new();
+        assert_eq!(parser.parse("0xg").unwrap().value(), "0");
+        assert_eq!(parser.parse("xyz").is_none(), true);
+    }
+
+    #[test]
+    fn test_given_hex_parser_with_minimum_length_when_the_match_is_shorter_then_it_doesnt_match() {
+        let mut parser = HexParser::new();
+        parser.set_min_length(Some(4));
+        assert_eq!(parser.parse("0x7f").is_some(), true);
+        assert_eq!(parser.parse("7f").is_none(), true);
+    }
+}
@@ -0,0 +1,147 @@
+use std::hash::{SipHasher, Hash, Hasher};
+
+use parsers::{ParserBase, Parser, ObjectSafeHash, ParseResult};
+use matcher::cache::{self, Encoder, Decoder};
+
+const MAX_LABEL_LENGTH: usize = 63;
+const MAX_HOSTNAME_LENGTH: usize = 253;
+
+#[derive(Clone, Debug, Hash)]
+pub struct HostnameParser {
+    base: ParserBase,
+}
+
+impl HostnameParser {
+    pub fn with_name<S: Into<String>>(name: S) -> HostnameParser {
+        HostnameParser { base: ParserBase::with_name(name.into()) }
+    }
+
+    pub fn new() -> HostnameParser {
+        HostnameParser::default()
+    }
+
+    pub fn decode(decoder: &mut Decoder) -> Result<HostnameParser, cache::Error> {
+        Ok(HostnameParser {
+            base: try!(ParserBase::decode(decoder)),
+        })
+    }
+}
+
+impl Default for HostnameParser {
+    fn default() -> Self {
+        HostnameParser { base: ParserBase::new() }
+    }
+}
+
+fn is_label_char(c: u8) -> bool {
+    c.is_ascii_alphanumeric() || c == b'-'
+}
+
+// A label can't start or end with a hyphen, trailing hyphens aren't part of
+// the label.
+fn label_len(bytes: &[u8]) -> Option<usize> {
+    if bytes.first().map_or(true, |c| !c.is_ascii_alphanumeric()) {
+        return None;
+    }
+
+    let len = bytes.iter().take_while(|c| is_label_char(**c)).count();
+    let len = len - bytes[..len].iter().rev().take_while(|c| **c == b'-').count();
+
+    if len <= MAX_LABEL_LENGTH {
+        Some(len)
+    } else {
+        None
+    }
+}
+
+// Returns the length of the longest prefix of `bytes` which consists of dot
+// separated labels. A dot which isn't followed by a label isn't consumed.
+pub fn match_len(bytes: &[u8]) -> Option<usize> {
+    let mut len = match label_len(bytes) {
+        Some(len) => len,
+        None => return None,
+    };
+
+    while bytes.get(len) == Some(&b'.') {
+        match label_len(&bytes[len + 1..]) {
+            Some(next_len) if len + 1 + next_len <= MAX_HOSTNAME_LENGTH => len += 1 + next_len,
+            _ => break,
+        }
+    }
+
+    Some(len)
+}
+
+impl Parser for HostnameParser {
+    fn parse_bytes<'a, 'b>(&'a self, value: &'b [u8]) -> Option<ParseResult<'a, 'b, [u8]>> {
+        match_len(value).map(|len| ParseResult::new(self, &value[..len]))
+    }
+
+    fn name(&self) -> Option<&str> {
+        self.base.name()
+    }
+
+    fn set_name(&mut self, name: Option<String>) {
+        self.base.set_name(name);
+    }
+
+    fn kind(&self) -> &'static str {
+        "HOSTNAME"
+    }
+
+    fn encode(&self, encoder: &mut Encoder) {
+        self.base.encode(encoder);
+    }
+
+    fn boxed_clone(&self) -> Box<Parser> {
+        Box::new(self.clone())
+    }
+}
+
+impl ObjectSafeHash for HostnameParser {
+    fn hash_os(&self) -> u64 {
+        let mut hasher = SipHasher::new();
+        "parser:hostname".hash(&mut hasher);
+        self.hash(&mut hasher);
+        hasher.finish()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use parsers::{HostnameParser, Parser};
+
+    #[test]
+    fn test_given_hostname_parser_when_the_value_starts_with_a_hostname_then_it_matches() {
+        let parser = HostnameParser::with_name("host");
+        let res = parser.parse("mail-01.example.com: connected").unwrap();
+        assert_eq!(res.parser().name(), Some("host"));
+        assert_eq!(res.value(), "mail-01.example.com");
+        assert_eq!(parser.parse("localhost").unwrap().value(), "localhost");
+    }
+
+    #[test]
+    fn test_given_hostname_parser_when_the_hostname_is_followed_by_a_dot_or_hyphen_then_they_are_not_consumed
+        () {
+        let parser = HostnameParser::new();
+        assert_eq!(parser.parse("example.com.").unwrap().value(), "example.com");
+        assert_eq!(parser.parse("example.com- foo").unwrap().value(), "example.com");
+        assert_eq!(parser.parse("example.-com").unwrap().value(), "example");
+    }
+
+    #[test]
+    fn test_given_hostname_parser_when_the_value_does_not_start_with_a_label_then_it_does_not_match
+        () {
+        let parser = HostnameParser::new();
+        assert_eq!(parser.parse("-example.com").is_none(), true);
+        assert_eq!(parser.parse(".example.com").is_none(), true);
+        assert_eq!(parser.parse("").is_none(), true);
+    }
+
+    #[test]
+    fn test_given_hostname_parser_when_a_label_is_too_long_then_it_does_not_match() {
+        let parser = HostnameParser::new();
+        let label = "a".repeat(64);
+        assert_eq!(parser.parse(&label).is_none(), true);
+    }
+}
@@ -1,6 +1,8 @@
 use std::hash::{SipHasher, Hash, Hasher};
 
-use parsers::{Parser, ObjectSafeHash, SetParser, ParseResult, HasLengthConstraint};
+use parsers::{Parser, ObjectSafeHash, SetParser, ParseResult, HasLengthConstraint, ValueType,
+              OptionalParameter};
+use matcher::cache::{self, Encoder, Decoder};
 
 #[derive(Clone, Debug, Hash)]
 pub struct IntParser {
@@ -16,11 +18,18 @@ impl IntParser {
     pub fn new() -> IntParser {
         IntParser::default()
     }
+
+    pub fn decode(decoder: &mut Decoder) -> Result<IntParser, cache::Error> {
+        Ok(IntParser { delegate: try!(SetParser::decode(decoder)) })
+    }
 }
 
 impl Parser for IntParser {
-    fn parse<'a, 'b>(&'a self, value: &'b str) -> Option<ParseResult<'a, 'b>> {
-        self.delegate.parse(value)
+    fn parse_bytes<'a, 'b>(&'a self, value: &'b [u8]) -> Option<ParseResult<'a, 'b, [u8]>> {
+        self.delegate.parse_bytes(value).map(|mut result| {
+            result.set_value_type(self.value_type());
+            result
+        })
     }
 
     fn name(&self) -> Option<&str> {
@@ -31,9 +40,25 @@ impl Parser for IntParser {
         self.delegate.set_name(name);
     }
 
+    fn kind(&self) -> &'static str {
+        "INT"
+    }
+
+    fn encode(&self, encoder: &mut Encoder) {
+        self.delegate.encode(encoder);
+    }
+
+    fn parameters(&self) -> Vec<OptionalParameter<'static>> {
+        self.delegate.length_parameters()
+    }
+
     fn boxed_clone(&self) -> Box<Parser> {
         Box::new(self.clone())
     }
+
+    fn value_type(&self) -> ValueType {
+        ValueType::Int
+    }
 }
 
 impl Default for IntParser {
@@ -68,7 +93,7 @@ impl ObjectSafeHash for IntParser {
 
 #[cfg(test)]
 mod test {
-    use parsers::{IntParser, Parser, HasLengthConstraint};
+    use parsers::{IntParser, Parser, HasLengthConstraint, ValueType};
 
     #[test]
     fn test_given_int_parser_when_the_match_is_empty_then_the_result_isnt_successful() {
@@ -94,4 +119,10 @@ mod test {
         parser.set_max_length(Some(3));
         assert_eq!(parser.parse("1234asd").is_none(), true);
     }
+
+    #[test]
+    fn test_given_int_parser_when_a_value_is_parsed_then_it_is_typed_as_int() {
+        let parser = IntParser::with_name("pid");
+        assert_eq!(parser.parse("42 foo").unwrap().value_type(), ValueType::Int);
+    }
 }
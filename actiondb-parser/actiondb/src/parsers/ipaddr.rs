@@ -0,0 +1,106 @@
+use std::hash::{SipHasher, Hash, Hasher};
+
+use parsers::{ParserBase, Parser, ObjectSafeHash, ParseResult, ValueType};
+use super::{ipv4, ipv6};
+use matcher::cache::{self, Encoder, Decoder};
+
+#[derive(Clone, Debug, Hash)]
+pub struct IpAddrParser {
+    base: ParserBase,
+}
+
+impl IpAddrParser {
+    pub fn with_name<S: Into<String>>(name: S) -> IpAddrParser {
+        IpAddrParser { base: ParserBase::with_name(name.into()) }
+    }
+
+    pub fn new() -> IpAddrParser {
+        IpAddrParser::default()
+    }
+
+    pub fn decode(decoder: &mut Decoder) -> Result<IpAddrParser, cache::Error> {
+        Ok(IpAddrParser {
+            base: try!(ParserBase::decode(decoder)),
+        })
+    }
+}
+
+impl Default for IpAddrParser {
+    fn default() -> Self {
+        IpAddrParser { base: ParserBase::new() }
+    }
+}
+
+impl Parser for IpAddrParser {
+    fn parse_bytes<'a, 'b>(&'a self, value: &'b [u8]) -> Option<ParseResult<'a, 'b, [u8]>> {
+        let len = match (ipv4::match_len(value), ipv6::match_len(value)) {
+            (Some(v4_len), Some(v6_len)) => Some(::std::cmp::max(v4_len, v6_len)),
+            (v4_len, v6_len) => v4_len.or(v6_len),
+        };
+        len.map(|len| ParseResult::new(self, &value[..len]))
+    }
+
+    fn name(&self) -> Option<&str> {
+        self.base.name()
+    }
+
+    fn set_name(&mut self, name: Option<String>) {
+        self.base.set_name(name);
+    }
+
+    fn kind(&self) -> &'static str {
+        "IPADDR"
+    }
+
+    fn encode(&self, encoder: &mut Encoder) {
+        self.base.encode(encoder);
+    }
+
+    fn boxed_clone(&self) -> Box<Parser> {
+        Box::new(self.clone())
+    }
+
+    fn value_type(&self) -> ValueType {
+        ValueType::IpAddr
+    }
+}
+
+impl ObjectSafeHash for IpAddrParser {
+    fn hash_os(&self) -> u64 {
+        let mut hasher = SipHasher::new();
+        "parser:ipaddr".hash(&mut hasher);
+        self.hash(&mut hasher);
+        hasher.finish()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use parsers::{IpAddrParser, Ipv4Parser, Ipv6Parser, Parser, ObjectSafeHash};
+
+    #[test]
+    fn test_given_ipaddr_parser_when_the_value_starts_with_an_ipv4_address_then_it_matches() {
+        let parser = IpAddrParser::with_name("ip");
+        let res = parser.parse("10.1.2.3:22").unwrap();
+        assert_eq!(res.parser().name(), Some("ip"));
+        assert_eq!(res.value(), "10.1.2.3");
+    }
+
+    #[test]
+    fn test_given_ipaddr_parser_when_the_value_starts_with_an_ipv6_address_then_it_matches() {
+        let parser = IpAddrParser::new();
+        assert_eq!(parser.parse("2001:db8::8a2e:370:7334 x").unwrap().value(),
+                   "2001:db8::8a2e:370:7334");
+    }
+
+    #[test]
+    fn test_given_ip_parsers_with_the_same_name_when_they_are_hashed_then_the_hashes_are_different
+        () {
+        let ipv4 = Ipv4Parser::with_name("ip");
+        let ipv6 = Ipv6Parser::with_name("ip");
+        let ipaddr = IpAddrParser::with_name("ip");
+        assert_eq!(ipv4.hash_os() == ipv6.hash_os(), false);
+        assert_eq!(ipv4.hash_os() == ipaddr.hash_os(), false);
+        assert_eq!(ipv6.hash_os() == ipaddr.hash_os(), false);
+    }
+}
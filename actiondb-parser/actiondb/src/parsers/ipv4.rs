@@ -0,0 +1,131 @@
+use std::hash::{SipHasher, Hash, Hasher};
+use std::net::Ipv4Addr;
+use std::str::{self, FromStr};
+
+use parsers::{ParserBase, Parser, ObjectSafeHash, ParseResult, ValueType};
+use matcher::cache::{self, Encoder, Decoder};
+
+// "255.255.255.255"
+const MAX_ADDRESS_LENGTH: usize = 15;
+
+#[derive(Clone, Debug, Hash)]
+pub struct Ipv4Parser {
+    base: ParserBase,
+}
+
+impl Ipv4Parser {
+    pub fn with_name<S: Into<String>>(name: S) -> Ipv4Parser {
+        Ipv4Parser { base: ParserBase::with_name(name.into()) }
+    }
+
+    pub fn new() -> Ipv4Parser {
+        Ipv4Parser::default()
+    }
+
+    pub fn decode(decoder: &mut Decoder) -> Result<Ipv4Parser, cache::Error> {
+        Ok(Ipv4Parser {
+            base: try!(ParserBase::decode(decoder)),
+        })
+    }
+}
+
+impl Default for Ipv4Parser {
+    fn default() -> Self {
+        Ipv4Parser { base: ParserBase::new() }
+    }
+}
+
+// The candidates contain only ASCII characters, so they are valid UTF-8.
+fn is_address(bytes: &[u8]) -> bool {
+    str::from_utf8(bytes).ok().map_or(false, |address| Ipv4Addr::from_str(address).is_ok())
+}
+
+// Returns the length of the longest prefix of `bytes` which is a valid IPv4
+// address and isn't directly followed by an other digit.
+pub fn match_len(bytes: &[u8]) -> Option<usize> {
+    let candidate_len = bytes.iter()
+                             .take(MAX_ADDRESS_LENGTH)
+                             .take_while(|c| c.is_ascii_digit() || **c == b'.')
+                             .count();
+
+    for len in (1..candidate_len + 1).rev() {
+        let is_followed_by_digit = bytes.get(len).map_or(false, |c| c.is_ascii_digit());
+        if !is_followed_by_digit && is_address(&bytes[..len]) {
+            return Some(len);
+        }
+    }
+    None
+}
+
+impl Parser for Ipv4Parser {
+    fn parse_bytes<'a, 'b>(&'a self, value: &'b [u8]) -> Option<ParseResult<'a, 'b, [u8]>> {
+        match_len(value).map(|len| ParseResult::new(self, &value[..len]))
+    }
+
+    fn name(&self) -> Option<&str> {
+        self.base.name()
+    }
+
+    fn set_name(&mut self, name: Option<String>) {
+        self.base.set_name(name);
+    }
+
+    fn kind(&self) -> &'static str {
+        "IPV4"
+    }
+
+    fn encode(&self, encoder: &mut Encoder) {
+        self.base.encode(encoder);
+    }
+
+    fn boxed_clone(&self) -> Box<Parser> {
+        Box::new(self.clone())
+    }
+
+    fn value_type(&self) -> ValueType {
+        ValueType::IpAddr
+    }
+}
+
+impl ObjectSafeHash for Ipv4Parser {
+    fn hash_os(&self) -> u64 {
+        let mut hasher = SipHasher::new();
+        "parser:ipv4".hash(&mut hasher);
+        self.hash(&mut hasher);
+        hasher.finish()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use parsers::{Ipv4Parser, Parser};
+
+    #[test]
+    fn test_given_ipv4_parser_when_the_value_starts_with_an_address_then_it_matches() {
+        let parser = Ipv4Parser::with_name("ip");
+        let res = parser.parse("192.168.0.1 port 22").unwrap();
+        assert_eq!(res.parser().name(), Some("ip"));
+        assert_eq!(res.value(), "192.168.0.1");
+    }
+
+    #[test]
+    fn test_given_ipv4_parser_when_the_address_is_followed_by_a_dot_then_the_dot_is_not_consumed() {
+        let parser = Ipv4Parser::new();
+        assert_eq!(parser.parse("10.0.0.1.").unwrap().value(), "10.0.0.1");
+    }
+
+    #[test]
+    fn test_given_ipv4_parser_when_an_octet_is_out_of_range_then_it_does_not_match() {
+        let parser = Ipv4Parser::new();
+        assert_eq!(parser.parse("999.1.1.1").is_none(), true);
+        assert_eq!(parser.parse("1.1.1.256").is_none(), true);
+    }
+
+    #[test]
+    fn test_given_ipv4_parser_when_the_address_is_incomplete_then_it_does_not_match() {
+        let parser = Ipv4Parser::new();
+        assert_eq!(parser.parse("999.1.").is_none(), true);
+        assert_eq!(parser.parse("1.2.3").is_none(), true);
+        assert_eq!(parser.parse("").is_none(), true);
+    }
+}
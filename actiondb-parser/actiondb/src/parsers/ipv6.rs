@@ -0,0 +1,139 @@
+use std::hash::{SipHasher, Hash, Hasher};
+use std::net::Ipv6Addr;
+use std::str::{self, FromStr};
+
+use parsers::{ParserBase, Parser, ObjectSafeHash, ParseResult, ValueType};
+use matcher::cache::{self, Encoder, Decoder};
+
+// "ffff:ffff:ffff:ffff:ffff:ffff:255.255.255.255"
+const MAX_ADDRESS_LENGTH: usize = 45;
+
+#[derive(Clone, Debug, Hash)]
+pub struct Ipv6Parser {
+    base: ParserBase,
+}
+
+impl Ipv6Parser {
+    pub fn with_name<S: Into<String>>(name: S) -> Ipv6Parser {
+        Ipv6Parser { base: ParserBase::with_name(name.into()) }
+    }
+
+    pub fn new() -> Ipv6Parser {
+        Ipv6Parser::default()
+    }
+
+    pub fn decode(decoder: &mut Decoder) -> Result<Ipv6Parser, cache::Error> {
+        Ok(Ipv6Parser {
+            base: try!(ParserBase::decode(decoder)),
+        })
+    }
+}
+
+impl Default for Ipv6Parser {
+    fn default() -> Self {
+        Ipv6Parser { base: ParserBase::new() }
+    }
+}
+
+fn is_address_char(c: u8) -> bool {
+    c.is_ascii_hexdigit() || c == b':' || c == b'.'
+}
+
+// The candidates contain only ASCII characters, so they are valid UTF-8.
+fn is_address(bytes: &[u8]) -> bool {
+    str::from_utf8(bytes).ok().map_or(false, |address| Ipv6Addr::from_str(address).is_ok())
+}
+
+// Returns the length of the longest prefix of `bytes` which is a valid IPv6
+// address (including the compressed and the IPv4 embedding forms) and isn't
+// directly followed by an other hexadecimal digit or colon.
+pub fn match_len(bytes: &[u8]) -> Option<usize> {
+    let candidate_len = bytes.iter()
+                             .take(MAX_ADDRESS_LENGTH)
+                             .take_while(|c| is_address_char(**c))
+                             .count();
+
+    for len in (2..candidate_len + 1).rev() {
+        let is_followed_by_group = bytes.get(len).map_or(false, |c| c.is_ascii_hexdigit() || *c == b':');
+        if !is_followed_by_group && is_address(&bytes[..len]) {
+            return Some(len);
+        }
+    }
+    None
+}
+
+impl Parser for Ipv6Parser {
+    fn parse_bytes<'a, 'b>(&'a self, value: &'b [u8]) -> Option<ParseResult<'a, 'b, [u8]>> {
+        match_len(value).map(|len| ParseResult::new(self, &value[..len]))
+    }
+
+    fn name(&self) -> Option<&str> {
+        self.base.name()
+    }
+
+    fn set_name(&mut self, name: Option<String>) {
+        self.base.set_name(name);
+    }
+
+    fn kind(&self) -> &'static str {
+        "IPV6"
+    }
+
+    fn encode(&self, encoder: &mut Encoder) {
+        self.base.encode(encoder);
+    }
+
+    fn boxed_clone(&self) -> Box<Parser> {
+        Box::new(self.clone())
+    }
+
+    fn value_type(&self) -> ValueType {
+        ValueType::IpAddr
+    }
+}
+
+impl ObjectSafeHash for Ipv6Parser {
+    fn hash_os(&self) -> u64 {
+        let mut hasher = SipHasher::new();
+        "parser:ipv6".hash(&mut hasher);
+        self.hash(&mut hasher);
+        hasher.finish()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use parsers::{Ipv6Parser, Parser};
+
+    #[test]
+    fn test_given_ipv6_parser_when_the_value_starts_with_a_full_address_then_it_matches() {
+        let parser = Ipv6Parser::with_name("ip");
+        let res = parser.parse("2001:0db8:0000:0000:0000:ff00:0042:8329 port 22").unwrap();
+        assert_eq!(res.parser().name(), Some("ip"));
+        assert_eq!(res.value(), "2001:0db8:0000:0000:0000:ff00:0042:8329");
+    }
+
+    #[test]
+    fn test_given_ipv6_parser_when_the_address_is_compressed_then_it_matches() {
+        let parser = Ipv6Parser::new();
+        assert_eq!(parser.parse("fe80::1%eth0").unwrap().value(), "fe80::1");
+        assert_eq!(parser.parse("::1]").unwrap().value(), "::1");
+        assert_eq!(parser.parse(":: ").unwrap().value(), "::");
+    }
+
+    #[test]
+    fn test_given_ipv6_parser_when_the_address_has_an_embedded_ipv4_tail_then_it_matches() {
+        let parser = Ipv6Parser::new();
+        assert_eq!(parser.parse("::ffff:192.168.0.1 port").unwrap().value(),
+                   "::ffff:192.168.0.1");
+    }
+
+    #[test]
+    fn test_given_ipv6_parser_when_the_address_is_invalid_then_it_does_not_match() {
+        let parser = Ipv6Parser::new();
+        assert_eq!(parser.parse("1::2::3").is_none(), true);
+        assert_eq!(parser.parse("12345::1").is_none(), true);
+        assert_eq!(parser.parse("192.168.0.1").is_none(), true);
+        assert_eq!(parser.parse("").is_none(), true);
+    }
+}
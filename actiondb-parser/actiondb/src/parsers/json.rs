@@ -0,0 +1,463 @@
+use std::borrow::Cow;
+use std::char;
+use std::hash::{SipHasher, Hash, Hasher};
+
+use parsers::{ParserBase, Parser, ObjectSafeHash, ParseResult, OptionalParameter, ValueType};
+use matcher::cache::{self, Encoder, Decoder};
+
+const MAX_DEPTH: usize = 64;
+
+#[derive(Clone, Debug, Hash)]
+pub struct JsonParser {
+    base: ParserBase,
+    prefix: String,
+}
+
+impl JsonParser {
+    pub fn with_name<S: Into<String>>(name: S) -> JsonParser {
+        let mut parser = JsonParser::new();
+        parser.set_name(Some(name.into()));
+        parser
+    }
+
+    pub fn new() -> JsonParser {
+        JsonParser::default()
+    }
+
+    pub fn set_prefix(&mut self, prefix: String) {
+        self.prefix = prefix;
+    }
+
+    pub fn decode(decoder: &mut Decoder) -> Result<JsonParser, cache::Error> {
+        Ok(JsonParser {
+            base: try!(ParserBase::decode(decoder)),
+            prefix: try!(decoder.read_string()),
+        })
+    }
+}
+
+impl Default for JsonParser {
+    fn default() -> Self {
+        JsonParser {
+            base: ParserBase::new(),
+            prefix: String::new(),
+        }
+    }
+}
+
+// Scans a JSON object or array and collects its scalar values with their
+// dotted keys and types. The values are slices of the scanned text unless a
+// string contains escape sequences, then it's unescaped into an owned value.
+struct Scanner<'b> {
+    text: &'b [u8],
+    pos: usize,
+    pairs: Vec<(String, Cow<'b, [u8]>, ValueType)>,
+}
+
+impl<'b> Scanner<'b> {
+    fn new(text: &'b [u8]) -> Scanner<'b> {
+        Scanner {
+            text: text,
+            pos: 0,
+            pairs: Vec::new(),
+        }
+    }
+
+    fn peek(&self) -> Option<u8> {
+        self.text.get(self.pos).cloned()
+    }
+
+    fn skip_whitespace(&mut self) {
+        while let Some(b' ') | Some(b'\t') | Some(b'\n') | Some(b'\r') = self.peek() {
+            self.pos += 1;
+        }
+    }
+
+    fn consume(&mut self, c: u8) -> bool {
+        self.skip_whitespace();
+        if self.peek() == Some(c) {
+            self.pos += 1;
+            true
+        } else {
+            false
+        }
+    }
+
+    fn scan_root(&mut self, prefix: &str) -> bool {
+        match self.peek() {
+            Some(b'{') | Some(b'[') => self.scan_value(prefix, 0),
+            _ => false,
+        }
+    }
+
+    fn scan_value(&mut self, key: &str, depth: usize) -> bool {
+        if depth > MAX_DEPTH {
+            return false;
+        }
+
+        self.skip_whitespace();
+        match self.peek() {
+            Some(b'{') => self.scan_object(key, depth),
+            Some(b'[') => self.scan_array(key, depth),
+            Some(b'"') => {
+                match self.scan_string() {
+                    Some(value) => {
+                        self.pairs.push((key.to_owned(), value, ValueType::String));
+                        true
+                    }
+                    None => false,
+                }
+            }
+            Some(_) => {
+                match self.scan_literal() {
+                    Some(value) => {
+                        self.pairs.push((key.to_owned(), Cow::Borrowed(value), Scanner::literal_type(value)));
+                        true
+                    }
+                    None => false,
+                }
+            }
+            None => false,
+        }
+    }
+
+    fn scan_object(&mut self, key: &str, depth: usize) -> bool {
+        self.pos += 1;
+        if self.consume(b'}') {
+            return true;
+        }
+
+        loop {
+            self.skip_whitespace();
+            let member = match self.scan_string() {
+                Some(member) => member,
+                None => return false,
+            };
+            if !self.consume(b':') {
+                return false;
+            }
+            let member_key = Scanner::join_key(key, &String::from_utf8_lossy(&member), depth);
+            if !self.scan_value(&member_key, depth + 1) {
+                return false;
+            }
+            if self.consume(b'}') {
+                return true;
+            } else if !self.consume(b',') {
+                return false;
+            }
+        }
+    }
+
+    fn scan_array(&mut self, key: &str, depth: usize) -> bool {
+        self.pos += 1;
+        if self.consume(b']') {
+            return true;
+        }
+
+        let mut index = 0;
+        loop {
+            let item_key = Scanner::join_key(key, &index.to_string(), depth);
+            if !self.scan_value(&item_key, depth + 1) {
+                return false;
+            }
+            index += 1;
+            if self.consume(b']') {
+                return true;
+            } else if !self.consume(b',') {
+                return false;
+            }
+        }
+    }
+
+    // Returns the content of the string without its quotes.
+    fn scan_string(&mut self) -> Option<Cow<'b, [u8]>> {
+        if self.peek() != Some(b'"') {
+            return None;
+        }
+        let start = self.pos + 1;
+        let mut pos = start;
+        let mut unescaped: Option<Vec<u8>> = None;
+
+        while let Some(&c) = self.text.get(pos) {
+            if c == b'"' {
+                self.pos = pos + 1;
+                return match unescaped {
+                    Some(unescaped) => Some(Cow::Owned(unescaped)),
+                    None => Some(Cow::Borrowed(&self.text[start..pos])),
+                };
+            } else if c == b'\\' {
+                let buffer = unescaped.get_or_insert_with(|| self.text[start..pos].to_vec());
+                match Scanner::unescape(self.text, pos + 1, buffer) {
+                    Some(next) => pos = next,
+                    None => break,
+                }
+            } else if c < 0x20 {
+                return None;
+            } else {
+                if let Some(ref mut buffer) = unescaped {
+                    buffer.push(c);
+                }
+                pos += 1;
+            }
+        }
+        None
+    }
+
+    // Appends the character of the escape sequence starting at `pos` (after
+    // the backslash) and returns the position after it.
+    fn unescape(text: &[u8], pos: usize, buffer: &mut Vec<u8>) -> Option<usize> {
+        let c = match text.get(pos) {
+            Some(&b'"') => '"',
+            Some(&b'\\') => '\\',
+            Some(&b'/') => '/',
+            Some(&b'b') => '\u{8}',
+            Some(&b'f') => '\u{c}',
+            Some(&b'n') => '\n',
+            Some(&b'r') => '\r',
+            Some(&b't') => '\t',
+            Some(&b'u') => {
+                return Scanner::unescape_unicode(text, pos + 1).map(|(c, pos)| {
+                    Scanner::push_char(buffer, c);
+                    pos
+                })
+            }
+            _ => return None,
+        };
+        Scanner::push_char(buffer, c);
+        Some(pos + 1)
+    }
+
+    // Decodes the hex digits of a `\u` escape, including a following low
+    // surrogate if the first one is a high surrogate.
+    fn unescape_unicode(text: &[u8], pos: usize) -> Option<(char, usize)> {
+        match Scanner::hex4(text, pos) {
+            Some(high) if (0xd800..0xdc00).contains(&high) => {
+                if !text[pos + 4..].starts_with(b"\\u") {
+                    return None;
+                }
+                match Scanner::hex4(text, pos + 6) {
+                    Some(low) if (0xdc00..0xe000).contains(&low) => {
+                        char::from_u32(0x10000 + ((high - 0xd800) << 10) + (low - 0xdc00)).map(|c| (c, pos + 10))
+                    }
+                    _ => None,
+                }
+            }
+            Some(code) => char::from_u32(code).map(|c| (c, pos + 4)),
+            None => None,
+        }
+    }
+
+    fn hex4(text: &[u8], pos: usize) -> Option<u32> {
+        text.get(pos..pos + 4).and_then(|digits| {
+            digits.iter().try_fold(0, |value, &digit| (digit as char).to_digit(16).map(|digit| value * 16 + digit))
+        })
+    }
+
+    fn push_char(buffer: &mut Vec<u8>, c: char) {
+        let mut encoded = [0; 4];
+        buffer.extend_from_slice(c.encode_utf8(&mut encoded).as_bytes());
+    }
+
+    // `-?(0|[1-9][0-9]*)(\.[0-9]+)?([eE][+-]?[0-9]+)?`
+    fn number_len(text: &[u8]) -> Option<usize> {
+        fn digits(text: &[u8], pos: usize) -> usize {
+            text[pos..].iter().take_while(|c| c.is_ascii_digit()).count()
+        }
+
+        let mut pos = if text.first() == Some(&b'-') { 1 } else { 0 };
+        match text.get(pos) {
+            Some(&b'0') => pos += 1,
+            Some(c) if c.is_ascii_digit() => pos += digits(text, pos),
+            _ => return None,
+        }
+        if text.get(pos) == Some(&b'.') {
+            let len = digits(text, pos + 1);
+            if len == 0 {
+                return None;
+            }
+            pos += 1 + len;
+        }
+        if let Some(&b'e') | Some(&b'E') = text.get(pos) {
+            pos += 1;
+            if let Some(&b'+') | Some(&b'-') = text.get(pos) {
+                pos += 1;
+            }
+            let len = digits(text, pos);
+            if len == 0 {
+                return None;
+            }
+            pos += len;
+        }
+        Some(pos)
+    }
+
+    // numbers, `true`, `false` and `null`
+    fn scan_literal(&mut self) -> Option<&'b [u8]> {
+        let rest = &self.text[self.pos..];
+        let len = if rest.starts_with(b"true") || rest.starts_with(b"null") {
+            Some(4)
+        } else if rest.starts_with(b"false") {
+            Some(5)
+        } else {
+            Scanner::number_len(rest)
+        };
+
+        len.map(|len| {
+            self.pos += len;
+            &rest[..len]
+        })
+    }
+
+    // `null` has no type of its own, it's kept as a string.
+    fn literal_type(literal: &[u8]) -> ValueType {
+        match literal {
+            b"true" | b"false" => ValueType::Boolean,
+            b"null" => ValueType::String,
+            _ if literal.iter().any(|&c| c == b'.' || c == b'e' || c == b'E') => ValueType::Float,
+            _ => ValueType::Int,
+        }
+    }
+
+    // The members of the root are only prefixed by the parser's prefix.
+    fn join_key(key: &str, member: &str, depth: usize) -> String {
+        if depth == 0 {
+            format!("{}{}", key, member)
+        } else {
+            format!("{}.{}", key, member)
+        }
+    }
+}
+
+impl Parser for JsonParser {
+    fn parse_bytes<'a, 'b>(&'a self, value: &'b [u8]) -> Option<ParseResult<'a, 'b, [u8]>> {
+        let mut scanner = Scanner::new(value);
+
+        if scanner.scan_root(&self.prefix) {
+            let mut result = ParseResult::new(self, &value[..scanner.pos]);
+            result.set_pairs(scanner.pairs);
+            Some(result)
+        } else {
+            None
+        }
+    }
+
+    fn name(&self) -> Option<&str> {
+        self.base.name()
+    }
+
+    fn set_name(&mut self, name: Option<String>) {
+        self.base.set_name(name);
+    }
+
+    fn kind(&self) -> &'static str {
+        "JSON"
+    }
+
+    fn encode(&self, encoder: &mut Encoder) {
+        self.base.encode(encoder);
+        encoder.write_str(&self.prefix);
+    }
+
+    fn parameters(&self) -> Vec<OptionalParameter<'static>> {
+        if self.prefix.is_empty() {
+            Vec::new()
+        } else {
+            vec![OptionalParameter::Str("prefix", self.prefix.clone())]
+        }
+    }
+
+    fn boxed_clone(&self) -> Box<Parser> {
+        Box::new(self.clone())
+    }
+}
+
+impl ObjectSafeHash for JsonParser {
+    fn hash_os(&self) -> u64 {
+        let mut hasher = SipHasher::new();
+        "parser:json".hash(&mut hasher);
+        self.hash(&mut hasher);
+        hasher.finish()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use parsers::{JsonParser, Parser, ValueType};
+
+    use std::borrow::Cow;
+
+    fn pairs(pairs: &[(&str, &'static str, ValueType)]) -> Vec<(String, Cow<'static, str>, ValueType)> {
+        pairs.iter().map(|&(key, value, value_type)| (key.to_owned(), Cow::Borrowed(value), value_type)).collect()
+    }
+
+    #[test]
+    fn test_given_json_parser_when_the_value_starts_with_an_object_then_its_fields_are_flattened() {
+        let mut parser = JsonParser::with_name("json");
+        parser.set_prefix("req.".to_owned());
+        let text = r#"{"status": 200, "user": {"name": "john", "admin": false}, "tags": ["a", null]} foo"#;
+        let res = parser.parse(text).unwrap();
+        assert_eq!(res.parser().name(), Some("json"));
+        assert_eq!(res.value(),
+                   r#"{"status": 200, "user": {"name": "john", "admin": false}, "tags": ["a", null]}"#);
+        assert_eq!(res.pairs(),
+                   &pairs(&[("req.status", "200", ValueType::Int),
+                            ("req.user.name", "john", ValueType::String),
+                            ("req.user.admin", "false", ValueType::Boolean),
+                            ("req.tags.0", "a", ValueType::String),
+                            ("req.tags.1", "null", ValueType::String)])[..]);
+    }
+
+    #[test]
+    fn test_given_json_parser_without_prefix_when_an_array_is_parsed_then_the_keys_are_the_indices() {
+        let parser = JsonParser::new();
+        let res = parser.parse(r#"[1.5e3, "x\"y", {}]"#).unwrap();
+        assert_eq!(res.pairs(),
+                   &pairs(&[("0", "1.5e3", ValueType::Float), ("1", r#"x"y"#, ValueType::String)])[..]);
+    }
+
+    #[test]
+    fn test_given_json_parser_when_a_string_contains_escape_sequences_then_it_is_unescaped() {
+        let parser = JsonParser::new();
+        let res = parser.parse(r#"{"a\tb": "\\x\/\n\u00e9\ud83d\ude00"}"#).unwrap();
+        assert_eq!(res.pairs(), &pairs(&[("a\tb", "\\x/\n\u{e9}\u{1f600}", ValueType::String)])[..]);
+        assert_eq!(parser.parse(r#"["\x"]"#).is_none(), true);
+        assert_eq!(parser.parse(r#"["\u00g0"]"#).is_none(), true);
+        assert_eq!(parser.parse(r#"["\ud83d"]"#).is_none(), true);
+    }
+
+    #[test]
+    fn test_given_json_parser_when_a_number_is_not_valid_json_then_it_doesnt_match() {
+        let parser = JsonParser::new();
+        assert_eq!(parser.parse("[-.5]").is_none(), true);
+        assert_eq!(parser.parse("[.5]").is_none(), true);
+        assert_eq!(parser.parse("[+5]").is_none(), true);
+        assert_eq!(parser.parse("[01]").is_none(), true);
+        assert_eq!(parser.parse("[-01]").is_none(), true);
+        assert_eq!(parser.parse("[1.]").is_none(), true);
+        assert_eq!(parser.parse("[1e]").is_none(), true);
+        let res = parser.parse("[0, -0.5, 10, 1E+2]").unwrap();
+        assert_eq!(res.pairs(),
+                   &pairs(&[("0", "0", ValueType::Int),
+                            ("1", "-0.5", ValueType::Float),
+                            ("2", "10", ValueType::Int),
+                            ("3", "1E+2", ValueType::Float)])[..]);
+    }
+
+    #[test]
+    fn test_given_json_parser_when_the_json_is_not_balanced_or_invalid_then_it_doesnt_match() {
+        let parser = JsonParser::new();
+        assert_eq!(parser.parse(r#"{"status": 200"#).is_none(), true);
+        assert_eq!(parser.parse(r#"{"status": 200,}"#).is_none(), true);
+        assert_eq!(parser.parse(r#"{status: 200}"#).is_none(), true);
+        assert_eq!(parser.parse(r#"["a" "b"]"#).is_none(), true);
+        assert_eq!(parser.parse("42").is_none(), true);
+        assert_eq!(parser.parse(" {}").is_none(), true);
+    }
+
+    #[test]
+    fn test_given_json_parser_when_the_nesting_is_too_deep_then_it_doesnt_match() {
+        let parser = JsonParser::new();
+        let text = format!("{}{}", "[".repeat(100), "]".repeat(100));
+        assert_eq!(parser.parse(&text).is_none(), true);
+    }
+}
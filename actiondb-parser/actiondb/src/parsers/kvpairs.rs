@@ -0,0 +1,210 @@
+use std::borrow::Cow;
+use std::hash::{SipHasher, Hash, Hasher};
+
+use utils;
+use parsers::{ParserBase, Parser, ObjectSafeHash, ParseResult, OptionalParameter, ValueType};
+use matcher::cache::{self, Encoder, Decoder};
+
+const DEFAULT_SEPARATOR: &'static str = "=";
+const DEFAULT_DELIMITER: &'static str = " ";
+
+#[derive(Clone, Debug, Hash)]
+pub struct KeyValuePairsParser {
+    base: ParserBase,
+    separator: String,
+    delimiter: String,
+    prefix: String,
+}
+
+impl KeyValuePairsParser {
+    pub fn with_name<S: Into<String>>(name: S) -> KeyValuePairsParser {
+        let mut parser = KeyValuePairsParser::new();
+        parser.set_name(Some(name.into()));
+        parser
+    }
+
+    pub fn new() -> KeyValuePairsParser {
+        KeyValuePairsParser::default()
+    }
+
+    pub fn set_separator(&mut self, separator: String) {
+        self.separator = separator;
+    }
+
+    pub fn set_delimiter(&mut self, delimiter: String) {
+        self.delimiter = delimiter;
+    }
+
+    pub fn set_prefix(&mut self, prefix: String) {
+        self.prefix = prefix;
+    }
+
+    // Returns the key and the value of the first pair in `value`. The value
+    // lasts until the next delimiter or the end of the text.
+    fn parse_pair<'b>(&self, value: &'b [u8]) -> Option<(&'b [u8], &'b [u8])> {
+        utils::find_bytes(value, self.separator.as_bytes()).and_then(|key_len| {
+            let key = &value[..key_len];
+            if key.is_empty() || utils::find_bytes(key, self.delimiter.as_bytes()).is_some() {
+                return None;
+            }
+
+            let value = &value[key_len + self.separator.len()..];
+            let value_len = utils::find_bytes(value, self.delimiter.as_bytes()).unwrap_or(value.len());
+            Some((key, &value[..value_len]))
+        })
+    }
+
+    pub fn decode(decoder: &mut Decoder) -> Result<KeyValuePairsParser, cache::Error> {
+        Ok(KeyValuePairsParser {
+            base: try!(ParserBase::decode(decoder)),
+            separator: try!(decoder.read_string()),
+            delimiter: try!(decoder.read_string()),
+            prefix: try!(decoder.read_string()),
+        })
+    }
+}
+
+impl Default for KeyValuePairsParser {
+    fn default() -> Self {
+        KeyValuePairsParser {
+            base: ParserBase::new(),
+            separator: DEFAULT_SEPARATOR.to_owned(),
+            delimiter: DEFAULT_DELIMITER.to_owned(),
+            prefix: String::new(),
+        }
+    }
+}
+
+impl Parser for KeyValuePairsParser {
+    fn parse_bytes<'a, 'b>(&'a self, value: &'b [u8]) -> Option<ParseResult<'a, 'b, [u8]>> {
+        if self.separator.is_empty() || self.delimiter.is_empty() {
+            return None;
+        }
+
+        let mut pairs = Vec::new();
+        let mut match_len = 0;
+
+        loop {
+            let pair_start = if pairs.is_empty() {
+                0
+            } else if value[match_len..].starts_with(self.delimiter.as_bytes()) {
+                match_len + self.delimiter.len()
+            } else {
+                break;
+            };
+
+            match self.parse_pair(&value[pair_start..]) {
+                Some((key, pair_value)) => {
+                    let key_len = key.len() + self.separator.len();
+                    match_len = pair_start + key_len + pair_value.len();
+                    pairs.push((format!("{}{}", self.prefix, String::from_utf8_lossy(key)),
+                                Cow::Borrowed(pair_value),
+                                ValueType::String));
+                }
+                None => break,
+            }
+        }
+
+        if pairs.is_empty() {
+            None
+        } else {
+            let mut result = ParseResult::new(self, &value[..match_len]);
+            result.set_pairs(pairs);
+            Some(result)
+        }
+    }
+
+    fn name(&self) -> Option<&str> {
+        self.base.name()
+    }
+
+    fn set_name(&mut self, name: Option<String>) {
+        self.base.set_name(name);
+    }
+
+    fn kind(&self) -> &'static str {
+        "KVPAIRS"
+    }
+
+    fn encode(&self, encoder: &mut Encoder) {
+        self.base.encode(encoder);
+        encoder.write_str(&self.separator);
+        encoder.write_str(&self.delimiter);
+        encoder.write_str(&self.prefix);
+    }
+
+    fn parameters(&self) -> Vec<OptionalParameter<'static>> {
+        let mut parameters = Vec::new();
+        if self.separator != DEFAULT_SEPARATOR {
+            parameters.push(OptionalParameter::Str("separator", self.separator.clone()));
+        }
+        if self.delimiter != DEFAULT_DELIMITER {
+            parameters.push(OptionalParameter::Str("delimiter", self.delimiter.clone()));
+        }
+        if !self.prefix.is_empty() {
+            parameters.push(OptionalParameter::Str("prefix", self.prefix.clone()));
+        }
+        parameters
+    }
+
+    fn boxed_clone(&self) -> Box<Parser> {
+        Box::new(self.clone())
+    }
+}
+
+impl ObjectSafeHash for KeyValuePairsParser {
+    fn hash_os(&self) -> u64 {
+        let mut hasher = SipHasher::new();
+        "parser:kvpairs".hash(&mut hasher);
+        self.hash(&mut hasher);
+        hasher.finish()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use parsers::{KeyValuePairsParser, Parser, ValueType};
+
+    use std::borrow::Cow;
+
+    fn pairs(pairs: &[(&str, &'static str)]) -> Vec<(String, Cow<'static, str>, ValueType)> {
+        pairs.iter().map(|&(key, value)| (key.to_owned(), Cow::Borrowed(value), ValueType::String)).collect()
+    }
+
+    #[test]
+    fn test_given_kvpairs_parser_when_the_value_contains_pairs_then_each_of_them_is_extracted() {
+        let parser = KeyValuePairsParser::new();
+        let res = parser.parse("src=10.0.0.1 dst=10.0.0.2 action=drop").unwrap();
+        assert_eq!(res.value(), "src=10.0.0.1 dst=10.0.0.2 action=drop");
+        assert_eq!(res.pairs(),
+                   &pairs(&[("src", "10.0.0.1"), ("dst", "10.0.0.2"), ("action", "drop")])[..]);
+    }
+
+    #[test]
+    fn test_given_kvpairs_parser_when_a_non_pair_follows_the_pairs_then_the_parsing_stops_before_it
+        () {
+        let parser = KeyValuePairsParser::new();
+        let res = parser.parse("a=1 b= c d=4").unwrap();
+        assert_eq!(res.value(), "a=1 b=");
+        assert_eq!(res.pairs(), &pairs(&[("a", "1"), ("b", "")])[..]);
+    }
+
+    #[test]
+    fn test_given_kvpairs_parser_with_custom_separator_delimiter_and_prefix_when_a_value_is_parsed_then_they_are_used
+        () {
+        let mut parser = KeyValuePairsParser::new();
+        parser.set_separator(":".to_owned());
+        parser.set_delimiter(", ".to_owned());
+        parser.set_prefix("kv.".to_owned());
+        let res = parser.parse("user:john, uid:42").unwrap();
+        assert_eq!(res.pairs(), &pairs(&[("kv.user", "john"), ("kv.uid", "42")])[..]);
+    }
+
+    #[test]
+    fn test_given_kvpairs_parser_when_the_value_doesnt_start_with_a_pair_then_it_doesnt_match() {
+        let parser = KeyValuePairsParser::new();
+        assert_eq!(parser.parse("foo bar=baz").is_none(), true);
+        assert_eq!(parser.parse("=baz").is_none(), true);
+        assert_eq!(parser.parse("").is_none(), true);
+    }
+}
@@ -0,0 +1,159 @@
+use std::hash::{SipHasher, Hash, Hasher};
+use std::str;
+
+use parsers::{ParserBase, Parser, ObjectSafeHash, ParseResult};
+use matcher::cache::{self, Encoder, Decoder};
+
+// Matches a literal which can differ in its case or in the length of its
+// whitespace runs. Patterns use it instead of the exact literals when their
+// `ignore_case` or `collapse_whitespace` flags are set.
+#[derive(Clone, Debug, Hash)]
+pub struct LiteralParser {
+    base: ParserBase,
+    literal: String,
+    ignore_case: bool,
+    collapse_whitespace: bool,
+}
+
+impl LiteralParser {
+    pub fn new<S: Into<String>>(literal: S) -> LiteralParser {
+        LiteralParser {
+            base: ParserBase::new(),
+            literal: literal.into(),
+            ignore_case: false,
+            collapse_whitespace: false,
+        }
+    }
+
+    pub fn literal(&self) -> &str {
+        &self.literal
+    }
+
+    pub fn set_ignore_case(&mut self, ignore_case: bool) {
+        self.ignore_case = ignore_case;
+    }
+
+    pub fn set_collapse_whitespace(&mut self, collapse_whitespace: bool) {
+        self.collapse_whitespace = collapse_whitespace;
+    }
+
+    fn chars_eq(&self, expected: char, got: char) -> bool {
+        expected == got || (self.ignore_case && expected.to_lowercase().eq(got.to_lowercase()))
+    }
+
+    // The literal is a string, so it can match only the valid UTF-8 prefix of
+    // the text.
+    fn valid_prefix(value: &[u8]) -> &str {
+        match str::from_utf8(value) {
+            Ok(value) => value,
+            Err(error) => str::from_utf8(&value[..error.valid_up_to()]).expect("The prefix is valid UTF-8"),
+        }
+    }
+
+    fn match_len(&self, value: &str) -> Option<usize> {
+        let mut literal = self.literal.chars().peekable();
+        let mut text = value.char_indices().peekable();
+
+        while let Some(expected) = literal.next() {
+            if self.collapse_whitespace && expected.is_whitespace() {
+                while literal.peek().map_or(false, |c| c.is_whitespace()) {
+                    literal.next();
+                }
+                match text.next() {
+                    Some((_, got)) if got.is_whitespace() => (),
+                    _ => return None,
+                }
+                while text.peek().map_or(false, |&(_, c)| c.is_whitespace()) {
+                    text.next();
+                }
+            } else {
+                match text.next() {
+                    Some((_, got)) if self.chars_eq(expected, got) => (),
+                    _ => return None,
+                }
+            }
+        }
+        Some(text.peek().map_or(value.len(), |&(pos, _)| pos))
+    }
+
+    pub fn decode(decoder: &mut Decoder) -> Result<LiteralParser, cache::Error> {
+        Ok(LiteralParser {
+            base: try!(ParserBase::decode(decoder)),
+            literal: try!(decoder.read_string()),
+            ignore_case: try!(decoder.read_bool()),
+            collapse_whitespace: try!(decoder.read_bool()),
+        })
+    }
+}
+
+impl Parser for LiteralParser {
+    fn parse_bytes<'a, 'b>(&'a self, value: &'b [u8]) -> Option<ParseResult<'a, 'b, [u8]>> {
+        match self.match_len(LiteralParser::valid_prefix(value)) {
+            Some(len) if len > 0 => Some(ParseResult::new(self, &value[..len])),
+            _ => None,
+        }
+    }
+
+    fn name(&self) -> Option<&str> {
+        self.base.name()
+    }
+
+    fn set_name(&mut self, name: Option<String>) {
+        self.base.set_name(name);
+    }
+
+    fn kind(&self) -> &'static str {
+        "LITERAL"
+    }
+
+    fn encode(&self, encoder: &mut Encoder) {
+        self.base.encode(encoder);
+        encoder.write_str(&self.literal);
+        encoder.write_bool(self.ignore_case);
+        encoder.write_bool(self.collapse_whitespace);
+    }
+
+    fn boxed_clone(&self) -> Box<Parser> {
+        Box::new(self.clone())
+    }
+}
+
+impl ObjectSafeHash for LiteralParser {
+    fn hash_os(&self) -> u64 {
+        let mut hasher = SipHasher::new();
+        "parser:literal".hash(&mut hasher);
+        self.hash(&mut hasher);
+        hasher.finish()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use parsers::{LiteralParser, Parser};
+
+    #[test]
+    fn test_given_literal_parser_which_ignores_case_when_the_case_differs_then_it_matches() {
+        let mut parser = LiteralParser::new("Session Closed");
+        parser.set_ignore_case(true);
+        assert_eq!(parser.parse("SESSION closed for").unwrap().value(), "SESSION closed");
+        assert_eq!(parser.parse("SESSION  closed").is_none(), true);
+        assert_eq!(parser.name(), None);
+    }
+
+    #[test]
+    fn test_given_literal_parser_which_collapses_whitespace_when_the_whitespace_runs_differ_then_it_matches
+        () {
+        let mut parser = LiteralParser::new("session closed ");
+        parser.set_collapse_whitespace(true);
+        assert_eq!(parser.parse("session \t closed  for").unwrap().value(), "session \t closed  ");
+        assert_eq!(parser.parse("sessionclosed ").is_none(), true);
+        assert_eq!(parser.parse("Session closed ").is_none(), true);
+    }
+
+    #[test]
+    fn test_given_literal_parser_without_flags_when_the_text_matches_exactly_then_it_matches() {
+        let parser = LiteralParser::new("foo");
+        assert_eq!(parser.parse("foobar").unwrap().value(), "foo");
+        assert_eq!(parser.parse("fo").is_none(), true);
+    }
+}
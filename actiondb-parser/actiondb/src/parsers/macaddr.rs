@@ -0,0 +1,135 @@
+use std::hash::{SipHasher, Hash, Hasher};
+
+use parsers::{ParserBase, Parser, ObjectSafeHash, ParseResult};
+use matcher::cache::{self, Encoder, Decoder};
+
+#[derive(Clone, Debug, Hash)]
+pub struct MacAddrParser {
+    base: ParserBase,
+}
+
+impl MacAddrParser {
+    pub fn with_name<S: Into<String>>(name: S) -> MacAddrParser {
+        MacAddrParser { base: ParserBase::with_name(name.into()) }
+    }
+
+    pub fn new() -> MacAddrParser {
+        MacAddrParser::default()
+    }
+
+    pub fn decode(decoder: &mut Decoder) -> Result<MacAddrParser, cache::Error> {
+        Ok(MacAddrParser {
+            base: try!(ParserBase::decode(decoder)),
+        })
+    }
+}
+
+impl Default for MacAddrParser {
+    fn default() -> Self {
+        MacAddrParser { base: ParserBase::new() }
+    }
+}
+
+fn is_hexdigit_at(bytes: &[u8], pos: usize) -> bool {
+    bytes.get(pos).map_or(false, |c| c.is_ascii_hexdigit())
+}
+
+fn is_followed_by_group(bytes: &[u8], len: usize, separator: u8) -> bool {
+    is_hexdigit_at(bytes, len) ||
+    (bytes.get(len) == Some(&separator) && is_hexdigit_at(bytes, len + 1))
+}
+
+// Checks `len` long groups of hexadecimal digits separated by `separator`.
+fn is_grouped(bytes: &[u8], group_len: usize, group_count: usize, separator: u8) -> bool {
+    let address_len = group_count * (group_len + 1) - 1;
+    if bytes.len() < address_len {
+        return false;
+    }
+
+    bytes[..address_len].iter().enumerate().all(|(pos, c)| {
+        if pos % (group_len + 1) == group_len {
+            *c == separator
+        } else {
+            c.is_ascii_hexdigit()
+        }
+    })
+}
+
+// Accepts the `01:23:45:67:89:ab`, `01-23-45-67-89-ab` and `0123.4567.89ab`
+// forms. The address can't be followed by an other group.
+pub fn match_len(bytes: &[u8]) -> Option<usize> {
+    for &(group_len, group_count, separator) in &[(2, 6, b':'), (2, 6, b'-'), (4, 3, b'.')] {
+        let address_len = group_count * (group_len + 1) - 1;
+        if is_grouped(bytes, group_len, group_count, separator) &&
+           !is_followed_by_group(bytes, address_len, separator) {
+            return Some(address_len);
+        }
+    }
+    None
+}
+
+impl Parser for MacAddrParser {
+    fn parse_bytes<'a, 'b>(&'a self, value: &'b [u8]) -> Option<ParseResult<'a, 'b, [u8]>> {
+        match_len(value).map(|len| ParseResult::new(self, &value[..len]))
+    }
+
+    fn name(&self) -> Option<&str> {
+        self.base.name()
+    }
+
+    fn set_name(&mut self, name: Option<String>) {
+        self.base.set_name(name);
+    }
+
+    fn kind(&self) -> &'static str {
+        "MACADDR"
+    }
+
+    fn encode(&self, encoder: &mut Encoder) {
+        self.base.encode(encoder);
+    }
+
+    fn boxed_clone(&self) -> Box<Parser> {
+        Box::new(self.clone())
+    }
+}
+
+impl ObjectSafeHash for MacAddrParser {
+    fn hash_os(&self) -> u64 {
+        let mut hasher = SipHasher::new();
+        "parser:macaddr".hash(&mut hasher);
+        self.hash(&mut hasher);
+        hasher.finish()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use parsers::{MacAddrParser, Parser};
+
+    #[test]
+    fn test_given_macaddr_parser_when_the_value_starts_with_an_address_then_it_matches() {
+        let parser = MacAddrParser::with_name("mac");
+        let res = parser.parse("00:1a:2B:3c:4d:5e on eth0").unwrap();
+        assert_eq!(res.parser().name(), Some("mac"));
+        assert_eq!(res.value(), "00:1a:2B:3c:4d:5e");
+        assert_eq!(parser.parse("00-1a-2b-3c-4d-5e").unwrap().value(), "00-1a-2b-3c-4d-5e");
+        assert_eq!(parser.parse("001a.2b3c.4d5e.").unwrap().value(), "001a.2b3c.4d5e");
+    }
+
+    #[test]
+    fn test_given_macaddr_parser_when_the_separators_are_mixed_then_it_does_not_match() {
+        let parser = MacAddrParser::new();
+        assert_eq!(parser.parse("00:1a-2b:3c:4d:5e").is_none(), true);
+        assert_eq!(parser.parse("00:1a:2b:3c:4d").is_none(), true);
+        assert_eq!(parser.parse("00:1a:2b:3c:4d:5g").is_none(), true);
+    }
+
+    #[test]
+    fn test_given_macaddr_parser_when_the_address_is_followed_by_an_other_group_then_it_does_not_match
+        () {
+        let parser = MacAddrParser::new();
+        assert_eq!(parser.parse("00:1a:2b:3c:4d:5e:6f:70").is_none(), true);
+        assert_eq!(parser.parse("00:1a:2b:3c:4d:5ef").is_none(), true);
+    }
+}
@@ -3,41 +3,137 @@ mod base;
 mod int;
 pub mod has_length_constraint;
 mod greedy;
+mod ipv4;
+mod ipv6;
+mod ipaddr;
+mod qstring;
+mod estring;
+mod float;
+mod number;
+mod hex;
+pub mod timestamp;
+mod macaddr;
+mod hostname;
+mod email;
+mod kvpairs;
+mod json;
+mod regex;
+mod literal;
 
-use std::fmt::Debug;
+use std::borrow::Cow;
+use std::fmt::{self, Debug};
+use std::str;
+use matcher::cache::{self, Encoder, Decoder};
 pub use self::set::SetParser;
 pub use self::base::ParserBase;
 pub use self::int::IntParser;
 pub use self::has_length_constraint::HasLengthConstraint;
 pub use self::greedy::GreedyParser;
+pub use self::ipv4::Ipv4Parser;
+pub use self::ipv6::Ipv6Parser;
+pub use self::ipaddr::IpAddrParser;
+pub use self::qstring::QStringParser;
+pub use self::estring::EStringParser;
+pub use self::float::FloatParser;
+pub use self::number::NumberParser;
+pub use self::hex::HexParser;
+pub use self::timestamp::TimestampParser;
+pub use self::macaddr::MacAddrParser;
+pub use self::hostname::HostnameParser;
+pub use self::email::EmailParser;
+pub use self::kvpairs::KeyValuePairsParser;
+pub use self::json::JsonParser;
+pub use self::regex::RegexParser;
+pub use self::literal::LiteralParser;
 
 pub trait ObjectSafeHash {
     fn hash_os(&self) -> u64;
 }
 
 pub trait Parser: Debug + ObjectSafeHash {
-    fn parse<'a, 'b>(&'a self, value: &'b str) -> Option<ParseResult<'a, 'b>>;
+    // The parsers work on bytes, so the messages don't have to be valid UTF-8.
+    fn parse_bytes<'a, 'b>(&'a self, value: &'b [u8]) -> Option<ParseResult<'a, 'b, [u8]>>;
+    fn parse<'a, 'b>(&'a self, value: &'b str) -> Option<ParseResult<'a, 'b>> {
+        self.parse_bytes(value.as_bytes()).and_then(|result| result.into_str())
+    }
     fn name(&self) -> Option<&str>;
     fn set_name(&mut self, Option<String>);
+    // The keyword of the parser in the pattern language, e.g. INT
+    fn kind(&self) -> &'static str;
+    // Writes the parameters of the parser into a matcher cache
+    fn encode(&self, encoder: &mut Encoder);
+    // The parameters of the parser in the pattern language. The positional
+    // parameters of SET and REGEX are named `set` and `expression`.
+    fn parameters(&self) -> Vec<OptionalParameter<'static>> {
+        Vec::new()
+    }
     fn boxed_clone(&self) -> Box<Parser>;
+    fn value_type(&self) -> ValueType {
+        ValueType::String
+    }
 }
 
-#[derive(Debug)]
+// The type of the extracted values, so they don't have to be handled as
+// strings by the users of the results.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum ValueType {
+    String,
+    Int,
+    Float,
+    IpAddr,
+    Timestamp,
+    Boolean,
+}
+
+impl ValueType {
+    pub fn as_str(&self) -> &'static str {
+        match *self {
+            ValueType::String => "string",
+            ValueType::Int => "int",
+            ValueType::Float => "float",
+            ValueType::IpAddr => "ipaddr",
+            ValueType::Timestamp => "timestamp",
+            ValueType::Boolean => "boolean",
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
 pub enum OptionalParameter<'a> {
     Int(&'a str, usize),
+    Str(&'a str, String),
 }
 
-#[derive(Debug)]
-pub struct ParseResult<'a, 'b> {
+// The value and the pairs are either string slices or byte slices of the
+// parsed text. The values of the pairs are owned if they had to be unescaped,
+// every pair has its own type.
+pub struct ParseResult<'a, 'b, V: ?Sized + ToOwned + 'b = str> {
     parser: &'a Parser,
-    value: &'b str,
+    value: &'b V,
+    consumed_length: usize,
+    value_type: ValueType,
+    pairs: Vec<(String, Cow<'b, V>, ValueType)>,
 }
 
-impl<'a, 'b> ParseResult<'a, 'b> {
-    pub fn new(parser: &'a Parser, value: &'b str) -> ParseResult<'a, 'b> {
+impl<'a, 'b, V: ?Sized + ToOwned + AsRef<[u8]> + 'b> ParseResult<'a, 'b, V> {
+    pub fn new(parser: &'a Parser, value: &'b V) -> ParseResult<'a, 'b, V> {
+        let consumed_length = value.as_ref().len();
+        ParseResult::with_consumed_length(parser, value, consumed_length)
+    }
+
+    // Some parsers consume more characters than the value they extract (e.g.
+    // delimiters or terminators), so the matchers have to advance with this
+    // length instead of the value's length.
+    pub fn with_consumed_length(parser: &'a Parser,
+                                value: &'b V,
+                                consumed_length: usize)
+                                -> ParseResult<'a, 'b, V> {
         ParseResult {
             parser: parser,
             value: value,
+            consumed_length: consumed_length,
+            value_type: parser.value_type(),
+            pairs: Vec::new(),
         }
     }
 
@@ -45,9 +141,127 @@ impl<'a, 'b> ParseResult<'a, 'b> {
         self.parser
     }
 
-    pub fn value(&self) -> &'b str {
+    pub fn value(&self) -> &'b V {
         self.value
     }
+
+    pub fn consumed_length(&self) -> usize {
+        self.consumed_length
+    }
+
+    pub fn value_type(&self) -> ValueType {
+        self.value_type
+    }
+
+    pub fn set_value_type(&mut self, value_type: ValueType) {
+        self.value_type = value_type;
+    }
+
+    // Additional key-value pairs extracted by the parser. They are stored
+    // under their own keys, independently of the parser's name.
+    pub fn pairs(&self) -> &[(String, Cow<'b, V>, ValueType)] {
+        &self.pairs
+    }
+
+    pub fn set_pairs(&mut self, pairs: Vec<(String, Cow<'b, V>, ValueType)>) {
+        self.pairs = pairs;
+    }
+
+    pub fn into_pairs(self) -> Vec<(String, Cow<'b, V>, ValueType)> {
+        self.pairs
+    }
+}
+
+impl<'a, 'b> ParseResult<'a, 'b, [u8]> {
+    // Returns None if the value or a pair isn't valid UTF-8.
+    pub fn into_str(self) -> Option<ParseResult<'a, 'b>> {
+        let value = match str::from_utf8(self.value) {
+            Ok(value) => value,
+            Err(_) => return None,
+        };
+        let mut pairs = Vec::with_capacity(self.pairs.len());
+        for (key, pair_value, value_type) in self.pairs {
+            match into_str(pair_value) {
+                Some(pair_value) => pairs.push((key, pair_value, value_type)),
+                None => return None,
+            }
+        }
+        Some(ParseResult {
+            parser: self.parser,
+            value: value,
+            consumed_length: self.consumed_length,
+            value_type: self.value_type,
+            pairs: pairs,
+        })
+    }
+}
+
+pub fn into_str(value: Cow<[u8]>) -> Option<Cow<str>> {
+    match value {
+        Cow::Borrowed(value) => str::from_utf8(value).ok().map(Cow::Borrowed),
+        Cow::Owned(value) => String::from_utf8(value).ok().map(Cow::Owned),
+    }
+}
+
+// The derived impl would miss the `V::Owned: Debug` bound of the pairs.
+impl<'a, 'b, V> Debug for ParseResult<'a, 'b, V>
+    where V: ?Sized + ToOwned + Debug + 'b,
+          V::Owned: Debug
+{
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("ParseResult")
+            .field("parser", &self.parser)
+            .field("value", &self.value)
+            .field("consumed_length", &self.consumed_length)
+            .field("value_type", &self.value_type)
+            .field("pairs", &self.pairs)
+            .finish()
+    }
+}
+
+impl<'a, 'b, V: ?Sized + ToOwned + 'b> Clone for ParseResult<'a, 'b, V> {
+    fn clone(&self) -> ParseResult<'a, 'b, V> {
+        ParseResult {
+            parser: self.parser,
+            value: self.value,
+            consumed_length: self.consumed_length,
+            value_type: self.value_type,
+            pairs: self.pairs.clone(),
+        }
+    }
+}
+
+// The kind of the parser is written before its parameters, so the right
+// parser can be created when it's decoded.
+pub fn encode(parser: &Parser, encoder: &mut Encoder) {
+    encoder.write_str(parser.kind());
+    parser.encode(encoder);
+}
+
+pub fn decode(decoder: &mut Decoder) -> Result<Box<Parser>, cache::Error> {
+    let parser: Box<Parser> = match try!(decoder.read_str()) {
+        "EMAIL" => Box::new(try!(EmailParser::decode(decoder))),
+        "ESTRING" => Box::new(try!(EStringParser::decode(decoder))),
+        "FLOAT" => Box::new(try!(FloatParser::decode(decoder))),
+        "GREEDY" => Box::new(try!(GreedyParser::decode(decoder))),
+        "HEX" => Box::new(try!(HexParser::decode(decoder))),
+        "HOSTNAME" => Box::new(try!(HostnameParser::decode(decoder))),
+        "INT" => Box::new(try!(IntParser::decode(decoder))),
+        "IPADDR" => Box::new(try!(IpAddrParser::decode(decoder))),
+        "IPV4" => Box::new(try!(Ipv4Parser::decode(decoder))),
+        "IPV6" => Box::new(try!(Ipv6Parser::decode(decoder))),
+        "JSON" => Box::new(try!(JsonParser::decode(decoder))),
+        "KVPAIRS" => Box::new(try!(KeyValuePairsParser::decode(decoder))),
+        "LITERAL" => Box::new(try!(LiteralParser::decode(decoder))),
+        "MACADDR" => Box::new(try!(MacAddrParser::decode(decoder))),
+        "NUMBER" => Box::new(try!(NumberParser::decode(decoder))),
+        "QSTRING" => Box::new(try!(QStringParser::decode(decoder))),
+        "REGEX" => Box::new(try!(RegexParser::decode(decoder))),
+        "SET" => Box::new(try!(SetParser::decode(decoder))),
+        "TIMESTAMP" => Box::new(try!(TimestampParser::decode(decoder))),
+        kind => return Err(cache::Error::UnknownParser(kind.to_owned())),
+    };
+    Ok(parser)
 }
 
 pub trait ParserFactory: {
@@ -59,4 +273,19 @@ pub trait ParserFactory: {
                    opt_params: Option<Vec<OptionalParameter>>)
                    -> Box<Parser>;
     fn new_greedy(name: Option<&str>, end_string: Option<&str>) -> Box<Parser>;
+    fn new_ipv4(name: Option<&str>) -> Box<Parser>;
+    fn new_ipv6(name: Option<&str>) -> Box<Parser>;
+    fn new_ipaddr(name: Option<&str>) -> Box<Parser>;
+    fn new_macaddr(name: Option<&str>) -> Box<Parser>;
+    fn new_hostname(name: Option<&str>) -> Box<Parser>;
+    fn new_email(name: Option<&str>) -> Box<Parser>;
+    fn new_float(name: Option<&str>, opt_params: Option<Vec<OptionalParameter>>) -> Box<Parser>;
+    fn new_number(name: Option<&str>, opt_params: Option<Vec<OptionalParameter>>) -> Box<Parser>;
+    fn new_hex(name: Option<&str>, opt_params: Option<Vec<OptionalParameter>>) -> Box<Parser>;
+    fn new_timestamp(name: Option<&str>, opt_params: Option<Vec<OptionalParameter>>) -> Box<Parser>;
+    fn new_kvpairs(name: Option<&str>, opt_params: Option<Vec<OptionalParameter>>) -> Box<Parser>;
+    fn new_json(name: Option<&str>, opt_params: Option<Vec<OptionalParameter>>) -> Box<Parser>;
+    fn new_qstring(name: Option<&str>, opt_params: Option<Vec<OptionalParameter>>) -> Box<Parser>;
+    fn new_estring(name: Option<&str>, end_string: &str) -> Box<Parser>;
+    fn new_regex(name: Option<&str>, expression: &str) -> Result<Box<Parser>, ::regex::Error>;
 }
@@ -0,0 +1,170 @@
+use std::hash::{SipHasher, Hash, Hasher};
+
+use parsers::{ParserBase, Parser, ObjectSafeHash, ParseResult, HasLengthConstraint, ValueType, OptionalParameter};
+use super::{float, hex};
+use matcher::cache::{self, Encoder, Decoder};
+
+#[derive(Clone, Debug, Hash)]
+pub struct NumberParser {
+    base: ParserBase,
+    min_length: Option<usize>,
+    max_length: Option<usize>,
+}
+
+impl NumberParser {
+    pub fn with_name<S: Into<String>>(name: S) -> NumberParser {
+        NumberParser {
+            base: ParserBase::with_name(name.into()),
+            min_length: None,
+            max_length: None,
+        }
+    }
+
+    pub fn new() -> NumberParser {
+        NumberParser::default()
+    }
+
+    // Signed decimal, floating point or `0x` prefixed hexadecimal number.
+    fn match_len(value: &[u8]) -> Option<usize> {
+        let sign_len = float::sign_len(value);
+        let hex_len = hex::prefixed_match_len(&value[sign_len..]).map(|len| sign_len + len);
+
+        match (hex_len, float::match_len(value)) {
+            (Some(hex_len), Some(float_len)) => Some(::std::cmp::max(hex_len, float_len)),
+            (hex_len, float_len) => hex_len.or(float_len),
+        }
+    }
+
+    // The hexadecimal numbers are kept as strings.
+    fn number_type(number: &[u8]) -> ValueType {
+        if number.iter().any(|c| b"xX".contains(c)) {
+            ValueType::String
+        } else if number.iter().any(|c| b".eE".contains(c)) {
+            ValueType::Float
+        } else {
+            ValueType::Int
+        }
+    }
+
+    pub fn decode(decoder: &mut Decoder) -> Result<NumberParser, cache::Error> {
+        Ok(NumberParser {
+            base: try!(ParserBase::decode(decoder)),
+            min_length: try!(decoder.read_opt_usize()),
+            max_length: try!(decoder.read_opt_usize()),
+        })
+    }
+}
+
+impl Default for NumberParser {
+    fn default() -> Self {
+        NumberParser {
+            base: ParserBase::new(),
+            min_length: None,
+            max_length: None,
+        }
+    }
+}
+
+impl Parser for NumberParser {
+    fn parse_bytes<'a, 'b>(&'a self, value: &'b [u8]) -> Option<ParseResult<'a, 'b, [u8]>> {
+        match NumberParser::match_len(value) {
+            Some(len) if self.is_match_length_ok(len) => {
+                let mut result = ParseResult::new(self, &value[..len]);
+                result.set_value_type(NumberParser::number_type(result.value()));
+                Some(result)
+            }
+            _ => None,
+        }
+    }
+
+    fn name(&self) -> Option<&str> {
+        self.base.name()
+    }
+
+    fn set_name(&mut self, name: Option<String>) {
+        self.base.set_name(name);
+    }
+
+    fn kind(&self) -> &'static str {
+        "NUMBER"
+    }
+
+    fn encode(&self, encoder: &mut Encoder) {
+        self.base.encode(encoder);
+        encoder.write_opt_usize(self.min_length);
+        encoder.write_opt_usize(self.max_length);
+    }
+
+    fn parameters(&self) -> Vec<OptionalParameter<'static>> {
+        self.length_parameters()
+    }
+
+    fn boxed_clone(&self) -> Box<Parser> {
+        Box::new(self.clone())
+    }
+}
+
+impl HasLengthConstraint for NumberParser {
+    fn min_length(&self) -> Option<usize> {
+        self.min_length
+    }
+    fn set_min_length(&mut self, length: Option<usize>) {
+        self.min_length = length;
+    }
+    fn max_length(&self) -> Option<usize> {
+        self.max_length
+    }
+    fn set_max_length(&mut self, length: Option<usize>) {
+        self.max_length = length;
+    }
+}
+
+impl ObjectSafeHash for NumberParser {
+    fn hash_os(&self) -> u64 {
+        let mut hasher = SipHasher::new();
+        "parser:number".hash(&mut hasher);
+        self.hash(&mut hasher);
+        hasher.finish()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use parsers::{NumberParser, FloatParser, HexParser, Parser, ObjectSafeHash, ValueType};
+
+    #[test]
+    fn test_given_number_parser_when_the_value_is_a_number_then_it_matches() {
+        let parser = NumberParser::with_name("number");
+        assert_eq!(parser.parse("-12 bytes").unwrap().value(), "-12");
+        assert_eq!(parser.parse("3.14").unwrap().value(), "3.14");
+        assert_eq!(parser.parse("1e-3").unwrap().value(), "1e-3");
+        assert_eq!(parser.parse("0x7f,").unwrap().value(), "0x7f");
+        assert_eq!(parser.parse("-0x10").unwrap().value(), "-0x10");
+    }
+
+    #[test]
+    fn test_given_number_parser_when_the_value_is_garbage_then_it_doesnt_match() {
+        let parser = NumberParser::new();
+        assert_eq!(parser.parse("--.").is_none(), true);
+        assert_eq!(parser.parse("abc").is_none(), true);
+    }
+
+    #[test]
+    fn test_given_number_parsers_with_the_same_name_when_they_are_hashed_then_the_hashes_are_different
+        () {
+        let number = NumberParser::with_name("n");
+        let float = FloatParser::with_name("n");
+        let hex = HexParser::with_name("n");
+        assert_eq!(number.hash_os() == float.hash_os(), false);
+        assert_eq!(number.hash_os() == hex.hash_os(), false);
+        assert_eq!(float.hash_os() == hex.hash_os(), false);
+    }
+
+    #[test]
+    fn test_given_number_parser_when_a_value_is_parsed_then_its_type_depends_on_the_number() {
+        let parser = NumberParser::new();
+        assert_eq!(parser.parse("-42").unwrap().value_type(), ValueType::Int);
+        assert_eq!(parser.parse("1.5e3").unwrap().value_type(), ValueType::Float);
+        assert_eq!(parser.parse("0x7f").unwrap().value_type(), ValueType::String);
+    }
+}
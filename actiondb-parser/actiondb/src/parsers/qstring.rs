@@ -0,0 +1,198 @@
+use std::hash::{SipHasher, Hash, Hasher};
+
+use parsers::{ParserBase, Parser, ObjectSafeHash, ParseResult, OptionalParameter};
+use matcher::cache::{self, Encoder, Decoder};
+
+const DEFAULT_QUOTES: &'static str = "\"";
+
+#[derive(Clone, Debug, Hash)]
+pub struct QStringParser {
+    base: ParserBase,
+    quotes: String,
+    pairs: String,
+}
+
+impl QStringParser {
+    pub fn with_name<S: Into<String>>(name: S) -> QStringParser {
+        QStringParser {
+            base: ParserBase::with_name(name.into()),
+            quotes: DEFAULT_QUOTES.to_owned(),
+            pairs: String::new(),
+        }
+    }
+
+    pub fn new() -> QStringParser {
+        QStringParser::default()
+    }
+
+    // Every quote character both opens and closes the value, e.g. `"'`.
+    pub fn set_quotes(&mut self, quotes: &str) {
+        self.quotes = quotes.to_owned();
+    }
+
+    // The pairs of an opening and a closing character, e.g. `<>[]`.
+    pub fn set_pairs(&mut self, pairs: &str) {
+        self.pairs = pairs.to_owned();
+    }
+
+    // Returns the position of the closing quote. Quotes escaped with a
+    // backslash don't close the string, they remain escaped in the value, as
+    // the value is a slice of the message.
+    fn find_closing_quote(value: &[u8], quote: &[u8]) -> Option<usize> {
+        let mut is_escaped = false;
+
+        for (pos, c) in value.iter().enumerate() {
+            if is_escaped {
+                is_escaped = false;
+            } else if *c == b'\\' {
+                is_escaped = true;
+            } else if value[pos..].starts_with(quote) {
+                return Some(pos);
+            }
+        }
+        None
+    }
+
+    // The opening and closing quotes of the value, as the quotes can be
+    // multibyte characters.
+    fn quote_pair(&self, value: &[u8]) -> Option<(&str, &str)> {
+        for (pos, c) in self.quotes.char_indices() {
+            let quote = &self.quotes[pos..pos + c.len_utf8()];
+            if value.starts_with(quote.as_bytes()) {
+                return Some((quote, quote));
+            }
+        }
+        let mut pairs = self.pairs.char_indices().map(|(pos, c)| &self.pairs[pos..pos + c.len_utf8()]);
+        while let (Some(open), Some(close)) = (pairs.next(), pairs.next()) {
+            if value.starts_with(open.as_bytes()) {
+                return Some((open, close));
+            }
+        }
+        None
+    }
+
+    pub fn decode(decoder: &mut Decoder) -> Result<QStringParser, cache::Error> {
+        Ok(QStringParser {
+            base: try!(ParserBase::decode(decoder)),
+            quotes: try!(decoder.read_string()),
+            pairs: try!(decoder.read_string()),
+        })
+    }
+}
+
+impl Default for QStringParser {
+    fn default() -> Self {
+        QStringParser {
+            base: ParserBase::new(),
+            quotes: DEFAULT_QUOTES.to_owned(),
+            pairs: String::new(),
+        }
+    }
+}
+
+impl Parser for QStringParser {
+    fn parse_bytes<'a, 'b>(&'a self, value: &'b [u8]) -> Option<ParseResult<'a, 'b, [u8]>> {
+        let (open, close) = match self.quote_pair(value) {
+            Some((open, close)) => (open.as_bytes(), close.as_bytes()),
+            None => return None,
+        };
+        let content = &value[open.len()..];
+
+        QStringParser::find_closing_quote(content, close).map(|pos| {
+            let consumed_length = open.len() + pos + close.len();
+            ParseResult::with_consumed_length(self, &content[..pos], consumed_length)
+        })
+    }
+
+    fn name(&self) -> Option<&str> {
+        self.base.name()
+    }
+
+    fn set_name(&mut self, name: Option<String>) {
+        self.base.set_name(name);
+    }
+
+    fn kind(&self) -> &'static str {
+        "QSTRING"
+    }
+
+    fn encode(&self, encoder: &mut Encoder) {
+        self.base.encode(encoder);
+        encoder.write_str(&self.quotes);
+        encoder.write_str(&self.pairs);
+    }
+
+    fn parameters(&self) -> Vec<OptionalParameter<'static>> {
+        let mut parameters = Vec::new();
+        if !self.quotes.is_empty() && (self.quotes != DEFAULT_QUOTES || !self.pairs.is_empty()) {
+            parameters.push(OptionalParameter::Str("quotes", self.quotes.clone()));
+        }
+        if !self.pairs.is_empty() {
+            parameters.push(OptionalParameter::Str("pairs", self.pairs.clone()));
+        }
+        parameters
+    }
+
+    fn boxed_clone(&self) -> Box<Parser> {
+        Box::new(self.clone())
+    }
+}
+
+impl ObjectSafeHash for QStringParser {
+    fn hash_os(&self) -> u64 {
+        let mut hasher = SipHasher::new();
+        "parser:qstring".hash(&mut hasher);
+        self.hash(&mut hasher);
+        hasher.finish()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use parsers::{Parser, QStringParser};
+
+    #[test]
+    fn test_given_qstring_parser_when_the_value_is_quoted_then_the_quotes_are_stripped() {
+        let parser = QStringParser::with_name("name");
+        let res = parser.parse("\"foo bar\" baz").unwrap();
+        assert_eq!(res.parser().name(), Some("name"));
+        assert_eq!(res.value(), "foo bar");
+        assert_eq!(res.consumed_length(), 9);
+    }
+
+    #[test]
+    fn test_given_qstring_parser_when_the_value_is_not_quoted_then_it_doesnt_match() {
+        let parser = QStringParser::with_name("name");
+        assert_eq!(parser.parse("foo bar").is_none(), true);
+        assert_eq!(parser.parse("\"foo bar").is_none(), true);
+    }
+
+    #[test]
+    fn test_given_qstring_parser_when_the_value_contains_escaped_quotes_then_they_dont_close_the_string
+        () {
+        let parser = QStringParser::with_name("name");
+        let res = parser.parse(r#""foo \"bar\"" baz"#).unwrap();
+        assert_eq!(res.value(), r#"foo \"bar\""#);
+    }
+
+    #[test]
+    fn test_given_qstring_parser_with_more_quotes_when_a_value_is_parsed_then_it_is_closed_by_its_own_quote
+        () {
+        let mut parser = QStringParser::with_name("name");
+        parser.set_quotes("\"'");
+        assert_eq!(parser.parse("'foo \"bar' baz").unwrap().value(), "foo \"bar");
+        assert_eq!(parser.parse("\"foo 'bar\" baz").unwrap().value(), "foo 'bar");
+    }
+
+    #[test]
+    fn test_given_qstring_parser_with_different_opening_and_closing_quotes_when_a_value_is_parsed_then_they_are_stripped
+        () {
+        let mut parser = QStringParser::with_name("name");
+        parser.set_quotes("");
+        parser.set_pairs("<>");
+        let res = parser.parse("<foo <bar> baz").unwrap();
+        assert_eq!(res.value(), "foo <bar");
+        assert_eq!(res.consumed_length(), 10);
+        assert!(parser.parse(">foo< baz").is_none());
+    }
+}
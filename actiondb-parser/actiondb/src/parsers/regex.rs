@@ -0,0 +1,144 @@
+use std::hash::{SipHasher, Hash, Hasher};
+
+use regex::{self, bytes::Regex};
+
+use parsers::{ParserBase, Parser, ObjectSafeHash, ParseResult, OptionalParameter};
+use matcher::cache::{self, Encoder, Decoder};
+
+#[derive(Clone, Debug)]
+pub struct RegexParser {
+    base: ParserBase,
+    expression: String,
+    regex: Regex,
+}
+
+impl RegexParser {
+    // The expression is anchored to the current position of the text.
+    pub fn new(expression: &str) -> Result<RegexParser, regex::Error> {
+        let regex = try!(Regex::new(&format!("^(?:{})", expression)));
+        Ok(RegexParser {
+            base: ParserBase::new(),
+            expression: expression.to_owned(),
+            regex: regex,
+        })
+    }
+
+    pub fn from_str(name: &str, expression: &str) -> Result<RegexParser, regex::Error> {
+        let mut parser = try!(RegexParser::new(expression));
+        parser.set_name(Some(name.to_owned()));
+        Ok(parser)
+    }
+
+    pub fn expression(&self) -> &str {
+        &self.expression
+    }
+
+    pub fn decode(decoder: &mut Decoder) -> Result<RegexParser, cache::Error> {
+        let base = try!(ParserBase::decode(decoder));
+        let expression = try!(decoder.read_str());
+        let mut parser = try!(RegexParser::new(expression)
+                                  .map_err(|error| cache::Error::InvalidParser(error.to_string())));
+        parser.base = base;
+        Ok(parser)
+    }
+}
+
+// The compiled regex isn't hashable, but it is determined by the expression.
+impl Hash for RegexParser {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.base.hash(state);
+        self.expression.hash(state);
+    }
+}
+
+impl Parser for RegexParser {
+    fn parse_bytes<'a, 'b>(&'a self, value: &'b [u8]) -> Option<ParseResult<'a, 'b, [u8]>> {
+        match self.regex.find(value) {
+            Some(m) if m.end() > 0 => Some(ParseResult::new(self, &value[..m.end()])),
+            _ => None,
+        }
+    }
+
+    fn name(&self) -> Option<&str> {
+        self.base.name()
+    }
+
+    fn set_name(&mut self, name: Option<String>) {
+        self.base.set_name(name);
+    }
+
+    fn kind(&self) -> &'static str {
+        "REGEX"
+    }
+
+    fn encode(&self, encoder: &mut Encoder) {
+        self.base.encode(encoder);
+        encoder.write_str(&self.expression);
+    }
+
+    fn parameters(&self) -> Vec<OptionalParameter<'static>> {
+        vec![OptionalParameter::Str("expression", self.expression.clone())]
+    }
+
+    fn boxed_clone(&self) -> Box<Parser> {
+        Box::new(self.clone())
+    }
+}
+
+impl ObjectSafeHash for RegexParser {
+    fn hash_os(&self) -> u64 {
+        let mut hasher = SipHasher::new();
+        "parser:regex".hash(&mut hasher);
+        self.hash(&mut hasher);
+        hasher.finish()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use parsers::{Parser, RegexParser, ObjectSafeHash};
+
+    #[test]
+    fn test_given_regex_parser_when_the_expression_matches_at_the_beginning_then_the_match_is_returned
+        () {
+        let parser = RegexParser::from_str("ticket", "[A-Z]{3}-[0-9]{4}").unwrap();
+        let res = parser.parse("ABC-1234 closed").unwrap();
+        assert_eq!(res.parser().name(), Some("ticket"));
+        assert_eq!(res.value(), "ABC-1234");
+    }
+
+    #[test]
+    fn test_given_regex_parser_when_the_expression_matches_only_later_in_the_text_then_it_doesnt_match
+        () {
+        let parser = RegexParser::new("[A-Z]{3}-[0-9]{4}").unwrap();
+        assert_eq!(parser.parse("ticket ABC-1234").is_none(), true);
+    }
+
+    #[test]
+    fn test_given_regex_parser_when_the_alternatives_are_not_grouped_then_all_of_them_are_anchored
+        () {
+        let parser = RegexParser::new("foo|bar").unwrap();
+        assert_eq!(parser.parse("bar").unwrap().value(), "bar");
+        assert_eq!(parser.parse("xbar").is_none(), true);
+    }
+
+    #[test]
+    fn test_given_regex_parser_when_the_match_is_empty_then_it_doesnt_match() {
+        let parser = RegexParser::new("[0-9]*").unwrap();
+        assert_eq!(parser.parse("abc").is_none(), true);
+    }
+
+    #[test]
+    fn test_given_invalid_expression_when_a_regex_parser_is_created_then_we_get_an_error() {
+        assert_eq!(RegexParser::new("[A-Z").is_err(), true);
+    }
+
+    #[test]
+    fn test_given_regex_parsers_when_they_have_the_same_expression_then_their_hashes_are_equal() {
+        let p1 = RegexParser::from_str("ticket", "[A-Z]+").unwrap();
+        let p2 = RegexParser::from_str("ticket", "[A-Z]+").unwrap();
+        let p3 = RegexParser::from_str("ticket", "[A-Z]*").unwrap();
+        assert_eq!(p1.hash_os() == p2.hash_os(), true);
+        assert_eq!(p1.hash_os() == p3.hash_os(), false);
+    }
+}
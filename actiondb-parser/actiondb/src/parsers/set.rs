@@ -2,12 +2,15 @@ use std::collections::BTreeSet;
 use std::iter::FromIterator;
 use std::hash::{SipHasher, Hash, Hasher};
 
-use parsers::{Parser, ObjectSafeHash, ParseResult, ParserBase, HasLengthConstraint};
+use parsers::{Parser, ObjectSafeHash, ParseResult, ParserBase, HasLengthConstraint, OptionalParameter};
+use matcher::cache::{self, Encoder, Decoder};
 
 #[derive(Clone, Debug, Hash)]
 pub struct SetParser {
     base: ParserBase,
     character_set: BTreeSet<u8>,
+    // the characters of the set, as the byte set splits the multibyte ones
+    characters: String,
     min_length: Option<usize>,
     max_length: Option<usize>,
 }
@@ -17,6 +20,7 @@ impl SetParser {
         SetParser {
             base: ParserBase::with_name(name),
             character_set: SetParser::create_set_from_str(set),
+            characters: SetParser::characters(set),
             min_length: None,
             max_length: None,
         }
@@ -26,6 +30,7 @@ impl SetParser {
         SetParser {
             base: ParserBase::new(),
             character_set: SetParser::create_set_from_str(set),
+            characters: SetParser::characters(set),
             min_length: None,
             max_length: None,
         }
@@ -37,6 +42,7 @@ impl SetParser {
 
     pub fn set_character_set(&mut self, set: &str) {
         self.character_set = SetParser::create_set_from_str(set);
+        self.characters = SetParser::characters(set);
     }
 
     fn create_set_from_str(set: &str) -> BTreeSet<u8> {
@@ -44,11 +50,15 @@ impl SetParser {
         BTreeSet::from_iter(vset)
     }
 
-    fn calculate_match_length(&self, value: &str) -> usize {
+    fn characters(set: &str) -> String {
+        set.chars().collect::<BTreeSet<char>>().into_iter().collect()
+    }
+
+    fn calculate_match_length(&self, value: &[u8]) -> usize {
         let mut match_len = 0;
 
-        for c in value.bytes() {
-            if self.character_set.contains(&c) {
+        for c in value {
+            if self.character_set.contains(c) {
                 match_len += 1;
             } else {
                 break;
@@ -57,6 +67,18 @@ impl SetParser {
 
         match_len
     }
+
+    pub fn decode(decoder: &mut Decoder) -> Result<SetParser, cache::Error> {
+        let base = try!(ParserBase::decode(decoder));
+        let set = try!(decoder.read_string());
+        Ok(SetParser {
+            base: base,
+            character_set: SetParser::create_set_from_str(&set),
+            characters: set,
+            min_length: try!(decoder.read_opt_usize()),
+            max_length: try!(decoder.read_opt_usize()),
+        })
+    }
 }
 
 impl HasLengthConstraint for SetParser {
@@ -75,7 +97,7 @@ impl HasLengthConstraint for SetParser {
 }
 
 impl Parser for SetParser {
-    fn parse<'a, 'b>(&'a self, value: &'b str) -> Option<ParseResult<'a, 'b>> {
+    fn parse_bytes<'a, 'b>(&'a self, value: &'b [u8]) -> Option<ParseResult<'a, 'b, [u8]>> {
         let match_len = self.calculate_match_length(value);
 
         if self.is_match_length_ok(match_len) {
@@ -93,6 +115,23 @@ impl Parser for SetParser {
         self.base.set_name(name);
     }
 
+    fn kind(&self) -> &'static str {
+        "SET"
+    }
+
+    fn encode(&self, encoder: &mut Encoder) {
+        self.base.encode(encoder);
+        encoder.write_str(&self.characters);
+        encoder.write_opt_usize(self.min_length);
+        encoder.write_opt_usize(self.max_length);
+    }
+
+    fn parameters(&self) -> Vec<OptionalParameter<'static>> {
+        let mut parameters = vec![OptionalParameter::Str("set", self.characters.clone())];
+        parameters.extend(self.length_parameters());
+        parameters
+    }
+
     fn boxed_clone(&self) -> Box<Parser> {
         Box::new(self.clone())
     }
@@ -109,7 +148,7 @@ impl ObjectSafeHash for SetParser {
 
 #[cfg(test)]
 mod test {
-    use parsers::{Parser, SetParser, HasLengthConstraint};
+    use parsers::{Parser, SetParser, HasLengthConstraint, OptionalParameter};
 
     #[test]
     fn test_given_empty_string_when_parsed_it_wont_match() {
@@ -166,4 +205,11 @@ mod test {
         let p2 = SetParser::from_str("test", "01234");
         assert_eq!(p1.hash_os() == p2.hash_os(), false);
     }
+
+    #[test]
+    fn test_given_set_parser_with_multibyte_characters_when_its_parameters_are_queried_then_the_characters_are_kept
+        () {
+        let p = SetParser::from_str("test", "éáa");
+        assert_eq!(p.parameters(), vec![OptionalParameter::Str("set", "aáé".to_owned())]);
+    }
 }
@@ -0,0 +1,387 @@
+use std::hash::{SipHasher, Hash, Hasher};
+
+use parsers::{ParserBase, Parser, ObjectSafeHash, ParseResult, ValueType, OptionalParameter};
+use matcher::cache::{self, Encoder, Decoder};
+
+const ISO8601_FORMATS: &'static [&'static str] = &["%Y-%m-%dT%H:%M:%S",
+                                                    "%Y-%m-%dT%H:%M:%S%z",
+                                                    "%Y-%m-%dT%H:%M:%S.%f",
+                                                    "%Y-%m-%dT%H:%M:%S.%f%z"];
+const RFC3164_FORMATS: &'static [&'static str] = &["%b %e %H:%M:%S"];
+const EPOCH_FORMATS: &'static [&'static str] = &["%s", "%s.%f"];
+
+const MONTHS: &'static [&'static str] = &["Jan", "Feb", "Mar", "Apr", "May", "Jun", "Jul", "Aug",
+                                          "Sep", "Oct", "Nov", "Dec"];
+const WEEKDAYS: &'static [&'static str] = &["Mon", "Tue", "Wed", "Thu", "Fri", "Sat", "Sun"];
+
+pub fn preset_formats(preset: &str) -> Option<&'static [&'static str]> {
+    match preset {
+        "ISO8601" => Some(ISO8601_FORMATS),
+        "RFC3164" => Some(RFC3164_FORMATS),
+        "epoch" => Some(EPOCH_FORMATS),
+        _ => None,
+    }
+}
+
+#[derive(Clone, Debug, Hash)]
+pub struct TimestampParser {
+    base: ParserBase,
+    formats: Vec<String>,
+}
+
+impl TimestampParser {
+    pub fn with_name<S: Into<String>>(name: S) -> TimestampParser {
+        let mut parser = TimestampParser::new();
+        parser.set_name(Some(name.into()));
+        parser
+    }
+
+    pub fn new() -> TimestampParser {
+        TimestampParser::default()
+    }
+
+    pub fn formats(&self) -> &[String] {
+        &self.formats
+    }
+
+    pub fn set_formats(&mut self, formats: Vec<String>) {
+        self.formats = formats;
+    }
+
+    fn match_format(format: &str, value: &[u8]) -> Option<usize> {
+        let mut pos = 0;
+        let mut chars = format.chars();
+
+        while let Some(c) = chars.next() {
+            let rest = &value[pos..];
+            let len = if c == '%' {
+                match chars.next() {
+                    Some('Y') => number_len(rest, 4, 4, 0, 9999),
+                    Some('y') => number_len(rest, 2, 2, 0, 99),
+                    Some('m') => number_len(rest, 2, 2, 1, 12),
+                    Some('d') => number_len(rest, 2, 2, 1, 31),
+                    Some('e') => padded_day_len(rest),
+                    Some('H') => number_len(rest, 2, 2, 0, 23),
+                    Some('M') => number_len(rest, 2, 2, 0, 59),
+                    Some('S') => number_len(rest, 2, 2, 0, 60),
+                    Some('f') => digits_len(rest, 1, 9),
+                    Some('s') => digits_len(rest, 1, 20),
+                    Some('b') => name_len(rest, MONTHS),
+                    Some('a') => name_len(rest, WEEKDAYS),
+                    Some('z') => timezone_len(rest),
+                    Some('%') => literal_len(rest, '%'),
+                    _ => None,
+                }
+            } else {
+                literal_len(rest, c)
+            };
+            match len {
+                Some(len) => pos += len,
+                None => return None,
+            }
+        }
+        Some(pos)
+    }
+
+    pub fn decode(decoder: &mut Decoder) -> Result<TimestampParser, cache::Error> {
+        let base = try!(ParserBase::decode(decoder));
+        let mut formats = Vec::new();
+        for _ in 0..try!(decoder.read_usize()) {
+            formats.push(try!(decoder.read_string()));
+        }
+        Ok(TimestampParser {
+            base: base,
+            formats: formats,
+        })
+    }
+}
+
+/// Converts `value` to an epoch timestamp with milliseconds (e.g. `1456947132.123`).
+///
+/// Only the epoch timestamps and the ISO8601 timestamps with a timezone can be
+/// converted, the others depend on the local timezone or lack the year.
+pub fn to_epoch(value: &[u8]) -> Option<String> {
+    epoch(value).or_else(|| iso8601_epoch(value)).map(|(seconds, fraction)| {
+        let millis = fraction.iter().chain(b"000").take(3).fold(0, |acc, c| acc * 10 + (c - b'0') as i64);
+        let total = seconds * 1000 + millis;
+        // the fraction belongs to the sign too, e.g. -0.5 s is `-0.500`
+        let sign = if total < 0 { "-" } else { "" };
+        format!("{}{}.{:03}", sign, total.abs() / 1000, total.abs() % 1000)
+    })
+}
+
+fn epoch(value: &[u8]) -> Option<(i64, &[u8])> {
+    digits_len(value, 1, 18).and_then(|len| {
+        let rest = &value[len..];
+        let fraction = if rest.is_empty() {
+            rest
+        } else if rest[0] == b'.' && digits_len(&rest[1..], 1, 9) == Some(rest.len() - 1) {
+            &rest[1..]
+        } else {
+            return None;
+        };
+        Some((to_number(&value[..len]), fraction))
+    })
+}
+
+fn iso8601_epoch(value: &[u8]) -> Option<(i64, &[u8])> {
+    if value.len() <= 19 || TimestampParser::match_format("%Y-%m-%dT%H:%M:%S", &value[..19]).is_none() {
+        return None;
+    }
+    let rest = &value[19..];
+    let (fraction, zone) = if rest[0] == b'.' {
+        let len = digits_len(&rest[1..], 1, 9).unwrap_or(0);
+        (&rest[1..1 + len], &rest[1 + len..])
+    } else {
+        (&rest[..0], rest)
+    };
+    timezone_offset(zone).map(|offset| {
+        let days = days_from_civil(to_number(&value[0..4]), to_number(&value[5..7]), to_number(&value[8..10]));
+        let seconds = days * 86400 + to_number(&value[11..13]) * 3600 + to_number(&value[14..16]) * 60 +
+                      to_number(&value[17..19]) - offset;
+        (seconds, fraction)
+    })
+}
+
+// the offset of a `%z` timezone in seconds if it's the whole `zone`
+fn timezone_offset(zone: &[u8]) -> Option<i64> {
+    if timezone_len(zone) != Some(zone.len()) {
+        return None;
+    }
+    match zone[0] {
+        b'Z' => Some(0),
+        sign => {
+            let minutes = if zone.len() > 3 {
+                to_number(&zone[zone.len() - 2..])
+            } else {
+                0
+            };
+            let offset = to_number(&zone[1..3]) * 3600 + minutes * 60;
+            Some(if sign == b'-' { -offset } else { offset })
+        }
+    }
+}
+
+// the number of days since 1970-01-01 in the proleptic Gregorian calendar
+fn days_from_civil(year: i64, month: i64, day: i64) -> i64 {
+    let year = if month <= 2 { year - 1 } else { year };
+    let era = year.div_euclid(400);
+    let year_of_era = year - era * 400;
+    let day_of_year = (153 * ((month + 9) % 12) + 2) / 5 + day - 1;
+    let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+    era * 146097 + day_of_era - 719468
+}
+
+fn to_number(digits: &[u8]) -> i64 {
+    digits.iter().fold(0, |acc, c| acc * 10 + (c - b'0') as i64)
+}
+
+fn digits_len(bytes: &[u8], min: usize, max: usize) -> Option<usize> {
+    let len = bytes.iter().take(max).take_while(|c| c.is_ascii_digit()).count();
+    if len >= min {
+        Some(len)
+    } else {
+        None
+    }
+}
+
+fn number_len(bytes: &[u8], min: usize, max: usize, lowest: u32, highest: u32) -> Option<usize> {
+    digits_len(bytes, min, max).and_then(|len| {
+        let number = bytes[..len].iter().fold(0, |acc, c| acc * 10 + (c - b'0') as u32);
+        if number >= lowest && number <= highest {
+            Some(len)
+        } else {
+            None
+        }
+    })
+}
+
+// `%e`: the day of the month, a single digit day can be padded by a space
+fn padded_day_len(bytes: &[u8]) -> Option<usize> {
+    if bytes.first() == Some(&b' ') {
+        number_len(&bytes[1..], 1, 1, 1, 9).map(|len| len + 1)
+    } else {
+        number_len(bytes, 1, 2, 1, 31)
+    }
+}
+
+fn name_len(bytes: &[u8], names: &[&str]) -> Option<usize> {
+    names.iter().find(|name| bytes.starts_with(name.as_bytes())).map(|name| name.len())
+}
+
+// `%z`: `Z`, `+hh`, `+hhmm` or `+hh:mm`
+fn timezone_len(bytes: &[u8]) -> Option<usize> {
+    match bytes.first() {
+        Some(&b'Z') => Some(1),
+        Some(&b'+') | Some(&b'-') => {
+            number_len(&bytes[1..], 2, 2, 0, 23).map(|hours| {
+                let minutes_pos = 1 + hours;
+                let separator_len = if bytes.get(minutes_pos) == Some(&b':') {
+                    1
+                } else {
+                    0
+                };
+                match number_len(&bytes[minutes_pos + separator_len..], 2, 2, 0, 59) {
+                    Some(minutes) => minutes_pos + separator_len + minutes,
+                    None => minutes_pos,
+                }
+            })
+        }
+        _ => None,
+    }
+}
+
+fn literal_len(bytes: &[u8], c: char) -> Option<usize> {
+    let mut buffer = [0; 4];
+    let literal = c.encode_utf8(&mut buffer).as_bytes();
+    if bytes.starts_with(literal) {
+        Some(literal.len())
+    } else {
+        None
+    }
+}
+
+impl Default for TimestampParser {
+    fn default() -> Self {
+        TimestampParser {
+            base: ParserBase::new(),
+            formats: ISO8601_FORMATS.iter().map(|format| (*format).to_owned()).collect(),
+        }
+    }
+}
+
+impl Parser for TimestampParser {
+    fn parse_bytes<'a, 'b>(&'a self, value: &'b [u8]) -> Option<ParseResult<'a, 'b, [u8]>> {
+        self.formats
+            .iter()
+            .filter_map(|format| TimestampParser::match_format(format, value))
+            .max()
+            .map(|len| ParseResult::new(self, &value[..len]))
+    }
+
+    fn name(&self) -> Option<&str> {
+        self.base.name()
+    }
+
+    fn set_name(&mut self, name: Option<String>) {
+        self.base.set_name(name);
+    }
+
+    fn kind(&self) -> &'static str {
+        "TIMESTAMP"
+    }
+
+    fn encode(&self, encoder: &mut Encoder) {
+        self.base.encode(encoder);
+        encoder.write_usize(self.formats.len());
+        for format in &self.formats {
+            encoder.write_str(format);
+        }
+    }
+
+    // The default formats are the ISO8601 preset.
+    fn parameters(&self) -> Vec<OptionalParameter<'static>> {
+        if self.formats.iter().map(|format| &format[..]).eq(ISO8601_FORMATS.iter().cloned()) {
+            Vec::new()
+        } else {
+            self.formats.iter().map(|format| OptionalParameter::Str("format", format.clone())).collect()
+        }
+    }
+
+    fn boxed_clone(&self) -> Box<Parser> {
+        Box::new(self.clone())
+    }
+
+    fn value_type(&self) -> ValueType {
+        ValueType::Timestamp
+    }
+}
+
+impl ObjectSafeHash for TimestampParser {
+    fn hash_os(&self) -> u64 {
+        let mut hasher = SipHasher::new();
+        "parser:timestamp".hash(&mut hasher);
+        self.hash(&mut hasher);
+        hasher.finish()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use parsers::{Parser, TimestampParser};
+    use super::{preset_formats, to_epoch};
+
+    fn parser_with_preset(preset: &str) -> TimestampParser {
+        let mut parser = TimestampParser::with_name("ts");
+        let formats = preset_formats(preset).unwrap();
+        parser.set_formats(formats.iter().map(|format| (*format).to_owned()).collect());
+        parser
+    }
+
+    #[test]
+    fn test_given_timestamp_parser_when_the_value_matches_the_format_then_the_exact_span_is_captured
+        () {
+        let mut parser = TimestampParser::with_name("ts");
+        parser.set_formats(vec!["%Y/%m/%d %H:%M".to_owned()]);
+        let res = parser.parse("2016/03/02 20:32 foo").unwrap();
+        assert_eq!(res.parser().name(), Some("ts"));
+        assert_eq!(res.value(), "2016/03/02 20:32");
+    }
+
+    #[test]
+    fn test_given_timestamp_parser_when_a_field_is_out_of_range_then_it_doesnt_match() {
+        let parser = TimestampParser::new();
+        assert_eq!(parser.parse("2016-13-02T20:32:12").is_none(), true);
+        assert_eq!(parser.parse("2016-03-02T24:32:12").is_none(), true);
+        assert_eq!(parser.parse("2016-03-2T20:32:12").is_none(), true);
+    }
+
+    #[test]
+    fn test_given_iso8601_preset_when_the_timestamp_has_fraction_and_timezone_then_the_longest_match_is_used
+        () {
+        let parser = parser_with_preset("ISO8601");
+        assert_eq!(parser.parse("2016-03-02T20:32:12 x").unwrap().value(),
+                   "2016-03-02T20:32:12");
+        assert_eq!(parser.parse("2016-03-02T20:32:12.123+01:00 x").unwrap().value(),
+                   "2016-03-02T20:32:12.123+01:00");
+        assert_eq!(parser.parse("2016-03-02T20:32:12Z").unwrap().value(),
+                   "2016-03-02T20:32:12Z");
+    }
+
+    #[test]
+    fn test_given_rfc3164_preset_when_the_day_is_padded_by_a_space_then_it_matches() {
+        let parser = parser_with_preset("RFC3164");
+        assert_eq!(parser.parse("Mar  2 20:32:12 host").unwrap().value(),
+                   "Mar  2 20:32:12");
+        assert_eq!(parser.parse("Mar 12 20:32:12 host").unwrap().value(),
+                   "Mar 12 20:32:12");
+        assert_eq!(parser.parse("Foo 12 20:32:12 host").is_none(), true);
+    }
+
+    #[test]
+    fn test_given_epoch_preset_when_the_timestamp_has_fraction_then_it_is_captured() {
+        let parser = parser_with_preset("epoch");
+        assert_eq!(parser.parse("1456947132.123 foo").unwrap().value(), "1456947132.123");
+        assert_eq!(parser.parse("1456947132 foo").unwrap().value(), "1456947132");
+    }
+
+    #[test]
+    fn test_given_timestamp_with_timezone_or_epoch_when_it_is_converted_to_epoch_then_we_get_the_milliseconds() {
+        assert_eq!(to_epoch(b"1456947132"), Some("1456947132.000".to_owned()));
+        assert_eq!(to_epoch(b"1456947132.1234"), Some("1456947132.123".to_owned()));
+        assert_eq!(to_epoch(b"2016-03-02T19:32:12Z"), Some("1456947132.000".to_owned()));
+        assert_eq!(to_epoch(b"2016-03-02T20:32:12.5+01:00"), Some("1456947132.500".to_owned()));
+        assert_eq!(to_epoch(b"2016-03-02T17:32:12-0200"), Some("1456947132.000".to_owned()));
+        assert_eq!(to_epoch(b"1969-12-31T23:59:59.5Z"), Some("-0.500".to_owned()));
+        assert_eq!(to_epoch(b"1969-12-31T23:59:58Z"), Some("-2.000".to_owned()));
+    }
+
+    #[test]
+    fn test_given_timestamp_without_timezone_or_year_when_it_is_converted_to_epoch_then_it_fails() {
+        assert_eq!(to_epoch(b"2016-03-02T20:32:12"), None);
+        assert_eq!(to_epoch(b"2016-03-02T20:32:12.123"), None);
+        assert_eq!(to_epoch(b"2016-03-02T20:32:12+01:"), None);
+        assert_eq!(to_epoch(b"Mar  2 20:32:12"), None);
+        assert_eq!(to_epoch(b"1456947132."), None);
+    }
+}
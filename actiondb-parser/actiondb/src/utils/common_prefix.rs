@@ -47,6 +47,22 @@ impl CommonPrefix for str {
     }
 }
 
+// The bytes don't have character boundaries, so the prefix may end in the
+// middle of a multibyte character.
+impl CommonPrefix for [u8] {
+    fn common_prefix_len(&self, other: &Self) -> usize {
+        self.iter().zip(other.iter()).take_while(|&(a, b)| a == b).count()
+    }
+
+    fn ltrunc(&self, len: usize) -> &Self {
+        &self[len..]
+    }
+    fn rtrunc(&self, len: usize) -> &Self {
+        let new_len = self.len() - len;
+        &self[..new_len]
+    }
+}
+
 #[cfg(test)]
 mod test {
     use utils::common_prefix::CommonPrefix;
@@ -83,4 +99,12 @@ mod test {
     fn test_given_a_string_with_multibyte_utf8_character_when_there_is_an_other_multibyte_character_with_the_same_first_byte_then_we_dont_split_them() {
         assert_eq!("\u{00AE}alpha".common_prefix_len("\u{00BE}beta"), 0);
     }
+
+    #[test]
+    fn test_given_bytes_when_we_count_their_common_prefix_len_then_every_byte_is_compared() {
+        assert_eq!(b"alpha"[..].common_prefix_len(b"aleph"), 2);
+        assert_eq!(b"\xe1lom"[..].common_prefix_len(b"\xe1lma"), 2);
+        assert_eq!(b"\xc2\xae"[..].common_prefix_len(b"\xc2\xbe"), 1);
+        assert_eq!(b"alpha"[..].ltrunc(2), b"pha");
+    }
 }
@@ -5,6 +5,14 @@ mod sortedvec;
 // it shouldn't be public, but https://github.com/rust-lang/rust/issues/16264
 pub mod common_prefix;
 
+// The position of the first occurrence of the needle in the haystack.
+pub fn find_bytes(haystack: &[u8], needle: &[u8]) -> Option<usize> {
+    if needle.is_empty() {
+        return Some(0);
+    }
+    haystack.windows(needle.len()).position(|window| window == needle)
+}
+
 pub fn flatten_vec<T>(vectors: Vec<Vec<T>>) -> Vec<T> {
     let mut flattened_vec = Vec::new();
     for vector in vectors.into_iter() {
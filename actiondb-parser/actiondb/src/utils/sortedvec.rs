@@ -47,6 +47,20 @@ impl <T: Ord> SortedVec<T> {
         self.array.is_empty()
     }
 
+    pub fn iter(&self) -> ::std::slice::Iter<T> {
+        self.array.iter()
+    }
+
+    pub fn iter_mut(&mut self) -> ::std::slice::IterMut<T> {
+        self.array.iter_mut()
+    }
+
+    pub fn retain<F>(&mut self, f: F)
+        where F: FnMut(&T) -> bool
+    {
+        self.array.retain(f);
+    }
+
     pub fn binary_search_by<F>(&self, f: F) -> Result<usize, usize>
         where F: FnMut(&T) -> Ordering
     {
@@ -164,6 +178,6 @@ mod test {
         sv.push(LiteralNode::new(""));
         let seek = "a";
 
-        assert_eq!(sv.binary_search_by(|probe| probe.cmp_str(&seek)), Ok(1));
+        assert_eq!(sv.binary_search_by(|probe| probe.cmp_bytes(seek.as_bytes())), Ok(1));
     }
 }
@@ -1,6 +1,13 @@
-use actiondb::matcher::PatternLoader;
+use actiondb::matcher::{Matcher, PatternLoader};
 use actiondb::matcher::BuildError;
+use actiondb::matcher::suffix_array::SuffixArrayMatcherFactory;
+use actiondb::matcher::compiled_pattern;
+use actiondb::matcher::pattern::Pattern;
+use actiondb::matcher::pattern::file::{patterndb, PatternFile};
+use serde_json;
+use serde_yaml;
 
+use std::collections::BTreeMap;
 use std::env;
 
 const DIR_PREFIX: &'static str = "tests/file";
@@ -55,3 +62,237 @@ fn test_given_not_existing_yaml_pattern_file_when_it_is_opened_we_get_io_error()
                    .err()
                    .expect("Successfully opened a non-existing YAML file");
 }
+
+#[test]
+fn test_given_pattern_file_when_it_is_written_in_json_and_yaml_then_the_same_patterns_are_loaded_back() {
+    let file = PatternLoader::load_file("tests/file/ssh_ok.json").expect("Failed to load a valid pattern file");
+    let json = file.to_json().expect("Failed to serialize a pattern file into JSON");
+
+    let from_json: PatternFile = serde_json::from_str(&json).expect("Failed to load a serialized JSON file");
+    let from_yaml: PatternFile = serde_yaml::from_str(&file.to_yaml().unwrap())
+                                     .expect("Failed to load a serialized YAML file");
+    assert_eq!(from_json.to_json().unwrap(), json);
+    assert_eq!(from_yaml.to_json().unwrap(), json);
+    assert_eq!(from_json.patterns()[2].test_messages().unwrap()[0].values().get("pid"),
+               Some(&"2000".to_owned()));
+}
+
+#[test]
+fn test_given_pattern_file_when_it_is_exported_into_patterndb_then_the_same_patterns_are_imported_back() {
+    let file = PatternLoader::load_file("tests/file/ssh_ok.json").expect("Failed to load a valid pattern file");
+    let export = patterndb::export(&file).expect("Failed to export a pattern file");
+    let reasons: Vec<&str> = export.unsupported().iter().map(|unsupported| unsupported.reason()).collect();
+    assert_eq!(reasons,
+               vec!["INT is written as NUMBER, which matches negative and hexadecimal numbers too",
+                    "patterndb doesn't support the names of the patterns: name=SSH_PUBKEY",
+                    "INT is written as NUMBER, which matches negative and hexadecimal numbers too",
+                    "patterndb doesn't support the names of the patterns: name=SSH_DISCONNECT",
+                    "INT is written as NUMBER, which matches negative and hexadecimal numbers too"]);
+
+    let conversion = patterndb::convert(export.xml()).expect("Failed to convert an exported patterndb file");
+    assert_eq!(conversion.unsupported().is_empty(), true);
+    let imported = conversion.into_pattern_file().expect("Failed to load an exported patterndb file");
+    assert_eq!(imported.patterns().len(), file.patterns().len());
+    for (pattern, imported) in file.patterns().iter().zip(imported.patterns()) {
+        // the INT parsers become NUMBER ones, a GREEDY with a literal after it an ESTRING
+        let expected = compiled_pattern::to_string(pattern.pattern())
+                           .replace("%{INT:", "%{NUMBER:")
+                           .replace("%{GREEDY:ipaddr}: ", r#"%{ESTRING(end=": "):ipaddr}"#);
+        assert_eq!(imported.uuid(), pattern.uuid());
+        assert_eq!(compiled_pattern::to_string(imported.pattern()), expected);
+        assert_eq!(imported.values(), pattern.values());
+        assert_eq!(imported.tags(), pattern.tags());
+        assert_eq!(test_messages(imported), test_messages(pattern));
+    }
+}
+
+fn test_messages(pattern: &Pattern) -> Vec<(&str, &BTreeMap<String, String>)> {
+    pattern.test_messages()
+           .unwrap_or(&[])
+           .iter()
+           .map(|test_message| (test_message.message(), test_message.values()))
+           .collect()
+}
+
+fn pattern_names(file: &PatternFile) -> Vec<&str> {
+    file.patterns().iter().map(|pattern| pattern.name().unwrap()).collect()
+}
+
+#[test]
+fn test_given_directory_when_it_is_loaded_then_its_pattern_files_and_their_includes_are_merged() {
+    let file = PatternLoader::load("tests/file/vendors").expect("Failed to load a directory of pattern files");
+    assert_eq!(pattern_names(&file),
+               vec!["CRON_CMD", "KERNEL_OOM", "SSH_PUBKEY", "SSH_SESSION_CLOSED"]);
+
+    let files = PatternLoader::load_files("tests/file/vendors").unwrap();
+    let paths: Vec<&str> = files.iter().map(|file| &file.0[..]).collect();
+    assert_eq!(paths,
+               vec!["tests/file/vendors/cron.yaml",
+                    "tests/file/vendors/../common/all.json",
+                    "tests/file/vendors/../common/kernel.json",
+                    "tests/file/vendors/../common/../vendors/sshd.json"]);
+}
+
+#[test]
+fn test_given_glob_when_it_is_loaded_then_only_the_matching_files_are_loaded() {
+    let file = PatternLoader::load("tests/file/vend*/*.json").expect("Failed to load the files of a glob");
+    assert_eq!(pattern_names(&file), vec!["SSH_PUBKEY", "SSH_SESSION_CLOSED"]);
+
+    match PatternLoader::load("tests/file/vendors/*.xml") {
+        Err(BuildError::NoPatternFile(path)) => assert_eq!(path, "tests/file/vendors/*.xml"),
+        _ => unreachable!(),
+    }
+}
+
+#[test]
+fn test_given_pattern_files_when_they_use_the_same_uuid_then_the_files_are_reported() {
+    match PatternLoader::load("tests/file/duplicate_uuid") {
+        Err(BuildError::DuplicateUuid { uuid, first_file, second_file }) => {
+            assert_eq!(uuid.hyphenated().to_string(), "c11c806a-766d-4a09-9f24-7de1fe02e51e");
+            assert_eq!(first_file, "tests/file/duplicate_uuid/first.json");
+            assert_eq!(second_file, "tests/file/duplicate_uuid/second.json");
+        }
+        _ => unreachable!(),
+    }
+}
+
+#[test]
+fn test_given_pattern_files_when_a_ruleset_uses_the_uuid_of_another_file_then_the_files_are_reported() {
+    match PatternLoader::load("tests/file/duplicate_ruleset_uuid") {
+        Err(BuildError::DuplicateUuid { uuid, first_file, second_file }) => {
+            assert_eq!(uuid.hyphenated().to_string(), "9a49c47d-29e9-4072-be84-3b76c6814743");
+            assert_eq!(first_file, "tests/file/duplicate_ruleset_uuid/first.json");
+            assert_eq!(second_file, "tests/file/duplicate_ruleset_uuid/second.yaml");
+        }
+        _ => unreachable!(),
+    }
+}
+
+#[test]
+fn test_given_pattern_file_when_it_uses_the_same_uuid_again_then_the_file_is_reported() {
+    match PatternLoader::load("tests/file/duplicate_uuid_in_file.json") {
+        Err(BuildError::DuplicateUuid { uuid, first_file, second_file }) => {
+            assert_eq!(uuid.hyphenated().to_string(), "4f9b8a6e-2c1d-4f3a-9e7b-5d6c8a1b2e3f");
+            assert_eq!(first_file, "tests/file/duplicate_uuid_in_file.json");
+            assert_eq!(second_file, "tests/file/duplicate_uuid_in_file.json");
+        }
+        _ => unreachable!(),
+    }
+}
+
+#[test]
+fn test_given_pattern_file_when_adjacent_patterns_use_the_same_uuid_then_the_file_is_reported() {
+    match PatternLoader::load("tests/file/adjacent_duplicate_uuid_in_file.yaml") {
+        Err(BuildError::DuplicateUuid { uuid, first_file, second_file }) => {
+            assert_eq!(uuid.hyphenated().to_string(), "4f9b8a6e-2c1d-4f3a-9e7b-5d6c8a1b2e3f");
+            assert_eq!(first_file, "tests/file/adjacent_duplicate_uuid_in_file.yaml");
+            assert_eq!(second_file, "tests/file/adjacent_duplicate_uuid_in_file.yaml");
+        }
+        _ => unreachable!(),
+    }
+}
+
+#[test]
+fn test_given_included_file_when_it_cannot_be_loaded_then_the_error_contains_its_path() {
+    let error = PatternLoader::load("tests/file/missing_include.json")
+                    .err()
+                    .expect("Successfully loaded a non-existing included file");
+    match error {
+        BuildError::File { ref path, error: ref inner, .. } => {
+            assert_eq!(path, "tests/file/non_existing.json");
+            match **inner {
+                BuildError::Io(_) => (),
+                _ => unreachable!(),
+            }
+        }
+        _ => unreachable!(),
+    }
+    assert!(error.to_string().starts_with("tests/file/non_existing.json: "));
+}
+
+#[test]
+fn test_given_directory_when_a_matcher_is_built_from_it_then_it_matches_the_patterns_of_every_file() {
+    let matcher = PatternLoader::from_file::<SuffixArrayMatcherFactory>("tests/file/vendors")
+                      .expect("Failed to build a matcher from a directory");
+    assert!(matcher.parse("(root) CMD (run-parts /etc/cron.hourly)").is_some());
+    assert!(matcher.parse("Accepted publickey for zts from 10.0.0.1 port 4242 ssh2").is_some());
+    assert!(matcher.parse("Out of memory: Kill process 1 (init) score 0 or sacrifice child").is_some());
+
+    match PatternLoader::from_file::<SuffixArrayMatcherFactory>("tests/file/ssh_wrong.json") {
+        Err(BuildError::File { path, .. }) => assert_eq!(path, "tests/file/ssh_wrong.json"),
+        _ => unreachable!(),
+    }
+}
+
+#[test]
+fn test_given_pattern_file_with_rulesets_when_it_is_loaded_then_the_ruleset_is_selected_by_the_field_of_the_message() {
+    let matcher = PatternLoader::rulesets_from_file::<SuffixArrayMatcherFactory>("tests/file/rulesets.yaml")
+                      .expect("Failed to load the rulesets of a pattern file");
+    let message = b"session closed for user root";
+    let name_of = |field: &'static str, value: &'static [u8]| {
+        let result = matcher.parse_bytes(|name| if name == field { Some(value) } else { None }, message);
+        result.and_then(|result| result.pattern().name().map(|name| name.to_owned()))
+    };
+    assert_eq!(name_of("PROGRAM", b"sshd-session"), Some("SSH_SESSION_CLOSED".to_owned()));
+    assert_eq!(name_of("HOST", b"fw1"), Some("FW_SESSION_CLOSED".to_owned()));
+    assert_eq!(name_of("PROGRAM", b"postfix"), Some("SESSION".to_owned()));
+    assert_eq!(matcher.uuids().len(), 3);
+
+    match PatternLoader::from_file::<SuffixArrayMatcherFactory>("tests/file/rulesets.yaml") {
+        Err(BuildError::UnexpectedRulesets(path)) => assert_eq!(path, "tests/file/rulesets.yaml"),
+        _ => unreachable!(),
+    }
+}
+
+#[test]
+fn test_given_rulesets_when_a_value_selects_more_of_them_then_they_are_rejected() {
+    match PatternLoader::rulesets_from_file::<SuffixArrayMatcherFactory>("tests/file/overlapping_rulesets.json") {
+        Err(BuildError::OverlappingRulesets { field, values }) => {
+            assert_eq!(field, "PROGRAM");
+            assert_eq!(values, vec!["sshd".to_owned(), "sshd-session".to_owned()]);
+        }
+        _ => unreachable!(),
+    }
+}
+
+#[test]
+fn test_given_pattern_file_when_one_of_its_patterns_is_invalid_then_the_error_points_into_the_pattern() {
+    let path = "tests/file/invalid_pattern.yaml";
+    let error = PatternLoader::from_file::<SuffixArrayMatcherFactory>(path)
+                    .err()
+                    .expect("Successfully loaded an invalid pattern");
+    let (file, location) = error.location().expect("The error of an invalid pattern has no location");
+    assert_eq!(file, path);
+    assert_eq!((location.line(), location.column()), (5, 51));
+    match error {
+        BuildError::File { error: ref inner, .. } => {
+            match **inner {
+                BuildError::Pattern { ref pattern, .. } => assert!(pattern.starts_with("Accepted publickey")),
+                _ => unreachable!(),
+            }
+        }
+        _ => unreachable!(),
+    }
+}
+
+#[test]
+fn test_given_pattern_file_when_its_test_message_fails_then_the_error_points_to_the_test_message() {
+    let path = "tests/file/invalid_test_message.json";
+    let error = PatternLoader::from_file::<SuffixArrayMatcherFactory>(path)
+                    .err()
+                    .expect("Successfully loaded a pattern with an invalid test message");
+    let location = error.location().expect("The error of a test message has no location").1;
+    assert_eq!((location.line(), location.column()), (8, 23));
+    assert!(error.to_string().starts_with("tests/file/invalid_test_message.json:8:23: "));
+    assert_eq!(location.render(&String::from_utf8(::std::fs::read(path).unwrap()).unwrap()),
+               Some("          \"message\": \"session closed for user zts\",\n                      ^".to_owned()));
+}
+
+#[test]
+fn test_given_pattern_file_when_it_is_not_well_formed_then_the_error_has_the_position_of_the_parser() {
+    let error = PatternLoader::from_file::<SuffixArrayMatcherFactory>("tests/file/ssh_wrong.json")
+                    .err()
+                    .unwrap();
+    let location = error.location().expect("The syntax error has no location").1;
+    assert_eq!((location.line(), location.column()), (1, 12));
+}
@@ -1,4 +1,6 @@
 extern crate actiondb;
+extern crate serde_json;
+extern crate serde_yaml;
 
 mod file;
 mod matcher;
@@ -1,7 +1,15 @@
 extern crate actiondb;
 
-use actiondb::matcher::PatternLoader;
+use actiondb::matcher::{Matcher, MatcherFactory, PatternLoader, BuildError, FromPatternSource, Pattern};
+use actiondb::matcher::lint::Warning;
+use actiondb::matcher::cache::{self, Error};
 use actiondb::matcher::trie::factory::TrieMatcherFactory;
+use actiondb::matcher::suffix_array::SuffixArrayMatcherFactory;
+
+use std::env;
+use std::fs;
+use std::io::Write;
+use std::process;
 
 #[test]
 fn test_given_json_file_when_its_syntax_is_ok_then_matcher_can_be_built_from_it() {
@@ -90,3 +98,187 @@ fn test_given_json_file_when_we_check_the_test_messages_then_the_resulting_patte
                    .expect("The UUID of the resulting pattern should be the same as the freshly \
                             inserted one");
 }
+
+#[test]
+fn test_given_json_file_when_it_sets_literal_flags_then_the_patterns_tolerate_case_and_whitespace_differences
+    () {
+    let pattern_file_path = "tests/matcher/ssh_loose_literals.json";
+    let matcher = PatternLoader::from_file::<TrieMatcherFactory>(pattern_file_path);
+    println!("matcher: {:?}", &matcher);
+    let _ = matcher.ok().expect("Failed to create a Matcher when the literal flags are set");
+}
+
+#[test]
+fn test_given_json_file_when_a_pattern_overrides_the_literal_flags_of_the_file_then_the_pattern_ones_are_used
+    () {
+    let pattern_file_path = "tests/matcher/ssh_loose_literals_can_be_turned_off.json";
+    let matcher = PatternLoader::from_file::<TrieMatcherFactory>(pattern_file_path);
+    println!("matcher: {:?}", &matcher);
+    let _ = matcher.err().expect("A pattern which doesn't ignore case matched a message with a \
+                                  different case");
+}
+
+#[test]
+fn test_given_json_file_when_a_pattern_has_higher_priority_then_it_is_preferred_over_the_literals() {
+    let pattern_file_path = "tests/matcher/ssh_priority.json";
+    let matcher = PatternLoader::from_file::<TrieMatcherFactory>(pattern_file_path);
+    println!("matcher: {:?}", &matcher);
+    let _ = matcher.ok().expect("Failed to create a Matcher when the priority of a pattern resolves an overlap");
+}
+
+#[test]
+fn test_given_json_file_when_a_pattern_has_higher_priority_then_the_suffix_array_prefers_it_too() {
+    let pattern_file_path = "tests/matcher/ssh_priority.json";
+    let matcher = PatternLoader::from_file::<SuffixArrayMatcherFactory>(pattern_file_path);
+    println!("matcher: {:?}", &matcher);
+    let _ = matcher.ok().expect("Failed to create a Matcher when the priority of a pattern resolves an overlap");
+}
+
+fn temp_path(name: &str) -> String {
+    let mut path = env::temp_dir();
+    path.push(format!("actiondb-{}-{}", process::id(), name));
+    path.to_str().unwrap().to_owned()
+}
+
+#[test]
+fn test_given_compiled_cache_file_when_it_is_loaded_then_the_matcher_parses_like_the_original() {
+    let pattern_file_path = "tests/matcher/ssh_ok.json";
+    let cache_path = temp_path("ssh_ok.adbc");
+    let message = "Jun 25 14:09:41 lobotomy sshd[26478]: pam_unix(sshd:session): session closed for user zts";
+    let matcher = PatternLoader::from_file::<SuffixArrayMatcherFactory>(pattern_file_path).unwrap();
+    let sources = PatternLoader::load_sources(pattern_file_path).unwrap();
+    cache::compile(&matcher, &sources, &cache_path).unwrap();
+
+    let cached = PatternLoader::from_file::<SuffixArrayMatcherFactory>(&cache_path);
+    let _ = fs::remove_file(&cache_path);
+    let cached = cached.ok().expect("Failed to load a Matcher from a compiled cache file");
+    let expected = matcher.parse(message).unwrap();
+    let result = cached.parse(message).unwrap();
+    assert_eq!(result.pattern().uuid(), expected.pattern().uuid());
+    assert_eq!(result.values(), expected.values());
+}
+
+#[test]
+fn test_given_compiled_cache_file_when_its_source_has_changed_then_it_is_not_loaded() {
+    let pattern_file_path = temp_path("ssh_stale.json");
+    let cache_path = temp_path("ssh_stale.adbc");
+    fs::copy("tests/matcher/ssh_ok.json", &pattern_file_path).unwrap();
+    let matcher = PatternLoader::from_file::<SuffixArrayMatcherFactory>(&pattern_file_path).unwrap();
+    let sources = PatternLoader::load_sources(&pattern_file_path).unwrap();
+    cache::compile(&matcher, &sources, &cache_path).unwrap();
+    fs::copy("tests/matcher/ssh_priority.json", &pattern_file_path).unwrap();
+
+    let cached = PatternLoader::from_file::<SuffixArrayMatcherFactory>(&cache_path);
+    let _ = fs::remove_file(&pattern_file_path);
+    let _ = fs::remove_file(&cache_path);
+    match cached {
+        Err(BuildError::Cache(Error::Stale(_))) => (),
+        other => panic!("A stale cache file was not rejected: {:?}", other),
+    }
+}
+
+#[test]
+fn test_given_compiled_cache_file_when_an_included_source_has_changed_then_it_is_not_loaded() {
+    let directory = temp_path("ssh_include");
+    let pattern_file_path = format!("{}/main.json", directory);
+    let included_file_path = format!("{}/ssh.json", directory);
+    let cache_path = temp_path("ssh_include.adbc");
+    fs::create_dir_all(&directory).unwrap();
+    fs::copy("tests/matcher/ssh_ok.json", &included_file_path).unwrap();
+    fs::File::create(&pattern_file_path).unwrap().write_all(br#"{"include": ["ssh.json"]}"#).unwrap();
+    let sources = PatternLoader::load_sources(&pattern_file_path).unwrap();
+    let matcher = PatternLoader::from_file::<SuffixArrayMatcherFactory>(&pattern_file_path).unwrap();
+    cache::compile(&matcher, &sources, &cache_path).unwrap();
+    let fresh = PatternLoader::from_file::<SuffixArrayMatcherFactory>(&cache_path);
+    fs::copy("tests/matcher/ssh_priority.json", &included_file_path).unwrap();
+
+    let cached = PatternLoader::from_file::<SuffixArrayMatcherFactory>(&cache_path);
+    let _ = fs::remove_dir_all(&directory);
+    let _ = fs::remove_file(&cache_path);
+    assert_eq!(sources.files.len(), 2);
+    fresh.ok().expect("Failed to load a Matcher from a compiled cache file");
+    match cached {
+        Err(BuildError::Cache(Error::Stale(ref source))) if source.ends_with("ssh.json") => (),
+        other => panic!("A cache file with a changed included source was loaded: {:?}", other),
+    }
+}
+
+#[test]
+fn test_given_compiled_cache_file_of_a_directory_when_a_file_is_added_to_it_then_it_is_not_loaded() {
+    let directory = temp_path("ssh_directory");
+    let cache_path = temp_path("ssh_directory.adbc");
+    fs::create_dir_all(&directory).unwrap();
+    fs::copy("tests/matcher/ssh_ok.json", format!("{}/ssh.json", directory)).unwrap();
+    let sources = PatternLoader::load_sources(&directory).unwrap();
+    let matcher = PatternLoader::from_file::<SuffixArrayMatcherFactory>(&directory).unwrap();
+    cache::compile(&matcher, &sources, &cache_path).unwrap();
+    let fresh = PatternLoader::from_file::<SuffixArrayMatcherFactory>(&cache_path);
+    fs::File::create(format!("{}/cron.json", directory)).unwrap().write_all(br#"{"patterns": []}"#).unwrap();
+
+    let cached = PatternLoader::from_file::<SuffixArrayMatcherFactory>(&cache_path);
+    let _ = fs::remove_dir_all(&directory);
+    let _ = fs::remove_file(&cache_path);
+    fresh.ok().expect("Failed to load a Matcher from a compiled cache file");
+    match cached {
+        Err(BuildError::Cache(Error::Stale(ref source))) if source.ends_with("ssh_directory") => (),
+        other => panic!("A cache file was loaded after a file was added to its directory: {:?}", other),
+    }
+}
+
+#[test]
+fn test_given_compiled_cache_file_when_its_source_cannot_be_read_then_it_is_not_loaded() {
+    let pattern_file_path = temp_path("ssh_removed.json");
+    let cache_path = temp_path("ssh_removed.adbc");
+    fs::copy("tests/matcher/ssh_ok.json", &pattern_file_path).unwrap();
+    let matcher = PatternLoader::from_file::<SuffixArrayMatcherFactory>(&pattern_file_path).unwrap();
+    let sources = PatternLoader::load_sources(&pattern_file_path).unwrap();
+    cache::compile(&matcher, &sources, &cache_path).unwrap();
+    fs::remove_file(&pattern_file_path).unwrap();
+
+    let cached = PatternLoader::from_file::<SuffixArrayMatcherFactory>(&cache_path);
+    let _ = fs::remove_file(&cache_path);
+    match cached {
+        Err(BuildError::Cache(Error::UnreadableSource(..))) => (),
+        other => panic!("A cache file without its source was loaded: {:?}", other),
+    }
+}
+
+#[test]
+fn test_given_compiled_cache_file_when_it_is_loaded_then_the_trie_parses_like_the_original() {
+    let pattern_file_path = "tests/matcher/ssh_ok.json";
+    let cache_path = temp_path("ssh_ok_trie.adbc");
+    let message = "Jun 25 14:09:41 lobotomy sshd[26478]: pam_unix(sshd:session): session closed for user zts";
+    let matcher = PatternLoader::from_file::<TrieMatcherFactory>(pattern_file_path).unwrap();
+    let sources = PatternLoader::load_sources(pattern_file_path).unwrap();
+    cache::compile(&matcher, &sources, &cache_path).unwrap();
+
+    let cached = PatternLoader::from_file::<TrieMatcherFactory>(&cache_path);
+    let _ = fs::remove_file(&cache_path);
+    let cached = cached.ok().expect("Failed to load a trie from a compiled cache file");
+    let expected = matcher.parse(message).unwrap();
+    let result = cached.parse(message).unwrap();
+    assert_eq!(result.pattern().uuid(), expected.pattern().uuid());
+    assert_eq!(result.values(), expected.values());
+}
+
+#[test]
+fn test_given_patterns_when_a_later_pattern_can_match_an_earlier_test_message_then_it_is_ambiguous() {
+    let root = Pattern::from_json(r#"{"uuid": "9a49c47d-29e9-4072-be84-3b76c6814743",
+                                      "pattern": "user root %{GREEDY:action}",
+                                      "test_messages": [{"message": "user root logged in"}]}"#)
+                   .unwrap();
+    let user = Pattern::from_json(r#"{"uuid": "fa8bdbcb-e0fd-4da1-9fa4-15ecfec28ad2",
+                                      "pattern": "user %{GREEDY:user}"}"#)
+                   .unwrap();
+    type SuffixTable = <SuffixArrayMatcherFactory as MatcherFactory>::Matcher;
+    let mut matcher = SuffixArrayMatcherFactory::new_matcher();
+    let test_messages = vec![SuffixTable::check_pattern(&mut matcher, Ok(root.clone())).unwrap(),
+                             SuffixTable::check_pattern(&mut matcher, Ok(user.clone())).unwrap()];
+
+    assert_eq!(SuffixTable::ambiguities(&matcher, &test_messages),
+               vec![Warning::Overlap {
+                        uuid: *root.uuid(),
+                        other: *user.uuid(),
+                        message: Some("user root logged in".to_owned()),
+                    }]);
+}
@@ -7,12 +7,21 @@ mod logger;
 mod parse;
 
 use clap::{Arg, App, SubCommand, ArgMatches};
-use actiondb::matcher::PatternLoader;
+use actiondb::matcher::{BuildError, PatternLoader};
 use actiondb::matcher::suffix_array::SuffixArrayMatcherSuite;
-use actiondb::matcher::MatcherSuite;
+use actiondb::matcher::{MatcherSuite, MatcherFactory};
+use actiondb::matcher::Matcher;
+use actiondb::matcher::lint;
+use actiondb::matcher::cache::{self, Cacheable};
+use actiondb::matcher::suggest::{self, Suggester};
+use actiondb::matcher::pattern::file::patterndb;
 use log::LogLevelFilter;
 use self::logger::StdoutLogger;
 
+use std::fs::File;
+use std::io::{BufRead, BufReader, Read, Write};
+use std::path::Path;
+
 const AUTHOR: &'static str = "Tibor Benke <tibor.benke@balabit.com>";
 const APPNAME: &'static str = "adbtool";
 const DEBUG: &'static str = "debug";
@@ -20,9 +29,19 @@ const DEBUG: &'static str = "debug";
 const PATTERN_FILE: &'static str = "pattern file";
 const VALIDATE: &'static str = "validate";
 const PARSE: &'static str = "parse";
+const LINT: &'static str = "lint";
+const EXPLAIN: &'static str = "explain";
+const COMPILE: &'static str = "compile";
+const SUGGEST: &'static str = "suggest";
+const CONVERT: &'static str = "convert";
+const MESSAGE: &'static str = "message";
+const FIELD: &'static str = "field";
 const IGNORE_ERRORS: &'static str = "ignore-errors";
 const INPUT_FILE: &'static str = "input file";
 const OUTPUT_FILE: &'static str = "output file";
+const SIMILARITY: &'static str = "similarity";
+const YAML: &'static str = "yaml";
+const KNOWN_PATTERNS: &'static str = "patterns";
 
 fn build_command_line_argument_parser<'a, 'b>() -> App<'a, 'b> {
     let version = env!("CARGO_PKG_VERSION");
@@ -40,7 +59,7 @@ fn build_command_line_argument_parser<'a, 'b>() -> App<'a, 'b> {
                         .arg(Arg::with_name(PATTERN_FILE)
                                  .required(true)
                                  .index(1)
-                                 .help("The pattern file to be validated"))
+                                 .help("The pattern file, directory or glob to be validated"))
                         .arg(Arg::with_name(IGNORE_ERRORS)
                                  .short("i")
                                  .help("Don't stop at the first test message error")))
@@ -60,23 +79,112 @@ fn build_command_line_argument_parser<'a, 'b>() -> App<'a, 'b> {
                                  .required(true)
                                  .index(3)
                                  .help("The output file where the results are written")))
+        .subcommand(SubCommand::with_name(LINT)
+                        .about("reports patterns which can match the same messages")
+                        .version(version)
+                        .author(AUTHOR)
+                        .arg(Arg::with_name(PATTERN_FILE)
+                                 .required(true)
+                                 .index(1)
+                                 .help("The pattern file, directory or glob to be checked")))
+        .subcommand(SubCommand::with_name(EXPLAIN)
+                        .about("shows how a message is matched against the patterns")
+                        .version(version)
+                        .author(AUTHOR)
+                        .arg(Arg::with_name(PATTERN_FILE)
+                                 .required(true)
+                                 .index(1)
+                                 .help("The pattern file, directory or glob which contains the patterns"))
+                        .arg(Arg::with_name(MESSAGE)
+                                 .required(true)
+                                 .index(2)
+                                 .help("The message to be explained"))
+                        .arg(Arg::with_name(FIELD)
+                                 .short("f")
+                                 .long(FIELD)
+                                 .takes_value(true)
+                                 .multiple(true)
+                                 .number_of_values(1)
+                                 .help("A field of the message which selects its ruleset, e.g. PROGRAM=sshd")))
+        .subcommand(SubCommand::with_name(COMPILE)
+                        .about("compiles a pattern file into a matcher cache file")
+                        .version(version)
+                        .author(AUTHOR)
+                        .arg(Arg::with_name(PATTERN_FILE)
+                                 .required(true)
+                                 .index(1)
+                                 .help("The pattern file, directory or glob to be compiled"))
+                        .arg(Arg::with_name(OUTPUT_FILE)
+                                 .required(true)
+                                 .index(2)
+                                 .help("The cache file to be created, its extension should be .adbc")))
+        .subcommand(SubCommand::with_name(SUGGEST)
+                        .about("suggests patterns for the lines of a file")
+                        .version(version)
+                        .author(AUTHOR)
+                        .arg(Arg::with_name(INPUT_FILE)
+                                 .required(true)
+                                 .index(1)
+                                 .help("The file which contains the lines"))
+                        .arg(Arg::with_name(SIMILARITY)
+                                 .short("s")
+                                 .long(SIMILARITY)
+                                 .takes_value(true)
+                                 .help("The ratio of the same tokens in the similar lines, between 0 and 1 (default: \
+                                        0.5)"))
+                        .arg(Arg::with_name(KNOWN_PATTERNS)
+                                 .short("p")
+                                 .long(KNOWN_PATTERNS)
+                                 .takes_value(true)
+                                 .help("A pattern file, the lines which are matched by its patterns are skipped"))
+                        .arg(Arg::with_name(YAML)
+                                 .short("y")
+                                 .long(YAML)
+                                 .help("Write the patterns in YAML instead of JSON")))
+        .subcommand(SubCommand::with_name(CONVERT)
+                        .about("converts a pattern file into an other format")
+                        .version(version)
+                        .author(AUTHOR)
+                        .arg(Arg::with_name(INPUT_FILE)
+                                 .required(true)
+                                 .index(1)
+                                 .help("The pattern file, directory or glob to be converted"))
+                        .arg(Arg::with_name(OUTPUT_FILE)
+                                 .required(true)
+                                 .index(2)
+                                 .help("The pattern file to be created, its format depends on its extension: .xml \
+                                        (patterndb), .yaml, .yml or .json")))
 }
 
 fn handle_validate<MS: MatcherSuite>(matches: &ArgMatches) {
     let pattern_file = matches.value_of(PATTERN_FILE).unwrap();
 
     let matcher = if matches.is_present(IGNORE_ERRORS) {
-        PatternLoader::from_file_ignore_errors::<MS::MatcherFactory>(pattern_file)
+        PatternLoader::rulesets_from_file_ignore_errors::<MS::MatcherFactory>(pattern_file)
     } else {
-        PatternLoader::from_file::<MS::MatcherFactory>(pattern_file)
+        PatternLoader::rulesets_from_file::<MS::MatcherFactory>(pattern_file)
     };
 
     if let Err(e) = matcher {
-        error!("{}", e);
+        report_build_error(&e);
         std::process::exit(1);
     }
 }
 
+// Prints the offending line of the pattern file with a caret under the
+// location of the error.
+fn report_build_error(error: &BuildError) {
+    error!("{}", error);
+    if let Some((path, location)) = error.location() {
+        let mut content = String::new();
+        if File::open(path).and_then(|mut file| file.read_to_string(&mut content)).is_ok() {
+            if let Some(snippet) = location.render(&content) {
+                println!("{}", snippet);
+            }
+        }
+    }
+}
+
 fn handle_parse<MS: MatcherSuite>(matches: &ArgMatches) {
     let pattern_file = matches.value_of(PATTERN_FILE).unwrap();
     let input_file = matches.value_of(INPUT_FILE).unwrap();
@@ -88,6 +196,198 @@ fn handle_parse<MS: MatcherSuite>(matches: &ArgMatches) {
     }
 }
 
+fn handle_lint<MS: MatcherSuite>(matches: &ArgMatches) {
+    let pattern_file = matches.value_of(PATTERN_FILE).unwrap();
+
+    let warnings = PatternLoader::load(pattern_file).and_then(|file| {
+        // The patterns of different rulesets can't match the same messages.
+        let mut warnings = try!(lint::lint::<MS::MatcherFactory>(file.patterns()));
+        for ruleset in file.rulesets() {
+            warnings.extend(try!(lint::lint::<MS::MatcherFactory>(ruleset.patterns())));
+        }
+        Ok(warnings)
+    });
+
+    match warnings {
+        Ok(warnings) => {
+            for warning in &warnings {
+                warn!("{}", warning);
+            }
+            if !warnings.is_empty() {
+                std::process::exit(1);
+            }
+        }
+        Err(e) => {
+            report_build_error(&e);
+            std::process::exit(1);
+        }
+    }
+}
+
+// The fields are given as `NAME=VALUE`.
+fn read_fields<'a>(matches: &'a ArgMatches) -> Result<Vec<(&'a str, &'a str)>, String> {
+    let mut fields = Vec::new();
+    for field in matches.values_of(FIELD).into_iter().flat_map(|values| values) {
+        let mut parts = field.splitn(2, '=');
+        match (parts.next(), parts.next()) {
+            (Some(name), Some(value)) if !name.is_empty() => fields.push((name, value)),
+            _ => return Err(format!("The field must be given as NAME=VALUE: field={}", field)),
+        }
+    }
+    Ok(fields)
+}
+
+// The message is explained against the ruleset which is selected by its
+// fields, or against the fallback patterns.
+fn handle_explain<MS: MatcherSuite>(matches: &ArgMatches) {
+    let pattern_file = matches.value_of(PATTERN_FILE).unwrap();
+    let message = matches.value_of(MESSAGE).unwrap();
+    let fields = match read_fields(matches) {
+        Ok(fields) => fields,
+        Err(e) => {
+            error!("{}", e);
+            std::process::exit(1);
+        }
+    };
+
+    match PatternLoader::rulesets_from_file::<MS::MatcherFactory>(pattern_file) {
+        Ok(rulesets) => {
+            let matcher = rulesets.select(|name| {
+                fields.iter().find(|field| field.0 == name).map(|field| field.1.as_bytes())
+            });
+            let explanation = matcher.explain(message);
+            println!("{}", explanation);
+            if explanation.matched().is_none() {
+                std::process::exit(1);
+            }
+        }
+        Err(e) => {
+            report_build_error(&e);
+            std::process::exit(1);
+        }
+    }
+}
+
+fn handle_compile<MS: MatcherSuite>(matches: &ArgMatches)
+    where <MS::MatcherFactory as MatcherFactory>::Matcher: Cacheable
+{
+    let pattern_file = matches.value_of(PATTERN_FILE).unwrap();
+    let output_file = matches.value_of(OUTPUT_FILE).unwrap();
+
+    if !cache::is_cache_file(output_file) {
+        warn!("The cache file can be loaded only if its extension is .{}: output_file={}",
+              cache::EXTENSION,
+              output_file);
+    }
+
+    // The cache is checked against every file which the directories, the
+    // globs and the includes of the pattern file are resolved to.
+    let sources = match PatternLoader::load_sources(pattern_file) {
+        Ok(sources) => sources,
+        Err(e) => {
+            error!("{}", e);
+            std::process::exit(1);
+        }
+    };
+    if sources.files.iter().any(|file| !file.1.rulesets().is_empty()) {
+        error!("The pattern files with rulesets can't be compiled: pattern_file={}", pattern_file);
+        std::process::exit(1);
+    }
+
+    let result = PatternLoader::from_file::<MS::MatcherFactory>(pattern_file).and_then(|matcher| {
+        cache::compile(&matcher, &sources, output_file).map_err(|e| e.into())
+    });
+
+    if let Err(e) = result {
+        error!("{}", e);
+        std::process::exit(1);
+    }
+}
+
+fn read_suggester_input<MS: MatcherSuite>(matches: &ArgMatches) -> Result<Suggester, String> {
+    let input_file = matches.value_of(INPUT_FILE).unwrap();
+    let similarity = match matches.value_of(SIMILARITY) {
+        Some(value) => {
+            match value.parse::<f64>() {
+                Ok(similarity) if similarity >= 0.0 && similarity <= 1.0 => similarity,
+                _ => return Err(format!("The similarity must be between 0 and 1: similarity={}", value)),
+            }
+        }
+        None => suggest::DEFAULT_SIMILARITY,
+    };
+    let known_patterns = match matches.value_of(KNOWN_PATTERNS) {
+        Some(pattern_file) => {
+            Some(try!(PatternLoader::from_file::<MS::MatcherFactory>(pattern_file).map_err(|e| e.to_string())))
+        }
+        None => None,
+    };
+
+    let file = try!(File::open(input_file).map_err(|e| e.to_string()));
+    let mut suggester = Suggester::new(similarity);
+    for line in BufReader::new(file).split(b'\n') {
+        let mut line = try!(line.map_err(|e| e.to_string()));
+        if line.last() == Some(&b'\r') {
+            line.pop();
+        }
+        if known_patterns.as_ref().map_or(false, |matcher| matcher.parse_bytes(&line).is_some()) {
+            continue;
+        }
+        suggester.add_line(&String::from_utf8_lossy(&line));
+    }
+    Ok(suggester)
+}
+
+fn handle_suggest<MS: MatcherSuite>(matches: &ArgMatches) {
+    match read_suggester_input::<MS>(matches) {
+        Ok(suggester) => {
+            let suggestions = suggester.suggestions();
+            for suggestion in &suggestions {
+                debug!("Suggested pattern: uuid={} lines={} pattern={:?}",
+                       suggestion.uuid().hyphenated(),
+                       suggestion.line_count(),
+                       suggestion.pattern());
+            }
+            if matches.is_present(YAML) {
+                println!("{}", suggest::to_yaml(&suggestions));
+            } else {
+                println!("{}", suggest::to_json(&suggestions));
+            }
+        }
+        Err(e) => {
+            error!("{}", e);
+            std::process::exit(1);
+        }
+    }
+}
+
+fn write_pattern_file(input_file: &str, output_file: &str) -> Result<(), String> {
+    let file = try!(PatternLoader::load(input_file).map_err(|e| e.to_string()));
+
+    let output = match Path::new(output_file).extension().and_then(|extension| extension.to_str()) {
+        Some(patterndb::EXTENSION) => {
+            let export = try!(patterndb::export(&file).map_err(|e| e.to_string()));
+            for unsupported in export.unsupported() {
+                warn!("{}", unsupported);
+            }
+            export.xml().to_owned()
+        }
+        Some("yaml") | Some("yml") | Some("YAML") | Some("YML") => try!(file.to_yaml().map_err(|e| e.to_string())),
+        _ => try!(file.to_json().map_err(|e| e.to_string())),
+    };
+    let mut output_file = try!(File::create(output_file).map_err(|e| e.to_string()));
+    output_file.write_all(output.as_bytes()).map_err(|e| e.to_string())
+}
+
+fn handle_convert(matches: &ArgMatches) {
+    let input_file = matches.value_of(INPUT_FILE).unwrap();
+    let output_file = matches.value_of(OUTPUT_FILE).unwrap();
+
+    if let Err(e) = write_pattern_file(input_file, output_file) {
+        error!("{}", e);
+        std::process::exit(1);
+    }
+}
+
 fn setup_stdout_logger(log_level: LogLevelFilter) {
     let _ = log::set_logger(|max_log_level| {
         max_log_level.set(log_level);
@@ -103,11 +403,23 @@ fn choose_log_level<'a>(matches: &ArgMatches<'a>) -> LogLevelFilter {
     }
 }
 
-fn process_command_line_args<'a, MS: MatcherSuite>(matches: ArgMatches<'a>) {
+fn process_command_line_args<'a, MS: MatcherSuite>(matches: ArgMatches<'a>)
+    where <MS::MatcherFactory as MatcherFactory>::Matcher: Cacheable
+{
     if let Some(matches) = matches.subcommand_matches(VALIDATE) {
         handle_validate::<MS>(&matches);
     } else if let Some(matches) = matches.subcommand_matches(PARSE) {
         handle_parse::<MS>(&matches);
+    } else if let Some(matches) = matches.subcommand_matches(LINT) {
+        handle_lint::<MS>(&matches);
+    } else if let Some(matches) = matches.subcommand_matches(EXPLAIN) {
+        handle_explain::<MS>(&matches);
+    } else if let Some(matches) = matches.subcommand_matches(COMPILE) {
+        handle_compile::<MS>(&matches);
+    } else if let Some(matches) = matches.subcommand_matches(SUGGEST) {
+        handle_suggest::<MS>(&matches);
+    } else if let Some(matches) = matches.subcommand_matches(CONVERT) {
+        handle_convert(&matches);
     } else {
         error!("{}", matches.usage.as_ref().unwrap());
     }
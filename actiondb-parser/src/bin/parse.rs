@@ -2,6 +2,7 @@ use std::fs::File;
 use std::io::{BufReader, BufRead, Error, ErrorKind, BufWriter, Write};
 use actiondb::Matcher;
 use actiondb::matcher::{PatternLoader, MatcherSuite};
+use actiondb::matcher::result::MatchResult;
 
 pub fn parse<MS: MatcherSuite>(pattern_file_path: &str,
              input_file_path: &str,
@@ -26,10 +27,14 @@ fn parse_file<MS: MatcherSuite>(input_file: &File, output_file: &mut File, match
     let mut writer = BufWriter::new(output_file);
     let mut count: usize = 0;
 
-    for line in reader.lines() {
-        if let Ok(l) = line {
-            let parse_result = matcher.parse(&l);
-            let _ = write!(&mut writer, "{:?}\n", parse_result);
+    // the lines are matched as bytes, so the ones which aren't valid UTF-8 are parsed too
+    for line in reader.split(b'\n') {
+        if let Ok(mut l) = line {
+            if l.last() == Some(&b'\r') {
+                l.pop();
+            }
+            let parse_result = matcher.parse_bytes(&l);
+            let _ = write!(&mut writer, "{}\n", format_result(parse_result.as_ref()));
             count += 1;
         } else {
             break;
@@ -38,3 +43,27 @@ fn parse_file<MS: MatcherSuite>(input_file: &File, output_file: &mut File, match
 
     info!("Total number of lines: {}", count);
 }
+
+// Every value is written with its type, e.g. `pid:int="42"`. The invalid UTF-8
+// sequences are replaced in the values.
+fn format_result(result: Option<&MatchResult<[u8]>>) -> String {
+    match result {
+        Some(result) => {
+            let mut line = result.pattern().uuid().hyphenated().to_string();
+            for (key, value) in result.values() {
+                line.push_str(&format!(" {}:{}={:?}",
+                                       key,
+                                       result.value_type(key).as_str(),
+                                       String::from_utf8_lossy(value)));
+            }
+            for (key, value) in result.pairs() {
+                line.push_str(&format!(" {}:{}={:?}",
+                                       key,
+                                       result.value_type(key).as_str(),
+                                       String::from_utf8_lossy(&**value)));
+            }
+            line
+        }
+        None => "no match".to_owned(),
+    }
+}
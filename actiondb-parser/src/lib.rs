@@ -13,8 +13,13 @@ extern crate syslog_ng_common;
 extern crate actiondb;
 
 use std::borrow::Borrow;
+use std::mem;
+use std::sync::{Arc, Mutex, MutexGuard};
+use std::time::{Duration, Instant};
 
 use actiondb::matcher::{Matcher, PatternLoader, MatcherSuite};
+use actiondb::matcher::ruleset::RulesetMatcher;
+use actiondb::matcher::stats::{self, Statistics};
 use syslog_ng_common::{Parser, ParserBuilder, Error, LogMessage, MessageFormatter, Pipe, GlobalConfig};
 
 mod msgfilller;
@@ -23,15 +28,39 @@ mod options;
 
 use self::msgfilller::MessageFiller;
 
+// The statistics are dumped every 5 minutes by default.
+const DEFAULT_STATS_INTERVAL: u64 = 300;
+
+// The clones of a parser share their statistics, so the stats file contains
+// every message they have seen. Every parser counts into its own statistics
+// and merges them into the shared ones only when they are dumped, so the
+// parsers don't wait for each other while they are parsing. The snapshots are
+// numbered, so an older one doesn't overwrite a newer one in the file.
+struct SharedStatistics {
+    stats: Statistics,
+    last_dump: Instant,
+    snapshots: u64,
+    written: Arc<Mutex<u64>>,
+}
+
+// The statistics are only counters, so they remain usable even if an other
+// parser panicked while it was holding the lock.
+fn lock<T>(mutex: &Mutex<T>) -> MutexGuard<T> {
+    mutex.lock().unwrap_or_else(|poisoned| poisoned.into_inner())
+}
+
 #[derive(Clone)]
 pub struct ActiondbParserBuilder<MS> where MS: MatcherSuite, MS::Matcher: Clone {
-    matcher: Option<MS::Matcher>,
-    formatter: MessageFormatter
+    matcher: Option<RulesetMatcher<MS::Matcher>>,
+    formatter: MessageFormatter,
+    stats: Arc<Mutex<SharedStatistics>>,
+    stats_file: Option<String>,
+    stats_interval: u64,
 }
 
 impl<MS> ActiondbParserBuilder<MS> where MS: MatcherSuite, MS::Matcher: Clone {
     pub fn set_pattern_file(&mut self, path: &str) {
-        match PatternLoader::from_file::<MS::MatcherFactory>(path) {
+        match PatternLoader::rulesets_from_file::<MS::MatcherFactory>(path) {
             Ok(matcher) => self.matcher = Some(matcher),
             Err(err) => {
                 error!("ActiondbParser: failed to set 'pattern_file': {}", err);
@@ -42,6 +71,30 @@ impl<MS> ActiondbParserBuilder<MS> where MS: MatcherSuite, MS::Matcher: Clone {
     pub fn set_prefix(&mut self, prefix: String) {
         self.formatter.set_prefix(prefix);
     }
+
+    pub fn set_stats_file(&mut self, path: String) {
+        self.stats_file = Some(path);
+    }
+
+    pub fn set_stats_interval(&mut self, value: &str) -> Result<(), Error> {
+        match value.parse() {
+            Ok(interval) if interval > 0 => {
+                self.stats_interval = interval;
+                Ok(())
+            }
+            _ => Err(Error::invalid_value(options::STATS_INTERVAL, value, "a positive number of seconds")),
+        }
+    }
+
+    pub fn set_stats_sample_size(&mut self, value: &str) -> Result<(), Error> {
+        match value.parse() {
+            Ok(sample_size) => {
+                lock(&self.stats).stats.set_sample_size(sample_size);
+                Ok(())
+            }
+            Err(_) => Err(Error::invalid_value(options::STATS_SAMPLE_SIZE, value, "a non-negative integer")),
+        }
+    }
 }
 
 impl<MS> ParserBuilder for ActiondbParserBuilder<MS> where MS: MatcherSuite + Clone, MS::Matcher: Clone {
@@ -50,6 +103,14 @@ impl<MS> ParserBuilder for ActiondbParserBuilder<MS> where MS: MatcherSuite + Cl
         ActiondbParserBuilder {
             matcher: None,
             formatter: MessageFormatter::new(),
+            stats: Arc::new(Mutex::new(SharedStatistics {
+                stats: Statistics::new(stats::DEFAULT_SAMPLE_SIZE),
+                last_dump: Instant::now(),
+                snapshots: 0,
+                written: Arc::new(Mutex::new(0)),
+            })),
+            stats_file: None,
+            stats_interval: DEFAULT_STATS_INTERVAL,
         }
     }
     fn option(&mut self, name: String, value: String) -> Result<(), Error> {
@@ -66,39 +127,130 @@ impl<MS> ParserBuilder for ActiondbParserBuilder<MS> where MS: MatcherSuite + Cl
                 self.set_prefix(value);
                 Ok(())
             }
+            options::STATS_FILE => {
+                self.set_stats_file(value);
+                Ok(())
+            }
+            options::STATS_INTERVAL => self.set_stats_interval(&value),
+            options::STATS_SAMPLE_SIZE => self.set_stats_sample_size(&value),
             _ => {
                 Err(Error::unknown_option(name))
             }
         }
     }
     fn build(self) -> Result<Self::Parser, Error> {
-        let ActiondbParserBuilder {matcher, formatter} = self;
+        let ActiondbParserBuilder {matcher, formatter, stats, stats_file, stats_interval} = self;
         debug!("ActiondbParser: building");
         let matcher =
             try!(matcher.ok_or(Error::missing_required_option(options::PATTERN_FILE)));
+        let sample_size = {
+            let mut shared = lock(&stats);
+            for uuid in matcher.uuids() {
+                shared.stats.add_pattern(uuid);
+            }
+            shared.stats.sample_size()
+        };
         Ok(ActiondbParser {
             matcher: matcher,
             formatter: formatter,
+            stats: Statistics::new(sample_size),
+            last_merge: Instant::now(),
+            shared_stats: stats,
+            stats_file: stats_file,
+            stats_interval: Duration::from_secs(stats_interval),
         })
     }
 }
 
 pub struct ActiondbParser<M> where M: Matcher + Clone {
-    pub matcher: M,
+    pub matcher: RulesetMatcher<M>,
     pub formatter: MessageFormatter,
+    // The messages which were seen since the last merge.
+    stats: Statistics,
+    last_merge: Instant,
+    shared_stats: Arc<Mutex<SharedStatistics>>,
+    stats_file: Option<String>,
+    stats_interval: Duration,
 }
 
-impl<M> Parser for ActiondbParser<M> where M: Matcher + Clone {
-    fn parse(&mut self, _: &mut Pipe, msg: &mut LogMessage, input: &str) -> bool {
-        if let Some(result) = self.matcher.parse(input) {
-            MessageFiller::fill_logmsg(&mut self.formatter, msg, &result);
-            true
-        } else {
-            false
+impl<M> ActiondbParser<M> where M: Matcher + Clone {
+    // The match counters of the patterns and the sample of the unmatched
+    // messages since the parser and its clones were built.
+    pub fn statistics(&self) -> Statistics {
+        let mut stats = lock(&self.shared_stats).stats.clone();
+        stats.merge(&self.stats);
+        stats
+    }
+
+    // The statistics are merged and dumped only when a message is parsed,
+    // so the stats file of an idle parser is updated when it's deinitialized.
+    // They are merged without a stats file too, so the clones see each other.
+    fn dump_statistics_if_needed(&mut self) {
+        if self.last_merge.elapsed() >= self.stats_interval {
+            self.dump_statistics(false);
+        }
+    }
+
+    // The file is written outside of the lock of the shared statistics, the
+    // other parsers wait only for the merge.
+    fn dump_statistics(&mut self, force: bool) {
+        self.last_merge = Instant::now();
+        let sample_size = self.stats.sample_size();
+        let stats = mem::replace(&mut self.stats, Statistics::new(sample_size));
+        let (snapshot, number, written) = {
+            let mut shared = lock(&self.shared_stats);
+            shared.stats.merge(&stats);
+            if !force && shared.last_dump.elapsed() < self.stats_interval {
+                return;
+            }
+            shared.last_dump = Instant::now();
+            shared.snapshots += 1;
+            (shared.stats.clone(), shared.snapshots, shared.written.clone())
+        };
+
+        if let Some(path) = self.stats_file.as_ref() {
+            let mut written = lock(&written);
+            if *written > number {
+                return;
+            }
+            *written = number;
+            if let Err(err) = snapshot.write_to_file(path) {
+                error!("ActiondbParser: failed to write the statistics into {}: {}", path, err);
+            }
         }
     }
 }
 
+impl<M> Parser for ActiondbParser<M> where M: Matcher + Clone {
+    fn parse(&mut self, pipe: &mut Pipe, msg: &mut LogMessage, input: &str) -> bool {
+        self.parse_bytes(pipe, msg, input.as_bytes())
+    }
+
+    // The messages are matched as bytes, so the ones which aren't valid UTF-8
+    // can be classified too. The ruleset is selected by the fields of the
+    // message, e.g. by its PROGRAM.
+    fn parse_bytes(&mut self, _: &mut Pipe, msg: &mut LogMessage, input: &[u8]) -> bool {
+        let matched = match self.matcher.parse_bytes(|field| msg.get(field), input) {
+            Some(result) => {
+                MessageFiller::fill_logmsg(&mut self.formatter, msg, &result);
+                self.stats.record_match(result.pattern().uuid());
+                true
+            }
+            None => {
+                self.stats.record_unmatched(input);
+                false
+            }
+        };
+        self.dump_statistics_if_needed();
+        matched
+    }
+
+    fn deinit(&mut self) -> bool {
+        self.dump_statistics(true);
+        true
+    }
+}
+
 
 // You can change the matcher implementation by uncommening these lines
 // and commenting out the last lines.
@@ -7,8 +7,10 @@
 // modified, or distributed except according to those terms.
 
 use syslog_ng_common::MessageFormatter;
-use syslog_ng_common::LogMessage;
+use syslog_ng_common::{LogMessage, LogMessageValueType};
 use actiondb::matcher::result::MatchResult;
+use actiondb::parsers::ValueType;
+use actiondb::parsers::timestamp;
 
 use keys;
 
@@ -17,30 +19,56 @@ pub struct MessageFiller;
 impl MessageFiller {
     pub fn fill_logmsg(formatter: &mut MessageFormatter,
                        msg: &mut LogMessage,
-                       result: &MatchResult) {
+                       result: &MatchResult<[u8]>) {
         MessageFiller::fill_values(formatter, msg, result);
         MessageFiller::fill_name(formatter, msg, result);
         MessageFiller::fill_uuid(formatter, msg, result);
         MessageFiller::fill_tags(msg, result);
     }
 
-    fn fill_values(formatter: &mut MessageFormatter, msg: &mut LogMessage, result: &MatchResult) {
+    fn fill_values(formatter: &mut MessageFormatter, msg: &mut LogMessage, result: &MatchResult<[u8]>) {
         MessageFiller::fill_parsed_values(formatter, msg, result);
         MessageFiller::fill_additional_values(formatter, msg, result);
     }
 
     fn fill_parsed_values(formatter: &mut MessageFormatter,
                           msg: &mut LogMessage,
-                          result: &MatchResult) {
+                          result: &MatchResult<[u8]>) {
         for (key, value) in result.values() {
+            let value_type = result.value_type(key);
             let (key, value) = formatter.format(key, value);
-            msg.insert(key, value.as_bytes());
+            let epoch = if value_type == ValueType::Timestamp {
+                timestamp::to_epoch(value)
+            } else {
+                None
+            };
+            match epoch {
+                Some(epoch) => msg.insert_with_type(key, epoch.as_bytes(), LogMessageValueType::DateTime),
+                None => msg.insert_with_type(key, value, MessageFiller::log_message_value_type(value_type)),
+            }
+        }
+        for (key, value) in result.pairs() {
+            let value_type = result.value_type(key);
+            let (key, value) = formatter.format(key, &**value);
+            msg.insert_with_type(key, value, MessageFiller::log_message_value_type(value_type));
+        }
+    }
+
+    // syslog-ng doesn't have a type for IP addresses and its datetime type
+    // requires epoch timestamps, so the IP addresses and the timestamps which
+    // can't be converted to epoch are stored as strings.
+    fn log_message_value_type(value_type: ValueType) -> LogMessageValueType {
+        match value_type {
+            ValueType::Int => LogMessageValueType::Integer,
+            ValueType::Float => LogMessageValueType::Double,
+            ValueType::Boolean => LogMessageValueType::Boolean,
+            ValueType::String | ValueType::IpAddr | ValueType::Timestamp => LogMessageValueType::String,
         }
     }
 
     fn fill_additional_values(formatter: &mut MessageFormatter,
                               msg: &mut LogMessage,
-                              result: &MatchResult) {
+                              result: &MatchResult<[u8]>) {
         if let Some(values) = result.pattern().values() {
             for (key, value) in values {
                 let (key, value) = formatter.format(key, value);
@@ -49,20 +77,20 @@ impl MessageFiller {
         }
     }
 
-    fn fill_name(formatter: &mut MessageFormatter, msg: &mut LogMessage, result: &MatchResult) {
+    fn fill_name(formatter: &mut MessageFormatter, msg: &mut LogMessage, result: &MatchResult<[u8]>) {
         if let Some(name) = result.pattern().name() {
             let (key, value) = formatter.format(keys::PATTERN_NAME, name);
             msg.insert(key, value.as_bytes());
         }
     }
 
-    fn fill_uuid(formatter: &mut MessageFormatter, msg: &mut LogMessage, result: &MatchResult) {
+    fn fill_uuid(formatter: &mut MessageFormatter, msg: &mut LogMessage, result: &MatchResult<[u8]>) {
         let uuid = result.pattern().uuid().hyphenated().to_string();
         let (key, value) = formatter.format(keys::PATTERN_UUID, &uuid);
         msg.insert(key, value.as_bytes());
     }
 
-    fn fill_tags(msg: &mut LogMessage, result: &MatchResult) {
+    fn fill_tags(msg: &mut LogMessage, result: &MatchResult<[u8]>) {
         if let Some(tags) = result.pattern().tags() {
             for i in tags {
                 msg.set_tag(i.as_bytes());
@@ -8,3 +8,6 @@
 
 pub const PATTERN_FILE: &'static str = "pattern_file";
 pub const PREFIX: &'static str = "prefix";
+pub const STATS_FILE: &'static str = "stats_file";
+pub const STATS_INTERVAL: &'static str = "stats_interval";
+pub const STATS_SAMPLE_SIZE: &'static str = "stats_sample_size";
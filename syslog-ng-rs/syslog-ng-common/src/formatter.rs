@@ -28,7 +28,7 @@ impl MessageFormatter {
     }
 
     /// Formats the given `key` and/or `value` parameters and returns the formatted pair as a tuple.
-    pub fn format<'a, 'b, 'c>(&'a mut self, key: &'b str, value: &'c str) -> (&'a str, &'c str) {
+    pub fn format<'a, 'b, 'c, V: ?Sized>(&'a mut self, key: &'b str, value: &'c V) -> (&'a str, &'c V) {
         self.buffer.clear();
         self.apply_prefix(key);
         (&self.buffer, value)
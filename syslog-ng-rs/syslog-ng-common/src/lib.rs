@@ -38,7 +38,7 @@ mod plugin;
 
 pub use syslog_ng_sys::{c_int, c_char, ssize_t};
 pub use logparser::LogParser;
-pub use logmsg::LogMessage;
+pub use logmsg::{LogMessage, LogMessageValueType};
 pub use formatter::MessageFormatter;
 pub use logger::init_logger;
 pub use cfg::GlobalConfig;
@@ -20,6 +20,17 @@ mod test;
 /// Wrapper around syslog-ng's NVHandle
 pub struct NVHandle(logmsg::NVHandle);
 
+/// Typesafe representation of syslog-ng's LogMessageValueType constants.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum LogMessageValueType {
+    String = 0,
+    Json = 1,
+    Boolean = 2,
+    Integer = 3,
+    Double = 4,
+    DateTime = 5,
+}
+
 /// High level wrapper around syslog-ng's LogMessage structure.
 pub struct LogMessage(pub *mut logmsg::LogMessage);
 
@@ -99,6 +110,25 @@ impl LogMessage {
         }
     }
 
+    /// Inserts a new (key, value) pair into the log message with the type of
+    /// the value, so it isn't handled as a string when it's formatted.
+    ///
+    /// syslog-ng supports value types from 4.0, with earlier versions the
+    /// type is dropped.
+    pub fn insert_with_type<K: Into<NVHandle>>(&mut self,
+                                               key: K,
+                                               value: &[u8],
+                                               value_type: LogMessageValueType) {
+        let handle = key.into();
+        unsafe {
+            logmsg::log_msg_set_typed_value(self.0,
+                                            handle.0,
+                                            value.as_ptr() as *const i8,
+                                            value.len() as isize,
+                                            value_type as c_int);
+        }
+    }
+
     /// Sets `tag` in the log message.
     ///
     /// # Panics
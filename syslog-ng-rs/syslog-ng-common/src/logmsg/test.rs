@@ -8,7 +8,7 @@
 
 use std::collections::BTreeMap;
 
-use super::LogMessage;
+use super::{LogMessage, LogMessageValueType};
 use SYSLOG_NG_INITIALIZED;
 use syslog_ng_global_init;
 
@@ -77,3 +77,13 @@ fn test_set_tags_can_be_iterated_over_in_log_message() {
     let actual = logmsg.tags();
     assert_eq!(expected, actual);
 }
+
+#[test]
+fn test_given_empty_log_msg_when_a_typed_value_is_inserted_then_we_can_get_it_back() {
+    SYSLOG_NG_INITIALIZED.call_once(|| {
+        unsafe { syslog_ng_global_init(); }
+    });
+    let mut logmsg = LogMessage::new();
+    logmsg.insert_with_type("pid", b"42", LogMessageValueType::Integer);
+    assert_eq!(Some(&b"42"[..]), logmsg.get("pid"));
+}
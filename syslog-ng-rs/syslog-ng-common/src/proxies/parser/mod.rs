@@ -10,6 +10,7 @@ use LogMessage;
 use Pipe;
 
 use std::panic::{UnwindSafe, catch_unwind};
+use std::str;
 
 mod error;
 mod proxy;
@@ -52,6 +53,17 @@ pub trait Parser {
     /// Parses `input` and inserts the new key-value pairs into `msg`. `pipe` represents the parent
     /// `LogPipe`. It can be mocked out to simplify the testing without syslog-ng.
     fn parse(&mut self, pipe: &mut Pipe, msg: &mut LogMessage, input: &str) -> bool;
+    /// Parses `input` which isn't necessarily valid UTF-8. The default implementation calls `parse()`
+    /// if `input` is valid UTF-8, otherwise an error is logged and the message is not parsed.
+    fn parse_bytes(&mut self, pipe: &mut Pipe, msg: &mut LogMessage, input: &[u8]) -> bool {
+        match str::from_utf8(input) {
+            Ok(input) => self.parse(pipe, msg, input),
+            Err(err) => {
+                error!("{}", err);
+                false
+            }
+        }
+    }
 }
 
 /// Converts a `bool` to a `c_int`
@@ -155,22 +167,11 @@ pub mod _parser_plugin {
         let wrapper_input = AssertUnwindSafe(input);
 
         let unwind_safe_call = move || {
-            let input = unsafe { CStr::from_ptr(*wrapper_input).to_str() };
-
-            let result: bool = match input {
-                Ok(input) => {
-                    let mut parent = LogParser::wrap_raw(*wrapper_parent);
-                    let mut msg = LogMessage::wrap_raw(*wrapper_msg);
-
-                    wrapper_this.process(&mut parent, &mut msg, input)
-                },
-                Err(err) => {
-                    error!("{}", err);
-                    false
-                }
-            };
-
-            bool_to_int(result)
+            let input = unsafe { CStr::from_ptr(*wrapper_input).to_bytes() };
+            let mut parent = LogParser::wrap_raw(*wrapper_parent);
+            let mut msg = LogMessage::wrap_raw(*wrapper_msg);
+
+            bool_to_int(wrapper_this.process(&mut parent, &mut msg, input))
         };
 
         abort_on_panic("process", unwind_safe_call)
@@ -102,16 +102,16 @@ impl<B> ParserProxy<B> where B: ParserBuilder
         }
     }
 
-    /// Calls the parser's `parse()` method.
+    /// Calls the parser's `parse_bytes()` method.
     ///
     /// # Panics
     ///
     /// Panics if `process()` is called without a built parser.
-    pub fn process(&mut self, parent: &mut LogParser, msg: &mut LogMessage, input: &str) -> bool {
+    pub fn process(&mut self, parent: &mut LogParser, msg: &mut LogMessage, input: &[u8]) -> bool {
         self.parser
             .as_mut()
             .expect("Called process on a non-existing Rust parser")
-            .parse(parent, msg, input)
+            .parse_bytes(parent, msg, input)
     }
 }
 
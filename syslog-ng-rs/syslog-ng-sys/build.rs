@@ -18,6 +18,11 @@ fn main() {
                 println!("cargo:rustc-link-search=native={:?}", dir);
             }
             println!("cargo:rustc-link-lib=dylib=syslog-ng");
+            // value types were introduced in syslog-ng 4.0
+            println!("cargo:rustc-check-cfg=cfg(syslog_ng_typed_values)");
+            if major_version(&value.version) >= 4 {
+                println!("cargo:rustc-cfg=syslog_ng_typed_values");
+            }
         },
         Err(err) => {
             println!("libsyslog-ng.so is not found by pkg-config: {}", err);
@@ -25,3 +30,7 @@ fn main() {
         }
     }
 }
+
+fn major_version(version: &str) -> u32 {
+    version.split('.').next().and_then(|major| major.parse().ok()).unwrap_or(0)
+}
@@ -57,5 +57,31 @@ extern "C" {
                              handle: NVHandle,
                              value: *const c_char,
                              value_length: ssize_t);
+    #[cfg(syslog_ng_typed_values)]
+    pub fn log_msg_set_value_with_type(msg: *mut LogMessage,
+                                       handle: NVHandle,
+                                       value: *const c_char,
+                                       value_length: ssize_t,
+                                       value_type: c_int);
     pub fn log_tags_global_init();
 }
+
+/// Sets the value with its type if syslog-ng supports value types (from 4.0),
+/// otherwise the type is dropped and the value is set as a string.
+#[cfg(syslog_ng_typed_values)]
+pub unsafe fn log_msg_set_typed_value(msg: *mut LogMessage,
+                                      handle: NVHandle,
+                                      value: *const c_char,
+                                      value_length: ssize_t,
+                                      value_type: c_int) {
+    log_msg_set_value_with_type(msg, handle, value, value_length, value_type)
+}
+
+#[cfg(not(syslog_ng_typed_values))]
+pub unsafe fn log_msg_set_typed_value(msg: *mut LogMessage,
+                                      handle: NVHandle,
+                                      value: *const c_char,
+                                      value_length: ssize_t,
+                                      _: c_int) {
+    log_msg_set_value(msg, handle, value, value_length)
+}